@@ -5,25 +5,25 @@
 //! collecting any syntax errors it finds along the way.
 
 use crate::ast::*;
-use crate::error::ParseError;
+use crate::error::{ParseError, ParseErrorKind};
 use crate::token::{Span, Token};
 use crate::Scribe;
 
 /// Defines the precedence levels for operators to manage order of operations.
 /// Higher variants have higher precedence.
-/// Note: Currently unused but reserved for future expression parsing implementation.
 #[derive(PartialEq, PartialOrd)]
-#[allow(dead_code)]
 enum Precedence {
     Lowest,
-    Assign,      // =
+    Or,          // or
+    And,         // and
     Equals,      // ==
     LessGreater, // > or <
+    Elvis,       // ?:
     Sum,         // +
     Product,     // *
     Prefix,      // -X or !X
     Call,        // myFunction(X)
-    Member,      // object.member
+    Member,      // object.member or object?.member
 }
 
 /// The Architect struct holds the state of the parser as it consumes tokens.
@@ -63,11 +63,18 @@ impl<'a> Architect<'a> {
     /// parsing definitions until it reaches the End-Of-File token.
     pub fn parse_program(&mut self) -> Program {
         let mut program = Program {
+            imports: Vec::new(),
             definitions: Vec::new(),
             span: Span::default(), // Will be updated at the end
         };
 
         while !matches!(self.current_token, Token::Eof(_)) {
+            if matches!(self.current_token, Token::Use(_)) {
+                if let Some(import) = self.parse_use_declaration() {
+                    program.imports.push(import);
+                }
+                continue;
+            }
             match self.parse_definition() {
                 Some(def) => program.definitions.push(def),
                 None => self.next_token(), // On error, skip token to prevent infinite loops
@@ -81,42 +88,95 @@ impl<'a> Architect<'a> {
     fn parse_definition(&mut self) -> Option<Definition> {
         match &self.current_token {
             Token::Contract(_) => self.parse_contract_definition().map(Definition::Contract),
-            Token::Let(_) => self.parse_let_statement().map(Definition::Statement),
+            Token::Enum(_) => self.parse_enum_definition().map(Definition::Enum),
+            Token::Interface(_) => self.parse_interface_definition().map(Definition::Interface),
+            Token::Let(_) => self.parse_let_definition(None),
+            Token::Async(span) if matches!(self.peek_token, Token::Let(_)) => {
+                let async_span = *span;
+                self.next_token(); // consume 'async'
+                self.parse_let_definition(Some(async_span))
+            }
+            Token::Public(span) => {
+                let public_span = *span;
+                self.next_token(); // consume 'public'
+                let mut definition = self.parse_definition()?;
+                match &mut definition {
+                    Definition::Contract(def) => def.is_public = true,
+                    Definition::Enum(def) => def.is_public = true,
+                    Definition::Interface(def) => def.is_public = true,
+                    Definition::Function(def) => def.is_public = true,
+                    _ => self.errors.push(ParseError {
+                        message: "Only contracts, enums, interfaces and functions can be public"
+                            .to_string(),
+                        kind: ParseErrorKind::InvalidModifier,
+                        span: public_span,
+                    }),
+                }
+                Some(definition)
+            }
+            _ if self.at_style_definition() => self.parse_style_definition().map(Definition::Style),
+            // A top-level call or assignment, e.g., `print(greeting)`, runs
+            // when the program starts.
+            Token::Identifier(..) => self.parse_statement().map(Definition::Statement),
+            // `app` blocks aren't parsed yet, so they are reported and
+            // skipped whole, body and all.
+            Token::App(_) => {
+                self.errors.push(ParseError {
+                    message: "`app` blocks aren't supported yet, so a program with a UI can't be checked or run".to_string(),
+                    kind: ParseErrorKind::UnexpectedToken,
+                    span: self.current_token.span(),
+                });
+                self.skip_block();
+                None
+            }
             _ => {
-                // For now, skip unknown tokens to prevent infinite loops
-                self.next_token();
+                self.errors.push(ParseError {
+                    message: "Expected a declaration, a call or an assignment at the top level"
+                        .to_string(),
+                    kind: ParseErrorKind::UnexpectedToken,
+                    span: self.current_token.span(),
+                });
+                self.skip_block();
                 None
             }
         }
     }
 
+    /// Skips the rest of the construct at the current token: the rest of its
+    /// line and every line indented past it. Stops at the token before the
+    /// next construct, which `parse_program` moves on to.
+    fn skip_block(&mut self) {
+        let column = self.scribe.indent_of(self.current_token.span().start);
+        while !matches!(self.peek_token, Token::Eof(_))
+            && self.scribe.column_of(self.peek_token.span().start) > column
+        {
+            self.next_token();
+        }
+    }
+
+    /// Skips the rest of a statement starting at `start` that didn't parse:
+    /// the rest of its line and every line indented past it.
+    fn skip_statement(&mut self, start: usize) {
+        let column = self.scribe.indent_of(start);
+        if self.current_token.span().start == start {
+            self.next_token();
+        }
+        while !matches!(self.current_token, Token::Eof(_))
+            && self.scribe.column_of(self.current_token.span().start) > column
+        {
+            self.next_token();
+        }
+    }
+
     /// The core of the Pratt parser for handling expressions.
     /// It recursively parses tokens based on their defined precedence.
-    /// Note: Reserved for future complete expression parsing implementation.
-    #[allow(dead_code)]
     fn parse_expression(&mut self, precedence: Precedence) -> Option<Expression> {
-        // Find a prefix parsing function for the current token.
-        // This handles literals, identifiers, and prefix operators like `-` or `!`.
-        let mut left_expression = match self.parse_prefix() {
-            Some(expr) => expr,
-            None => {
-                // Register an error if no prefix parse function is found.
-                self.errors.push(ParseError {
-                    message: format!("Unexpected token: {:?}", self.current_token),
-                    span: self.current_token.span(),
-                });
-                return None;
-            }
-        };
+        // Parse a literal, identifier or prefix operator like `-` or `!`.
+        let mut left_expression = self.parse_prefix()?;
 
-        // Loop as long as the next token is an infix operator with higher precedence.
-        while precedence < self.peek_precedence() {
-            self.next_token(); // Consume the operator
-                               // Find an infix parsing function for the new current token.
-            left_expression = match self.parse_infix(left_expression.clone()) {
-                Some(expr) => expr,
-                None => return Some(left_expression), // No infix function found, end of expression.
-            };
+        // Loop as long as the current token is an infix operator with higher precedence.
+        while precedence < self.current_precedence() {
+            left_expression = self.parse_infix(left_expression)?;
         }
 
         Some(left_expression)
@@ -135,11 +195,92 @@ impl<'a> Architect<'a> {
     // /// Parses a UI node definition, e.g., `text "Hello"`
     // fn parse_ui_node(&mut self) -> Option<UiNode> { ... }
     //
-    // /// Parses a function call expression, e.g., `my_func(a, b)`
-    // fn parse_call_expression(&mut self, function: Expression) -> Option<Expression> { ... }
-    //
     // ...and so on for every language construct.
 
+    /// Parse a `use` declaration, e.g., `use models.task` or
+    /// `use models.task.{Task, Status}`.
+    fn parse_use_declaration(&mut self) -> Option<UseDeclaration> {
+        let start_span = self.current_token.span();
+        self.next_token(); // consume 'use'
+
+        let mut path = Vec::new();
+        let mut names = None;
+        let mut end;
+        loop {
+            match &self.current_token {
+                Token::Identifier(segment, span) => {
+                    path.push(segment.clone());
+                    end = span.end;
+                    self.next_token();
+                }
+                Token::LBrace(_) if !path.is_empty() => {
+                    let (imported, rbrace_span) = self.parse_imported_names()?;
+                    names = Some(imported);
+                    end = rbrace_span.end;
+                    break;
+                }
+                _ => {
+                    self.errors.push(ParseError {
+                        message: "Expected a module name, e.g., `use models.task`".to_string(),
+                        kind: ParseErrorKind::ExpectedName,
+                        span: self.current_token.span(),
+                    });
+                    return None;
+                }
+            }
+            if !matches!(self.current_token, Token::Dot(_)) {
+                break;
+            }
+            self.next_token(); // consume '.'
+        }
+
+        Some(UseDeclaration {
+            path,
+            names,
+            span: Span {
+                start: start_span.start,
+                end,
+            },
+        })
+    }
+
+    /// Parses the braced list of names in a `use` declaration, returning
+    /// them with the span of the closing brace.
+    fn parse_imported_names(&mut self) -> Option<(Vec<ImportedName>, Span)> {
+        self.next_token(); // consume '{'
+        let mut names = Vec::new();
+        loop {
+            let Token::Identifier(name, span) = &self.current_token else {
+                self.errors.push(ParseError {
+                    message: "Expected a name to import".to_string(),
+                    kind: ParseErrorKind::ExpectedName,
+                    span: self.current_token.span(),
+                });
+                return None;
+            };
+            names.push(ImportedName {
+                name: name.clone(),
+                span: *span,
+            });
+            self.next_token();
+            match self.current_token {
+                Token::Comma(_) => self.next_token(),
+                Token::RBrace(span) => {
+                    self.next_token();
+                    return Some((names, span));
+                }
+                _ => {
+                    self.errors.push(ParseError {
+                        message: "Expected ',' or '}' in the list of imported names".to_string(),
+                        kind: ParseErrorKind::ExpectedPunctuation,
+                        span: self.current_token.span(),
+                    });
+                    return None;
+                }
+            }
+        }
+    }
+
     /// Parse a contract definition
     fn parse_contract_definition(&mut self) -> Option<ContractDefinition> {
         let start_span = self.current_token.span();
@@ -158,52 +299,42 @@ impl<'a> Architect<'a> {
         } else {
             self.errors.push(ParseError {
                 message: "Expected contract name".to_string(),
+                kind: ParseErrorKind::ExpectedName,
                 span: self.current_token.span(),
             });
             return None;
         };
 
         // Parse generic parameters if present: <T>, <T, U>, etc.
-        let mut generic_params = Vec::new();
-        if matches!(self.current_token, Token::LessThan(_)) {
-            self.next_token(); // consume '<'
-
-            // Parse first parameter
-            if let Token::Identifier(param_name, _) = &self.current_token {
-                generic_params.push(param_name.clone());
-                self.next_token();
-
-                // Parse additional parameters separated by commas
-                while matches!(self.current_token, Token::Comma(_)) {
-                    self.next_token(); // consume ','
-
-                    if let Token::Identifier(param_name, _) = &self.current_token {
-                        generic_params.push(param_name.clone());
-                        self.next_token();
-                    } else {
-                        self.errors.push(ParseError {
-                            message: "Expected generic parameter name after comma".to_string(),
-                            span: self.current_token.span(),
-                        });
-                        return None;
-                    }
-                }
+        let generic_params = self.parse_generic_params()?;
+        if let Some(bounded) = generic_params.iter().find(|param| param.bound.is_some()) {
+            self.errors.push(ParseError {
+                message: "Only the type parameters of functions can have bounds".to_string(),
+                kind: ParseErrorKind::InvalidModifier,
+                span: bounded.span,
+            });
+            return None;
+        }
+        let generic_params = generic_params.into_iter().map(|param| param.name).collect();
 
-                // Expect closing '>'
-                if !matches!(self.current_token, Token::GreaterThan(_)) {
+        // Parse the interfaces the contract implements, e.g., `is Displayable, Comparable`.
+        let mut interfaces = Vec::new();
+        if matches!(self.current_token, Token::Is(_)) {
+            loop {
+                self.next_token(); // consume 'is' or ','
+                let Token::Identifier(interface, _) = &self.current_token else {
                     self.errors.push(ParseError {
-                        message: "Expected '>' to close generic parameters".to_string(),
+                        message: "Expected interface name".to_string(),
+                        kind: ParseErrorKind::ExpectedName,
                         span: self.current_token.span(),
                     });
                     return None;
+                };
+                interfaces.push(interface.clone());
+                self.next_token();
+                if !matches!(self.current_token, Token::Comma(_)) {
+                    break;
                 }
-                self.next_token(); // consume '>'
-            } else {
-                self.errors.push(ParseError {
-                    message: "Expected generic parameter name after '<'".to_string(),
-                    span: self.current_token.span(),
-                });
-                return None;
             }
         }
 
@@ -211,19 +342,35 @@ impl<'a> Architect<'a> {
         if !matches!(self.current_token, Token::Colon(_)) {
             self.errors.push(ParseError {
                 message: "Expected ':' after contract name".to_string(),
+                kind: ParseErrorKind::ExpectedPunctuation,
                 span: self.current_token.span(),
             });
             return None;
         }
         self.next_token();
 
-        // Parse fields (simplified - just parse lines with "name: type" format)
+        // Parse fields (simplified - just parse lines with "name: type" format),
+        // and methods and computed properties, which are indented `let's` definitions.
+        let column = self.scribe.indent_of(start_span.start);
         let mut fields = Vec::new();
-        while !matches!(self.current_token, Token::Eof(_))
-            && !matches!(self.current_token, Token::Let(_))
-            && !matches!(self.current_token, Token::Contract(_))
-            && !matches!(self.current_token, Token::App(_))
-        {
+        let mut methods = Vec::new();
+        let mut properties = Vec::new();
+        loop {
+            if self.at_member_definition(column) {
+                match self.parse_definition() {
+                    Some(Definition::Function(method)) => methods.push(method),
+                    Some(Definition::Statement(Statement::Let(let_stmt))) => {
+                        if let Some(property) = self.computed_property(let_stmt) {
+                            properties.push(property);
+                        }
+                    }
+                    Some(_) | None => {}
+                }
+                continue;
+            }
+            if self.at_definition_boundary() {
+                break;
+            }
             if let Token::Identifier(field_name, field_span) = &self.current_token {
                 let field_name = field_name.clone();
                 let field_start_span = *field_span;
@@ -232,23 +379,19 @@ impl<'a> Architect<'a> {
                 if matches!(self.current_token, Token::Colon(_)) {
                     self.next_token();
 
-                    if let Token::Identifier(type_name, _) = &self.current_token {
-                        let type_name = type_name.clone();
-                        self.next_token();
-
+                    if let Some(type_ann) = self.parse_type_identifier() {
+                        // A default value, e.g., `is_done: boolean = false`.
+                        let mut default_value = None;
+                        if matches!(self.current_token, Token::Assign(_)) {
+                            self.next_token();
+                            default_value = self.parse_expression(Precedence::Lowest);
+                        }
                         fields.push(ContractField {
                             name: field_name,
-                            type_ann: TypeIdentifier::Simple {
-                                name: type_name,
-                                span: self.current_token.span(),
-                            },
+                            type_ann,
+                            default_value,
                             span: field_start_span,
                         });
-                    } else {
-                        self.errors.push(ParseError {
-                            message: "Expected type annotation".to_string(),
-                            span: self.current_token.span(),
-                        });
                     }
                 } else {
                     // Skip this token and continue
@@ -262,15 +405,416 @@ impl<'a> Architect<'a> {
 
         Some(ContractDefinition {
             name,
+            is_public: false,
             generic_params,
+            interfaces,
             fields,
+            methods,
+            properties,
             span: start_span,
         })
     }
 
-    /// Parse a let statement
+    /// Turns a `let's` in a contract body into a computed property. Its type
+    /// must be written out, like the return type of a method.
+    fn computed_property(&mut self, let_stmt: LetStatement) -> Option<ComputedProperty> {
+        if let_stmt.is_tracked {
+            self.errors.push(ParseError {
+                message: format!(
+                    "Computed property '{}' can't be tracked; declare a field instead",
+                    let_stmt.name
+                ),
+                kind: ParseErrorKind::InvalidModifier,
+                span: let_stmt.span,
+            });
+            return None;
+        }
+        let Some(type_ann) = let_stmt.type_annotation else {
+            self.errors.push(ParseError {
+                message: format!(
+                    "Computed property '{}' needs a type, e.g., `let's {}: number = ...`",
+                    let_stmt.name, let_stmt.name
+                ),
+                kind: ParseErrorKind::ExpectedType,
+                span: let_stmt.span,
+            });
+            return None;
+        };
+        Some(ComputedProperty {
+            name: let_stmt.name,
+            type_ann,
+            value: let_stmt.value,
+            span: let_stmt.span,
+        })
+    }
+
+    /// Parse an enum definition, e.g.
+    ///
+    /// ```text
+    /// enum LoadState:
+    ///     Loading
+    ///     Success(Data)
+    ///     Failure(string, number)
+    /// ```
+    fn parse_enum_definition(&mut self) -> Option<EnumDefinition> {
+        let start_span = self.current_token.span();
+
+        // Consume 'enum' token
+        if !matches!(self.current_token, Token::Enum(_)) {
+            return None;
+        }
+        self.next_token();
+
+        // Get enum name
+        let name = if let Token::Identifier(name, _) = &self.current_token {
+            let enum_name = name.clone();
+            self.next_token();
+            enum_name
+        } else {
+            self.errors.push(ParseError {
+                message: "Expected enum name".to_string(),
+                kind: ParseErrorKind::ExpectedName,
+                span: self.current_token.span(),
+            });
+            return None;
+        };
+
+        // Expect colon
+        if !matches!(self.current_token, Token::Colon(_)) {
+            self.errors.push(ParseError {
+                message: "Expected ':' after enum name".to_string(),
+                kind: ParseErrorKind::ExpectedPunctuation,
+                span: self.current_token.span(),
+            });
+            return None;
+        }
+        self.next_token();
+
+        // Parse variants, each optionally followed by a parenthesized list of types.
+        let mut variants = Vec::new();
+        while !self.at_definition_boundary() {
+            let Token::Identifier(variant_name, variant_span) = &self.current_token else {
+                // Skip separators such as commas between variants
+                self.next_token();
+                continue;
+            };
+            let variant_name = variant_name.clone();
+            let variant_span = *variant_span;
+            self.next_token();
+
+            let mut types = Vec::new();
+            if matches!(self.current_token, Token::LParen(_)) {
+                self.next_token(); // consume '('
+                while !matches!(self.current_token, Token::RParen(_) | Token::Eof(_)) {
+                    types.push(self.parse_type_identifier()?);
+                    if matches!(self.current_token, Token::Comma(_)) {
+                        self.next_token();
+                    } else {
+                        break;
+                    }
+                }
+                if !matches!(self.current_token, Token::RParen(_)) {
+                    self.errors.push(ParseError {
+                        message: "Expected ')' to close variant types".to_string(),
+                        kind: ParseErrorKind::UnclosedDelimiter,
+                        span: self.current_token.span(),
+                    });
+                    return None;
+                }
+                self.next_token(); // consume ')'
+            }
+
+            variants.push(EnumVariant {
+                name: variant_name,
+                types,
+                span: variant_span,
+            });
+        }
+
+        Some(EnumDefinition {
+            name,
+            is_public: false,
+            variants,
+            span: start_span,
+        })
+    }
+
+    /// Parses the generic parameters of a contract or function if present,
+    /// e.g., `<T>`, `<T, U>` or `<T: Comparable>`.
+    fn parse_generic_params(&mut self) -> Option<Vec<GenericParam>> {
+        let mut generic_params = Vec::new();
+        if !matches!(self.current_token, Token::LessThan(_)) {
+            return Some(generic_params);
+        }
+        self.next_token(); // consume '<'
+
+        loop {
+            let Token::Identifier(param_name, param_span) = &self.current_token else {
+                let after = if generic_params.is_empty() {
+                    "'<'"
+                } else {
+                    "comma"
+                };
+                self.errors.push(ParseError {
+                    message: format!("Expected generic parameter name after {}", after),
+                    kind: ParseErrorKind::ExpectedName,
+                    span: self.current_token.span(),
+                });
+                return None;
+            };
+            let (name, span) = (param_name.clone(), *param_span);
+            self.next_token();
+
+            // An optional bound, e.g., the `Comparable` in `T: Comparable`.
+            let mut bound = None;
+            if matches!(self.current_token, Token::Colon(_)) {
+                self.next_token();
+                let Token::Identifier(interface, _) = &self.current_token else {
+                    self.errors.push(ParseError {
+                        message: format!("Expected an interface name after '{}:'", name),
+                        kind: ParseErrorKind::ExpectedType,
+                        span: self.current_token.span(),
+                    });
+                    return None;
+                };
+                bound = Some(interface.clone());
+                self.next_token();
+            }
+            generic_params.push(GenericParam { name, bound, span });
+
+            // Parse additional parameters separated by commas
+            if !matches!(self.current_token, Token::Comma(_)) {
+                break;
+            }
+            self.next_token(); // consume ','
+        }
+
+        // Expect closing '>'
+        if !matches!(self.current_token, Token::GreaterThan(_)) {
+            self.errors.push(ParseError {
+                message: "Expected '>' to close generic parameters".to_string(),
+                kind: ParseErrorKind::UnclosedDelimiter,
+                span: self.current_token.span(),
+            });
+            return None;
+        }
+        self.next_token(); // consume '>'
+        Some(generic_params)
+    }
+
+    /// Parses a type identifier, e.g., `number`, `Task` or `Map<string, List<Task>>`.
+    fn parse_type_identifier(&mut self) -> Option<TypeIdentifier> {
+        let (name, name_span) = if let Token::Identifier(name, span) = &self.current_token {
+            (name.clone(), *span)
+        } else {
+            self.errors.push(ParseError {
+                message: "Expected type annotation".to_string(),
+                kind: ParseErrorKind::ExpectedType,
+                span: self.current_token.span(),
+            });
+            return None;
+        };
+        self.next_token();
+
+        if !matches!(self.current_token, Token::LessThan(_)) {
+            let simple = TypeIdentifier::Simple {
+                name,
+                span: name_span,
+            };
+            return Some(self.parse_optional_suffix(simple));
+        }
+        self.next_token(); // consume '<'
+
+        let mut args = vec![self.parse_type_identifier()?];
+        while matches!(self.current_token, Token::Comma(_)) {
+            self.next_token(); // consume ','
+            args.push(self.parse_type_identifier()?);
+        }
+
+        if !matches!(self.current_token, Token::GreaterThan(_)) {
+            self.errors.push(ParseError {
+                message: "Expected '>' to close type arguments".to_string(),
+                kind: ParseErrorKind::UnclosedDelimiter,
+                span: self.current_token.span(),
+            });
+            return None;
+        }
+        let end = self.current_token.span().end;
+        self.next_token(); // consume '>'
+
+        let generic = TypeIdentifier::Generic {
+            name,
+            args,
+            span: Span {
+                start: name_span.start,
+                end,
+            },
+        };
+        Some(self.parse_optional_suffix(generic))
+    }
+
+    /// Wraps a type in `Optional` if it is followed by `?`, so `User?` is
+    /// shorthand for `Optional<User>`.
+    fn parse_optional_suffix(&mut self, inner: TypeIdentifier) -> TypeIdentifier {
+        let question_span = match self.current_token {
+            Token::Question(span) => {
+                self.next_token(); // consume '?'
+                span
+            }
+            // In `-> T?:` the Scribe reads `?:` as the elvis operator; split
+            // it back into the `?` and the `:` that ends the signature.
+            Token::Elvis(span) => {
+                self.current_token = Token::Colon(Span {
+                    start: span.start + 1,
+                    end: span.end,
+                });
+                Span {
+                    start: span.start,
+                    end: span.start + 1,
+                }
+            }
+            _ => return inner,
+        };
+        let start = inner.span().start;
+        TypeIdentifier::Generic {
+            name: "Optional".to_string(),
+            args: vec![inner],
+            span: Span {
+                start,
+                end: question_span.end,
+            },
+        }
+    }
+
+    /// Returns true if the current token starts a new top-level definition,
+    /// which ends the body of the definition being parsed.
+    fn at_definition_boundary(&self) -> bool {
+        matches!(
+            self.current_token,
+            Token::Eof(_)
+                | Token::Let(_)
+                | Token::Contract(_)
+                | Token::App(_)
+                | Token::Enum(_)
+                | Token::Interface(_)
+                | Token::Use(_)
+                | Token::Public(_)
+        ) || self.at_style_definition()
+    }
+
+    /// Returns true if the current token starts a `let's` indented past
+    /// `column`, such as a method in a contract body.
+    fn at_member_definition(&self, column: usize) -> bool {
+        let starts_let = match self.current_token {
+            Token::Let(_) => true,
+            Token::Async(_) => matches!(self.peek_token, Token::Let(_)),
+            _ => false,
+        };
+        starts_let && self.scribe.column_of(self.current_token.span().start) > column
+    }
+
+    /// `style` is only a keyword when it starts a named style declaration,
+    /// so it can still be used as a UI property name.
+    fn at_style_definition(&self) -> bool {
+        matches!(&self.current_token, Token::Identifier(ident, _) if ident == "style")
+            && matches!(self.peek_token, Token::Identifier(..))
+    }
+
+    /// Parse a named style definition, e.g.
+    ///
+    /// ```text
+    /// style HeaderText:
+    ///     font_size: 28
+    ///     text_color: "#333333"
+    /// ```
+    fn parse_style_definition(&mut self) -> Option<StyleDefinition> {
+        let start_span = self.current_token.span();
+
+        // Consume 'style'
+        self.next_token();
+
+        // Get style name
+        let name = if let Token::Identifier(name, _) = &self.current_token {
+            let style_name = name.clone();
+            self.next_token();
+            style_name
+        } else {
+            self.errors.push(ParseError {
+                message: "Expected style name".to_string(),
+                kind: ParseErrorKind::ExpectedName,
+                span: self.current_token.span(),
+            });
+            return None;
+        };
+
+        // Expect colon
+        if !matches!(self.current_token, Token::Colon(_)) {
+            self.errors.push(ParseError {
+                message: "Expected ':' after style name".to_string(),
+                kind: ParseErrorKind::ExpectedPunctuation,
+                span: self.current_token.span(),
+            });
+            return None;
+        }
+        self.next_token();
+
+        let mut fields = Vec::new();
+        while !self.at_definition_boundary() {
+            if let Token::Identifier(field_name, field_span) = &self.current_token {
+                let field_name = field_name.clone();
+                let field_span = *field_span;
+                self.next_token();
+
+                if !matches!(self.current_token, Token::Colon(_)) {
+                    self.errors.push(ParseError {
+                        message: format!("Expected ':' after style property '{}'", field_name),
+                        kind: ParseErrorKind::ExpectedPunctuation,
+                        span: self.current_token.span(),
+                    });
+                    return None;
+                }
+                self.next_token();
+
+                let value = self.parse_expression(Precedence::Lowest)?;
+                fields.push(StyleField {
+                    name: field_name,
+                    value,
+                    span: field_span,
+                });
+            } else {
+                // Skip separators such as commas
+                self.next_token();
+            }
+        }
+
+        Some(StyleDefinition {
+            name,
+            fields,
+            span: start_span,
+        })
+    }
+
+    /// Parse a let statement inside a function body. Functions can't be nested.
     fn parse_let_statement(&mut self) -> Option<Statement> {
         let start_span = self.current_token.span();
+        match self.parse_let_definition(None)? {
+            Definition::Statement(stmt) => Some(stmt),
+            _ => {
+                self.errors.push(ParseError {
+                    message: "Functions can only be declared at the top level".to_string(),
+                    kind: ParseErrorKind::MisplacedDeclaration,
+                    span: start_span,
+                });
+                None
+            }
+        }
+    }
+
+    /// Parse a definition that starts with `let's`: a function if the name is
+    /// followed by type parameters or a parameter list, and a let statement
+    /// otherwise. `async_span` is the span of a preceding `async` keyword.
+    fn parse_let_definition(&mut self, async_span: Option<Span>) -> Option<Definition> {
+        let start_span = async_span.unwrap_or(self.current_token.span());
 
         // Consume 'let' token
         if !matches!(self.current_token, Token::Let(_)) {
@@ -294,52 +838,300 @@ impl<'a> Architect<'a> {
         }
 
         // Get variable name
-        let name = if let Token::Identifier(name, _) = &self.current_token {
-            let var_name = name.clone();
+        let (name, name_span) = if let Token::Identifier(name, span) = &self.current_token {
+            let var_name = (name.clone(), *span);
             self.next_token();
             var_name
         } else {
             self.errors.push(ParseError {
                 message: "Expected variable name".to_string(),
+                kind: ParseErrorKind::ExpectedName,
                 span: self.current_token.span(),
             });
             return None;
         };
 
+        if matches!(self.current_token, Token::LessThan(_) | Token::LParen(_)) {
+            if is_tracked {
+                self.errors.push(ParseError {
+                    message: format!("Function '{}' can't be tracked", name),
+                    kind: ParseErrorKind::InvalidModifier,
+                    span: start_span,
+                });
+                return None;
+            }
+            return self
+                .parse_function_definition(name, async_span.is_some(), start_span)
+                .map(Definition::Function);
+        }
+        if let Some(async_span) = async_span {
+            self.errors.push(ParseError {
+                message: "Only functions can be async".to_string(),
+                kind: ParseErrorKind::InvalidModifier,
+                span: async_span,
+            });
+            return None;
+        }
+
         // Check for type annotation
         let mut type_annotation = None;
         if matches!(self.current_token, Token::Colon(_)) {
             self.next_token();
-
-            if let Token::Identifier(type_name, _) = &self.current_token {
-                type_annotation = Some(type_name.clone());
-                self.next_token();
-            }
+            type_annotation = Some(self.parse_type_identifier()?);
         }
 
         // Expect assignment
         if !matches!(self.current_token, Token::Assign(_)) {
             self.errors.push(ParseError {
                 message: "Expected '=' in let statement".to_string(),
+                kind: ParseErrorKind::ExpectedPunctuation,
                 span: self.current_token.span(),
             });
             return None;
         }
         self.next_token();
 
-        // Parse value expression (simplified)
-        let value = self.parse_simple_expression()?;
+        // Parse value expression
+        let value = self.parse_expression(Precedence::Lowest)?;
 
-        Some(Statement::Let(LetStatement {
+        Some(Definition::Statement(Statement::Let(LetStatement {
             name,
+            name_span,
             is_tracked,
             type_annotation,
             value,
             span: start_span,
-        }))
+        })))
+    }
+
+    /// Parse the rest of a function definition after its name, e.g.
+    ///
+    /// ```text
+    /// let's first<T>(items: List<T>) -> T?:
+    ///     return items.get(0)
+    /// ```
+    ///
+    /// The body is every statement indented past the line that declares the function.
+    fn parse_function_definition(
+        &mut self,
+        name: String,
+        is_async: bool,
+        start_span: Span,
+    ) -> Option<FunctionDefinition> {
+        let generic_params = self.parse_generic_params()?;
+
+        let parameters = self.parse_parameters(&name)?;
+        let return_type = self.parse_return_annotation()?;
+
+        if !matches!(self.current_token, Token::Colon(_)) {
+            self.errors.push(ParseError {
+                message: format!("Expected ':' after the signature of '{}'", name),
+                kind: ParseErrorKind::ExpectedPunctuation,
+                span: self.current_token.span(),
+            });
+            return None;
+        }
+        let body_start = self.current_token.span().end;
+        self.next_token();
+
+        let column = self.scribe.indent_of(start_span.start);
+        let mut statements = Vec::new();
+        while !matches!(self.current_token, Token::Eof(_))
+            && self.scribe.column_of(self.current_token.span().start) > column
+        {
+            // A statement that doesn't parse is reported and skipped, so the
+            // function, and the declarations after it, are still checked.
+            let statement_start = self.current_token.span().start;
+            match self.parse_statement() {
+                Some(statement) => statements.push(statement),
+                None => self.skip_statement(statement_start),
+            }
+        }
+        let body_end = statements.last().map_or(body_start, |stmt| stmt.span().end);
+
+        Some(FunctionDefinition {
+            name,
+            is_public: false,
+            is_async,
+            generic_params,
+            parameters,
+            return_type,
+            body: BlockStatement {
+                statements,
+                span: Span {
+                    start: body_start,
+                    end: body_end,
+                },
+            },
+            span: start_span,
+        })
+    }
+
+    /// Parse the parameter list of a function or method, e.g., `(a: number, b: T)`.
+    fn parse_parameters(&mut self, name: &str) -> Option<Vec<Parameter>> {
+        if !matches!(self.current_token, Token::LParen(_)) {
+            self.errors.push(ParseError {
+                message: format!("Expected '(' after function name '{}'", name),
+                kind: ParseErrorKind::ExpectedPunctuation,
+                span: self.current_token.span(),
+            });
+            return None;
+        }
+        self.next_token(); // consume '('
+
+        let mut parameters = Vec::new();
+        while !matches!(self.current_token, Token::RParen(_) | Token::Eof(_)) {
+            let Token::Identifier(param_name, param_span) = &self.current_token else {
+                self.errors.push(ParseError {
+                    message: "Expected parameter name".to_string(),
+                    kind: ParseErrorKind::ExpectedName,
+                    span: self.current_token.span(),
+                });
+                return None;
+            };
+            let (param_name, param_span) = (param_name.clone(), *param_span);
+            self.next_token();
+
+            if !matches!(self.current_token, Token::Colon(_)) {
+                self.errors.push(ParseError {
+                    message: format!("Expected ':' after parameter '{}'", param_name),
+                    kind: ParseErrorKind::ExpectedPunctuation,
+                    span: self.current_token.span(),
+                });
+                return None;
+            }
+            self.next_token();
+
+            parameters.push(Parameter {
+                name: param_name,
+                type_annotation: self.parse_type_identifier()?,
+                span: param_span,
+            });
+            if matches!(self.current_token, Token::Comma(_)) {
+                self.next_token();
+            }
+        }
+        self.expect_closing(|token| matches!(token, Token::RParen(_)), "')'")?;
+        Some(parameters)
+    }
+
+    /// Parse the return type annotation of a function or method if present, e.g., `-> T?`.
+    fn parse_return_annotation(&mut self) -> Option<Option<TypeIdentifier>> {
+        let mut return_type = None;
+        if matches!(self.current_token, Token::Arrow(_)) {
+            self.next_token();
+            return_type = Some(self.parse_type_identifier()?);
+        }
+        Some(return_type)
+    }
+
+    /// Parse an interface definition, e.g.
+    ///
+    /// ```text
+    /// interface Displayable:
+    ///     let's display() -> string
+    /// ```
+    fn parse_interface_definition(&mut self) -> Option<InterfaceDefinition> {
+        let start_span = self.current_token.span();
+        self.next_token(); // consume 'interface'
+
+        let Token::Identifier(name, _) = &self.current_token else {
+            self.errors.push(ParseError {
+                message: "Expected interface name".to_string(),
+                kind: ParseErrorKind::ExpectedName,
+                span: self.current_token.span(),
+            });
+            return None;
+        };
+        let name = name.clone();
+        self.next_token();
+
+        if !matches!(self.current_token, Token::Colon(_)) {
+            self.errors.push(ParseError {
+                message: "Expected ':' after interface name".to_string(),
+                kind: ParseErrorKind::ExpectedPunctuation,
+                span: self.current_token.span(),
+            });
+            return None;
+        }
+        self.next_token();
+
+        let column = self.scribe.indent_of(start_span.start);
+        let mut methods = Vec::new();
+        while matches!(self.current_token, Token::Let(_))
+            && self.scribe.column_of(self.current_token.span().start) > column
+        {
+            let method_span = self.current_token.span();
+            self.next_token(); // consume 'let's'
+            let Token::Identifier(method_name, _) = &self.current_token else {
+                self.errors.push(ParseError {
+                    message: "Expected method name".to_string(),
+                    kind: ParseErrorKind::ExpectedName,
+                    span: self.current_token.span(),
+                });
+                return None;
+            };
+            let method_name = method_name.clone();
+            self.next_token();
+
+            let parameters = self.parse_parameters(&method_name)?;
+            let return_type = self.parse_return_annotation()?;
+            methods.push(InterfaceMethod {
+                name: method_name,
+                parameters,
+                return_type,
+                span: method_span,
+            });
+        }
+
+        Some(InterfaceDefinition {
+            name,
+            is_public: false,
+            methods,
+            span: start_span,
+        })
+    }
+
+    /// Parse a statement in a function body: a `return`, a `let's`, an
+    /// assignment or an expression.
+    fn parse_statement(&mut self) -> Option<Statement> {
+        let start_span = self.current_token.span();
+        match self.current_token {
+            Token::Let(_) => self.parse_let_statement(),
+            Token::Return(_) => {
+                self.next_token(); // consume 'return'
+                let value = self.parse_expression(Precedence::Lowest)?;
+                let span = Span {
+                    start: start_span.start,
+                    end: value.span().end,
+                };
+                Some(Statement::Return(ReturnStatement { value, span }))
+            }
+            _ => {
+                let expression = self.parse_expression(Precedence::Lowest)?;
+                if !matches!(self.current_token, Token::Assign(_)) {
+                    let span = expression.span();
+                    return Some(Statement::Expression(ExpressionStatement {
+                        expression,
+                        span,
+                    }));
+                }
+                self.next_token(); // consume '='
+                let value = self.parse_expression(Precedence::Lowest)?;
+                let span = Span {
+                    start: expression.span().start,
+                    end: value.span().end,
+                };
+                Some(Statement::Assign(AssignStatement {
+                    target: expression,
+                    value,
+                    span,
+                }))
+            }
+        }
     }
 
-    /// Parse a simple expression (number, string, identifier, or map literal)
+    /// Parse a simple expression (number, string, boolean, identifier, list or map literal)
     fn parse_simple_expression(&mut self) -> Option<Expression> {
         match &self.current_token {
             Token::Number(num, span) => {
@@ -357,13 +1149,21 @@ impl<'a> Architect<'a> {
                 self.next_token();
                 Some(expr)
             }
+            Token::True(span) | Token::False(span) => {
+                let value = matches!(self.current_token, Token::True(_));
+                let expr = Expression::Literal(Literal::Boolean(value), *span);
+                self.next_token();
+                Some(expr)
+            }
             Token::LBrace(_) => {
                 // Parse map literal
                 self.parse_map_literal()
             }
+            Token::LBracket(_) => self.parse_list_literal(),
             _ => {
                 self.errors.push(ParseError {
                     message: format!("Unexpected token in expression: {:?}", self.current_token),
+                    kind: ParseErrorKind::UnexpectedToken,
                     span: self.current_token.span(),
                 });
                 None
@@ -392,6 +1192,7 @@ impl<'a> Architect<'a> {
             if !matches!(self.current_token, Token::Colon(_)) {
                 self.errors.push(ParseError {
                     message: "Expected ':' in map literal".to_string(),
+                    kind: ParseErrorKind::ExpectedPunctuation,
                     span: self.current_token.span(),
                 });
                 return None;
@@ -399,7 +1200,7 @@ impl<'a> Architect<'a> {
             self.next_token();
 
             // Parse value
-            let value = self.parse_simple_expression()?;
+            let value = self.parse_expression(Precedence::Lowest)?;
 
             pairs.push((key, value));
 
@@ -409,64 +1210,252 @@ impl<'a> Architect<'a> {
             }
         }
 
-        // Consume '}'
+        // Consume '}'. The literal spans from '{' to '}', so fixes can add
+        // entries before the '}'.
+        let mut span = start_span;
         if matches!(self.current_token, Token::RBrace(_)) {
+            span.end = self.current_token.span().end;
             self.next_token();
         } else {
             self.errors.push(ParseError {
                 message: "Expected '}' to close map literal".to_string(),
+                kind: ParseErrorKind::UnclosedDelimiter,
+                span: self.current_token.span(),
+            });
+        }
+
+        Some(Expression::Literal(
+            Literal::Map(MapLiteral { pairs, span }),
+            span,
+        ))
+    }
+
+    /// Parse a list literal: [element, element]
+    fn parse_list_literal(&mut self) -> Option<Expression> {
+        let start_span = self.current_token.span();
+
+        if !matches!(self.current_token, Token::LBracket(_)) {
+            return None;
+        }
+        self.next_token(); // Consume '['
+
+        let mut elements = Vec::new();
+        while !matches!(self.current_token, Token::RBracket(_))
+            && !matches!(self.current_token, Token::Eof(_))
+        {
+            elements.push(self.parse_expression(Precedence::Lowest)?);
+
+            // Skip comma if present
+            if matches!(self.current_token, Token::Comma(_)) {
+                self.next_token();
+            }
+        }
+
+        // Consume ']'
+        if matches!(self.current_token, Token::RBracket(_)) {
+            self.next_token();
+        } else {
+            self.errors.push(ParseError {
+                message: "Expected ']' to close list literal".to_string(),
+                kind: ParseErrorKind::UnclosedDelimiter,
                 span: self.current_token.span(),
             });
         }
 
         Some(Expression::Literal(
-            Literal::Map(MapLiteral {
-                pairs,
+            Literal::List(ListLiteral {
+                elements,
                 span: start_span,
             }),
             start_span,
         ))
     }
 
-    /// Minimal implementation of parse_prefix for basic expressions
-    #[allow(dead_code)]
+    /// Parses the start of an expression: a prefix operator, `nothing`,
+    /// `await`, a lambda, a parenthesized expression or a simple expression.
     fn parse_prefix(&mut self) -> Option<Expression> {
+        let start_span = self.current_token.span();
         match &self.current_token {
-            Token::Number(num, span) => {
-                let expr = Expression::Literal(Literal::Number(num.clone()), *span);
+            Token::Minus(_) | Token::Bang(_) => {
+                let operator = if matches!(self.current_token, Token::Minus(_)) {
+                    PrefixOperator::Minus
+                } else {
+                    PrefixOperator::Not
+                };
                 self.next_token();
-                Some(expr)
+                let right = self.parse_expression(Precedence::Prefix)?;
+                let span = Span {
+                    start: start_span.start,
+                    end: right.span().end,
+                };
+                Some(Expression::Prefix(Box::new(PrefixExpression {
+                    operator,
+                    right,
+                    span,
+                })))
             }
-            Token::String(s, span) => {
-                let expr = Expression::Literal(Literal::String(s.clone()), *span);
+            Token::Nothing(span) => {
+                let expr = Expression::Literal(Literal::Nothing, *span);
                 self.next_token();
                 Some(expr)
             }
-            Token::Identifier(ident, span) => {
-                let expr = Expression::Identifier(ident.clone(), *span);
+            Token::Await(_) => {
                 self.next_token();
+                let expression = self.parse_expression(Precedence::Prefix)?;
+                let span = Span {
+                    start: start_span.start,
+                    end: expression.span().end,
+                };
+                Some(Expression::Await(Box::new(AwaitExpression {
+                    expression,
+                    span,
+                })))
+            }
+            Token::Identifier(name, span) if matches!(self.peek_token, Token::FatArrow(_)) => {
+                let parameter = LambdaParameter {
+                    name: name.clone(),
+                    type_annotation: None,
+                    span: *span,
+                };
+                self.next_token(); // consume the parameter
+                self.next_token(); // consume '=>'
+                let body = self.parse_expression(Precedence::Lowest)?;
+                let span = Span {
+                    start: start_span.start,
+                    end: body.span().end,
+                };
+                Some(Expression::Lambda(Box::new(LambdaExpression {
+                    parameters: vec![parameter],
+                    body,
+                    span,
+                })))
+            }
+            Token::LParen(_) => {
+                self.next_token(); // consume '('
+                let expr = self.parse_expression(Precedence::Lowest)?;
+                self.expect_closing(|token| matches!(token, Token::RParen(_)), "')'")?;
                 Some(expr)
             }
-            Token::LBrace(_) => self.parse_map_literal(),
-            _ => None,
+            _ => self.parse_simple_expression(),
         }
     }
 
-    /// Get precedence of the peek token
-    #[allow(dead_code)]
-    fn peek_precedence(&self) -> Precedence {
-        match &self.peek_token {
+    /// Get the precedence of the current token when it is used as an infix operator.
+    fn current_precedence(&self) -> Precedence {
+        match &self.current_token {
+            Token::Or(_) => Precedence::Or,
+            Token::And(_) => Precedence::And,
+            Token::Equals(_) | Token::NotEquals(_) => Precedence::Equals,
+            Token::LessThan(_) | Token::GreaterThan(_) => Precedence::LessGreater,
+            Token::Elvis(_) => Precedence::Elvis,
             Token::Plus(_) | Token::Minus(_) => Precedence::Sum,
-            Token::Asterisk(_) | Token::Slash(_) => Precedence::Product,
-            Token::Equals(_) => Precedence::Equals,
+            Token::Asterisk(_) | Token::Slash(_) | Token::Percent(_) => Precedence::Product,
+            Token::LParen(_) => Precedence::Call,
+            Token::Dot(_) | Token::QuestionDot(_) => Precedence::Member,
             _ => Precedence::Lowest,
         }
     }
 
-    /// Minimal implementation of parse_infix (stub for now)
-    #[allow(dead_code)]
-    fn parse_infix(&mut self, _left: Expression) -> Option<Expression> {
-        // For now, just return the left expression (no infix operations)
-        None
+    /// Parses the operator at the current token and its right-hand side,
+    /// combining it with the already-parsed `left` expression.
+    fn parse_infix(&mut self, left: Expression) -> Option<Expression> {
+        let start = left.span().start;
+        let (operator, precedence) = match &self.current_token {
+            Token::Plus(_) => (InfixOperator::Plus, Precedence::Sum),
+            Token::Minus(_) => (InfixOperator::Minus, Precedence::Sum),
+            Token::Asterisk(_) => (InfixOperator::Multiply, Precedence::Product),
+            Token::Slash(_) => (InfixOperator::Divide, Precedence::Product),
+            Token::Percent(_) => (InfixOperator::Modulo, Precedence::Product),
+            Token::Equals(_) => (InfixOperator::Equal, Precedence::Equals),
+            Token::NotEquals(_) => (InfixOperator::NotEqual, Precedence::Equals),
+            Token::LessThan(_) => (InfixOperator::LessThan, Precedence::LessGreater),
+            Token::GreaterThan(_) => (InfixOperator::GreaterThan, Precedence::LessGreater),
+            Token::Elvis(_) => (InfixOperator::Elvis, Precedence::Elvis),
+            Token::And(_) => (InfixOperator::And, Precedence::And),
+            Token::Or(_) => (InfixOperator::Or, Precedence::Or),
+            Token::LParen(_) => return self.parse_call_expression(left),
+            Token::Dot(_) | Token::QuestionDot(_) => return self.parse_member_access(left),
+            _ => return Some(left),
+        };
+        self.next_token(); // consume the operator
+        let right = self.parse_expression(precedence)?;
+        let span = Span {
+            start,
+            end: right.span().end,
+        };
+        Some(Expression::Infix(Box::new(InfixExpression {
+            left,
+            operator,
+            right,
+            span,
+        })))
+    }
+
+    /// Parses a member access, e.g., `user.name`, or a safe-navigation
+    /// access, e.g., `user?.name`.
+    fn parse_member_access(&mut self, object: Expression) -> Option<Expression> {
+        let is_safe = matches!(self.current_token, Token::QuestionDot(_));
+        self.next_token(); // consume '.' or '?.'
+
+        let Token::Identifier(property, property_span) = &self.current_token else {
+            self.errors.push(ParseError {
+                message: "Expected a property name after '.'".to_string(),
+                kind: ParseErrorKind::ExpectedName,
+                span: self.current_token.span(),
+            });
+            return None;
+        };
+        let member_access = Box::new(MemberAccessExpression {
+            span: Span {
+                start: object.span().start,
+                end: property_span.end,
+            },
+            object,
+            property: property.clone(),
+        });
+        self.next_token();
+
+        Some(if is_safe {
+            Expression::SafeMemberAccess(member_access)
+        } else {
+            Expression::MemberAccess(member_access)
+        })
+    }
+
+    /// Parses the arguments of a call expression, e.g., `my_func(a, b)`.
+    fn parse_call_expression(&mut self, function: Expression) -> Option<Expression> {
+        let start = function.span().start;
+        self.next_token(); // consume '('
+
+        let mut arguments = Vec::new();
+        while !matches!(self.current_token, Token::RParen(_) | Token::Eof(_)) {
+            arguments.push(self.parse_expression(Precedence::Lowest)?);
+            if matches!(self.current_token, Token::Comma(_)) {
+                self.next_token();
+            }
+        }
+        let end = self.current_token.span().end;
+        self.expect_closing(|token| matches!(token, Token::RParen(_)), "')'")?;
+
+        Some(Expression::Call(Box::new(CallExpression {
+            function,
+            arguments,
+            span: Span { start, end },
+        })))
+    }
+
+    /// Consumes the closing delimiter of a construct, or records an error
+    /// naming the delimiter that was expected.
+    fn expect_closing(&mut self, is_closing: fn(&Token) -> bool, expected: &str) -> Option<()> {
+        if !is_closing(&self.current_token) {
+            self.errors.push(ParseError {
+                message: format!("Expected {}", expected),
+                kind: ParseErrorKind::UnclosedDelimiter,
+                span: self.current_token.span(),
+            });
+            return None;
+        }
+        self.next_token();
+        Some(())
     }
 }
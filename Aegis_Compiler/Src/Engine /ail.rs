@@ -1,42 +1,126 @@
+use crate::token::Span;
+
 /// Aegis Intermediate Language Instructions.
+///
+/// AIL runs on a stack: instructions pop their operands and push their
+/// result. Operands are pushed left to right, so for `Subtract` the top of
+/// the stack is the right operand.
 #[derive(Debug, Clone, PartialEq)]
 pub enum Instruction {
     // --- Stack Manipulation ---
     PushI64(i64),
+    PushF64(f64),
     PushBool(bool),
     PushString(String),
+    PushNothing,
+    Pop, // Discard the top of the stack
+    Dup, // Push a copy of the top of the stack
 
     // --- Variable Handling ---
-    Store(String), // Store top of stack into a local variable
+    Store(String), // Store top of stack into a local variable, or the global of that name
     Load(String),  // Load a local variable onto the stack
+    /// Stores the top of the stack into a new local variable of the current
+    /// function, e.g., a parameter, even if a global has the same name.
+    StoreLocal(String),
 
     // --- Arithmetic & Logic---
     Add,
     Subtract,
     Multiply,
     Divide,
+    Modulo,
+    Negate,
     GreaterThan,
+    LessThan,
     Equals,
+    NotEqual,
+    Not,
 
     // --- Control Flow ---
     Label(String),       // Marks a destination for jumps, e.g., L0
     Jump(String),        // Unconditional jump to a label
-    JumpIfFalse(String), // Jump to a label if the top of the stack is 0 (false)
-    
+    JumpIfFalse(String), // Pop the top of the stack and jump to a label if it is false
+    JumpIfTrue(String),  // Pop the top of the stack and jump to a label if it is true
+    /// Pops an enum value and jumps to the label of its variant, or to
+    /// `default` if no case names it. Each case is a `(variant, label)` pair.
+    JumpTable {
+        cases: Vec<(String, String)>,
+        default: String,
+    },
+
     // --- Function Calls ---
-    Call(String), // Call a function by name
+    Call(String, usize),       // Call a function by name with that many arguments
+    CallMethod(String, usize), // Call a method of the value below the arguments
+    CallValue(usize),          // Call the closure below the arguments
+    /// Calls a method of the Optional below the arguments, e.g., `orElse`,
+    /// rather than one of the value it holds.
+    CallOptionalMethod(String, usize),
+    /// Pushes a closure over the sequence `function`, which receives the
+    /// current values of the locals `captures` before its arguments.
+    MakeClosure {
+        function: String,
+        captures: Vec<String>,
+    },
     Return,
+
+    // --- Collections ---
+    MakeList(usize), // Pop that many elements into a list
+    MakeMap(usize),  // Pop that many key-value pairs, each key below its value, into a map
+    Length,          // Pop a list or a string and push its length
+    Index,           // Pop an index and a list and push the element at the index
+
+    // --- Contracts & Enums ---
+    /// Pops one value per field, the last field on top, into an instance of
+    /// the contract `name`.
+    MakeContract {
+        name: String,
+        fields: Vec<String>,
+    },
+    GetField(String), // Pop an instance and push the value of its field
+    SetField(String), // Pop a value and an instance and set the instance's field
+    /// Pops `arity` payload values into a value of the variant `variant`.
+    MakeEnum {
+        enum_name: String,
+        variant: String,
+        arity: usize,
+    },
+    /// Pops an enum value and pushes whether it is the variant `variant`.
+    IsVariant {
+        enum_name: String,
+        variant: String,
+    },
+    GetPayload(usize), // Pop an enum value and push its payload value at that position
 }
 
 /// A sequence of instructions representing a function or block.
+///
+/// The sequences of a module are listed together, its top-level statements
+/// first.
 #[derive(Debug, Clone, Default)]
 pub struct InstructionSequence {
     pub name: String,
+    /// The name of the module the sequence was lowered from, e.g.,
+    /// `models.task`, or `main` for the entry module.
+    pub module: String,
+    /// The names of the sequence's parameters. Its prologue stores the
+    /// arguments into them, the last argument first.
+    pub params: Vec<String>,
     pub instructions: Vec<Instruction>,
+    /// The span of source code each instruction was lowered from, to point
+    /// runtime errors at it.
+    pub spans: Vec<Span>,
 }
 
 impl InstructionSequence {
     pub fn new(name: &str) -> Self {
-        Self { name: name.to_string(), instructions: Vec::new() }
+        Self {
+            name: name.to_string(),
+            ..Self::default()
+        }
+    }
+
+    /// Records `span` as the source of every instruction that has none yet.
+    pub fn cover(&mut self, span: Span) {
+        self.spans.resize(self.instructions.len(), span);
     }
 }
@@ -3,53 +3,99 @@
 //! native Android project, leveraging modern practices like Kotlin Coroutines
 //! and RecyclerView.
 
-use crate::architect::ast::Program;
+use crate::ast::{
+    AppDefinition, CallExpression, ContractDefinition, Definition, EnumDefinition, Expression,
+    FunctionDefinition, InfixOperator, InterfaceDefinition, Literal, PrefixOperator, Statement,
+    TypeIdentifier, UiElement, UiNode, UiProperty,
+};
+use crate::engine::Artifact;
+use crate::error::CodeGenError;
 use crate::guardian::Guardian;
-use std::collections::HashMap;
-use std::path::Path;
+use crate::guardian_types::Type;
+use crate::project::{Module, Project};
+use crate::token::Span;
+use std::path::{Path, PathBuf};
+
+/// The package of every generated Kotlin file.
+pub const PACKAGE: &str = "com.aegisapp";
+
+/// The directory of the generated Kotlin files.
+const SOURCE_DIR: &str = "app/src/main/java/com/aegisapp";
+
+/// The imports of every generated Kotlin file.
+const IMPORTS: &str = "import kotlinx.coroutines.*\n";
+
+/// The imports the main activity needs on top of those.
+const ACTIVITY_IMPORTS: &str = "import android.os.Bundle\n\
+    import android.view.View\n\
+    import android.widget.*\n\
+    import androidx.appcompat.app.AppCompatActivity\n\
+    import androidx.core.widget.doAfterTextChanged\n\
+    import androidx.lifecycle.lifecycleScope\n";
 
 /// The AndroidCodeGen is the final stage of the compiler for the Android target.
+#[derive(Default)]
 pub struct AndroidCodeGen {}
 
 impl AndroidCodeGen {
-    pub fn new() -> Self { Self {} }
+    pub fn new() -> Self {
+        Self {}
+    }
 
-    /// The main entry point to generate a full Android project.
-    /// This function orchestrates the creation of all necessary directories and files.
-    pub fn generate_project(
+    /// Generates all necessary project files for a checked project: a Kotlin
+    /// file for each module, with the main Activity in the entry module's,
+    /// and the layout of the entry module's app. Fails with the errors of
+    /// every module that uses something the target can't translate yet, each
+    /// with the path of its module.
+    pub fn generate_project_files(
         &self,
-        program: &Program,
-        guardian: &Guardian,
-        output_dir: &Path,
-    ) {
-        // This is a high-level orchestration method.
-        // It would create the necessary directory structure for an Android project
-        // (e.g., `app/src/main/java`, `app/src/main/res/layout`, etc.).
-        let project_files = self.generate_project_files(program, guardian);
+        project: &Project,
+    ) -> Result<Vec<Artifact>, Vec<(PathBuf, CodeGenError)>> {
+        let mut files = Vec::new();
+        let mut errors = Vec::new();
+        let Some(entry) = project.entry() else {
+            return Ok(files);
+        };
 
-        // Write each generated file to the appropriate location in the output directory.
-        for (file_path, content) in project_files {
-            let final_path = output_dir.join(file_path);
-            // In a real implementation, this would use `std::fs::write`.
-            println!("Writing file to: {:?}", final_path);
+        // Top-level statements other than declarations run when the app
+        // starts, in the order the modules are loaded.
+        let mut startup = Vec::new();
+        let mut entry_declarations = None;
+        for module in &project.modules {
+            let result = self
+                .startup_statements_kt(module)
+                .map(|lines| startup.extend(lines))
+                .and_then(|()| self.generate_module_kt(module));
+            match result {
+                Ok(declarations) if module.name == entry.name => {
+                    entry_declarations = Some(declarations)
+                }
+                Ok(declarations) => files.push(Artifact {
+                    path: Path::new(SOURCE_DIR).join(module.path.with_extension("kt")),
+                    contents: declarations,
+                }),
+                Err(error) => errors.push((module.path.clone(), error)),
+            }
         }
-    }
+        let Some(declarations) = entry_declarations else {
+            return Err(errors);
+        };
 
-    /// Generates all necessary project files as a map of file path to file content.
-    fn generate_project_files(
-        &self,
-        program: &Program,
-        guardian: &Guardian
-    ) -> HashMap<String, String> {
-        let mut files = HashMap::new();
-
-        // Generate the main Activity, which contains state, event handlers, and reactive logic.
-        let main_activity_content = self.generate_activity_kt(program, guardian);
-        files.insert("app/src/main/java/com/aegisapp/MainActivity.kt".to_string(), main_activity_content);
-        
-        // Generate the main XML layout for the static UI structure.
-        let main_layout_content = self.generate_layout_xml(program);
-        files.insert("app/src/main/res/layout/activity_main.xml".to_string(), main_layout_content);
+        // Generate the main Activity, which contains state, event handlers, and reactive logic,
+        // and the main XML layout for the static UI structure.
+        match self.generate_activity_kt(entry, declarations, &startup) {
+            Ok((activity, layout)) => {
+                files.push(Artifact {
+                    path: Path::new(SOURCE_DIR).join("MainActivity.kt"),
+                    contents: activity,
+                });
+                files.push(Artifact {
+                    path: PathBuf::from("app/src/main/res/layout/activity_main.xml"),
+                    contents: layout,
+                });
+            }
+            Err(error) => errors.push((entry.path.clone(), error)),
+        }
 
         // In a complete implementation, this would also generate:
         // - `styles.xml` from static `style` blocks.
@@ -57,22 +103,916 @@ impl AndroidCodeGen {
         // - `...ListAdapter.kt` files for each `RecyclerView`.
         // - `build.gradle` files, `AndroidManifest.xml`, etc.
 
-        files
-    }
-    
-    // This file would continue with all the helper methods for generating specific
-    // Kotlin and XML code. Each of these is a complex function that traverses
-    // parts of the AST and Guardian's metadata. For example:
-    //
-    // /// Generates the content for MainActivity.kt
-    // fn generate_activity_kt(&self, program: &Program, guardian: &Guardian) -> String { ... }
-    //
-    // /// Generates the content for activity_main.xml
-    // fn generate_layout_xml(&self, program: &Program) -> String { ... }
-    //
-    // /// Generates a full RecyclerView.Adapter class for a given `for` loop.
-    // fn generate_recycler_view_adapter(&self, for_loop_node: &ForStatement) -> String { ... }
-    //
-    // /// Generates Kotlin `suspend fun` for an Aegis `async let's` function.
-    // fn generate_function_kt(&self, func_def: &FunctionDefinition) -> String { ... }
+        if errors.is_empty() {
+            Ok(files)
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Generates the Kotlin file of a module: its contracts, enums,
+    /// interfaces and functions, and its top-level `let's` as properties.
+    fn generate_module_kt(&self, module: &Module) -> Result<String, CodeGenError> {
+        let guardian = &module.guardian;
+        let mut file = format!("package {}\n\n{}", PACKAGE, IMPORTS);
+        for def in &module.program.definitions {
+            let declaration = match def {
+                Definition::Contract(contract_def) => {
+                    self.generate_contract_kt(guardian, contract_def)?
+                }
+                Definition::Enum(enum_def) => self.generate_enum_kt(enum_def),
+                Definition::Interface(interface_def) => self.generate_interface_kt(interface_def),
+                Definition::Function(func_def) => self.generate_function_kt(guardian, func_def)?,
+                Definition::Statement(stmt @ Statement::Let(_)) => {
+                    self.kotlin_statement(guardian, stmt)?
+                }
+                // Other statements run at startup, the app becomes the main
+                // Activity, and styles aren't generated yet.
+                Definition::Statement(_) | Definition::App(_) | Definition::Style(_) => continue,
+            };
+            file.push('\n');
+            file.push_str(&declaration);
+            file.push('\n');
+        }
+        Ok(file)
+    }
+
+    /// Translates the top-level statements of a module that aren't
+    /// declarations into the lines of Kotlin that run them.
+    fn startup_statements_kt(&self, module: &Module) -> Result<Vec<String>, CodeGenError> {
+        let guardian = &module.guardian;
+        module
+            .program
+            .definitions
+            .iter()
+            .filter_map(|def| match def {
+                Definition::Statement(Statement::Let(_)) => None,
+                Definition::Statement(stmt) => Some(self.kotlin_statement(guardian, stmt)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Generates the content for MainActivity.kt, which follows the entry
+    /// module's `declarations`, along with the content for activity_main.xml.
+    /// The Activity holds the app's state, runs the project's startup
+    /// statements, wires up event handlers, and shows the state in the views
+    /// bound to it with `render()`, which runs again after every event.
+    fn generate_activity_kt(
+        &self,
+        entry: &Module,
+        declarations: String,
+        startup: &[String],
+    ) -> Result<(String, String), CodeGenError> {
+        let guardian = &entry.guardian;
+        let app = entry.program.definitions.iter().find_map(|def| match def {
+            Definition::App(app) => Some(app),
+            _ => None,
+        });
+        let mut layout = Layout::default();
+        if let Some(root) = app.and_then(|app| app.body.show_block.as_ref()) {
+            let UiNode::Element(element) = &root.root_node;
+            self.generate_layout_xml(guardian, element, 0, &mut layout)?;
+        } else {
+            layout.xml.push_str(
+                "<LinearLayout\n    android:layout_width=\"match_parent\"\n    android:layout_height=\"match_parent\"\n    android:orientation=\"vertical\" />\n",
+            );
+        }
+        let xml = format!(
+            "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n{}",
+            layout.xml.replacen(
+                "\n",
+                "\n    xmlns:android=\"http://schemas.android.com/apk/res/android\"\n",
+                1
+            )
+        );
+
+        let mut activity = declarations;
+        activity.insert_str(activity.find(IMPORTS).unwrap_or_default(), ACTIVITY_IMPORTS);
+        activity.push_str("\nclass MainActivity : AppCompatActivity() {\n");
+        for stmt in app.map_or(&[][..], |app: &AppDefinition| &app.body.statements) {
+            if let Statement::Let(_) = stmt {
+                activity.push_str(&format!(
+                    "    private {}\n",
+                    self.kotlin_statement(guardian, stmt)?
+                ));
+            }
+        }
+        activity.push_str(
+            "\n    override fun onCreate(savedInstanceState: Bundle?) {\n        super.onCreate(savedInstanceState)\n        setContentView(R.layout.activity_main)\n",
+        );
+        for line in startup {
+            activity.push_str(&format!("        {}\n", line));
+        }
+        for stmt in app.map_or(&[][..], |app: &AppDefinition| &app.body.statements) {
+            if !matches!(stmt, Statement::Let(_)) {
+                activity.push_str(&format!(
+                    "        {}\n",
+                    self.kotlin_statement(guardian, stmt)?
+                ));
+            }
+        }
+        for handler in &layout.handlers {
+            activity.push_str(&indent(handler, "        "));
+        }
+        activity.push_str("        render()\n    }\n\n");
+        activity.push_str("    /** Shows the current state in the views bound to it. */\n");
+        activity.push_str("    private fun render() {\n");
+        for binding in &layout.bindings {
+            activity.push_str(&format!("        {}\n", binding));
+        }
+        activity.push_str("    }\n}\n");
+        Ok((activity, xml))
+    }
+
+    /// Generates the XML of a UI element and its children at the indentation
+    /// `depth`. Properties with literal values become XML attributes; the
+    /// others are bound to the element in `render()`.
+    fn generate_layout_xml(
+        &self,
+        guardian: &Guardian,
+        element: &UiElement,
+        depth: usize,
+        layout: &mut Layout,
+    ) -> Result<(), CodeGenError> {
+        let id = format!("view_{}", layout.views);
+        layout.views += 1;
+        let view = format!("findViewById<{}>(R.id.{})", android_view(&element.name), id);
+        let size = if depth == 0 {
+            "match_parent"
+        } else {
+            "wrap_content"
+        };
+        let mut attributes = vec![
+            format!("android:id=\"@+id/{}\"", id),
+            format!("android:layout_width=\"{}\"", size),
+            format!("android:layout_height=\"{}\"", size),
+        ];
+        match element.name.as_str() {
+            "column" => attributes.push("android:orientation=\"vertical\"".to_string()),
+            "row" => attributes.push("android:orientation=\"horizontal\"".to_string()),
+            "list" => return Err(unsupported("`list`", element.span)),
+            _ => {}
+        }
+
+        let mut positional = 0;
+        for property in &element.properties {
+            let (name, value) = match property {
+                UiProperty::Positional(value) => {
+                    positional += 1;
+                    (positional_name(&element.name, positional - 1), value)
+                }
+                UiProperty::Named(name, value) => (name.as_str(), value),
+                UiProperty::EventBinding(event, handler) => {
+                    let mut body = String::new();
+                    for stmt in &handler.statements {
+                        body.push_str(&format!(
+                            "        {}\n",
+                            self.kotlin_statement(guardian, stmt)?
+                        ));
+                    }
+                    // Handlers run in a coroutine, so they can `await`.
+                    let listener = match event.as_str() {
+                        "on_change" => "doAfterTextChanged {",
+                        "on_submit" => "setOnEditorActionListener { _, _, _ ->",
+                        _ => "setOnClickListener {",
+                    };
+                    let result = if event == "on_submit" {
+                        "\n    true"
+                    } else {
+                        ""
+                    };
+                    layout.handlers.push(format!(
+                        "{}.{}\n    lifecycleScope.launch {{\n{}        render()\n    }}{}\n}}\n",
+                        view, listener, body, result
+                    ));
+                    continue;
+                }
+            };
+            match (name, value) {
+                // Named styles and `style { ... }` blocks aren't generated yet.
+                ("style", _) => {}
+                ("source", Expression::Literal(Literal::String(source), _)) => {
+                    let resource = source.rsplit('/').next().unwrap_or(source);
+                    let resource = resource.split('.').next().unwrap_or(resource);
+                    attributes.push(format!(
+                        "android:src=\"@drawable/{}\"",
+                        xml_escape(resource)
+                    ));
+                }
+                ("source", value) => {
+                    return Err(CodeGenError::new(
+                        "Images on Android must name their source with a string literal"
+                            .to_string(),
+                        Some(value.span()),
+                    ))
+                }
+                ("size", value) => {
+                    let size = self.literal_text(value).ok_or_else(|| {
+                        CodeGenError::new(
+                            "The size of a spacer on Android must be a number literal".to_string(),
+                            Some(value.span()),
+                        )
+                    })?;
+                    attributes[1] = format!("android:layout_width=\"{}dp\"", size);
+                    attributes[2] = format!("android:layout_height=\"{}dp\"", size);
+                }
+                (name, value) => {
+                    let attribute = match name {
+                        "placeholder" => "hint",
+                        "description" => "contentDescription",
+                        "enabled" => "enabled",
+                        _ => "text",
+                    };
+                    match self.literal_text(value) {
+                        Some(text) => attributes.push(format!(
+                            "android:{}=\"{}\"",
+                            attribute,
+                            xml_escape(&text)
+                        )),
+                        None => {
+                            let value = self.kotlin_expression(guardian, value)?;
+                            layout.bindings.push(match attribute {
+                                "enabled" => format!("{}.isEnabled = {}", view, value),
+                                "text" if element.name == "input" => format!(
+                                    "{}.let {{ if (it.text.toString() != {1}) it.setText({1}) }}",
+                                    view, value
+                                ),
+                                _ => format!("{}.{} = ({}).toString()", view, attribute, value),
+                            });
+                        }
+                    }
+                }
+            }
+        }
+
+        let indentation = "    ".repeat(depth);
+        layout
+            .xml
+            .push_str(&format!("{}<{}", indentation, android_view(&element.name)));
+        for attribute in &attributes {
+            layout
+                .xml
+                .push_str(&format!("\n{}    {}", indentation, attribute));
+        }
+        if element.children.is_empty() {
+            layout.xml.push_str(" />\n");
+            return Ok(());
+        }
+        layout.xml.push_str(">\n");
+        for child in &element.children {
+            let UiNode::Element(child) = child;
+            self.generate_layout_xml(guardian, child, depth + 1, layout)?;
+        }
+        layout.xml.push_str(&format!(
+            "{}</{}>\n",
+            indentation,
+            android_view(&element.name)
+        ));
+        Ok(())
+    }
+
+    /// The text of a literal string, number or boolean, as it is shown.
+    fn literal_text(&self, expr: &Expression) -> Option<String> {
+        match expr {
+            Expression::Literal(Literal::String(text) | Literal::Number(text), _) => {
+                Some(text.clone())
+            }
+            Expression::Literal(Literal::Boolean(value), _) => Some(value.to_string()),
+            _ => None,
+        }
+    }
+
+    /// Generates a Kotlin function, signature and body, for an Aegis `let's` function.
+    fn generate_function_kt(
+        &self,
+        guardian: &Guardian,
+        func_def: &FunctionDefinition,
+    ) -> Result<String, CodeGenError> {
+        let mut function = format!("{} {{\n", self.generate_function_signature_kt(func_def));
+        for stmt in &func_def.body.statements {
+            function.push_str(&format!("    {}\n", self.kotlin_statement(guardian, stmt)?));
+        }
+        function.push('}');
+        Ok(function)
+    }
+
+    /// Generates the Kotlin signature of an Aegis function. Type parameters
+    /// become Kotlin generics and `async` functions become `suspend fun`, e.g.,
+    /// `let's first<T>(items: List<T>) -> T?:` becomes
+    /// `fun <T> first(items: MutableList<T>): T?`.
+    fn generate_function_signature_kt(&self, func_def: &FunctionDefinition) -> String {
+        let mut signature = String::new();
+        if func_def.is_async {
+            signature.push_str("suspend ");
+        }
+        signature.push_str("fun ");
+        if !func_def.generic_params.is_empty() {
+            let params: Vec<String> = func_def
+                .generic_params
+                .iter()
+                .map(|param| match &param.bound {
+                    Some(bound) => format!("{} : {}", param.name, bound),
+                    None => param.name.clone(),
+                })
+                .collect();
+            signature.push_str(&format!("<{}> ", params.join(", ")));
+        }
+        let params: Vec<String> = func_def
+            .parameters
+            .iter()
+            .map(|param| {
+                format!(
+                    "{}: {}",
+                    param.name,
+                    self.kotlin_type(&param.type_annotation)
+                )
+            })
+            .collect();
+        signature.push_str(&format!("{}({})", func_def.name, params.join(", ")));
+        if let Some(return_type) = &func_def.return_type {
+            signature.push_str(&format!(": {}", self.kotlin_type(return_type)));
+        }
+        signature
+    }
+
+    /// Generates a Kotlin data class for an Aegis contract. Fields become
+    /// constructor properties, keeping their default values, computed
+    /// properties become `val`s with a getter, and methods become member
+    /// functions, e.g.
+    ///
+    /// ```text
+    /// data class Employee(val name: String, val salary: Double = 0.0) : Displayable {
+    ///     val bonus: Double
+    ///         get() = this.salary * 0.1
+    /// }
+    /// ```
+    fn generate_contract_kt(
+        &self,
+        guardian: &Guardian,
+        contract_def: &ContractDefinition,
+    ) -> Result<String, CodeGenError> {
+        let mut class = String::new();
+        // A Kotlin data class needs at least one constructor property.
+        class.push_str(if contract_def.fields.is_empty() {
+            "class "
+        } else {
+            "data class "
+        });
+        class.push_str(&contract_def.name);
+        if !contract_def.generic_params.is_empty() {
+            class.push_str(&format!("<{}>", contract_def.generic_params.join(", ")));
+        }
+        let mut fields = Vec::new();
+        for field in &contract_def.fields {
+            let mut property = format!("val {}: {}", field.name, self.kotlin_type(&field.type_ann));
+            match &field.default_value {
+                Some(default_value) => property.push_str(&format!(
+                    " = {}",
+                    self.kotlin_expression(guardian, default_value)?
+                )),
+                // Optional fields that literals leave out are `nothing`.
+                None if self.kotlin_type(&field.type_ann).ends_with('?') => {
+                    property.push_str(" = null")
+                }
+                None => {}
+            }
+            fields.push(property);
+        }
+        class.push_str(&format!("({})", fields.join(", ")));
+        if !contract_def.interfaces.is_empty() {
+            class.push_str(&format!(" : {}", contract_def.interfaces.join(", ")));
+        }
+        if contract_def.properties.is_empty() && contract_def.methods.is_empty() {
+            return Ok(class);
+        }
+
+        class.push_str(" {\n");
+        for property in &contract_def.properties {
+            class.push_str(&format!(
+                "    val {}: {}\n        get() = {}\n",
+                property.name,
+                self.kotlin_type(&property.type_ann),
+                self.kotlin_expression(guardian, &property.value)?
+            ));
+        }
+        for method in &contract_def.methods {
+            // Methods that fulfil an interface override its abstract members.
+            let modifier = if contract_def.interfaces.is_empty() {
+                ""
+            } else {
+                "override "
+            };
+            class.push_str(&format!(
+                "\n    {}{} {{\n",
+                modifier,
+                self.generate_function_signature_kt(method)
+            ));
+            for stmt in &method.body.statements {
+                class.push_str(&format!(
+                    "        {}\n",
+                    self.kotlin_statement(guardian, stmt)?
+                ));
+            }
+            class.push_str("    }\n");
+        }
+        class.push('}');
+        Ok(class)
+    }
+
+    /// Generates a Kotlin enum class for an Aegis enum whose variants carry
+    /// no values, or a sealed class otherwise, e.g.
+    ///
+    /// ```text
+    /// sealed class LoadState {
+    ///     object Loading : LoadState()
+    ///     data class Success(val value0: String) : LoadState()
+    /// }
+    /// ```
+    fn generate_enum_kt(&self, enum_def: &EnumDefinition) -> String {
+        if enum_def
+            .variants
+            .iter()
+            .all(|variant| variant.types.is_empty())
+        {
+            let variants: Vec<&str> = enum_def
+                .variants
+                .iter()
+                .map(|variant| variant.name.as_str())
+                .collect();
+            return format!("enum class {} {{ {} }}", enum_def.name, variants.join(", "));
+        }
+        let mut class = format!("sealed class {} {{\n", enum_def.name);
+        for variant in &enum_def.variants {
+            if variant.types.is_empty() {
+                class.push_str(&format!(
+                    "    object {} : {}()\n",
+                    variant.name, enum_def.name
+                ));
+            } else {
+                let values: Vec<String> = variant
+                    .types
+                    .iter()
+                    .enumerate()
+                    .map(|(index, ty)| format!("val value{}: {}", index, self.kotlin_type(ty)))
+                    .collect();
+                class.push_str(&format!(
+                    "    data class {}({}) : {}()\n",
+                    variant.name,
+                    values.join(", "),
+                    enum_def.name
+                ));
+            }
+        }
+        class.push('}');
+        class
+    }
+
+    /// Generates a Kotlin interface for an Aegis interface.
+    fn generate_interface_kt(&self, interface_def: &InterfaceDefinition) -> String {
+        let mut interface = format!("interface {} {{\n", interface_def.name);
+        for method in &interface_def.methods {
+            let params: Vec<String> = method
+                .parameters
+                .iter()
+                .map(|param| {
+                    format!(
+                        "{}: {}",
+                        param.name,
+                        self.kotlin_type(&param.type_annotation)
+                    )
+                })
+                .collect();
+            interface.push_str(&format!("    fun {}({})", method.name, params.join(", ")));
+            if let Some(return_type) = &method.return_type {
+                interface.push_str(&format!(": {}", self.kotlin_type(return_type)));
+            }
+            interface.push('\n');
+        }
+        interface.push('}');
+        interface
+    }
+
+    /// Translates a statement of a function body into a line of Kotlin.
+    fn kotlin_statement(
+        &self,
+        guardian: &Guardian,
+        stmt: &Statement,
+    ) -> Result<String, CodeGenError> {
+        Ok(match stmt {
+            Statement::Let(let_stmt) => {
+                let keyword = if let_stmt.is_tracked { "var" } else { "val" };
+                let annotation = let_stmt
+                    .type_annotation
+                    .as_ref()
+                    .map(|type_ann| format!(": {}", self.kotlin_type(type_ann)))
+                    .unwrap_or_default();
+                format!(
+                    "{} {}{} = {}",
+                    keyword,
+                    let_stmt.name,
+                    annotation,
+                    self.kotlin_expression(guardian, &let_stmt.value)?
+                )
+            }
+            Statement::Return(return_stmt) => {
+                format!(
+                    "return {}",
+                    self.kotlin_expression(guardian, &return_stmt.value)?
+                )
+            }
+            Statement::Expression(expr_stmt) => {
+                self.kotlin_expression(guardian, &expr_stmt.expression)?
+            }
+            Statement::Assign(assign_stmt) => format!(
+                "{} = {}",
+                self.kotlin_expression(guardian, &assign_stmt.target)?,
+                self.kotlin_expression(guardian, &assign_stmt.value)?
+            ),
+            Statement::For(_) | Statement::Block(_) => {
+                return Err(unsupported("loops and blocks in functions", stmt.span()))
+            }
+        })
+    }
+
+    /// Translates an Aegis expression into Kotlin. `self` becomes `this`,
+    /// numbers are `Double`s, and map literals the Guardian checked as
+    /// contracts call the contract's constructor.
+    fn kotlin_expression(
+        &self,
+        guardian: &Guardian,
+        expr: &Expression,
+    ) -> Result<String, CodeGenError> {
+        Ok(match expr {
+            Expression::Identifier(name, _) if name == "self" => "this".to_string(),
+            Expression::Identifier(name, _) => name.clone(),
+            Expression::Literal(literal, _) => match literal {
+                Literal::Number(number) if number.contains('.') => number.clone(),
+                Literal::Number(number) => format!("{}.0", number),
+                Literal::String(text) => format!(
+                    "\"{}\"",
+                    text.replace('\\', "\\\\")
+                        .replace('"', "\\\"")
+                        .replace('$', "\\$")
+                ),
+                Literal::Boolean(value) => value.to_string(),
+                Literal::Nothing => "null".to_string(),
+                Literal::List(list) => {
+                    let elements = self.kotlin_expressions(guardian, &list.elements)?;
+                    format!("mutableListOf({})", elements.join(", "))
+                }
+                Literal::Map(map) => {
+                    let contract = guardian.contract_literals.get(&expr.span());
+                    let mut pairs = Vec::new();
+                    for (key, value) in &map.pairs {
+                        let value = self.kotlin_expression(guardian, value)?;
+                        pairs.push(match (contract, key) {
+                            (
+                                Some(_),
+                                Expression::Identifier(field, _)
+                                | Expression::Literal(Literal::String(field), _),
+                            ) => format!("{} = {}", field, value),
+                            // A bare key is the string of its name.
+                            (_, Expression::Identifier(key, span)) => format!(
+                                "{} to {}",
+                                self.kotlin_expression(
+                                    guardian,
+                                    &Expression::Literal(Literal::String(key.clone()), *span)
+                                )?,
+                                value
+                            ),
+                            (_, key) => {
+                                format!("{} to {}", self.kotlin_expression(guardian, key)?, value)
+                            }
+                        });
+                    }
+                    match contract {
+                        Some(contract) => format!("{}({})", contract, pairs.join(", ")),
+                        None => format!("mutableMapOf({})", pairs.join(", ")),
+                    }
+                }
+            },
+            Expression::Prefix(prefix) => {
+                let operator = match prefix.operator {
+                    PrefixOperator::Not => "!",
+                    PrefixOperator::Minus => "-",
+                };
+                format!(
+                    "{}{}",
+                    operator,
+                    self.kotlin_expression(guardian, &prefix.right)?
+                )
+            }
+            Expression::Infix(infix) => {
+                let operator = match infix.operator {
+                    InfixOperator::Plus => "+",
+                    InfixOperator::Minus => "-",
+                    InfixOperator::Multiply => "*",
+                    InfixOperator::Divide => "/",
+                    InfixOperator::Modulo => "%",
+                    InfixOperator::Equal => "==",
+                    InfixOperator::NotEqual => "!=",
+                    InfixOperator::LessThan => "<",
+                    InfixOperator::GreaterThan => ">",
+                    InfixOperator::Elvis => "?:",
+                    InfixOperator::And => "&&",
+                    InfixOperator::Or => "||",
+                };
+                format!(
+                    "({} {} {})",
+                    self.kotlin_expression(guardian, &infix.left)?,
+                    operator,
+                    self.kotlin_expression(guardian, &infix.right)?
+                )
+            }
+            Expression::If(if_expr) => format!(
+                "if ({}) {} else {}",
+                self.kotlin_expression(guardian, &if_expr.condition)?,
+                self.kotlin_expression(guardian, &if_expr.then_branch)?,
+                match &if_expr.else_branch {
+                    Some(else_branch) => self.kotlin_expression(guardian, else_branch)?,
+                    None => "Unit".to_string(),
+                }
+            ),
+            Expression::Call(call) => self.kotlin_call(guardian, call)?,
+            Expression::MemberAccess(access) => {
+                format!(
+                    "{}.{}",
+                    self.kotlin_expression(guardian, &access.object)?,
+                    access.property
+                )
+            }
+            Expression::SafeMemberAccess(access) => {
+                format!(
+                    "{}?.{}",
+                    self.kotlin_expression(guardian, &access.object)?,
+                    access.property
+                )
+            }
+            Expression::Await(await_expr) => match &await_expr.expression {
+                // `sleep` suspends the coroutine the code runs in.
+                Expression::Call(call) if is_builtin(&call.function, "sleep") => {
+                    match call.arguments.as_slice() {
+                        [duration] => format!(
+                            "delay({})",
+                            self.kotlin_integer(guardian, duration, "toLong")?
+                        ),
+                        _ => return Err(unsupported("this call of `sleep`", call.span)),
+                    }
+                }
+                // Async functions are `suspend fun`s, which a call awaits.
+                Expression::Call(call) => self.kotlin_call(guardian, call)?,
+                future => format!("{}.await()", self.kotlin_expression(guardian, future)?),
+            },
+            Expression::Lambda(lambda) => {
+                let params: Vec<String> = lambda
+                    .parameters
+                    .iter()
+                    .map(|param| param.name.clone())
+                    .collect();
+                format!(
+                    "{{ {} -> {} }}",
+                    params.join(", "),
+                    self.kotlin_expression(guardian, &lambda.body)?
+                )
+            }
+            Expression::When(_) => return Err(unsupported("`when` expressions", expr.span())),
+            Expression::AskJs(_) => return Err(unsupported("`ask_js`", expr.span())),
+        })
+    }
+
+    fn kotlin_expressions(
+        &self,
+        guardian: &Guardian,
+        exprs: &[Expression],
+    ) -> Result<Vec<String>, CodeGenError> {
+        exprs
+            .iter()
+            .map(|expr| self.kotlin_expression(guardian, expr))
+            .collect()
+    }
+
+    /// Translates a call. `print` prints a line, and the methods of the
+    /// built-in types call their Kotlin counterparts, e.g., `tasks.length()`
+    /// becomes `tasks.size.toDouble()`. Fails for a built-in method of a
+    /// value whose type the Guardian couldn't tell.
+    fn kotlin_call(
+        &self,
+        guardian: &Guardian,
+        call: &CallExpression,
+    ) -> Result<String, CodeGenError> {
+        let mut args = self.kotlin_arguments(guardian, &call.arguments)?;
+        let (access, dot) = match &call.function {
+            Expression::MemberAccess(access) => (access, "."),
+            Expression::SafeMemberAccess(access) => (access, "?."),
+            function if is_builtin(function, "print") => {
+                return Ok(match args.as_slice() {
+                    [arg] => format!("println({})", arg),
+                    _ => format!("println(listOf({}).joinToString(\" \"))", args.join(", ")),
+                })
+            }
+            function if is_builtin(function, "sleep") => {
+                return Err(unsupported("`sleep` without `await`", call.span))
+            }
+            function => {
+                return Ok(format!(
+                    "{}({})",
+                    self.kotlin_expression(guardian, function)?,
+                    args.join(", ")
+                ))
+            }
+        };
+        let object = self.kotlin_expression(guardian, &access.object)?;
+        let name = access.property.as_str();
+        let receiver = match guardian.method_receivers.get(&call.span) {
+            // Enum variants and the methods of contracts and interfaces
+            // keep their names.
+            None
+            | Some(
+                Type::Custom(_) | Type::GenericInstance(..) | Type::Interface(_) | Type::Param(_),
+            ) => return Ok(format!("{}{}{}({})", object, dot, name, args.join(", "))),
+            Some(receiver) => receiver,
+        };
+        if let (Type::List(_), "insert" | "remove" | "get", Some(index)) =
+            (receiver, name, call.arguments.first())
+        {
+            args[0] = self.kotlin_integer(guardian, index, "toInt")?;
+        }
+        let method = match (receiver, name, args.as_slice()) {
+            (Type::Optional(_), "isNothing", []) => return Ok(format!("({} == null)", object)),
+            (Type::Optional(_), "orElse", [default]) => {
+                return Ok(format!("({} ?: {})", object, default))
+            }
+            (Type::Optional(_), "map", [transform]) => {
+                return Ok(format!("{}?.let({})", object, transform))
+            }
+            _ => builtin_method(receiver, name, &args),
+        };
+        match method {
+            Some(segments) => Ok(format!("{}{}{}", object, dot, segments.join(dot))),
+            None => Err(unsupported(
+                &format!("the method '{}' of {}", name, receiver),
+                call.span,
+            )),
+        }
+    }
+
+    /// Translates the arguments of a call. An argument the Guardian checked
+    /// as a lambda of an implicit `it`, as in `tasks.filter(it.done)`, is a
+    /// Kotlin lambda.
+    fn kotlin_arguments(
+        &self,
+        guardian: &Guardian,
+        arguments: &[Expression],
+    ) -> Result<Vec<String>, CodeGenError> {
+        arguments
+            .iter()
+            .map(|argument| {
+                let kotlin = self.kotlin_expression(guardian, argument)?;
+                Ok(if guardian.implicit_lambdas.contains(&argument.span()) {
+                    format!("{{ {} }}", kotlin)
+                } else {
+                    kotlin
+                })
+            })
+            .collect()
+    }
+
+    /// Translates a number where Kotlin expects an integer, e.g., the index
+    /// of a list: a literal becomes an integer literal, and anything else is
+    /// converted with `conversion`, e.g., `toInt`.
+    fn kotlin_integer(
+        &self,
+        guardian: &Guardian,
+        expr: &Expression,
+        conversion: &str,
+    ) -> Result<String, CodeGenError> {
+        Ok(match expr {
+            Expression::Literal(Literal::Number(number), _) if !number.contains('.') => {
+                number.clone()
+            }
+            _ => format!(
+                "({}).{}()",
+                self.kotlin_expression(guardian, expr)?,
+                conversion
+            ),
+        })
+    }
+
+    /// Translates an Aegis type annotation into the Kotlin type it compiles to.
+    /// Collections that Aegis code can mutate map to Kotlin's mutable collections.
+    fn kotlin_type(&self, type_ann: &TypeIdentifier) -> String {
+        match type_ann {
+            TypeIdentifier::Simple { name, .. } => match name.as_str() {
+                "number" => "Double".to_string(),
+                "string" => "String".to_string(),
+                "boolean" => "Boolean".to_string(),
+                "nothing" => "Unit".to_string(),
+                // Contracts, enums and type parameters keep their names.
+                _ => name.clone(),
+            },
+            TypeIdentifier::Generic { name, args, .. } => {
+                let args: Vec<String> = args.iter().map(|arg| self.kotlin_type(arg)).collect();
+                match name.as_str() {
+                    "Optional" => format!("{}?", args[0]),
+                    "Future" => format!("Deferred<{}>", args[0]),
+                    "List" => format!("MutableList<{}>", args[0]),
+                    "Set" => format!("MutableSet<{}>", args[0]),
+                    "Map" => format!("MutableMap<{}>", args.join(", ")),
+                    _ => format!("{}<{}>", name, args.join(", ")),
+                }
+            }
+        }
+    }
+}
+
+/// The XML layout of the app and the Kotlin that brings it to life, built
+/// while walking its UI elements.
+#[derive(Default)]
+struct Layout {
+    xml: String,
+    /// The views generated so far, which numbers their ids.
+    views: usize,
+    /// Statements for `render()` that show state in a view.
+    bindings: Vec<String>,
+    /// Statements for `onCreate` that set up the event handlers.
+    handlers: Vec<String>,
+}
+
+/// The Android view a built-in UI component is shown with.
+fn android_view(component: &str) -> &'static str {
+    match component {
+        "column" | "row" => "LinearLayout",
+        "text" => "TextView",
+        "button" => "Button",
+        "input" => "EditText",
+        "image" => "ImageView",
+        "spacer" => "Space",
+        _ => "View",
+    }
+}
+
+/// The name of the positional argument at `index` of a built-in component,
+/// e.g., the `label` of a `button`.
+fn positional_name(component: &str, index: usize) -> &'static str {
+    crate::guardian_components::component(component)
+        .and_then(|schema| schema.positional.get(index))
+        .map_or("text", |spec| spec.name)
+}
+
+/// Returns true if `function` names the built-in function `name`.
+fn is_builtin(function: &Expression, name: &str) -> bool {
+    matches!(function, Expression::Identifier(function_name, _) if function_name == name)
+}
+
+/// The Kotlin that calls the built-in method `name` of a List, Map, Set or
+/// string with the translated `args`, as the segments of a chain of calls,
+/// e.g., `size` and `toDouble()` for the `length()` of a List. Kotlin has
+/// the other methods under the same names.
+fn builtin_method(receiver: &Type, name: &str, args: &[String]) -> Option<Vec<String>> {
+    let call = |method: &str| format!("{}({})", method, args.join(", "));
+    let to_double = || "toDouble()".to_string();
+    let to_list = || "toMutableList()".to_string();
+    Some(match (receiver, name) {
+        (Type::List(_) | Type::Map(..) | Type::Set(_), "length") => {
+            vec!["size".to_string(), to_double()]
+        }
+        (Type::String, "length") => vec!["length".to_string(), to_double()],
+        (Type::List(_), "insert") => vec![call("add")],
+        (Type::List(_), "remove") => vec![call("removeAt")],
+        (Type::List(_), "get") => vec![call("getOrNull")],
+        (Type::List(_), "first" | "last") => vec![call(&format!("{}OrNull", name))],
+        (Type::List(_), "indexOf") => vec![call("indexOf"), to_double()],
+        (Type::List(_), "findIndex") => vec![call("indexOfFirst"), to_double()],
+        (Type::List(_), "filter" | "map") | (Type::String, "split") => vec![call(name), to_list()],
+        (Type::List(_), "join") => vec![call("joinToString")],
+        (Type::Map(..), "set") => vec![call("put")],
+        (Type::Map(..), "keys" | "values") => vec![name.to_string(), to_list()],
+        (Type::Set(_), "toList") => vec![to_list()],
+        (Type::String, "toUpperCase") => vec![call("uppercase")],
+        (Type::String, "toLowerCase") => vec![call("lowercase")],
+        (Type::List(_) | Type::Map(..) | Type::Set(_) | Type::String, _) => vec![call(name)],
+        _ => return None,
+    })
+}
+
+/// The error for a construct the Android target can't translate yet.
+fn unsupported(what: &str, span: Span) -> CodeGenError {
+    CodeGenError::new(
+        format!("The Android target doesn't support {} yet", what),
+        Some(span),
+    )
+}
+
+/// Indents every line of `text` with `prefix`.
+fn indent(text: &str, prefix: &str) -> String {
+    text.lines()
+        .map(|line| format!("{}{}\n", prefix, line))
+        .collect()
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('"', "&quot;")
 }
@@ -1,14 +1,64 @@
-use crate::architect::ast::*;
+use crate::ast::*;
 use crate::engine::ail::{Instruction, InstructionSequence};
+use crate::error::CodeGenError;
+use crate::guardian::Guardian;
+use crate::guardian_reactivity::collect_reads;
+use crate::guardian_types::Type;
+use crate::token::Span;
+use std::collections::{HashMap, HashSet};
 
 /// The Lowerer walks the AST and emits AIL instructions.
+///
+/// Every expression leaves exactly one value on the stack, `nothing` if it
+/// has none, and every statement leaves the stack as it found it.
+#[derive(Default)]
 pub struct Lowerer {
     label_counter: u32,
+    temp_counter: u32,
+    lambda_counter: u32,
+    /// The fields of every contract lowered so far, by contract name, to
+    /// build contracts from map literals and fill in their default values.
+    contracts: HashMap<String, Vec<ContractField>>,
+    enums: HashSet<String>,
+    /// The parameter types of every function lowered so far, by the name of
+    /// its sequence, so a map literal passed to a contract parameter builds
+    /// the contract.
+    functions: HashMap<String, Vec<TypeIdentifier>>,
+    /// The name of the module being lowered, `main` for the entry module.
+    module: String,
+    /// The top-level names the module being lowered can use, each with its
+    /// name in AIL: the module's functions and globals, and the public
+    /// functions of the modules it imports.
+    scope: HashMap<String, String>,
+    /// The public functions of every module lowered so far, by module, each
+    /// with its name in AIL.
+    exports: HashMap<String, Vec<(String, String)>>,
+    /// The map literals of the module being lowered that the Guardian
+    /// checked as contracts, by span, with the contract each one builds.
+    contract_literals: HashMap<Span, String>,
+    /// The calls of the module being lowered that the Guardian checked as
+    /// calls of a method of an Optional, by span.
+    optional_calls: HashSet<Span>,
+    /// The calls of the module being lowered that the Guardian checked as
+    /// calls of a method of a Set, which the VM has no values of, by span.
+    set_calls: HashSet<Span>,
+    /// The arguments of the module being lowered that the Guardian checked
+    /// as lambdas of an implicit `it`, by span.
+    implicit_lambdas: HashSet<Span>,
+    /// The locals of the function being lowered, which its lambdas capture.
+    /// `None` at the top level of a module, whose variables are globals.
+    locals: Option<Vec<String>>,
+    /// The declared return type of the function being lowered.
+    return_type: Option<TypeIdentifier>,
+    /// The sequences of the lambdas lowered since the last were collected.
+    lambdas: Vec<InstructionSequence>,
+    /// The constructs AIL can't express, e.g., `ask_js`.
+    pub errors: Vec<CodeGenError>,
 }
 
 impl Lowerer {
     pub fn new() -> Self {
-        Self { label_counter: 0 }
+        Self::default()
     }
 
     fn new_label(&mut self) -> String {
@@ -17,81 +67,921 @@ impl Lowerer {
         label
     }
 
+    /// A name for a hidden local, e.g., `$when0`, which can't clash with a
+    /// name in the source.
+    fn new_temp(&mut self, prefix: &str) -> String {
+        let temp = format!("${}{}", prefix, self.temp_counter);
+        self.temp_counter += 1;
+        temp
+    }
+
+    /// Lowers a program on its own, checking it first for what lowering
+    /// needs to know, like which map literals build contracts.
     pub fn lower_program(&mut self, program: &Program) -> Vec<InstructionSequence> {
-        let mut sequences = Vec::new();
-        // In a real compiler, we'd find the main entry point.
-        // For now, we flatten all top-level statements into one sequence.
-        let mut main_seq = InstructionSequence::new("main");
+        let mut guardian = Guardian::new();
+        guardian.check_program(program);
+        self.lower_module("main", program, &guardian)
+    }
 
+    /// Lowers a module of a project. Its top-level statements are flattened
+    /// into one sequence named after the module, which comes first, e.g.,
+    /// `models.task`; each function gets a sequence of its own, and so do
+    /// contract methods and computed properties, e.g., `Task.rename`, and
+    /// lambdas, e.g., `lambda0`. The functions and globals of modules other
+    /// than the entry module, `main`, are qualified with the module's name,
+    /// e.g., `models.task.finish`. The modules a module imports must be
+    /// lowered before it, and `guardian` is the one that checked it.
+    pub fn lower_module(
+        &mut self,
+        name: &str,
+        program: &Program,
+        guardian: &Guardian,
+    ) -> Vec<InstructionSequence> {
+        self.module = name.to_string();
+        self.contract_literals = guardian.contract_literals.clone();
+        self.optional_calls = guardian
+            .method_receivers
+            .iter()
+            .filter(|(_, receiver)| matches!(receiver, Type::Optional(_)))
+            .map(|(span, _)| *span)
+            .collect();
+        self.set_calls = guardian
+            .method_receivers
+            .iter()
+            .filter(|(_, receiver)| matches!(receiver, Type::Set(_)))
+            .map(|(span, _)| *span)
+            .collect();
+        self.implicit_lambdas = guardian.implicit_lambdas.clone();
+        self.scope.clear();
+        for import in &program.imports {
+            let exports = self.exports.get(&import.module_name());
+            for (export, qualified) in exports.into_iter().flatten() {
+                let is_imported = import
+                    .names
+                    .as_ref()
+                    .is_none_or(|names| names.iter().any(|imported| imported.name == *export));
+                if is_imported {
+                    self.scope.insert(export.clone(), qualified.clone());
+                }
+            }
+        }
+        let mut exports = Vec::new();
+        for def in &program.definitions {
+            match def {
+                Definition::Contract(contract) => {
+                    self.contracts
+                        .insert(contract.name.clone(), contract.fields.clone());
+                }
+                Definition::Enum(enum_def) => {
+                    self.enums.insert(enum_def.name.clone());
+                }
+                Definition::Function(func_def) => {
+                    let param_types = func_def
+                        .parameters
+                        .iter()
+                        .map(|param| param.type_annotation.clone())
+                        .collect();
+                    let qualified = self.qualify(&func_def.name);
+                    self.functions.insert(qualified.clone(), param_types);
+                    if func_def.is_public {
+                        exports.push((func_def.name.clone(), qualified.clone()));
+                    }
+                    self.scope.insert(func_def.name.clone(), qualified);
+                }
+                Definition::Statement(Statement::Let(let_stmt)) => {
+                    let qualified = self.qualify(&let_stmt.name);
+                    self.scope.insert(let_stmt.name.clone(), qualified);
+                }
+                _ => {}
+            }
+        }
+        self.exports.insert(name.to_string(), exports);
+
+        let mut sequences = Vec::new();
+        let mut main_seq = InstructionSequence::new(name);
         for def in &program.definitions {
             match def {
                 Definition::Statement(stmt) => self.lower_statement(stmt, &mut main_seq),
                 Definition::Function(func_def) => {
-                    // Each function gets its own sequence of instructions.
-                    let mut func_seq = InstructionSequence::new(&func_def.name);
-                    self.lower_statement(&Statement::Block(func_def.body.clone()), &mut func_seq);
-                    sequences.push(func_seq);
+                    let name = self.qualify(&func_def.name);
+                    sequences.push(self.lower_function(&name, &[], func_def));
+                    sequences.append(&mut self.lambdas);
+                }
+                Definition::Contract(contract) => {
+                    for method in &contract.methods {
+                        let name = format!("{}.{}", contract.name, method.name);
+                        sequences.push(self.lower_function(&name, &["self"], method));
+                        sequences.append(&mut self.lambdas);
+                    }
+                    for property in &contract.properties {
+                        sequences.push(self.lower_property(&contract.name, property));
+                        sequences.append(&mut self.lambdas);
+                    }
                 }
-                _ => {} // App, Contract definitions are handled by codegen directly for now
+                // Apps, styles and interfaces have no behavior of their own
+                // to lower; enums only need their names.
+                _ => {}
             }
         }
+        sequences.append(&mut self.lambdas);
+        main_seq.cover(program.span);
         sequences.insert(0, main_seq);
+        for seq in &mut sequences {
+            seq.module = name.to_string();
+        }
         sequences
     }
 
+    /// Lowers a function into a sequence named `name`, whose parameters are
+    /// `leading`, e.g., the `self` of a method, then the function's own.
+    fn lower_function(
+        &mut self,
+        name: &str,
+        leading: &[&str],
+        func_def: &FunctionDefinition,
+    ) -> InstructionSequence {
+        let mut params: Vec<String> = leading.iter().map(|param| param.to_string()).collect();
+        params.extend(func_def.parameters.iter().map(|param| param.name.clone()));
+        let mut seq = InstructionSequence::new(name);
+        let outer = self.enter_function(&params, func_def.return_type.clone(), &mut seq);
+        self.lower_block(&func_def.body, &mut seq);
+        if seq.instructions.last() != Some(&Instruction::Return) {
+            seq.instructions.push(Instruction::PushNothing);
+            seq.instructions.push(Instruction::Return);
+        }
+        seq.cover(func_def.span);
+        self.exit_function(outer);
+        seq
+    }
+
+    /// Lowers a computed property into a sequence named after it, e.g.,
+    /// `Task.is_late`, which takes the instance as `self`.
+    fn lower_property(
+        &mut self,
+        contract_name: &str,
+        property: &ComputedProperty,
+    ) -> InstructionSequence {
+        let mut seq = InstructionSequence::new(&format!("{}.{}", contract_name, property.name));
+        let params = ["self".to_string()];
+        let outer = self.enter_function(&params, Some(property.type_ann.clone()), &mut seq);
+        self.lower_expression_as(&property.value, Some(&property.type_ann), &mut seq);
+        seq.instructions.push(Instruction::Return);
+        seq.cover(property.span);
+        self.exit_function(outer);
+        seq
+    }
+
+    /// Starts lowering the body of a function or lambda into `seq`: declares
+    /// its parameters and emits the prologue that stores its arguments into
+    /// them. Returns the state of the enclosing function, for
+    /// `exit_function`.
+    fn enter_function(
+        &mut self,
+        params: &[String],
+        return_type: Option<TypeIdentifier>,
+        seq: &mut InstructionSequence,
+    ) -> (Option<Vec<String>>, Option<TypeIdentifier>) {
+        seq.params = params.to_vec();
+        for param in params.iter().rev() {
+            seq.instructions
+                .push(Instruction::StoreLocal(param.clone()));
+        }
+        let locals = self.locals.replace(params.to_vec());
+        let return_type = std::mem::replace(&mut self.return_type, return_type);
+        (locals, return_type)
+    }
+
+    fn exit_function(&mut self, outer: (Option<Vec<String>>, Option<TypeIdentifier>)) {
+        (self.locals, self.return_type) = outer;
+    }
+
+    fn declare_local(&mut self, name: &str) {
+        if let Some(locals) = &mut self.locals {
+            locals.push(name.to_string());
+        }
+    }
+
+    /// Declares the variable `name` and returns the instruction that stores
+    /// its value: into a local in a function, even if a global has the same
+    /// name, or into a global at the top level of a module.
+    fn declare(&mut self, name: &str) -> Instruction {
+        if self.locals.is_none() {
+            let qualified = self.qualify(name);
+            self.scope.insert(name.to_string(), qualified.clone());
+            return Instruction::Store(qualified);
+        }
+        self.declare_local(name);
+        Instruction::StoreLocal(name.to_string())
+    }
+
+    /// The name in AIL of the top-level declaration `name` of the module
+    /// being lowered: `name` itself in the entry module, and qualified with
+    /// the module's name in the others, e.g., `util.math.helper`, so the
+    /// private declarations of different modules don't clash.
+    fn qualify(&self, name: &str) -> String {
+        if self.module == "main" {
+            name.to_string()
+        } else {
+            format!("{}.{}", self.module, name)
+        }
+    }
+
+    /// The name in AIL of the variable or function `name` read in the module
+    /// being lowered: a local, a declaration of the module, an imported
+    /// function, or a built-in.
+    fn resolve(&self, name: &str) -> String {
+        if self.is_local(name) {
+            return name.to_string();
+        }
+        self.scope
+            .get(name)
+            .cloned()
+            .unwrap_or_else(|| name.to_string())
+    }
+
+    fn is_local(&self, name: &str) -> bool {
+        self.locals
+            .as_ref()
+            .is_some_and(|locals| locals.iter().any(|local| local == name))
+    }
+
+    fn lower_block(&mut self, block: &BlockStatement, seq: &mut InstructionSequence) {
+        for s in &block.statements {
+            self.lower_statement(s, seq);
+        }
+    }
+
     fn lower_statement(&mut self, stmt: &Statement, seq: &mut InstructionSequence) {
         match stmt {
             Statement::Let(let_stmt) => {
-                self.lower_expression(&let_stmt.value, seq);
-                seq.instructions.push(Instruction::Store(let_stmt.name.clone()));
+                self.lower_expression_as(&let_stmt.value, let_stmt.type_annotation.as_ref(), seq);
+                let store = self.declare(&let_stmt.name);
+                seq.instructions.push(store);
             }
             Statement::Expression(expr_stmt) => {
                 self.lower_expression(&expr_stmt.expression, seq);
+                seq.instructions.push(Instruction::Pop);
             }
             Statement::Return(ret_stmt) => {
-                self.lower_expression(&ret_stmt.value, seq);
+                let return_type = self.return_type.clone();
+                self.lower_expression_as(&ret_stmt.value, return_type.as_ref(), seq);
                 seq.instructions.push(Instruction::Return);
             }
-            Statement::Block(block_stmt) => {
-                for s in &block_stmt.statements {
-                    self.lower_statement(s, seq);
+            Statement::Block(block_stmt) => self.lower_block(block_stmt, seq),
+            Statement::Assign(assign) => match &assign.target {
+                Expression::MemberAccess(member) => {
+                    self.lower_expression(&member.object, seq);
+                    self.lower_expression(&assign.value, seq);
+                    seq.instructions
+                        .push(Instruction::SetField(member.property.clone()));
                 }
-            }
-            _ => {} // For loops are complex and might be handled directly in codegen
+                target => {
+                    self.lower_expression(&assign.value, seq);
+                    if let Expression::Identifier(name, _) = target {
+                        seq.instructions
+                            .push(Instruction::Store(self.resolve(name)));
+                    }
+                }
+            },
+            Statement::For(for_stmt) => self.lower_for(for_stmt, seq),
         }
+        seq.cover(stmt.span());
+    }
+
+    /// Lowers `for item in items:` into a loop over the indices of the list,
+    /// which is evaluated once and kept in a hidden local.
+    fn lower_for(&mut self, for_stmt: &ForStatement, seq: &mut InstructionSequence) {
+        let list = self.new_temp("list");
+        let index = self.new_temp("i");
+        let start_label = self.new_label();
+        let end_label = self.new_label();
+
+        self.lower_expression(&for_stmt.collection, seq);
+        let store_variable = self.declare(&for_stmt.variable_name);
+        seq.instructions.extend([
+            Instruction::Store(list.clone()),
+            Instruction::PushI64(0),
+            Instruction::Store(index.clone()),
+            Instruction::Label(start_label.clone()),
+            Instruction::Load(index.clone()),
+            Instruction::Load(list.clone()),
+            Instruction::Length,
+            Instruction::LessThan,
+            Instruction::JumpIfFalse(end_label.clone()),
+            Instruction::Load(list),
+            Instruction::Load(index.clone()),
+            Instruction::Index,
+            store_variable,
+        ]);
+        self.lower_statement(&for_stmt.body, seq);
+        seq.instructions.extend([
+            Instruction::Load(index.clone()),
+            Instruction::PushI64(1),
+            Instruction::Add,
+            Instruction::Store(index),
+            Instruction::Jump(start_label),
+            Instruction::Label(end_label),
+        ]);
     }
 
     fn lower_expression(&mut self, expr: &Expression, seq: &mut InstructionSequence) {
+        self.lower_expression_as(expr, None, seq);
+    }
+
+    /// Lowers an expression whose value is expected to be of type `expected`,
+    /// e.g., the annotation of a `let's`. A map literal the Guardian checked
+    /// as a contract, or expected to be one, builds the contract.
+    fn lower_expression_as(
+        &mut self,
+        expr: &Expression,
+        expected: Option<&TypeIdentifier>,
+        seq: &mut InstructionSequence,
+    ) {
         match expr {
-            Expression::Literal(Literal::Number(n), _) => {
-                seq.instructions.push(Instruction::PushI64(n.parse().unwrap_or(0)));
+            Expression::Literal(Literal::Map(map), span)
+                if self
+                    .contract_literals
+                    .get(span)
+                    .is_some_and(|name| self.contracts.contains_key(name)) =>
+            {
+                let name = self.contract_literals[span].clone();
+                self.lower_contract(&name, map, seq);
             }
+            Expression::Literal(literal, _) => self.lower_literal(literal, expected, seq),
             Expression::Identifier(name, _) => {
-                seq.instructions.push(Instruction::Load(name.clone()));
+                seq.instructions.push(Instruction::Load(self.resolve(name)));
             }
-            Expression::Infix(infix_expr) => {
-                self.lower_expression(&infix_expr.left, seq);
-                self.lower_expression(&infix_expr.right, seq);
-                let op = match infix_expr.operator {
-                    // ... map operators to AIL instructions ...
-                    _ => Instruction::Add, // Placeholder
-                };
-                seq.instructions.push(op);
+            Expression::Prefix(prefix_expr) => {
+                self.lower_expression(&prefix_expr.right, seq);
+                seq.instructions.push(match prefix_expr.operator {
+                    PrefixOperator::Not => Instruction::Not,
+                    PrefixOperator::Minus => Instruction::Negate,
+                });
             }
+            Expression::Infix(infix_expr) => self.lower_infix(infix_expr, expected, seq),
             Expression::If(if_expr) => {
                 let else_label = self.new_label();
                 let end_label = self.new_label();
                 self.lower_expression(&if_expr.condition, seq);
-                seq.instructions.push(Instruction::JumpIfFalse(else_label.clone()));
-                self.lower_statement(&Statement::Block(if_expr.consequence.clone()), seq);
+                seq.instructions
+                    .push(Instruction::JumpIfFalse(else_label.clone()));
+                self.lower_expression_as(&if_expr.then_branch, expected, seq);
                 seq.instructions.push(Instruction::Jump(end_label.clone()));
                 seq.instructions.push(Instruction::Label(else_label));
-                if let Some(alt) = &if_expr.alternative {
-                    self.lower_statement(alt, seq);
+                match &if_expr.else_branch {
+                    Some(else_branch) => self.lower_expression_as(else_branch, expected, seq),
+                    None => seq.instructions.push(Instruction::PushNothing),
                 }
                 seq.instructions.push(Instruction::Label(end_label));
             }
-            _ => {} // Other expression types
+            Expression::When(when_expr) => self.lower_when(when_expr, expected, seq),
+            Expression::Call(call) => self.lower_call(call, seq),
+            Expression::MemberAccess(member) => match &member.object {
+                Expression::Identifier(name, _) if self.enums.contains(name) => {
+                    seq.instructions.push(Instruction::MakeEnum {
+                        enum_name: name.clone(),
+                        variant: member.property.clone(),
+                        arity: 0,
+                    });
+                }
+                object => {
+                    self.lower_expression(object, seq);
+                    seq.instructions
+                        .push(Instruction::GetField(member.property.clone()));
+                }
+            },
+            Expression::SafeMemberAccess(member) => {
+                // `nothing` is left on the stack as the result.
+                let end_label = self.new_label();
+                self.lower_expression(&member.object, seq);
+                seq.instructions.extend([
+                    Instruction::Dup,
+                    Instruction::PushNothing,
+                    Instruction::Equals,
+                    Instruction::JumpIfTrue(end_label.clone()),
+                    Instruction::GetField(member.property.clone()),
+                    Instruction::Label(end_label),
+                ]);
+            }
+            // AIL runs sequentially, so the awaited value is simply computed.
+            Expression::Await(await_expr) => {
+                self.lower_expression_as(&await_expr.expression, expected, seq);
+            }
+            Expression::AskJs(ask_js) => {
+                self.errors.push(CodeGenError::new(
+                    "AIL doesn't support `ask_js` yet".to_string(),
+                    Some(ask_js.span),
+                ));
+                seq.instructions.push(Instruction::PushNothing);
+            }
+            Expression::Lambda(lambda) => {
+                let params: Vec<String> = lambda
+                    .parameters
+                    .iter()
+                    .map(|param| param.name.clone())
+                    .collect();
+                self.lower_closure(&params, expr, &lambda.body, seq);
+            }
+        }
+        seq.cover(expr.span());
+    }
+
+    fn lower_literal(
+        &mut self,
+        literal: &Literal,
+        expected: Option<&TypeIdentifier>,
+        seq: &mut InstructionSequence,
+    ) {
+        let expected = expected.map(unwrap_optional);
+        match literal {
+            Literal::Number(n) => seq.instructions.push(match n.parse() {
+                Ok(int) => Instruction::PushI64(int),
+                Err(_) => Instruction::PushF64(n.parse().unwrap_or(0.0)),
+            }),
+            Literal::String(s) => seq.instructions.push(Instruction::PushString(s.clone())),
+            Literal::Boolean(b) => seq.instructions.push(Instruction::PushBool(*b)),
+            Literal::Nothing => seq.instructions.push(Instruction::PushNothing),
+            Literal::List(list) => {
+                let element_type = type_argument(expected, "List", 0);
+                for element in &list.elements {
+                    self.lower_expression_as(element, element_type, seq);
+                }
+                seq.instructions
+                    .push(Instruction::MakeList(list.elements.len()));
+            }
+            Literal::Map(map) => {
+                let contract = expected
+                    .map(type_name)
+                    .filter(|name| self.contracts.contains_key(*name));
+                match contract {
+                    Some(name) => self.lower_contract(name, map, seq),
+                    None => {
+                        let value_type = type_argument(expected, "Map", 1);
+                        for (key, value) in &map.pairs {
+                            match key {
+                                Expression::Identifier(name, _) => {
+                                    seq.instructions.push(Instruction::PushString(name.clone()))
+                                }
+                                key => self.lower_expression(key, seq),
+                            }
+                            self.lower_expression_as(value, value_type, seq);
+                        }
+                        seq.instructions.push(Instruction::MakeMap(map.pairs.len()));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Builds an instance of the contract `name` from a map literal: the
+    /// fields it gives, in its order, then the default values of the fields
+    /// it leaves out.
+    fn lower_contract(&mut self, name: &str, map: &MapLiteral, seq: &mut InstructionSequence) {
+        let contract_fields = self.contracts[name].clone();
+        let mut fields = Vec::new();
+        for (key, value) in &map.pairs {
+            let field_name = match key {
+                Expression::Identifier(field_name, _) => field_name.clone(),
+                Expression::Literal(Literal::String(field_name), _) => field_name.clone(),
+                _ => continue,
+            };
+            let field_type = contract_fields
+                .iter()
+                .find(|field| field.name == field_name)
+                .map(|field| &field.type_ann);
+            self.lower_expression_as(value, field_type, seq);
+            fields.push(field_name);
         }
+        for field in &contract_fields {
+            if let (Some(default_value), false) =
+                (&field.default_value, fields.contains(&field.name))
+            {
+                self.lower_expression_as(default_value, Some(&field.type_ann), seq);
+                fields.push(field.name.clone());
+            }
+        }
+        seq.instructions.push(Instruction::MakeContract {
+            name: name.to_string(),
+            fields,
+        });
+    }
+
+    fn lower_infix(
+        &mut self,
+        infix_expr: &InfixExpression,
+        expected: Option<&TypeIdentifier>,
+        seq: &mut InstructionSequence,
+    ) {
+        // `and`, `or` and `?:` only evaluate their right side if the left
+        // side doesn't already decide the result, which is left on the stack.
+        let short_circuit = match infix_expr.operator {
+            InfixOperator::And => Some(Instruction::JumpIfFalse as fn(String) -> Instruction),
+            InfixOperator::Or => Some(Instruction::JumpIfTrue as fn(String) -> Instruction),
+            InfixOperator::Elvis => None,
+            _ => {
+                self.lower_expression(&infix_expr.left, seq);
+                self.lower_expression(&infix_expr.right, seq);
+                seq.instructions.push(match infix_expr.operator {
+                    InfixOperator::Plus => Instruction::Add,
+                    InfixOperator::Minus => Instruction::Subtract,
+                    InfixOperator::Multiply => Instruction::Multiply,
+                    InfixOperator::Divide => Instruction::Divide,
+                    InfixOperator::Modulo => Instruction::Modulo,
+                    InfixOperator::Equal => Instruction::Equals,
+                    InfixOperator::NotEqual => Instruction::NotEqual,
+                    InfixOperator::LessThan => Instruction::LessThan,
+                    InfixOperator::GreaterThan => Instruction::GreaterThan,
+                    InfixOperator::And | InfixOperator::Or | InfixOperator::Elvis => {
+                        unreachable!()
+                    }
+                });
+                return;
+            }
+        };
+        let end_label = self.new_label();
+        self.lower_expression_as(&infix_expr.left, expected, seq);
+        seq.instructions.push(Instruction::Dup);
+        match short_circuit {
+            Some(jump) => seq.instructions.push(jump(end_label.clone())),
+            None => seq.instructions.extend([
+                Instruction::PushNothing,
+                Instruction::Equals,
+                Instruction::JumpIfFalse(end_label.clone()),
+            ]),
+        }
+        seq.instructions.push(Instruction::Pop);
+        self.lower_expression_as(&infix_expr.right, expected, seq);
+        seq.instructions.push(Instruction::Label(end_label));
+    }
+
+    /// Lowers a call. A named function is called by name, a variant of an
+    /// enum builds the enum value, a method is called on the value it is
+    /// called on, unless a safe call's value is `nothing`, and anything else
+    /// is called as a closure.
+    fn lower_call(&mut self, call: &CallExpression, seq: &mut InstructionSequence) {
+        let count = call.arguments.len();
+        match &call.function {
+            Expression::Identifier(name, _) => {
+                let name = self.resolve(name);
+                let param_types = self.functions.get(&name).cloned().unwrap_or_default();
+                self.lower_arguments(&call.arguments, &param_types, seq);
+                seq.instructions.push(Instruction::Call(name, count));
+            }
+            Expression::MemberAccess(member) => match &member.object {
+                Expression::Identifier(enum_name, _) if self.enums.contains(enum_name) => {
+                    self.lower_arguments(&call.arguments, &[], seq);
+                    seq.instructions.push(Instruction::MakeEnum {
+                        enum_name: enum_name.clone(),
+                        variant: member.property.clone(),
+                        arity: count,
+                    });
+                }
+                object => {
+                    self.lower_expression(object, seq);
+                    self.lower_method_call(call, &member.property, seq);
+                }
+            },
+            Expression::SafeMemberAccess(member) => {
+                // `nothing` is left on the stack as the result.
+                let end_label = self.new_label();
+                self.lower_expression(&member.object, seq);
+                seq.instructions.extend([
+                    Instruction::Dup,
+                    Instruction::PushNothing,
+                    Instruction::Equals,
+                    Instruction::JumpIfTrue(end_label.clone()),
+                ]);
+                self.lower_method_call(call, &member.property, seq);
+                seq.instructions.push(Instruction::Label(end_label));
+            }
+            function => {
+                self.lower_expression(function, seq);
+                self.lower_arguments(&call.arguments, &[], seq);
+                seq.instructions.push(Instruction::CallValue(count));
+            }
+        }
+    }
+
+    /// Lowers the arguments of a call of the method `name` and the call,
+    /// once the value it is called on is on the stack. The methods of an
+    /// Optional are told apart from those of the value it holds, e.g., the
+    /// `map` of a `List?` from that of a List.
+    fn lower_method_call(
+        &mut self,
+        call: &CallExpression,
+        name: &str,
+        seq: &mut InstructionSequence,
+    ) {
+        let count = call.arguments.len();
+        if self.set_calls.contains(&call.span) {
+            self.errors.push(CodeGenError::new(
+                format!(
+                    "AIL doesn't support the methods of `Set` yet, like '{}'",
+                    name
+                ),
+                Some(call.span),
+            ));
+        }
+        self.lower_arguments(&call.arguments, &[], seq);
+        seq.instructions
+            .push(if self.optional_calls.contains(&call.span) {
+                Instruction::CallOptionalMethod(name.to_string(), count)
+            } else {
+                Instruction::CallMethod(name.to_string(), count)
+            });
+    }
+
+    /// Lowers the arguments of a call, each expected to be of its parameter's
+    /// type if it is known. An argument the Guardian checked as a lambda of
+    /// an implicit `it`, as in `tasks.filter(it.done)`, becomes a closure.
+    fn lower_arguments(
+        &mut self,
+        arguments: &[Expression],
+        param_types: &[TypeIdentifier],
+        seq: &mut InstructionSequence,
+    ) {
+        for (i, argument) in arguments.iter().enumerate() {
+            if self.implicit_lambdas.contains(&argument.span()) {
+                self.lower_closure(&["it".to_string()], argument, argument, seq);
+            } else {
+                self.lower_expression_as(argument, param_types.get(i), seq);
+            }
+        }
+    }
+
+    /// Lowers a lambda with the parameters `params` and the body `body` into
+    /// a sequence of its own and pushes a closure over it. The closure
+    /// captures the values of the enclosing function's locals that `lambda`
+    /// reads.
+    fn lower_closure(
+        &mut self,
+        params: &[String],
+        lambda: &Expression,
+        body: &Expression,
+        seq: &mut InstructionSequence,
+    ) {
+        let mut reads = Vec::new();
+        collect_reads(lambda, &mut reads);
+        let mut captures: Vec<String> = Vec::new();
+        for read in reads {
+            if self.is_local(read)
+                && !params.iter().any(|param| param == read)
+                && !captures.iter().any(|capture| capture == read)
+            {
+                captures.push(read.to_string());
+            }
+        }
+
+        let name = format!("lambda{}", self.lambda_counter);
+        self.lambda_counter += 1;
+        let mut lambda_seq = InstructionSequence::new(&name);
+        let outer = self.enter_function(params, None, &mut lambda_seq);
+        if let Some(locals) = &mut self.locals {
+            locals.extend(captures.iter().cloned());
+        }
+        self.lower_expression(body, &mut lambda_seq);
+        lambda_seq.instructions.push(Instruction::Return);
+        lambda_seq.cover(lambda.span());
+        self.exit_function(outer);
+        self.lambdas.push(lambda_seq);
+
+        seq.instructions.push(Instruction::MakeClosure {
+            function: name,
+            captures,
+        });
+    }
+
+    /// Lowers a `when`. If every arm matches a variant of an enum, with at
+    /// most a catch-all arm at the end, it is a jump table; otherwise each
+    /// arm's pattern and guard are tested in turn. If no arm matches, the
+    /// result is `nothing`.
+    fn lower_when(
+        &mut self,
+        when_expr: &WhenExpression,
+        expected: Option<&TypeIdentifier>,
+        seq: &mut InstructionSequence,
+    ) {
+        let value = self.new_temp("when");
+        let end_label = self.new_label();
+        self.lower_expression(&when_expr.value, seq);
+        seq.instructions.push(Instruction::Store(value.clone()));
+
+        if is_jump_table(&when_expr.cases) {
+            let mut cases = Vec::new();
+            let mut arms = Vec::new();
+            let mut default = None;
+            for case in &when_expr.cases {
+                let label = self.new_label();
+                if let WhenPattern::EnumVariant { variant_name, .. } = &case.pattern {
+                    if cases.iter().any(|(variant, _)| variant == variant_name) {
+                        continue;
+                    }
+                    cases.push((variant_name.clone(), label.clone()));
+                } else {
+                    default = Some(label.clone());
+                }
+                arms.push((label, case));
+            }
+            let default = default.unwrap_or_else(|| self.new_label());
+            seq.instructions.push(Instruction::Load(value.clone()));
+            seq.instructions.push(Instruction::JumpTable {
+                cases,
+                default: default.clone(),
+            });
+            let mut has_default = false;
+            for (label, case) in arms {
+                has_default |= label == default;
+                seq.instructions.push(Instruction::Label(label));
+                // The jump table already chose the variant, and these arms
+                // only bind names, so they can't fail.
+                match &case.pattern {
+                    WhenPattern::EnumVariant { arguments, .. } => {
+                        self.lower_payload(arguments, &value, &end_label, seq)
+                    }
+                    pattern => self.lower_pattern(pattern, &value, &end_label, seq),
+                }
+                self.lower_expression_as(&case.body, expected, seq);
+                seq.instructions.push(Instruction::Jump(end_label.clone()));
+            }
+            if !has_default {
+                seq.instructions.push(Instruction::Label(default));
+                seq.instructions.push(Instruction::PushNothing);
+            }
+        } else {
+            for case in &when_expr.cases {
+                let next_label = self.new_label();
+                self.lower_pattern(&case.pattern, &value, &next_label, seq);
+                if let Some(guard) = &case.guard {
+                    self.lower_expression(guard, seq);
+                    seq.instructions
+                        .push(Instruction::JumpIfFalse(next_label.clone()));
+                }
+                self.lower_expression_as(&case.body, expected, seq);
+                seq.instructions.push(Instruction::Jump(end_label.clone()));
+                seq.instructions.push(Instruction::Label(next_label));
+            }
+            seq.instructions.push(Instruction::PushNothing);
+        }
+        seq.instructions.push(Instruction::Label(end_label));
+    }
+
+    /// Tests the value in the local `value` against `pattern`, jumping to
+    /// `fail` if it doesn't match and binding the names the pattern
+    /// introduces if it does.
+    fn lower_pattern(
+        &mut self,
+        pattern: &WhenPattern,
+        value: &str,
+        fail: &str,
+        seq: &mut InstructionSequence,
+    ) {
+        let load = || Instruction::Load(value.to_string());
+        match pattern {
+            WhenPattern::Wildcard | WhenPattern::Else => {}
+            WhenPattern::Identifier(name) => {
+                seq.instructions.push(load());
+                let store = self.declare(name);
+                seq.instructions.push(store);
+            }
+            WhenPattern::Literal(literal) => {
+                seq.instructions.push(load());
+                self.lower_literal(literal, None, seq);
+                seq.instructions.push(Instruction::Equals);
+                seq.instructions
+                    .push(Instruction::JumpIfFalse(fail.to_string()));
+            }
+            WhenPattern::Range {
+                start,
+                end,
+                inclusive,
+                ..
+            } => {
+                // Fails if `value < start`, then if `end < value`, or for an
+                // exclusive range if not `value < end`.
+                seq.instructions.push(load());
+                self.lower_literal(start, None, seq);
+                seq.instructions.push(Instruction::LessThan);
+                seq.instructions
+                    .push(Instruction::JumpIfTrue(fail.to_string()));
+                if *inclusive {
+                    self.lower_literal(end, None, seq);
+                    seq.instructions.push(load());
+                    seq.instructions.push(Instruction::LessThan);
+                    seq.instructions
+                        .push(Instruction::JumpIfTrue(fail.to_string()));
+                } else {
+                    seq.instructions.push(load());
+                    self.lower_literal(end, None, seq);
+                    seq.instructions.push(Instruction::LessThan);
+                    seq.instructions
+                        .push(Instruction::JumpIfFalse(fail.to_string()));
+                }
+            }
+            WhenPattern::EnumVariant {
+                enum_name,
+                variant_name,
+                arguments,
+                ..
+            } => {
+                seq.instructions.push(load());
+                seq.instructions.push(Instruction::IsVariant {
+                    enum_name: enum_name.clone(),
+                    variant: variant_name.clone(),
+                });
+                seq.instructions
+                    .push(Instruction::JumpIfFalse(fail.to_string()));
+                self.lower_payload(arguments, value, fail, seq);
+            }
+        }
+    }
+
+    /// Tests the payload of the enum value in the local `value` against the
+    /// sub-patterns `arguments` of its variant's pattern.
+    fn lower_payload(
+        &mut self,
+        arguments: &[WhenPattern],
+        value: &str,
+        fail: &str,
+        seq: &mut InstructionSequence,
+    ) {
+        for (i, argument) in arguments.iter().enumerate() {
+            match argument {
+                WhenPattern::Wildcard | WhenPattern::Else => {}
+                WhenPattern::Identifier(name) => {
+                    seq.instructions.extend([
+                        Instruction::Load(value.to_string()),
+                        Instruction::GetPayload(i),
+                        self.declare(name),
+                    ]);
+                }
+                nested => {
+                    let payload = self.new_temp("payload");
+                    seq.instructions.extend([
+                        Instruction::Load(value.to_string()),
+                        Instruction::GetPayload(i),
+                        Instruction::Store(payload.clone()),
+                    ]);
+                    self.lower_pattern(nested, &payload, fail, seq);
+                }
+            }
+        }
+    }
+}
+
+/// Returns true if a `when` with these arms can be a jump table: each arm
+/// without a guard matches a variant of an enum, binding at most names from
+/// its payload, except that the last arm may match anything.
+fn is_jump_table(cases: &[WhenCase]) -> bool {
+    let Some((last, arms)) = cases.split_last() else {
+        return false;
+    };
+    let is_variant = |case: &WhenCase| match &case.pattern {
+        WhenPattern::EnumVariant { arguments, .. } => arguments.iter().all(|argument| {
+            matches!(
+                argument,
+                WhenPattern::Identifier(_) | WhenPattern::Wildcard | WhenPattern::Else
+            )
+        }),
+        _ => false,
+    };
+    let is_catch_all = matches!(
+        last.pattern,
+        WhenPattern::Identifier(_) | WhenPattern::Wildcard | WhenPattern::Else
+    );
+    cases.iter().all(|case| case.guard.is_none())
+        && arms.iter().all(is_variant)
+        && (is_variant(last) || (is_catch_all && !arms.is_empty()))
+}
+
+/// The `T` of a `T?`, or the type itself if it isn't optional.
+fn unwrap_optional(ty: &TypeIdentifier) -> &TypeIdentifier {
+    match ty {
+        TypeIdentifier::Generic { name, args, .. } if name == "Optional" && args.len() == 1 => {
+            &args[0]
+        }
+        ty => ty,
+    }
+}
+
+fn type_name(ty: &TypeIdentifier) -> &str {
+    match ty {
+        TypeIdentifier::Simple { name, .. } | TypeIdentifier::Generic { name, .. } => name,
+    }
+}
+
+/// The type argument at `index` of `ty` if it is the generic type `name`,
+/// e.g., the `Task` of a `List<Task>`.
+fn type_argument<'t>(
+    ty: Option<&'t TypeIdentifier>,
+    name: &str,
+    index: usize,
+) -> Option<&'t TypeIdentifier> {
+    match ty? {
+        TypeIdentifier::Generic {
+            name: generic_name,
+            args,
+            ..
+        } if generic_name == name => args.get(index),
+        _ => None,
     }
 }
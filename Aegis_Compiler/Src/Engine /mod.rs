@@ -1,3 +1,34 @@
+//! The Engine turns a checked program into something that runs: the
+//! Lowerer translates it into AIL, the Aegis Intermediate Language, and the
+//! code generators translate it for a target platform. The VM runs AIL
+//! directly, for programs without a UI.
+
 pub mod ail;
-pub mod lowerer;
+#[path = "codegen android.rs"]
 pub mod codegen_android;
+pub mod lowerer;
+pub mod vm;
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// A file a code generator generated.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Artifact {
+    /// The path of the file, relative to the root of the generated project.
+    pub path: PathBuf,
+    pub contents: String,
+}
+
+impl Artifact {
+    /// Writes the file under the directory `output_dir`, creating the
+    /// directories it is in.
+    pub fn write(&self, output_dir: &Path) -> io::Result<()> {
+        let path = output_dir.join(&self.path);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, &self.contents)
+    }
+}
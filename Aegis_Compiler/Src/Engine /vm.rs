@@ -0,0 +1,1029 @@
+//! The AIL virtual machine, which runs lowered programs directly, without
+//! compiling them for a platform.
+//!
+//! The VM runs on a stack of values and a stack of call frames, one per
+//! running sequence. A module's top-level sequence runs in the global frame,
+//! so the variables it stores are globals; every other sequence stores into
+//! locals of its own. Lists, maps and contract instances are shared, so a
+//! change through one reference is seen through every other.
+
+use crate::engine::ail::{Instruction, InstructionSequence};
+use crate::error::RuntimeError;
+use crate::token::Span;
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{self, Write};
+use std::rc::Rc;
+use std::thread;
+use std::time::Duration;
+
+/// How deep calls may nest before the program is stopped, e.g., by a
+/// recursion that never ends.
+const MAX_FRAMES: usize = 10_000;
+
+/// The functions every program can call without defining them.
+const BUILTINS: &[&str] = &["print", "sleep"];
+
+/// A value a running program computes.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Number(f64),
+    String(String),
+    Boolean(bool),
+    Nothing,
+    List(Rc<RefCell<Vec<Value>>>),
+    /// The entries of a map, in the order they were added.
+    Map(Rc<RefCell<Vec<(Value, Value)>>>),
+    Contract(Rc<RefCell<Instance>>),
+    Enum(Rc<EnumValue>),
+    Closure(Rc<Closure>),
+}
+
+/// An instance of a contract.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Instance {
+    pub name: String,
+    pub fields: Vec<(String, Value)>,
+}
+
+/// A value of an enum, e.g., `LoadState.Failure("offline")`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnumValue {
+    pub enum_name: String,
+    pub variant: String,
+    pub payload: Vec<Value>,
+}
+
+/// A function as a value: a sequence with the values of the locals it
+/// captured when it was made.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Closure {
+    pub function: String,
+    pub captures: Vec<(String, Value)>,
+}
+
+impl Value {
+    pub fn list(elements: Vec<Value>) -> Value {
+        Value::List(Rc::new(RefCell::new(elements)))
+    }
+
+    /// The name of the value's type, for error messages, e.g., `number` or
+    /// the name of a contract.
+    pub fn type_name(&self) -> String {
+        match self {
+            Value::Number(_) => "number".to_string(),
+            Value::String(_) => "string".to_string(),
+            Value::Boolean(_) => "boolean".to_string(),
+            Value::Nothing => "nothing".to_string(),
+            Value::List(_) => "List".to_string(),
+            Value::Map(_) => "Map".to_string(),
+            Value::Contract(instance) => instance.borrow().name.clone(),
+            Value::Enum(value) => value.enum_name.clone(),
+            Value::Closure(_) => "function".to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn list(f: &mut fmt::Formatter<'_>, values: &[Value]) -> fmt::Result {
+            for (i, value) in values.iter().enumerate() {
+                if i > 0 {
+                    write!(f, ", ")?;
+                }
+                write!(f, "{}", value)?;
+            }
+            Ok(())
+        }
+        match self {
+            // Whole numbers are written without a fraction, e.g., `3`.
+            Value::Number(n) if n.fract() == 0.0 && n.abs() < 1e15 => write!(f, "{}", *n as i64),
+            Value::Number(n) => write!(f, "{}", n),
+            Value::String(s) => write!(f, "{}", s),
+            Value::Boolean(b) => write!(f, "{}", b),
+            Value::Nothing => write!(f, "nothing"),
+            Value::List(elements) => {
+                write!(f, "[")?;
+                list(f, &elements.borrow())?;
+                write!(f, "]")
+            }
+            Value::Map(entries) => {
+                write!(f, "{{")?;
+                for (i, (key, value)) in entries.borrow().iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}: {}", key, value)?;
+                }
+                write!(f, "}}")
+            }
+            Value::Contract(instance) => {
+                let instance = instance.borrow();
+                write!(f, "{}(", instance.name)?;
+                for (i, (name, value)) in instance.fields.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}: {}", name, value)?;
+                }
+                write!(f, ")")
+            }
+            Value::Enum(value) if value.payload.is_empty() => write!(f, "{}", value.variant),
+            Value::Enum(value) => {
+                write!(f, "{}(", value.variant)?;
+                list(f, &value.payload)?;
+                write!(f, ")")
+            }
+            Value::Closure(closure) => write!(f, "<function {}>", closure.function),
+        }
+    }
+}
+
+/// A sequence with the positions of its labels.
+struct Compiled {
+    sequence: InstructionSequence,
+    labels: HashMap<String, usize>,
+}
+
+/// A running sequence.
+struct Frame {
+    sequence: usize,
+    /// The position of the next instruction.
+    pc: usize,
+    locals: HashMap<String, Value>,
+    /// The height of the value stack below the frame's arguments, which it
+    /// is cut back to when the frame returns.
+    base: usize,
+    /// Whether the frame runs a module's top-level statements, whose
+    /// variables are globals.
+    is_global: bool,
+}
+
+/// Runs the AIL of a program. What the program prints goes to `W`, which is
+/// standard output unless the VM is made `with_output`.
+pub struct Vm<W: Write = io::Stdout> {
+    sequences: Rc<Vec<Compiled>>,
+    /// The sequences that can be called by name: every one but the
+    /// top-level sequences of modules.
+    functions: HashMap<String, usize>,
+    globals: HashMap<String, Value>,
+    stack: Vec<Value>,
+    frames: Vec<Frame>,
+    output: W,
+}
+
+impl Vm {
+    pub fn new(sequences: &[InstructionSequence]) -> Self {
+        Self::with_output(sequences, io::stdout())
+    }
+}
+
+impl<W: Write> Vm<W> {
+    pub fn with_output(sequences: &[InstructionSequence], output: W) -> Self {
+        let mut functions = HashMap::new();
+        for (i, sequence) in sequences.iter().enumerate() {
+            if !is_module_start(sequences, i) {
+                functions.insert(sequence.name.clone(), i);
+            }
+        }
+        let sequences = sequences
+            .iter()
+            .map(|sequence| Compiled {
+                labels: sequence
+                    .instructions
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(pc, instruction)| match instruction {
+                        Instruction::Label(label) => Some((label.clone(), pc)),
+                        _ => None,
+                    })
+                    .collect(),
+                sequence: sequence.clone(),
+            })
+            .collect();
+        Self {
+            sequences: Rc::new(sequences),
+            functions,
+            globals: HashMap::new(),
+            stack: Vec::new(),
+            frames: Vec::new(),
+            output,
+        }
+    }
+
+    /// Runs the top-level statements of every module, in the order the
+    /// modules were lowered.
+    pub fn run(&mut self) -> Result<(), RuntimeError> {
+        let starts: Vec<usize> = (0..self.sequences.len())
+            .filter(|&i| is_module_start_compiled(&self.sequences, i))
+            .collect();
+        for sequence in starts {
+            self.frames.push(Frame {
+                sequence,
+                pc: 0,
+                locals: HashMap::new(),
+                base: self.stack.len(),
+                is_global: true,
+            });
+            self.finish(0)?;
+            self.stack.clear();
+        }
+        Ok(())
+    }
+
+    /// Calls the function `name` with `args` and returns its result, e.g.,
+    /// after `run` has set up the globals it reads.
+    pub fn call(&mut self, name: &str, args: Vec<Value>) -> Result<Value, RuntimeError> {
+        let callee = match self.functions.get(name) {
+            Some(_) => Value::Closure(Rc::new(Closure {
+                function: name.to_string(),
+                captures: Vec::new(),
+            })),
+            None => {
+                return Err(RuntimeError::new(
+                    format!("there is no function '{}'", name),
+                    None,
+                    String::new(),
+                ))
+            }
+        };
+        self.call_value(&callee, args)
+    }
+
+    /// The value of a global variable.
+    pub fn global(&self, name: &str) -> Option<&Value> {
+        self.globals.get(name)
+    }
+
+    /// Gives back what the program printed.
+    pub fn into_output(self) -> W {
+        self.output
+    }
+
+    /// Runs until the frame above `depth` returns. On an error, every
+    /// frame above `depth` is dropped.
+    fn finish(&mut self, depth: usize) -> Result<(), RuntimeError> {
+        let result = self.execute(depth);
+        if result.is_err() {
+            let base = self
+                .frames
+                .get(depth)
+                .map_or(self.stack.len(), |frame| frame.base);
+            self.frames.truncate(depth);
+            self.stack.truncate(base);
+        }
+        result
+    }
+
+    fn execute(&mut self, depth: usize) -> Result<(), RuntimeError> {
+        let sequences = Rc::clone(&self.sequences);
+        while self.frames.len() > depth {
+            let frame = self.frames.last_mut().unwrap();
+            let compiled = &sequences[frame.sequence];
+            let Some(instruction) = compiled.sequence.instructions.get(frame.pc) else {
+                // A module's top-level sequence ends without a `Return`.
+                self.stack.push(Value::Nothing);
+                self.return_from_frame()?;
+                continue;
+            };
+            frame.pc += 1;
+            self.step(instruction, &compiled.labels)?;
+        }
+        Ok(())
+    }
+
+    fn step(
+        &mut self,
+        instruction: &Instruction,
+        labels: &HashMap<String, usize>,
+    ) -> Result<(), RuntimeError> {
+        match instruction {
+            Instruction::PushI64(n) => self.stack.push(Value::Number(*n as f64)),
+            Instruction::PushF64(n) => self.stack.push(Value::Number(*n)),
+            Instruction::PushBool(b) => self.stack.push(Value::Boolean(*b)),
+            Instruction::PushString(s) => self.stack.push(Value::String(s.clone())),
+            Instruction::PushNothing => self.stack.push(Value::Nothing),
+            Instruction::Pop => {
+                self.pop()?;
+            }
+            Instruction::Dup => {
+                let top = self.stack[self.stack_index(0)?].clone();
+                self.stack.push(top);
+            }
+            Instruction::Store(name) => {
+                let value = self.pop()?;
+                self.store(name, value);
+            }
+            Instruction::StoreLocal(name) => {
+                let value = self.pop()?;
+                let frame = self.frames.last_mut().unwrap();
+                frame.locals.insert(name.clone(), value);
+            }
+            Instruction::Load(name) => {
+                let value = self.load(name)?;
+                self.stack.push(value);
+            }
+            Instruction::Add => {
+                let (left, right) = self.pop_pair()?;
+                let sum = match (left, right) {
+                    (Value::Number(l), Value::Number(r)) => Value::Number(l + r),
+                    (Value::String(l), Value::String(r)) => Value::String(l + &r),
+                    (l, r) => return Err(self.operands_error("add", &l, &r)),
+                };
+                self.stack.push(sum);
+            }
+            Instruction::Subtract
+            | Instruction::Multiply
+            | Instruction::Divide
+            | Instruction::Modulo => {
+                let (left, right) = self.pop_pair()?;
+                let (Value::Number(l), Value::Number(r)) = (&left, &right) else {
+                    let verb = match instruction {
+                        Instruction::Subtract => "subtract",
+                        Instruction::Multiply => "multiply",
+                        _ => "divide",
+                    };
+                    return Err(self.operands_error(verb, &left, &right));
+                };
+                let result = match instruction {
+                    Instruction::Subtract => l - r,
+                    Instruction::Multiply => l * r,
+                    _ if *r == 0.0 => return Err(self.error("division by zero".to_string())),
+                    Instruction::Divide => l / r,
+                    _ => l % r,
+                };
+                self.stack.push(Value::Number(result));
+            }
+            Instruction::Negate => match self.pop()? {
+                Value::Number(n) => self.stack.push(Value::Number(-n)),
+                value => return Err(self.error(format!("can't negate a {}", value.type_name()))),
+            },
+            Instruction::Not => {
+                let value = self.pop_boolean()?;
+                self.stack.push(Value::Boolean(!value));
+            }
+            Instruction::LessThan | Instruction::GreaterThan => {
+                let (left, right) = self.pop_pair()?;
+                let ordering = self.compare(&left, &right)?;
+                let expected = match instruction {
+                    Instruction::LessThan => Ordering::Less,
+                    _ => Ordering::Greater,
+                };
+                self.stack.push(Value::Boolean(ordering == expected));
+            }
+            Instruction::Equals | Instruction::NotEqual => {
+                let (left, right) = self.pop_pair()?;
+                let equal = left == right;
+                self.stack.push(Value::Boolean(
+                    equal == matches!(instruction, Instruction::Equals),
+                ));
+            }
+            Instruction::Label(_) => {}
+            Instruction::Jump(label) => self.jump(labels, label),
+            Instruction::JumpIfFalse(label) => {
+                if !self.pop_boolean()? {
+                    self.jump(labels, label);
+                }
+            }
+            Instruction::JumpIfTrue(label) => {
+                if self.pop_boolean()? {
+                    self.jump(labels, label);
+                }
+            }
+            Instruction::JumpTable { cases, default } => {
+                let target = match self.pop()? {
+                    Value::Enum(value) => cases
+                        .iter()
+                        .find(|(variant, _)| *variant == value.variant)
+                        .map_or(default, |(_, label)| label),
+                    value => {
+                        return Err(self
+                            .error(format!("expected an enum value, got {}", value.type_name())))
+                    }
+                };
+                self.jump(labels, target);
+            }
+            Instruction::Call(name, count) => self.call_named(name, *count)?,
+            Instruction::CallMethod(name, count) => self.call_method(name, *count)?,
+            Instruction::CallOptionalMethod(name, count) => {
+                let args = self.pop_many(*count)?;
+                let receiver = self.pop()?;
+                let result = self.optional_method(receiver, name, args)?;
+                self.stack.push(result);
+            }
+            Instruction::CallValue(count) => {
+                let callee = self.stack.remove(self.stack_index(*count)?);
+                self.enter_value(&callee, *count)?;
+            }
+            Instruction::MakeClosure { function, captures } => {
+                let captures = captures
+                    .iter()
+                    .map(|name| Ok((name.clone(), self.load(name)?)))
+                    .collect::<Result<_, RuntimeError>>()?;
+                self.stack.push(Value::Closure(Rc::new(Closure {
+                    function: function.clone(),
+                    captures,
+                })));
+            }
+            Instruction::Return => self.return_from_frame()?,
+            Instruction::MakeList(count) => {
+                let elements = self.pop_many(*count)?;
+                self.stack.push(Value::list(elements));
+            }
+            Instruction::MakeMap(count) => {
+                let flat = self.pop_many(count * 2)?;
+                let mut entries: Vec<(Value, Value)> = Vec::new();
+                for pair in flat.chunks(2) {
+                    set_entry(&mut entries, pair[0].clone(), pair[1].clone());
+                }
+                self.stack.push(Value::Map(Rc::new(RefCell::new(entries))));
+            }
+            Instruction::Length => {
+                let value = self.pop()?;
+                let length = self.length(&value)?;
+                self.stack.push(Value::Number(length as f64));
+            }
+            Instruction::Index => {
+                let index = self.pop()?;
+                let list = self.pop()?;
+                let element = match (&list, &index) {
+                    (Value::List(elements), Value::Number(i)) => {
+                        let elements = elements.borrow();
+                        self.element(&elements, *i)?.clone()
+                    }
+                    _ => {
+                        return Err(self.error(format!(
+                            "can't index a {} with a {}",
+                            list.type_name(),
+                            index.type_name()
+                        )))
+                    }
+                };
+                self.stack.push(element);
+            }
+            Instruction::MakeContract { name, fields } => {
+                let values = self.pop_many(fields.len())?;
+                let instance = Instance {
+                    name: name.clone(),
+                    fields: fields.iter().cloned().zip(values).collect(),
+                };
+                self.stack
+                    .push(Value::Contract(Rc::new(RefCell::new(instance))));
+            }
+            Instruction::GetField(name) => self.get_field(name)?,
+            Instruction::SetField(name) => {
+                let value = self.pop()?;
+                match self.pop()? {
+                    Value::Contract(instance) => {
+                        let mut instance = instance.borrow_mut();
+                        match instance.fields.iter_mut().find(|(field, _)| field == name) {
+                            Some((_, field)) => *field = value,
+                            None => instance.fields.push((name.clone(), value)),
+                        }
+                    }
+                    target => {
+                        return Err(self.error(format!(
+                            "can't set the field '{}' of a {}",
+                            name,
+                            target.type_name()
+                        )))
+                    }
+                }
+            }
+            Instruction::MakeEnum {
+                enum_name,
+                variant,
+                arity,
+            } => {
+                let payload = self.pop_many(*arity)?;
+                self.stack.push(Value::Enum(Rc::new(EnumValue {
+                    enum_name: enum_name.clone(),
+                    variant: variant.clone(),
+                    payload,
+                })));
+            }
+            Instruction::IsVariant { enum_name, variant } => {
+                let is_variant = matches!(
+                    self.pop()?,
+                    Value::Enum(value) if value.enum_name == *enum_name && value.variant == *variant
+                );
+                self.stack.push(Value::Boolean(is_variant));
+            }
+            Instruction::GetPayload(index) => match self.pop()? {
+                Value::Enum(value) if *index < value.payload.len() => {
+                    self.stack.push(value.payload[*index].clone());
+                }
+                value => {
+                    return Err(self.error(format!("{} has no payload value {}", value, index)))
+                }
+            },
+        }
+        Ok(())
+    }
+
+    /// An error at the instruction the innermost frame just ran.
+    fn error(&self, message: String) -> RuntimeError {
+        let Some(frame) = self.frames.last() else {
+            return RuntimeError::new(message, None, String::new());
+        };
+        let sequence = &self.sequences[frame.sequence].sequence;
+        let span: Option<Span> = frame
+            .pc
+            .checked_sub(1)
+            .and_then(|pc| sequence.spans.get(pc))
+            .copied();
+        RuntimeError::new(message, span, sequence.module.clone())
+    }
+
+    fn operands_error(&self, verb: &str, left: &Value, right: &Value) -> RuntimeError {
+        self.error(format!(
+            "can't {} a {} and a {}",
+            verb,
+            left.type_name(),
+            right.type_name()
+        ))
+    }
+
+    /// The index in the stack of the value `depth` values below its top,
+    /// which must be one the current frame pushed.
+    fn stack_index(&self, depth: usize) -> Result<usize, RuntimeError> {
+        let base = self.frames.last().map_or(0, |frame| frame.base);
+        match self.stack.len().checked_sub(depth + 1) {
+            Some(index) if index >= base => Ok(index),
+            // Lowering pushes every value an instruction takes, so this is a
+            // bug in the compiler rather than in the program.
+            _ => {
+                Err(self.error("the stack has fewer values than an instruction takes".to_string()))
+            }
+        }
+    }
+
+    fn pop(&mut self) -> Result<Value, RuntimeError> {
+        self.stack_index(0)?;
+        Ok(self.stack.pop().unwrap())
+    }
+
+    /// Pops a left and a right operand.
+    fn pop_pair(&mut self) -> Result<(Value, Value), RuntimeError> {
+        let right = self.pop()?;
+        let left = self.pop()?;
+        Ok((left, right))
+    }
+
+    /// Pops `count` values, the first pushed first.
+    fn pop_many(&mut self, count: usize) -> Result<Vec<Value>, RuntimeError> {
+        if count == 0 {
+            return Ok(Vec::new());
+        }
+        let start = self.stack_index(count - 1)?;
+        Ok(self.stack.split_off(start))
+    }
+
+    fn pop_boolean(&mut self) -> Result<bool, RuntimeError> {
+        match self.pop()? {
+            Value::Boolean(b) => Ok(b),
+            value => Err(self.error(format!("expected a boolean, got {}", value.type_name()))),
+        }
+    }
+
+    fn jump(&mut self, labels: &HashMap<String, usize>, label: &str) {
+        if let (Some(frame), Some(pc)) = (self.frames.last_mut(), labels.get(label)) {
+            frame.pc = *pc;
+        }
+    }
+
+    /// Stores into the current frame's locals, or into a global if the
+    /// frame runs top-level statements or the name is only a global.
+    fn store(&mut self, name: &str, value: Value) {
+        let frame = self.frames.last_mut().unwrap();
+        if frame.is_global || (!frame.locals.contains_key(name) && self.globals.contains_key(name))
+        {
+            self.globals.insert(name.to_string(), value);
+        } else {
+            frame.locals.insert(name.to_string(), value);
+        }
+    }
+
+    /// Loads a local, a global, or a function as a closure.
+    fn load(&self, name: &str) -> Result<Value, RuntimeError> {
+        let frame = self.frames.last().unwrap();
+        if let Some(value) = frame.locals.get(name).or_else(|| self.globals.get(name)) {
+            return Ok(value.clone());
+        }
+        if self.functions.contains_key(name) || BUILTINS.contains(&name) {
+            return Ok(Value::Closure(Rc::new(Closure {
+                function: name.to_string(),
+                captures: Vec::new(),
+            })));
+        }
+        Err(self.error(format!("'{}' is not defined", name)))
+    }
+
+    fn length(&self, value: &Value) -> Result<usize, RuntimeError> {
+        match value {
+            Value::List(elements) => Ok(elements.borrow().len()),
+            Value::Map(entries) => Ok(entries.borrow().len()),
+            Value::String(s) => Ok(s.chars().count()),
+            value => Err(self.error(format!("a {} has no length", value.type_name()))),
+        }
+    }
+
+    fn element<'v>(&self, elements: &'v [Value], index: f64) -> Result<&'v Value, RuntimeError> {
+        if index >= 0.0 && index.fract() == 0.0 && (index as usize) < elements.len() {
+            Ok(&elements[index as usize])
+        } else {
+            Err(self.error(format!(
+                "index {} is out of range for a list of {}",
+                Value::Number(index),
+                elements.len()
+            )))
+        }
+    }
+
+    /// Orders two numbers, two strings, or two instances of a contract with
+    /// a `compare_to` method.
+    fn compare(&mut self, left: &Value, right: &Value) -> Result<Ordering, RuntimeError> {
+        match (left, right) {
+            (Value::Number(l), Value::Number(r)) => Ok(l.partial_cmp(r).unwrap_or(Ordering::Equal)),
+            (Value::String(l), Value::String(r)) => Ok(l.cmp(r)),
+            (Value::Contract(instance), _) => {
+                let method = format!("{}.compare_to", instance.borrow().name);
+                if !self.functions.contains_key(&method) {
+                    return Err(self.operands_error("compare", left, right));
+                }
+                let callee = Value::Closure(Rc::new(Closure {
+                    function: method,
+                    captures: Vec::new(),
+                }));
+                match self.call_value(&callee, vec![left.clone(), right.clone()])? {
+                    Value::Number(n) => Ok(n.partial_cmp(&0.0).unwrap_or(Ordering::Equal)),
+                    value => Err(self.error(format!(
+                        "compare_to returned a {}, not a number",
+                        value.type_name()
+                    ))),
+                }
+            }
+            _ => Err(self.operands_error("compare", left, right)),
+        }
+    }
+
+    /// Calls a function by name with the `count` arguments on top of the
+    /// stack: a variable holding a closure, then a sequence, then a built-in.
+    fn call_named(&mut self, name: &str, count: usize) -> Result<(), RuntimeError> {
+        let frame = self.frames.last().unwrap();
+        if let Some(callee @ Value::Closure(_)) =
+            frame.locals.get(name).or_else(|| self.globals.get(name))
+        {
+            let callee = callee.clone();
+            return self.enter_value(&callee, count);
+        }
+        if let Some(&sequence) = self.functions.get(name) {
+            return self.enter(sequence, count, Vec::new());
+        }
+        if BUILTINS.contains(&name) {
+            let args = self.pop_many(count)?;
+            let result = self.call_builtin(name, args)?;
+            self.stack.push(result);
+            return Ok(());
+        }
+        Err(self.error(format!("there is no function '{}'", name)))
+    }
+
+    /// Starts running the closure `callee` with the `count` arguments on top
+    /// of the stack. A built-in runs at once.
+    fn enter_value(&mut self, callee: &Value, count: usize) -> Result<(), RuntimeError> {
+        let Value::Closure(closure) = callee else {
+            return Err(self.error(format!("can't call a {}", callee.type_name())));
+        };
+        match self.functions.get(&closure.function) {
+            Some(&sequence) => self.enter(sequence, count, closure.captures.clone()),
+            None if BUILTINS.contains(&closure.function.as_str()) => {
+                let args = self.pop_many(count)?;
+                let result = self.call_builtin(&closure.function, args)?;
+                self.stack.push(result);
+                Ok(())
+            }
+            None => Err(self.error(format!("there is no function '{}'", closure.function))),
+        }
+    }
+
+    /// Pushes a frame for a sequence whose `count` arguments are on top of
+    /// the stack.
+    fn enter(
+        &mut self,
+        sequence: usize,
+        count: usize,
+        captures: Vec<(String, Value)>,
+    ) -> Result<(), RuntimeError> {
+        let target = &self.sequences[sequence].sequence;
+        if target.params.len() != count {
+            return Err(self.error(format!(
+                "'{}' takes {} arguments but got {}",
+                target.name,
+                target.params.len(),
+                count
+            )));
+        }
+        if self.frames.len() >= MAX_FRAMES {
+            return Err(self.error(format!(
+                "calls nested more than {} deep; does '{}' recurse forever?",
+                MAX_FRAMES, target.name
+            )));
+        }
+        let base = match count {
+            0 => self.stack.len(),
+            _ => self.stack_index(count - 1)?,
+        };
+        self.frames.push(Frame {
+            sequence,
+            pc: 0,
+            locals: captures.into_iter().collect(),
+            base,
+            is_global: false,
+        });
+        Ok(())
+    }
+
+    /// Pops the current frame, leaving the value on top of its stack as the
+    /// result of the call.
+    fn return_from_frame(&mut self) -> Result<(), RuntimeError> {
+        let value = self.pop()?;
+        if let Some(frame) = self.frames.pop() {
+            self.stack.truncate(frame.base);
+        }
+        self.stack.push(value);
+        Ok(())
+    }
+
+    /// Calls a closure from within the VM, e.g., the predicate of a
+    /// `filter`, and runs it to its result.
+    fn call_value(&mut self, callee: &Value, args: Vec<Value>) -> Result<Value, RuntimeError> {
+        let depth = self.frames.len();
+        let count = args.len();
+        self.stack.extend(args);
+        self.enter_value(callee, count)?;
+        if self.frames.len() > depth {
+            self.finish(depth)?;
+        }
+        self.pop()
+    }
+
+    fn call_builtin(&mut self, name: &str, args: Vec<Value>) -> Result<Value, RuntimeError> {
+        match name {
+            "print" => {
+                let line: Vec<String> = args.iter().map(Value::to_string).collect();
+                writeln!(self.output, "{}", line.join(" "))
+                    .map_err(|error| self.error(format!("could not print: {}", error)))?;
+                Ok(Value::Nothing)
+            }
+            "sleep" => match args.as_slice() {
+                [Value::Number(ms)] if *ms >= 0.0 => {
+                    thread::sleep(Duration::from_millis(*ms as u64));
+                    Ok(Value::Nothing)
+                }
+                _ => Err(self.error("sleep takes a number of milliseconds".to_string())),
+            },
+            _ => Err(self.error(format!("there is no function '{}'", name))),
+        }
+    }
+
+    /// Pushes the value of a field of the instance on top of the stack, or
+    /// starts computing a computed property.
+    fn get_field(&mut self, name: &str) -> Result<(), RuntimeError> {
+        let value = self.pop()?;
+        if let Value::Contract(instance) = &value {
+            let contract = {
+                let instance = instance.borrow();
+                if let Some((_, field)) = instance.fields.iter().find(|(field, _)| field == name) {
+                    self.stack.push(field.clone());
+                    return Ok(());
+                }
+                instance.name.clone()
+            };
+            if let Some(&property) = self.functions.get(&format!("{}.{}", contract, name)) {
+                self.stack.push(value);
+                return self.enter(property, 1, Vec::new());
+            }
+        }
+        Err(self.error(format!("a {} has no field '{}'", value.type_name(), name)))
+    }
+
+    /// Calls a method of the value below the `count` arguments on top of the
+    /// stack: a contract's own, or one the standard library provides.
+    fn call_method(&mut self, name: &str, count: usize) -> Result<(), RuntimeError> {
+        let receiver = self.stack[self.stack_index(count)?].clone();
+        if let Value::Contract(instance) = &receiver {
+            let method = format!("{}.{}", instance.borrow().name, name);
+            if let Some(&sequence) = self.functions.get(&method) {
+                return self.enter(sequence, count + 1, Vec::new());
+            }
+        }
+        let args = self.pop_many(count)?;
+        self.pop()?;
+        let result = self.builtin_method(&receiver, name, args)?;
+        self.stack.push(result);
+        Ok(())
+    }
+
+    /// Runs a method of the standard library's `List`, `Map` or `string`.
+    fn builtin_method(
+        &mut self,
+        receiver: &Value,
+        name: &str,
+        args: Vec<Value>,
+    ) -> Result<Value, RuntimeError> {
+        match receiver {
+            Value::String(s) => self.string_method(s, name, &args),
+            Value::List(elements) => self.list_method(elements, name, args),
+            Value::Map(entries) => self.map_method(entries, name, args),
+            value => Err(self.error(format!("a {} has no method '{}'", value.type_name(), name))),
+        }
+    }
+
+    /// Runs a method of an Optional, whose value is `receiver`.
+    fn optional_method(
+        &mut self,
+        receiver: Value,
+        name: &str,
+        args: Vec<Value>,
+    ) -> Result<Value, RuntimeError> {
+        let arg = |i: usize| args.get(i).cloned().unwrap_or(Value::Nothing);
+        match (receiver, name) {
+            (Value::Nothing, "isNothing") => Ok(Value::Boolean(true)),
+            (_, "isNothing") => Ok(Value::Boolean(false)),
+            (Value::Nothing, "orElse") => Ok(arg(0)),
+            (value, "orElse") => Ok(value),
+            (Value::Nothing, "map") => Ok(Value::Nothing),
+            (value, "map") => self.call_value(&arg(0), vec![value]),
+            (_, name) => Err(self.error(format!("an Optional has no method '{}'", name))),
+        }
+    }
+
+    fn string_method(&self, s: &str, name: &str, args: &[Value]) -> Result<Value, RuntimeError> {
+        let text = |i: usize| match args.get(i) {
+            Some(Value::String(s)) => Ok(s.as_str()),
+            _ => Err(self.error(format!("'{}' takes a string", name))),
+        };
+        Ok(match name {
+            "length" => Value::Number(s.chars().count() as f64),
+            "isEmpty" => Value::Boolean(s.is_empty()),
+            "trim" => Value::String(s.trim().to_string()),
+            "toUpperCase" => Value::String(s.to_uppercase()),
+            "toLowerCase" => Value::String(s.to_lowercase()),
+            "split" => Value::list(
+                s.split(text(0)?)
+                    .map(|part| Value::String(part.to_string()))
+                    .collect(),
+            ),
+            "contains" => Value::Boolean(s.contains(text(0)?)),
+            "startsWith" => Value::Boolean(s.starts_with(text(0)?)),
+            "endsWith" => Value::Boolean(s.ends_with(text(0)?)),
+            "replace" => Value::String(s.replace(text(0)?, text(1)?)),
+            _ => return Err(self.error(format!("a string has no method '{}'", name))),
+        })
+    }
+
+    fn list_method(
+        &mut self,
+        elements: &Rc<RefCell<Vec<Value>>>,
+        name: &str,
+        args: Vec<Value>,
+    ) -> Result<Value, RuntimeError> {
+        let arg = |i: usize| args.get(i).cloned().unwrap_or(Value::Nothing);
+        let index = |i: usize| match args.get(i) {
+            Some(Value::Number(n)) => *n,
+            _ => -1.0,
+        };
+        // A copy, so the elements can be passed to closures that change
+        // the list.
+        let snapshot = elements.borrow().clone();
+        Ok(match name {
+            "add" => {
+                elements.borrow_mut().push(arg(0));
+                Value::Nothing
+            }
+            "insert" => {
+                let at = index(0);
+                if at < 0.0 || at.fract() != 0.0 || at as usize > snapshot.len() {
+                    self.element(&snapshot, at)?;
+                }
+                elements.borrow_mut().insert(at as usize, arg(1));
+                Value::Nothing
+            }
+            "remove" => {
+                self.element(&snapshot, index(0))?;
+                elements.borrow_mut().remove(index(0) as usize)
+            }
+            "clear" => {
+                elements.borrow_mut().clear();
+                Value::Nothing
+            }
+            "get" => self
+                .element(&snapshot, index(0))
+                .map_or(Value::Nothing, Value::clone),
+            "first" => snapshot.first().cloned().unwrap_or(Value::Nothing),
+            "last" => snapshot.last().cloned().unwrap_or(Value::Nothing),
+            "length" => Value::Number(snapshot.len() as f64),
+            "isEmpty" => Value::Boolean(snapshot.is_empty()),
+            "contains" => Value::Boolean(snapshot.contains(&arg(0))),
+            "indexOf" => Value::Number(
+                snapshot
+                    .iter()
+                    .position(|element| *element == arg(0))
+                    .map_or(-1.0, |i| i as f64),
+            ),
+            "findIndex" | "find" | "filter" => {
+                let mut matches = Vec::new();
+                for (i, element) in snapshot.into_iter().enumerate() {
+                    let found = match self.call_value(&arg(0), vec![element.clone()])? {
+                        Value::Boolean(found) => found,
+                        value => {
+                            return Err(self.error(format!(
+                                "the function given to '{}' returned a {}, not a boolean",
+                                name,
+                                value.type_name()
+                            )))
+                        }
+                    };
+                    if found {
+                        match name {
+                            "findIndex" => return Ok(Value::Number(i as f64)),
+                            "find" => return Ok(element),
+                            _ => matches.push(element),
+                        }
+                    }
+                }
+                match name {
+                    "findIndex" => Value::Number(-1.0),
+                    "find" => Value::Nothing,
+                    _ => Value::list(matches),
+                }
+            }
+            "map" => {
+                let mut mapped = Vec::new();
+                for element in snapshot {
+                    mapped.push(self.call_value(&arg(0), vec![element])?);
+                }
+                Value::list(mapped)
+            }
+            "join" => {
+                let separator = arg(0).to_string();
+                let parts: Vec<String> = snapshot.iter().map(Value::to_string).collect();
+                Value::String(parts.join(&separator))
+            }
+            _ => return Err(self.error(format!("a List has no method '{}'", name))),
+        })
+    }
+
+    fn map_method(
+        &mut self,
+        entries: &Rc<RefCell<Vec<(Value, Value)>>>,
+        name: &str,
+        args: Vec<Value>,
+    ) -> Result<Value, RuntimeError> {
+        let key = args.first().cloned().unwrap_or(Value::Nothing);
+        let position = entries.borrow().iter().position(|(k, _)| *k == key);
+        Ok(match name {
+            "set" => {
+                let value = args.get(1).cloned().unwrap_or(Value::Nothing);
+                set_entry(&mut entries.borrow_mut(), key, value);
+                Value::Nothing
+            }
+            "remove" => match position {
+                Some(i) => entries.borrow_mut().remove(i).1,
+                None => Value::Nothing,
+            },
+            "clear" => {
+                entries.borrow_mut().clear();
+                Value::Nothing
+            }
+            "get" => position.map_or(Value::Nothing, |i| entries.borrow()[i].1.clone()),
+            "containsKey" => Value::Boolean(position.is_some()),
+            "keys" => Value::list(entries.borrow().iter().map(|(k, _)| k.clone()).collect()),
+            "values" => Value::list(entries.borrow().iter().map(|(_, v)| v.clone()).collect()),
+            "length" => Value::Number(entries.borrow().len() as f64),
+            "isEmpty" => Value::Boolean(entries.borrow().is_empty()),
+            _ => return Err(self.error(format!("a Map has no method '{}'", name))),
+        })
+    }
+}
+
+/// Sets the value of `key`, keeping its place if it is already there.
+fn set_entry(entries: &mut Vec<(Value, Value)>, key: Value, value: Value) {
+    match entries.iter_mut().find(|(k, _)| *k == key) {
+        Some((_, v)) => *v = value,
+        None => entries.push((key, value)),
+    }
+}
+
+/// Returns true if the sequence at `index` holds a module's top-level
+/// statements: the first sequence of each module.
+fn is_module_start(sequences: &[InstructionSequence], index: usize) -> bool {
+    index == 0 || sequences[index].module != sequences[index - 1].module
+}
+
+fn is_module_start_compiled(sequences: &[Compiled], index: usize) -> bool {
+    index == 0 || sequences[index].sequence.module != sequences[index - 1].sequence.module
+}
@@ -0,0 +1,210 @@
+//! This module declares the built-in UI components that can appear in a
+//! `show:` block. Each component lists the positional arguments, named
+//! properties and events it accepts and whether it can have children. The
+//! Guardian validates every UI element against this registry.
+
+use crate::guardian_types::Type;
+
+/// The kind of value a UI property accepts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PropType {
+    /// Anything that can be displayed as text: a string, number or boolean.
+    Text,
+    String,
+    Number,
+    Boolean,
+    /// A list of items to render, e.g., the items of a `list`.
+    List,
+    /// A `style { ... }` block.
+    Style,
+}
+
+impl PropType {
+    /// Describes the values this property accepts, for error messages.
+    pub fn describe(&self) -> &'static str {
+        match self {
+            PropType::Text => "a string, number or boolean",
+            PropType::String => "a string",
+            PropType::Number => "a number",
+            PropType::Boolean => "a boolean",
+            PropType::List => "a List",
+            PropType::Style => "a style",
+        }
+    }
+
+    /// Returns true if a value of type `ty` can be passed for this property.
+    pub fn accepts(&self, ty: &Type) -> bool {
+        matches!(
+            (self, ty),
+            (_, Type::Error)
+                | (PropType::Text, Type::String | Type::Number | Type::Boolean)
+                | (PropType::String, Type::String)
+                | (PropType::Number, Type::Number)
+                | (PropType::Boolean, Type::Boolean)
+                | (PropType::List, Type::List(_))
+        )
+    }
+}
+
+/// A positional argument or named property of a component.
+#[derive(Debug, Clone, Copy)]
+pub struct PropSpec {
+    pub name: &'static str,
+    pub ty: PropType,
+    /// Whether the property must always be given.
+    pub required: bool,
+}
+
+/// Which children a component may contain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChildRule {
+    /// A leaf component, e.g., `text`.
+    None,
+    /// A container with any number of children, e.g., `column`.
+    Any,
+}
+
+/// The schema of a built-in UI component.
+#[derive(Debug, Clone, Copy)]
+pub struct ComponentSchema {
+    pub name: &'static str,
+    /// The positional arguments, in order.
+    pub positional: &'static [PropSpec],
+    /// The named properties, e.g., `style`.
+    pub props: &'static [PropSpec],
+    /// The events the component can handle, e.g., `when_clicked`.
+    pub events: &'static [&'static str],
+    pub children: ChildRule,
+}
+
+impl ComponentSchema {
+    /// Looks up a named property of this component.
+    pub fn prop(&self, name: &str) -> Option<&PropSpec> {
+        self.props.iter().find(|prop| prop.name == name)
+    }
+}
+
+const fn prop(name: &'static str, ty: PropType) -> PropSpec {
+    PropSpec {
+        name,
+        ty,
+        required: false,
+    }
+}
+
+const fn required(name: &'static str, ty: PropType) -> PropSpec {
+    PropSpec {
+        name,
+        ty,
+        required: true,
+    }
+}
+
+const STYLE: PropSpec = prop("style", PropType::Style);
+
+/// Every built-in UI component.
+pub const COMPONENTS: &[ComponentSchema] = &[
+    ComponentSchema {
+        name: "column",
+        positional: &[],
+        props: &[STYLE],
+        events: &["when_clicked"],
+        children: ChildRule::Any,
+    },
+    ComponentSchema {
+        name: "row",
+        positional: &[],
+        props: &[STYLE],
+        events: &["when_clicked"],
+        children: ChildRule::Any,
+    },
+    ComponentSchema {
+        name: "text",
+        positional: &[required("content", PropType::Text)],
+        props: &[STYLE],
+        events: &["when_clicked"],
+        children: ChildRule::None,
+    },
+    ComponentSchema {
+        name: "button",
+        positional: &[required("label", PropType::Text)],
+        props: &[STYLE, prop("enabled", PropType::Boolean)],
+        events: &["when_clicked"],
+        children: ChildRule::None,
+    },
+    ComponentSchema {
+        name: "input",
+        positional: &[prop("placeholder", PropType::String)],
+        props: &[STYLE, prop("value", PropType::String)],
+        events: &["on_change", "on_submit"],
+        children: ChildRule::None,
+    },
+    ComponentSchema {
+        name: "image",
+        positional: &[required("source", PropType::String)],
+        props: &[STYLE, prop("description", PropType::String)],
+        events: &["when_clicked"],
+        children: ChildRule::None,
+    },
+    ComponentSchema {
+        name: "list",
+        positional: &[required("items", PropType::List)],
+        props: &[STYLE],
+        events: &[],
+        children: ChildRule::Any,
+    },
+    ComponentSchema {
+        name: "spacer",
+        positional: &[],
+        props: &[prop("size", PropType::Number)],
+        events: &[],
+        children: ChildRule::None,
+    },
+];
+
+/// Looks up a built-in component by name.
+pub fn component(name: &str) -> Option<&'static ComponentSchema> {
+    COMPONENTS.iter().find(|schema| schema.name == name)
+}
+
+/// Returns the candidate closest to `name`, if one is close enough to be a
+/// likely typo.
+pub fn closest_match<'a>(
+    name: &str,
+    candidates: impl IntoIterator<Item = &'a str>,
+) -> Option<&'a str> {
+    let max_distance = (name.chars().count() / 3).max(1);
+    candidates
+        .into_iter()
+        .map(|candidate| (edit_distance(name, candidate), candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// The edit distance between two strings, counting a swap of two adjacent
+/// characters, the most common typo, as one edit.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    // rows[i][j] is the distance between the first i chars of `a` and the
+    // first j chars of `b`.
+    let mut rows = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in rows.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, distance) in rows[0].iter_mut().enumerate() {
+        *distance = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let substitution = rows[i - 1][j - 1] + usize::from(a[i - 1] != b[j - 1]);
+            let mut distance = substitution.min(rows[i - 1][j] + 1).min(rows[i][j - 1] + 1);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                distance = distance.min(rows[i - 2][j - 2] + 1);
+            }
+            rows[i][j] = distance;
+        }
+    }
+    rows[a.len()][b.len()]
+}
@@ -0,0 +1,429 @@
+//! This module implements exhaustiveness and reachability checking for `when`
+//! expressions. It is a small version of the classic "pattern matrix"
+//! usefulness algorithm: every arm is lowered into a simplified `Pattern`, and
+//! the Guardian asks two questions of the resulting matrix:
+//!
+//! 1. Is there a value of the subject type that no arm matches? If so, the
+//!    `when` is non-exhaustive and we produce the missing patterns.
+//! 2. Can a given arm ever match, given the arms written before it? If not,
+//!    the arm is unreachable.
+
+use crate::ast::{Literal, WhenCase, WhenPattern};
+use crate::guardian_symbol_table::{SymbolKind, SymbolTable};
+use crate::guardian_types::Type;
+use crate::token::Span;
+use std::collections::HashMap;
+
+/// The payload types of each variant of an enum, keyed by variant name.
+type VariantTypes = HashMap<String, Vec<Type>>;
+
+/// A head constructor that a pattern can match on.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Constructor {
+    /// A variant of an enum, e.g., `LoadState::Success`.
+    Variant { enum_name: String, variant: String },
+    /// One of the two boolean values.
+    Boolean(bool),
+    /// A number or string literal. These belong to infinite domains, so they
+    /// can never exhaust a subject on their own.
+    Literal(String),
+    /// A range of numbers, e.g., `1..=10`. Ranges are only compared with each
+    /// other for equality, which keeps the check conservative: an overlapping
+    /// range is never reported as unreachable.
+    Range(String),
+}
+
+/// The simplified form of a `WhenPattern` used by the algorithm.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Pattern {
+    /// Matches any value, e.g., `else` or a binding.
+    Wildcard,
+    /// Matches values built with `Constructor`, whose fields match the sub-patterns.
+    Constructor(Constructor, Vec<Pattern>),
+}
+
+impl Pattern {
+    /// Renders the pattern using Aegis surface syntax, e.g., `LoadState::Success(_)`.
+    pub fn to_source(&self) -> String {
+        match self {
+            Pattern::Wildcard => "_".to_string(),
+            Pattern::Constructor(ctor, args) => {
+                let head = match ctor {
+                    Constructor::Variant { enum_name, variant } => {
+                        format!("{}::{}", enum_name, variant)
+                    }
+                    Constructor::Boolean(value) => value.to_string(),
+                    Constructor::Literal(text) | Constructor::Range(text) => text.clone(),
+                };
+                if args.iter().all(|arg| *arg == Pattern::Wildcard) {
+                    head
+                } else {
+                    let args: Vec<String> = args.iter().map(Pattern::to_source).collect();
+                    format!("{}({})", head, args.join(", "))
+                }
+            }
+        }
+    }
+}
+
+/// An arm of a `when` expression that can never be selected.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnreachableArm {
+    /// The index of the arm within `WhenExpression::cases`.
+    pub index: usize,
+    pub span: Span,
+    /// True if the arm follows an unguarded catch-all such as `else`.
+    pub after_catch_all: bool,
+}
+
+/// The result of checking a single `when` expression.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MatchReport {
+    /// The patterns that are not covered by any arm, rendered as source text.
+    /// An entry of `"else"` means only a catch-all arm can complete the match.
+    /// This list is meant to be turned directly into new arms by tooling.
+    pub missing_patterns: Vec<String>,
+    /// Arms whose patterns are already covered by earlier arms.
+    pub unreachable_arms: Vec<UnreachableArm>,
+}
+
+impl MatchReport {
+    pub fn is_exhaustive(&self) -> bool {
+        self.missing_patterns.is_empty()
+    }
+}
+
+/// Checks `when` arms against the type of their subject.
+pub struct MatchChecker<'a> {
+    /// Used to recover the declaration order of enum variants.
+    symbol_table: &'a SymbolTable,
+}
+
+impl<'a> MatchChecker<'a> {
+    pub fn new(symbol_table: &'a SymbolTable) -> Self {
+        Self { symbol_table }
+    }
+
+    /// Checks every arm of a `when` expression whose subject has type `subject_type`.
+    pub fn check(&self, cases: &[WhenCase], subject_type: &Type) -> MatchReport {
+        let mut report = MatchReport::default();
+        let types = vec![subject_type.clone()];
+        let mut rows: Vec<Vec<Pattern>> = Vec::new();
+        let mut seen_catch_all = false;
+
+        for (index, case) in cases.iter().enumerate() {
+            let row = vec![self.lower_pattern(&case.pattern, subject_type)];
+            if !self.is_useful(&rows, &row, &types) {
+                report.unreachable_arms.push(UnreachableArm {
+                    index,
+                    span: case.span,
+                    after_catch_all: seen_catch_all,
+                });
+            }
+            // A guarded arm may decline to match at runtime, so it never
+            // counts towards covering the values of its pattern.
+            if case.guard.is_some() {
+                continue;
+            }
+            if row[0] == Pattern::Wildcard {
+                seen_catch_all = true;
+            }
+            rows.push(row);
+        }
+
+        let mut missing = self.missing(&rows, &types);
+        // A `when` without any arms over an enum or boolean is reported as
+        // missing each of its constructors rather than a single `else`.
+        if missing == vec![vec![Pattern::Wildcard]] {
+            if let Some(signature) = self.constructors_of(subject_type) {
+                missing = signature
+                    .into_iter()
+                    .map(|(ctor, fields)| {
+                        vec![Pattern::Constructor(
+                            ctor,
+                            vec![Pattern::Wildcard; fields.len()],
+                        )]
+                    })
+                    .collect();
+            }
+        }
+        report.missing_patterns = missing
+            .into_iter()
+            .map(|witness| match &witness[0] {
+                Pattern::Wildcard => "else".to_string(),
+                pattern => pattern.to_source(),
+            })
+            .collect();
+        report
+    }
+
+    /// Lowers an AST pattern into the simplified form used by the algorithm.
+    fn lower_pattern(&self, pattern: &WhenPattern, ty: &Type) -> Pattern {
+        match pattern {
+            WhenPattern::Else | WhenPattern::Wildcard | WhenPattern::Identifier(_) => {
+                Pattern::Wildcard
+            }
+            WhenPattern::Literal(Literal::Boolean(value)) => {
+                Pattern::Constructor(Constructor::Boolean(*value), Vec::new())
+            }
+            WhenPattern::Literal(literal) => {
+                Pattern::Constructor(Constructor::Literal(literal_source(literal)), Vec::new())
+            }
+            WhenPattern::Range {
+                start,
+                end,
+                inclusive,
+                ..
+            } => {
+                let operator = if *inclusive { "..=" } else { ".." };
+                let text = format!(
+                    "{}{}{}",
+                    literal_source(start),
+                    operator,
+                    literal_source(end)
+                );
+                Pattern::Constructor(Constructor::Range(text), Vec::new())
+            }
+            WhenPattern::EnumVariant {
+                enum_name,
+                variant_name,
+                arguments,
+                ..
+            } => {
+                let fields = self
+                    .enum_variants(ty)
+                    .and_then(|(_, mut variants)| variants.remove(variant_name))
+                    .unwrap_or_default();
+                // A variant pattern without sub-patterns matches every payload.
+                let arguments = if arguments.len() == fields.len() {
+                    arguments
+                        .iter()
+                        .zip(&fields)
+                        .map(|(argument, field)| self.lower_pattern(argument, field))
+                        .collect()
+                } else {
+                    vec![Pattern::Wildcard; fields.len()]
+                };
+                Pattern::Constructor(
+                    Constructor::Variant {
+                        enum_name: enum_name.clone(),
+                        variant: variant_name.clone(),
+                    },
+                    arguments,
+                )
+            }
+        }
+    }
+
+    /// Returns every constructor of `ty` together with the types of its fields,
+    /// or `None` if the type has too many values to enumerate.
+    fn constructors_of(&self, ty: &Type) -> Option<Vec<(Constructor, Vec<Type>)>> {
+        match ty {
+            Type::Boolean => Some(vec![
+                (Constructor::Boolean(true), Vec::new()),
+                (Constructor::Boolean(false), Vec::new()),
+            ]),
+            Type::Enum { name, .. } => {
+                let (order, mut variants) = self.enum_variants(ty)?;
+                Some(
+                    order
+                        .into_iter()
+                        .filter_map(|variant| {
+                            let fields = variants.remove(&variant)?;
+                            Some((
+                                Constructor::Variant {
+                                    enum_name: name.clone(),
+                                    variant,
+                                },
+                                fields,
+                            ))
+                        })
+                        .collect(),
+                )
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns true if every constructor of the type appears in `heads`.
+    fn is_complete(signature: &[(Constructor, Vec<Type>)], heads: &[&Constructor]) -> bool {
+        signature.iter().all(|(ctor, _)| heads.contains(&ctor))
+    }
+
+    /// Collects the constructors appearing in the first column of the matrix.
+    fn head_constructors(rows: &[Vec<Pattern>]) -> Vec<&Constructor> {
+        let mut heads: Vec<&Constructor> = Vec::new();
+        for row in rows {
+            if let Pattern::Constructor(ctor, _) = &row[0] {
+                if !heads.contains(&ctor) {
+                    heads.push(ctor);
+                }
+            }
+        }
+        heads
+    }
+
+    /// Keeps the rows that can match `ctor` and replaces their head with its fields.
+    fn specialize(rows: &[Vec<Pattern>], ctor: &Constructor, arity: usize) -> Vec<Vec<Pattern>> {
+        rows.iter()
+            .filter_map(|row| {
+                let mut fields = match &row[0] {
+                    Pattern::Wildcard => vec![Pattern::Wildcard; arity],
+                    Pattern::Constructor(head, args) if head == ctor => args.clone(),
+                    Pattern::Constructor(..) => return None,
+                };
+                fields.extend_from_slice(&row[1..]);
+                Some(fields)
+            })
+            .collect()
+    }
+
+    /// Keeps the rows whose head is a wildcard and drops the head.
+    fn default_matrix(rows: &[Vec<Pattern>]) -> Vec<Vec<Pattern>> {
+        rows.iter()
+            .filter(|row| row[0] == Pattern::Wildcard)
+            .map(|row| row[1..].to_vec())
+            .collect()
+    }
+
+    /// Returns true if `row` matches some value that no row in `rows` matches.
+    fn is_useful(&self, rows: &[Vec<Pattern>], row: &[Pattern], types: &[Type]) -> bool {
+        if types.is_empty() {
+            return rows.is_empty();
+        }
+        match &row[0] {
+            Pattern::Constructor(ctor, args) => {
+                let field_types = self.field_types(&types[0], ctor, args.len());
+                let rows = Self::specialize(rows, ctor, args.len());
+                let mut next_row = args.clone();
+                next_row.extend_from_slice(&row[1..]);
+                self.is_useful(&rows, &next_row, &concat(field_types, &types[1..]))
+            }
+            Pattern::Wildcard => {
+                let heads = Self::head_constructors(rows);
+                match self.constructors_of(&types[0]) {
+                    Some(signature) if Self::is_complete(&signature, &heads) => {
+                        signature.into_iter().any(|(ctor, fields)| {
+                            let rows = Self::specialize(rows, &ctor, fields.len());
+                            let mut next_row = vec![Pattern::Wildcard; fields.len()];
+                            next_row.extend_from_slice(&row[1..]);
+                            self.is_useful(&rows, &next_row, &concat(fields, &types[1..]))
+                        })
+                    }
+                    _ => self.is_useful(&Self::default_matrix(rows), &row[1..], &types[1..]),
+                }
+            }
+        }
+    }
+
+    /// Returns example rows of patterns that are matched by no row of `rows`.
+    fn missing(&self, rows: &[Vec<Pattern>], types: &[Type]) -> Vec<Vec<Pattern>> {
+        if types.is_empty() {
+            return if rows.is_empty() {
+                vec![Vec::new()]
+            } else {
+                Vec::new()
+            };
+        }
+
+        let heads = Self::head_constructors(rows);
+        let signature = self.constructors_of(&types[0]);
+
+        if let Some(signature) = &signature {
+            if Self::is_complete(signature, &heads) {
+                let mut witnesses = Vec::new();
+                for (ctor, fields) in signature {
+                    let arity = fields.len();
+                    let rows = Self::specialize(rows, ctor, arity);
+                    for mut witness in self.missing(&rows, &concat(fields.clone(), &types[1..])) {
+                        let rest = witness.split_off(arity);
+                        let mut full = vec![Pattern::Constructor(ctor.clone(), witness)];
+                        full.extend(rest);
+                        witnesses.push(full);
+                    }
+                }
+                return witnesses;
+            }
+        }
+
+        let rest = self.missing(&Self::default_matrix(rows), &types[1..]);
+        let mut witnesses = Vec::new();
+        for witness in rest {
+            match &signature {
+                // If the column mentions some constructors, name the ones it is missing.
+                Some(signature) if !heads.is_empty() => {
+                    for (ctor, fields) in signature {
+                        if heads.contains(&ctor) {
+                            continue;
+                        }
+                        let mut full = vec![Pattern::Constructor(
+                            ctor.clone(),
+                            vec![Pattern::Wildcard; fields.len()],
+                        )];
+                        full.extend(witness.iter().cloned());
+                        witnesses.push(full);
+                    }
+                }
+                // Otherwise any value is missing, which is reported as a wildcard.
+                _ => {
+                    let mut full = vec![Pattern::Wildcard];
+                    full.extend(witness);
+                    witnesses.push(full);
+                }
+            }
+        }
+        witnesses
+    }
+
+    /// Returns the types of the fields of `ctor` when matched against `ty`.
+    fn field_types(&self, ty: &Type, ctor: &Constructor, arity: usize) -> Vec<Type> {
+        if let Constructor::Variant { variant, .. } = ctor {
+            if let Some((_, mut variants)) = self.enum_variants(ty) {
+                if let Some(fields) = variants.remove(variant) {
+                    if fields.len() == arity {
+                        return fields;
+                    }
+                }
+            }
+        }
+        vec![Type::Error; arity]
+    }
+
+    /// Returns the variant names of an enum type in declaration order, along
+    /// with the payload types of each variant. The symbol table is preferred
+    /// over `ty` itself, because a recursive payload type only names its enum.
+    fn enum_variants(&self, ty: &Type) -> Option<(Vec<String>, VariantTypes)> {
+        let Type::Enum { name, variants } = ty else {
+            return None;
+        };
+        if let Some(symbol) = self.symbol_table.resolve(name) {
+            if let (SymbolKind::Enum { variants: order }, Type::Enum { variants, .. }) =
+                (symbol.kind, symbol.ty)
+            {
+                return Some((order, variants));
+            }
+        }
+        let mut order: Vec<String> = variants.keys().cloned().collect();
+        order.sort();
+        Some((order, variants.clone()))
+    }
+}
+
+/// Builds a new list of column types from the fields of a constructor and the
+/// remaining columns.
+fn concat(mut head: Vec<Type>, rest: &[Type]) -> Vec<Type> {
+    head.extend_from_slice(rest);
+    head
+}
+
+/// Renders a literal pattern back into source form.
+fn literal_source(literal: &Literal) -> String {
+    match literal {
+        Literal::Number(n) => n.clone(),
+        Literal::String(s) => format!("\"{}\"", s),
+        Literal::Boolean(b) => b.to_string(),
+        Literal::Nothing => "nothing".to_string(),
+        Literal::List(_) => "[...]".to_string(),
+        Literal::Map(_) => "{...}".to_string(),
+    }
+}
@@ -3,21 +3,67 @@
 //! type-check the instantiation of enum variants with their associated data.
 
 use crate::ast::*;
-use crate::error::{SemanticError, SemanticErrorType};
-use crate::guardian_symbol_table::{SymbolKind, SymbolTable};
-use crate::guardian_types::Type;
+use crate::error::{Fix, SemanticError, SemanticErrorType, TextEdit};
+use crate::guardian_components::{self, closest_match, ChildRule, ComponentSchema, PropType};
+use crate::guardian_exhaustiveness::MatchChecker;
+use crate::guardian_reactivity::{collect_reads, DependencyGraph, Dependent, Mutation};
+use crate::guardian_stdlib::{self, MethodSignature};
+use crate::guardian_symbol_table::{Symbol, SymbolKind, SymbolTable};
+use crate::guardian_types::{style_property, StyleLiteral, StyleValueKind, Type, STYLE_PROPERTIES};
+use crate::guardian_unify::{Reason, Unifier, UnifyError};
 use crate::token::Span;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 /// The Guardian walks the AST to find semantic errors and build metadata.
-#[derive(Default)]
 pub struct Guardian {
     /// A list of semantic errors found during analysis.
     pub errors: Vec<SemanticError>,
     /// The symbol table for managing scopes and declared identifiers.
     symbol_table: SymbolTable,
-    // Note: Additional context fields like dependency_graph, is_in_async_context,
-    // and current_return_type will be added when implementing those features.
+    /// Top-level names registered by the collection pass whose definitions
+    /// haven't been checked yet.
+    pending_declarations: HashSet<String>,
+    /// Whether the code being checked runs inside an `async` function or
+    /// event handler, where `await` is allowed.
+    is_in_async_context: bool,
+    /// The spans of UI event handlers that contain an `await`. These are
+    /// inferred to be async, and the Engine runs them in a coroutine.
+    pub async_event_handlers: Vec<Span>,
+    /// Which UI properties and derived values read which tracked variables.
+    pub dependency_graph: DependencyGraph,
+    /// The map literals checked as initializers of contracts, by their span,
+    /// with the contract each one builds.
+    pub contract_literals: HashMap<Span, String>,
+    /// The type of the value each method is called on, by the span of the
+    /// call, so the Engine can tell the `map` of an Optional from that of a
+    /// List.
+    pub method_receivers: HashMap<Span, Type>,
+    /// The spans of the arguments checked as lambdas of an implicit `it`,
+    /// as in `tasks.filter(it.done)`, so the Engine makes closures of the
+    /// same ones.
+    pub implicit_lambdas: HashSet<Span>,
+    /// Whether the statements being checked run once when executed, as in
+    /// function bodies and event handlers, rather than declaring reactive values.
+    is_in_imperative_block: bool,
+    /// The bindings of the type variables created for types that aren't known
+    /// yet, like the element type of an empty `[]`.
+    unifier: Unifier,
+    /// The declared return type of the function whose body is being checked,
+    /// with the reason it is expected, for checking `return` values.
+    current_return_type: Option<(Type, Reason)>,
+    /// The source of the program being checked, if known, for fixes that
+    /// need its indentation.
+    source: Option<String>,
+    /// The private declarations of the modules this one imports, with the
+    /// module that declares each and its file, to report naming one as
+    /// private.
+    private_names: HashMap<String, (String, String)>,
+}
+
+impl Default for Guardian {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Guardian {
@@ -26,15 +72,143 @@ impl Guardian {
         Self {
             errors: Vec::new(),
             symbol_table: SymbolTable::default(),
+            pending_declarations: HashSet::new(),
+            is_in_async_context: false,
+            async_event_handlers: Vec::new(),
+            dependency_graph: DependencyGraph::default(),
+            contract_literals: HashMap::new(),
+            method_receivers: HashMap::new(),
+            implicit_lambdas: HashSet::new(),
+            is_in_imperative_block: false,
+            unifier: Unifier::default(),
+            current_return_type: None,
+            source: None,
+            private_names: HashMap::new(),
+        }
+        .with_builtins()
+    }
+
+    /// Gives the Guardian the source of the program it will check, so the
+    /// fixes it attaches to errors can match the indentation around them.
+    pub fn with_source(mut self, source: &str) -> Self {
+        self.source = Some(source.to_string());
+        self
+    }
+
+    /// Defines the functions the language provides out of the box.
+    fn with_builtins(mut self) -> Self {
+        // `sleep(ms)` suspends the current async function for `ms` milliseconds.
+        let sleep_params = vec![Type::Number];
+        let sleep_return = Box::new(Type::Future(Box::new(Type::Nothing)));
+        self.symbol_table.define(
+            "sleep".to_string(),
+            Type::Function {
+                params: sleep_params.clone(),
+                return_type: sleep_return.clone(),
+            },
+            SymbolKind::Function {
+                param_types: sleep_params,
+                return_type: sleep_return,
+                bounds: Vec::new(),
+            },
+        );
+
+        // `print(values...)` writes its arguments, whatever their types.
+        let print_return = Box::new(Type::Nothing);
+        self.symbol_table.define(
+            "print".to_string(),
+            Type::Function {
+                params: Vec::new(),
+                return_type: print_return.clone(),
+            },
+            SymbolKind::VariadicFunction {
+                return_type: print_return,
+            },
+        );
+
+        // `Comparable` values can be ordered with `<` and `>`. Numbers and
+        // strings implement it; contracts do so with `compare_to`.
+        let compare_to = MethodSignature::new(
+            "compare_to",
+            vec![Type::Generic("Self".to_string())],
+            Type::Number,
+        );
+        self.symbol_table.define(
+            "Comparable".to_string(),
+            Type::Interface("Comparable".to_string()),
+            SymbolKind::Interface {
+                methods: HashMap::from([(compare_to.name.clone(), compare_to)]),
+            },
+        );
+        self
+    }
+
+    /// Looks up a name in the outermost scope, e.g., a top-level declaration
+    /// after the program has been checked.
+    pub fn lookup(&self, name: &str) -> Option<Symbol> {
+        self.symbol_table.resolve(name)
+    }
+
+    /// Declares a name imported from another module, before the program is
+    /// checked. Returns false if the name is already declared.
+    pub fn import_symbol(&mut self, symbol: Symbol) -> bool {
+        self.symbol_table
+            .define(symbol.name, symbol.ty, symbol.kind)
+    }
+
+    /// Makes declarations of other modules known without importing their
+    /// names, like the contracts that imported functions return, so values of
+    /// them can be used. The module's own declarations take precedence.
+    pub fn import_dependencies(&mut self, symbols: Vec<Symbol>, from: &Guardian) {
+        let mut dependencies = SymbolTable::new_hidden();
+        for symbol in symbols {
+            dependencies.define(symbol.name, symbol.ty, symbol.kind);
+        }
+        self.symbol_table.enclose_in(dependencies);
+        self.unifier.add_implementations_from(&from.unifier);
+    }
+
+    /// Records the names `module`, in the file `path`, declares without
+    /// `public`, so naming one here is reported as private rather than
+    /// undefined.
+    pub fn import_private_names<'a>(
+        &mut self,
+        module: &str,
+        path: &str,
+        names: impl IntoIterator<Item = &'a str>,
+    ) {
+        for name in names {
+            self.private_names
+                .insert(name.to_string(), (module.to_string(), path.to_string()));
         }
     }
 
     /// The main entry point for semantic analysis.
-    /// It iterates through all top-level definitions in the program.
+    /// Top-level declarations are order-independent, so the program is
+    /// checked in several passes over its definitions.
     pub fn check_program(&mut self, program: &Program) {
+        // 1. Register the name of every contract, enum, interface, function
+        //    and app, so that any definition can refer to any other one.
         for def in &program.definitions {
+            self.collect_declaration(def);
+        }
+
+        // 2. Resolve the shape of every type and the signature of every function.
+        for def in program.definitions.iter().filter(|d| is_declaration(d)) {
             self.check_definition(def);
         }
+        self.check_contract_cycles(program);
+        self.check_conformances(program);
+
+        // 3. Check statements, app bodies and function bodies in source order.
+        for def in &program.definitions {
+            match def {
+                Definition::Function(func_def) => self.check_function_body(func_def),
+                Definition::Contract(contract_def) => self.check_member_bodies(contract_def),
+                _ if !is_declaration(def) => self.check_definition(def),
+                _ => {}
+            }
+        }
     }
 
     /// Dispatches to the correct checking function based on the definition type.
@@ -45,24 +219,175 @@ impl Guardian {
             Definition::Function(func_def) => self.check_function_definition(func_def),
             Definition::Statement(stmt) => self.check_statement(stmt),
             Definition::App(app_def) => self.check_app_definition(app_def),
+            Definition::Style(style_def) => self.check_style_definition(style_def),
+            Definition::Interface(interface_def) => self.check_interface_definition(interface_def),
+        }
+    }
+
+    /// Registers a placeholder symbol for a top-level definition, to be
+    /// completed once the definition itself is checked. Duplicates are left
+    /// for the definition's own check to report.
+    fn collect_declaration(&mut self, def: &Definition) {
+        let (name, ty, kind) = match def {
+            Definition::Contract(contract_def) => (
+                &contract_def.name,
+                Type::Custom(contract_def.name.clone()),
+                if contract_def.generic_params.is_empty() {
+                    SymbolKind::Contract {
+                        fields: HashMap::new(),
+                        defaults: HashSet::new(),
+                        properties: HashMap::new(),
+                        methods: HashMap::new(),
+                    }
+                } else {
+                    SymbolKind::GenericContract {
+                        params: contract_def.generic_params.clone(),
+                        fields: HashMap::new(),
+                        defaults: HashSet::new(),
+                        properties: HashMap::new(),
+                        methods: HashMap::new(),
+                    }
+                },
+            ),
+            Definition::Interface(interface_def) => (
+                &interface_def.name,
+                Type::Interface(interface_def.name.clone()),
+                SymbolKind::Interface {
+                    methods: HashMap::new(),
+                },
+            ),
+            Definition::Enum(enum_def) => (
+                &enum_def.name,
+                Type::Enum {
+                    name: enum_def.name.clone(),
+                    variants: HashMap::new(),
+                },
+                SymbolKind::Enum {
+                    variants: enum_def.variants.iter().map(|v| v.name.clone()).collect(),
+                },
+            ),
+            Definition::Function(func_def) => (
+                &func_def.name,
+                Type::Error,
+                SymbolKind::Function {
+                    param_types: Vec::new(),
+                    return_type: Box::new(Type::Error),
+                    bounds: Vec::new(),
+                },
+            ),
+            Definition::App(app_def) => (
+                &app_def.name,
+                Type::Custom(format!("App<{}>", app_def.name)),
+                SymbolKind::Type,
+            ),
+            Definition::Style(style_def) => (&style_def.name, Type::Style, SymbolKind::Style),
+            Definition::Statement(_) => return,
+        };
+        if self.symbol_table.define(name.clone(), ty, kind) {
+            self.pending_declarations.insert(name.clone());
+        }
+    }
+
+    /// Defines a top-level symbol, completing its placeholder from the
+    /// collection pass if there is one. Returns false on a redeclaration.
+    fn define_declaration(&mut self, name: &str, ty: Type, kind: SymbolKind) -> bool {
+        if self.pending_declarations.remove(name) {
+            self.symbol_table.replace(name, ty, kind)
+        } else {
+            self.symbol_table.define(name.to_string(), ty, kind)
+        }
+    }
+
+    /// Reports contracts that contain themselves, directly or through other
+    /// contracts, without a `List` or `Optional` in between. Such a contract
+    /// would need an infinite amount of storage.
+    fn check_contract_cycles(&mut self, program: &Program) {
+        let contracts: HashMap<&str, &ContractDefinition> = program
+            .definitions
+            .iter()
+            .filter_map(|def| match def {
+                Definition::Contract(contract_def) => {
+                    Some((contract_def.name.as_str(), contract_def))
+                }
+                _ => None,
+            })
+            .collect();
+
+        let mut finished = HashSet::new();
+        for def in &program.definitions {
+            if let Definition::Contract(contract_def) = def {
+                let mut path = Vec::new();
+                self.find_contract_cycles(&contract_def.name, &contracts, &mut path, &mut finished);
+            }
         }
     }
 
+    /// Depth-first search for contract cycles. `path` holds the contracts
+    /// currently being visited, along with the field that was followed out of each.
+    fn find_contract_cycles(
+        &mut self,
+        name: &str,
+        contracts: &HashMap<&str, &ContractDefinition>,
+        path: &mut Vec<(String, String)>,
+        finished: &mut HashSet<String>,
+    ) {
+        if finished.contains(name) {
+            return;
+        }
+        let Some(contract_def) = contracts.get(name) else {
+            return;
+        };
+        let fields = match self.symbol_table.resolve(name).map(|symbol| symbol.kind) {
+            Some(SymbolKind::Contract { fields, .. })
+            | Some(SymbolKind::GenericContract { fields, .. }) => fields,
+            _ => return,
+        };
+
+        for field in &contract_def.fields {
+            // Only a direct field of a contract type is stored inline; lists,
+            // optionals and enums all break the cycle.
+            let Some(Type::Custom(target)) = fields.get(&field.name) else {
+                continue;
+            };
+            if !contracts.contains_key(target.as_str()) {
+                continue;
+            }
+            path.push((name.to_string(), field.name.clone()));
+            if let Some(start) = path.iter().position(|(contract, _)| contract == target) {
+                let cycle: Vec<String> = path[start..]
+                    .iter()
+                    .map(|(contract, field)| format!("{}.{}", contract, field))
+                    .collect();
+                self.errors.push(SemanticError::new(
+                    format!(
+                        "Contract '{}' has infinite size: {} -> {}. Wrap one of these fields in `Optional<...>` or `List<...>` to break the cycle",
+                        target,
+                        cycle.join(" -> "),
+                        target
+                    ),
+                    contracts[target.as_str()].span,
+                    SemanticErrorType::RecursiveContract,
+                ));
+            } else {
+                self.find_contract_cycles(target, contracts, path, finished);
+            }
+            path.pop();
+        }
+        finished.insert(name.to_string());
+    }
+
     /// The main entry point for type inference and checking of expressions.
     /// This function recursively determines the type of every expression,
     /// reporting errors for invalid operations.
     pub fn infer_expression_type(&mut self, expr: &Expression) -> Type {
         match expr {
-            Expression::Literal(Literal::Number(_), _) => Type::Number,
-            Expression::Literal(Literal::String(_), _) => Type::String,
-            Expression::Literal(Literal::Boolean(_), _) => Type::Boolean,
-            Expression::Literal(Literal::Nothing, _) => Type::Nothing,
+            Expression::Literal(literal, _) => self.infer_literal_type(literal),
 
-            Expression::Identifier(name, _) => {
-                if let Some(symbol) = self.symbol_table.resolve(name) {
-                    symbol.ty
+            Expression::Identifier(name, span) => {
+                if let Some(symbol) = self.symbol_table.resolve_visible(name) {
+                    self.unifier.resolve(&symbol.ty)
                 } else {
-                    // Error: Undefined identifier
+                    self.report_undefined(format!("Undefined variable '{}'", name), name, *span);
                     Type::Error
                 }
             }
@@ -102,8 +427,42 @@ impl Guardian {
                         }
                     }
                 }
-                // (Fallback to existing logic for regular function calls)
-                Type::Error
+                match &call_expr.function {
+                    Expression::MemberAccess(member_access) => {
+                        self.check_method_call(call_expr, member_access)
+                    }
+                    Expression::SafeMemberAccess(member_access) => {
+                        self.check_safe_method_call(call_expr, member_access)
+                    }
+                    _ => self.check_function_call(call_expr),
+                }
+            }
+
+            Expression::Await(await_expr) => {
+                let awaited_type = self.infer_expression_type(&await_expr.expression);
+                if !self.is_in_async_context {
+                    self.errors.push(
+                        SemanticError::new(
+                            "`await` can only be used inside an async function or event handler"
+                                .to_string(),
+                            await_expr.span,
+                            SemanticErrorType::AwaitOutsideAsync,
+                        )
+                        .with_suggestions(vec!["mark the enclosing function `async`".to_string()]),
+                    );
+                }
+                match awaited_type {
+                    Type::Future(inner) => *inner,
+                    Type::Error => Type::Error,
+                    other => {
+                        self.errors.push(SemanticError::new(
+                            format!("Cannot await a value of type {}; expected a Future", other),
+                            await_expr.span,
+                            SemanticErrorType::TypeMismatch,
+                        ));
+                        Type::Error
+                    }
+                }
             }
 
             // UPDATED: MemberAccess now also handles enum instantiation.
@@ -131,60 +490,92 @@ impl Guardian {
                         }
                     }
                 }
-                // (Fallback to existing logic for object.property access)
-                Type::Error
+                let object_type = self.infer_expression_type(&member_access.object);
+                self.check_field_access(&object_type, member_access)
+            }
+
+            // `user?.name` is `nothing` when `user` is, so the field's type becomes optional.
+            Expression::SafeMemberAccess(member_access) => {
+                let object_type = self.infer_expression_type(&member_access.object);
+                let inner = match object_type {
+                    Type::Optional(inner) => *inner,
+                    other => other,
+                };
+                let field_type = self.check_field_access(&inner, member_access);
+                optional_of(field_type)
             }
 
+            Expression::If(if_expr) => self.check_if_expression(if_expr),
+
+            Expression::Lambda(lambda) => self.check_lambda(lambda, None),
+
             // UPDATED: `when` expression checking is now more powerful.
             Expression::When(when_expr) => {
                 let subject_type = self.infer_expression_type(&when_expr.value);
                 let mut case_types = Vec::new();
+                // Once an unguarded `is nothing` arm has been seen, later arms
+                // only see values that aren't `nothing`.
+                let mut nothing_handled = false;
 
                 for case in &when_expr.cases {
-                    match &case.pattern {
-                        WhenPattern::Literal(literal) => {
-                            let literal_type = self.infer_literal_type(literal);
-                            if literal_type != subject_type {
-                                // Error: Pattern type mismatch.
-                            }
-                        }
-                        // NEW: Check enum variant patterns.
-                        WhenPattern::EnumVariant {
-                            enum_name,
-                            variant_name,
-                            ..
-                        } => {
-                            // Check that the pattern's enum type matches the subject's enum type.
-                            if let Type::Enum {
-                                name: subject_name, ..
-                            } = &subject_type
-                            {
-                                if subject_name != enum_name {
-                                    // Error: Pattern is for a different enum type.
-                                    return Type::Error;
-                                }
-                            } else {
-                                // Error: Subject is not an enum type.
-                                return Type::Error;
-                            }
-                            // Check that the variant name is valid for this enum.
-                            if let Some(symbol) = self.symbol_table.resolve(enum_name) {
-                                if let SymbolKind::Enum { variants } = &symbol.kind {
-                                    if !variants.contains(variant_name) {
-                                        // Error: Invalid variant name for enum.
-                                        return Type::Error;
-                                    }
-                                }
-                            }
+                    let arm_subject_type = match &subject_type {
+                        Type::Optional(inner) if nothing_handled => (**inner).clone(),
+                        other => other.clone(),
+                    };
+                    // Each arm gets its own scope for the names its pattern binds.
+                    let mut bindings = Vec::new();
+                    if !self.check_pattern(
+                        &case.pattern,
+                        &arm_subject_type,
+                        case.span,
+                        &mut bindings,
+                    ) {
+                        return Type::Error;
+                    }
+                    let narrowed = match &when_expr.value {
+                        Expression::Identifier(name, _) if nothing_handled => {
+                            self.narrow_to_non_nothing(name)
                         }
-                        WhenPattern::Identifier(_) => {
-                            // For now, assume identifier patterns are valid
+                        _ => false,
+                    };
+                    self.enter_scope();
+                    for (name, ty) in bindings {
+                        let kind = SymbolKind::Variable {
+                            is_tracked: false,
+                            declared_at: None,
+                        };
+                        if !self.symbol_table.define(name.clone(), ty, kind) {
+                            self.errors.push(SemanticError::new(
+                                format!("Name '{}' is bound more than once in this pattern", name),
+                                case.span,
+                                SemanticErrorType::DuplicateDeclaration,
+                            ));
                         }
-                        WhenPattern::Else => {
-                            // Else patterns are always valid
+                    }
+                    if let Some(guard) = &case.guard {
+                        let guard_type = self.infer_expression_type(guard);
+                        if !self.types_are_compatible(&Type::Boolean, &guard_type) {
+                            self.errors.push(SemanticError::new(
+                                format!("`when` guard must be a boolean, found {}", guard_type),
+                                case.span,
+                                SemanticErrorType::TypeMismatch,
+                            ));
                         }
                     }
                     case_types.push(self.infer_expression_type(&case.body));
+                    self.exit_scope();
+                    if narrowed {
+                        self.exit_scope();
+                    }
+                    if case.guard.is_none()
+                        && matches!(case.pattern, WhenPattern::Literal(Literal::Nothing))
+                    {
+                        nothing_handled = true;
+                    }
+                }
+
+                if subject_type != Type::Error {
+                    self.check_when_exhaustiveness(when_expr, &subject_type);
                 }
 
                 // Ensure all cases return the same type.
@@ -196,17 +587,7 @@ impl Guardian {
                 }
             }
 
-            Expression::Infix(infix_expr) => {
-                let left_type = self.infer_expression_type(&infix_expr.left);
-                let right_type = self.infer_expression_type(&infix_expr.right);
-                // Check if the operation is valid for the inferred types.
-                if left_type == Type::Number && right_type == Type::Number {
-                    Type::Number
-                } else {
-                    // If not, push a SemanticError to the `errors` vector.
-                    Type::Error
-                }
-            }
+            Expression::Infix(infix_expr) => self.check_infix_expression(infix_expr),
 
             // ... cases for all other expression types ...
             _ => Type::Error,
@@ -225,197 +606,2265 @@ impl Guardian {
     // /// Recursively checks a UI tree, validates components, and builds the dependency graph.
     // fn check_ui_node(&mut self, node: &UiNode) { ... }
 
-    /// UPDATED: Validates an enum definition, resolving and storing associated types.
-    pub fn check_enum_definition(&mut self, enum_def: &EnumDefinition) {
-        let mut resolved_variants = HashMap::new();
-
-        // 1. Resolve the type names for each variant.
-        for variant in &enum_def.variants {
-            let mut resolved_types = Vec::new();
-            for type_name in &variant.types {
-                // (Conceptual) Look up the type name in the symbol table to get its `Type`.
-                // For this example, we'll assume it's a `Custom` type.
-                resolved_types.push(Type::Custom(type_name.clone()));
+    /// Checks an infix operation and returns the type of its result.
+    fn check_infix_expression(&mut self, infix_expr: &InfixExpression) -> Type {
+        let mut left_type = self.infer_expression_type(&infix_expr.left);
+        let mut right_type = self.infer_expression_type(&infix_expr.right);
+        if infix_expr.operator != InfixOperator::Elvis
+            && (self.unifier.is_unbound(&left_type) || self.unifier.is_unbound(&right_type))
+        {
+            // An operand whose type isn't known yet, like a lambda parameter,
+            // takes the type of the other operand, or `number` for arithmetic.
+            let reason = Reason::new("the other operand", infix_expr.span);
+            let _ = self.unifier.unify(&left_type, &right_type, &reason);
+            if self.unifier.is_unbound(&left_type)
+                && !matches!(
+                    infix_expr.operator,
+                    InfixOperator::Equal | InfixOperator::NotEqual
+                )
+            {
+                let reason = Reason::new("the arithmetic operator", infix_expr.span);
+                let _ = self.unifier.unify(&Type::Number, &left_type, &reason);
             }
-            resolved_variants.insert(variant.name.clone(), resolved_types);
+            left_type = self.unifier.resolve(&left_type);
+            right_type = self.unifier.resolve(&right_type);
         }
-
-        // 2. Define the enum type itself in the current scope.
-        let enum_type = Type::Enum {
-            name: enum_def.name.clone(),
-            variants: resolved_variants.clone(),
-        };
-        let enum_kind = SymbolKind::Enum {
-            variants: resolved_variants.keys().cloned().collect(),
+        let result = match infix_expr.operator {
+            InfixOperator::Elvis => {
+                return self.check_elvis_expression(infix_expr, left_type, right_type)
+            }
+            InfixOperator::Plus
+            | InfixOperator::Minus
+            | InfixOperator::Multiply
+            | InfixOperator::Divide
+            | InfixOperator::Modulo => match (&left_type, &right_type) {
+                (Type::Number, Type::Number) => Some(Type::Number),
+                (Type::String, Type::String) if infix_expr.operator == InfixOperator::Plus => {
+                    Some(Type::String)
+                }
+                _ => None,
+            },
+            // Values of the same type can be ordered if it is `Comparable`.
+            InfixOperator::LessThan | InfixOperator::GreaterThan => (left_type == right_type
+                && self.unifier.implements(&left_type, "Comparable"))
+            .then_some(Type::Boolean),
+            // `x != nothing` is how an optional is tested, so values can be
+            // compared whenever one type fits the other.
+            InfixOperator::Equal | InfixOperator::NotEqual => (self
+                .types_are_compatible(&left_type, &right_type)
+                || self.types_are_compatible(&right_type, &left_type))
+            .then_some(Type::Boolean),
+            InfixOperator::And | InfixOperator::Or => {
+                (left_type == Type::Boolean && right_type == Type::Boolean).then_some(Type::Boolean)
+            }
         };
-        self.symbol_table
-            .define(enum_def.name.clone(), enum_type, enum_kind);
+        match result {
+            Some(ty) => ty,
+            None if left_type == Type::Error || right_type == Type::Error => Type::Error,
+            None => {
+                self.errors.push(SemanticError::new(
+                    format!(
+                        "Operator `{}` can't be applied to {} and {}",
+                        infix_expr.operator, left_type, right_type
+                    ),
+                    infix_expr.span,
+                    SemanticErrorType::InvalidOperation,
+                ));
+                Type::Error
+            }
+        }
     }
 
-    /// Helper method to infer the type of a literal value.
-    fn infer_literal_type(&self, literal: &Literal) -> Type {
-        match literal {
-            Literal::Number(_) => Type::Number,
-            Literal::String(_) => Type::String,
-            Literal::Boolean(_) => Type::Boolean,
-            Literal::Nothing => Type::Nothing,
-            Literal::List(_) => Type::Error, // Simplified for now
-            Literal::Map(_) => Type::Error,  // Simplified for now
+    /// Checks a `value ?: default` expression. The default is used when the
+    /// value is `nothing`, so the result is no longer optional unless the
+    /// default itself is.
+    fn check_elvis_expression(
+        &mut self,
+        infix_expr: &InfixExpression,
+        left_type: Type,
+        right_type: Type,
+    ) -> Type {
+        match left_type {
+            Type::Optional(inner) => {
+                if self.types_are_compatible(&inner, &right_type) {
+                    *inner
+                } else if self.types_are_compatible(&Type::Optional(inner.clone()), &right_type) {
+                    Type::Optional(inner)
+                } else {
+                    self.errors.push(SemanticError::new(
+                        format!(
+                            "Default value of type {} doesn't match {}",
+                            right_type, inner
+                        ),
+                        infix_expr.right.span(),
+                        SemanticErrorType::TypeMismatch,
+                    ));
+                    *inner
+                }
+            }
+            Type::Nothing => right_type,
+            Type::Error => Type::Error,
+            other => {
+                self.errors.push(
+                    SemanticError::new(
+                        format!(
+                            "The left side of `?:` has type {}, which is never nothing",
+                            other
+                        ),
+                        infix_expr.span,
+                        SemanticErrorType::InvalidOperation,
+                    )
+                    .with_suggestions(vec!["remove the `?:` default".to_string()]),
+                );
+                other
+            }
         }
     }
 
-    /// UPDATED: This function now handles generic parameters in contract definitions.
-    pub fn check_contract_definition(&mut self, contract_def: &ContractDefinition) {
-        // 1. Create a temporary scope for resolving generic types within the contract.
-        let mut contract_scope = SymbolTable::new_enclosed(self.symbol_table.clone());
-        for param in &contract_def.generic_params {
-            // Register each generic parameter as a `Generic` type within this scope.
-            let generic_type = Type::Generic(param.clone());
-            contract_scope.define(param.clone(), generic_type, SymbolKind::Type);
+    /// Checks an `if` expression. In the branch where a condition like
+    /// `x != nothing` holds, `x` is narrowed from `T?` to `T`.
+    fn check_if_expression(&mut self, if_expr: &IfExpression) -> Type {
+        let condition_type = self.infer_expression_type(&if_expr.condition);
+        if !self.types_are_compatible(&Type::Boolean, &condition_type) {
+            self.errors.push(SemanticError::new(
+                format!("`if` condition must be a boolean, found {}", condition_type),
+                if_expr.condition.span(),
+                SemanticErrorType::TypeMismatch,
+            ));
         }
 
-        // 2. Resolve the types of the fields using the temporary scope.
-        let mut resolved_fields = HashMap::new();
-        for field in &contract_def.fields {
-            let field_type = self.resolve_type_identifier(&field.type_ann, &contract_scope);
-            resolved_fields.insert(field.name.clone(), field_type);
-        }
+        let nothing_check = nothing_check(&if_expr.condition);
+        let then_type = self.infer_narrowed(
+            &if_expr.then_branch,
+            nothing_check
+                .filter(|(_, holds)| *holds)
+                .map(|(name, _)| name),
+        );
+        let Some(else_branch) = &if_expr.else_branch else {
+            return Type::Nothing;
+        };
+        let else_type = self.infer_narrowed(
+            else_branch,
+            nothing_check
+                .filter(|(_, holds)| !holds)
+                .map(|(name, _)| name),
+        );
 
-        // 3. Define the contract in the main scope.
-        // The symbol will note that this is a generic type definition.
-        let contract_kind = if contract_def.generic_params.is_empty() {
-            // Regular contract
-            SymbolKind::Contract {
-                fields: resolved_fields,
+        match (then_type, else_type) {
+            (Type::Nothing, Type::Nothing) => Type::Nothing,
+            (Type::Nothing, other) | (other, Type::Nothing) => optional_of(other),
+            (then_type, else_type) if self.types_are_compatible(&then_type, &else_type) => {
+                then_type
             }
-        } else {
-            // Generic contract
-            SymbolKind::GenericContract {
-                params: contract_def.generic_params.clone(),
-                fields: resolved_fields,
+            (then_type, else_type) if self.types_are_compatible(&else_type, &then_type) => {
+                else_type
+            }
+            (then_type, else_type) => {
+                self.errors.push(SemanticError::new(
+                    format!(
+                        "`if` branches have different types: {} and {}",
+                        then_type, else_type
+                    ),
+                    if_expr.span,
+                    SemanticErrorType::TypeMismatch,
+                ));
+                Type::Error
             }
-        };
-
-        // The "type" here is a placeholder, as it can't be a concrete type until instantiated.
-        let contract_type = Type::Custom(contract_def.name.clone());
-
-        if !self
-            .symbol_table
-            .define(contract_def.name.clone(), contract_type, contract_kind)
-        {
-            self.errors.push(SemanticError::new(
-                format!("Contract '{}' is already declared", contract_def.name),
-                contract_def.span,
-                SemanticErrorType::DuplicateDeclaration,
-            ));
         }
     }
 
-    /// Checks a function definition, manages its scope, and validates return types.
-    pub fn check_function_definition(&mut self, func: &FunctionDefinition) {
-        // For now, just add the function to the symbol table
-        // TODO: Implement full function body checking
-        let param_types: Vec<Type> = func
-            .parameters
-            .iter()
-            .map(|p| self.resolve_type_from_string(&p.type_annotation))
-            .collect();
+    /// Infers the type of `expr` with the optional variable `narrowed` known
+    /// not to be `nothing`.
+    fn infer_narrowed(&mut self, expr: &Expression, narrowed: Option<&str>) -> Type {
+        let narrowed = narrowed.is_some_and(|name| self.narrow_to_non_nothing(name));
+        let ty = self.infer_expression_type(expr);
+        if narrowed {
+            self.exit_scope();
+        }
+        ty
+    }
 
-        let return_type = if let Some(ret_type) = &func.return_type {
-            Box::new(self.resolve_type_from_string(ret_type))
-        } else {
-            Box::new(Type::Nothing)
+    /// Opens a scope in which the optional variable `name` has its non-optional
+    /// type. Tracked state can change at any time, so only plain bindings are
+    /// narrowed. Returns false, without opening a scope, if nothing was narrowed.
+    fn narrow_to_non_nothing(&mut self, name: &str) -> bool {
+        let Some(symbol) = self.symbol_table.resolve(name) else {
+            return false;
         };
-
-        let func_type = Type::Function {
-            params: param_types.clone(),
-            return_type: return_type.clone(),
+        let (
+            Type::Optional(inner),
+            SymbolKind::Variable {
+                is_tracked: false, ..
+            }
+            | SymbolKind::Parameter,
+        ) = (symbol.ty, &symbol.kind)
+        else {
+            return false;
         };
+        self.enter_scope();
+        self.symbol_table
+            .define(name.to_string(), *inner, symbol.kind.clone());
+        true
+    }
 
-        let func_kind = SymbolKind::Function {
-            param_types,
-            return_type,
+    /// Checks a lambda. When it is passed where a function type is expected,
+    /// its parameters take their types from that function type, so
+    /// `tasks.filter(task => task.is_done)` needs no annotations. Otherwise an
+    /// unannotated parameter gets a type variable, bound by how it is used.
+    fn check_lambda(
+        &mut self,
+        lambda: &LambdaExpression,
+        expected: Option<(&Type, &Reason)>,
+    ) -> Type {
+        let (expected_params, expected_return) = match expected.map(|(ty, _)| ty) {
+            Some(Type::Function {
+                params,
+                return_type,
+            }) => (Some(params.clone()), Some((**return_type).clone())),
+            _ => (None, None),
         };
+        if let Some(expected_params) = &expected_params {
+            if expected_params.len() != lambda.parameters.len() {
+                self.errors.push(SemanticError::new(
+                    format!(
+                        "Expected a function of {} parameter(s), but this lambda takes {}",
+                        expected_params.len(),
+                        lambda.parameters.len()
+                    ),
+                    lambda.span,
+                    SemanticErrorType::ArityMismatch,
+                ));
+                return Type::Error;
+            }
+        }
 
-        if !self
-            .symbol_table
-            .define(func.name.clone(), func_type, func_kind)
-        {
-            self.errors.push(SemanticError::new(
-                format!("Function '{}' is already declared", func.name),
-                func.span,
-                SemanticErrorType::DuplicateDeclaration,
-            ));
+        self.enter_scope();
+        let mut param_types = Vec::new();
+        for (index, param) in lambda.parameters.iter().enumerate() {
+            let expected_param = expected_params.as_ref().map(|params| params[index].clone());
+            let param_type = match (&param.type_annotation, expected_param) {
+                (Some(annotation), Some(expected_param)) => {
+                    let annotated = self.resolve_annotation(annotation);
+                    let reason = expected
+                        .map(|(_, reason)| reason.clone())
+                        .unwrap_or_else(|| Reason::new("the expected function type", lambda.span));
+                    if let Err(error) = self.unifier.unify(&annotated, &expected_param, &reason) {
+                        self.report_unify_error(*error, &reason, param.span, "Type mismatch");
+                    }
+                    annotated
+                }
+                (Some(annotation), None) => self.resolve_annotation(annotation),
+                (None, Some(expected_param)) => expected_param,
+                (None, None) => self.unifier.fresh(),
+            };
+            self.symbol_table.define(
+                param.name.clone(),
+                param_type.clone(),
+                SymbolKind::Parameter,
+            );
+            param_types.push(param_type);
         }
+        let return_type = match (expected_return, expected) {
+            (Some(expected_return), Some((_, reason))) => {
+                self.check_expression_against(&lambda.body, &expected_return, reason)
+            }
+            _ => self.infer_expression_type(&lambda.body),
+        };
+        self.exit_scope();
+
+        self.unifier.resolve(&Type::Function {
+            params: param_types,
+            return_type: Box::new(return_type),
+        })
     }
 
-    /// Checks a statement
-    pub fn check_statement(&mut self, stmt: &Statement) {
-        match stmt {
-            Statement::Let(let_stmt) => self.check_let_statement(let_stmt),
-            _ => {
-                // Other statement types not implemented yet
+    /// Returns the fields of a contract, with the type parameters of a generic
+    /// contract replaced by the given type arguments.
+    fn contract_fields(&self, contract_name: &str, args: &[Type]) -> Option<HashMap<String, Type>> {
+        match self.symbol_table.resolve(contract_name)?.kind {
+            SymbolKind::Contract { fields, .. } => Some(fields),
+            SymbolKind::GenericContract { params, fields, .. } => {
+                let bindings: HashMap<String, Type> =
+                    params.into_iter().zip(args.iter().cloned()).collect();
+                Some(
+                    fields
+                        .into_iter()
+                        .map(|(name, ty)| (name, guardian_stdlib::substitute(&ty, &bindings)))
+                        .collect(),
+                )
             }
+            _ => None,
         }
     }
 
-    /// Checks an app definition
-    pub fn check_app_definition(&mut self, app: &AppDefinition) {
-        // For now, just register the app
-        // TODO: Check app body and show blocks
-        let app_type = Type::Custom(format!("App<{}>", app.name));
-        let app_kind = SymbolKind::Type;
-
-        if !self
-            .symbol_table
-            .define(app.name.clone(), app_type, app_kind)
-        {
-            self.errors.push(SemanticError::new(
-                format!("App '{}' is already declared", app.name),
-                app.span,
-                SemanticErrorType::DuplicateDeclaration,
-            ));
+    /// Returns the type of a computed property of a contract, with the type
+    /// parameters of a generic contract replaced by the given type arguments.
+    fn contract_property(&self, contract_name: &str, args: &[Type], name: &str) -> Option<Type> {
+        match self.symbol_table.resolve(contract_name)?.kind {
+            SymbolKind::Contract { properties, .. } => properties.get(name).cloned(),
+            SymbolKind::GenericContract {
+                params, properties, ..
+            } => {
+                let bindings: HashMap<String, Type> =
+                    params.into_iter().zip(args.iter().cloned()).collect();
+                let ty = properties.get(name)?;
+                Some(guardian_stdlib::substitute(ty, &bindings))
+            }
+            _ => None,
         }
     }
 
-    /// Checks a let statement including contract initializers
-    pub fn check_let_statement(&mut self, let_stmt: &LetStatement) {
-        // Infer the type of the value expression
-        let value_type = self.infer_expression_type(&let_stmt.value);
-
-        // If there's a type annotation, validate it matches
-        if let Some(expected_type_str) = &let_stmt.type_annotation {
-            let expected_type = self.resolve_type_from_string(expected_type_str);
-
-            // Special handling for contract initializers (map literals)
-            if let Expression::Literal(Literal::Map(map_literal), _) = &let_stmt.value {
-                if let Type::Custom(contract_name) = &expected_type {
-                    self.check_contract_initialization(contract_name, map_literal, &let_stmt.span);
-                }
-            } else {
-                // Regular type checking
-                if !self.types_are_compatible(&expected_type, &value_type) {
-                    self.errors.push(SemanticError::new(
+    /// Checks access to a field or computed property of a contract value,
+    /// e.g., `task.title`.
+    fn check_field_access(
+        &mut self,
+        object_type: &Type,
+        member_access: &MemberAccessExpression,
+    ) -> Type {
+        let name = &member_access.property;
+        match object_type {
+            Type::Error => Type::Error,
+            Type::Dynamic => Type::Dynamic,
+            Type::Optional(_) => {
+                self.errors.push(
+                    SemanticError::new(
                         format!(
-                            "Type mismatch: expected {:?}, found {:?}",
-                            expected_type, value_type
+                            "Value of type {} may be nothing, so '{}' can't be accessed directly",
+                            object_type, name
                         ),
-                        let_stmt.span,
-                        SemanticErrorType::TypeMismatch,
-                    ));
+                        member_access.span,
+                        SemanticErrorType::InvalidMemberAccess,
+                    )
+                    .with_suggestions(vec![
+                        format!("use `?.{}` to access it safely", name),
+                        "check that the value is not `nothing` first".to_string(),
+                    ]),
+                );
+                Type::Error
+            }
+            Type::Custom(contract_name) | Type::GenericInstance(contract_name, _) => {
+                let args = match object_type {
+                    Type::GenericInstance(_, args) => args.as_slice(),
+                    _ => &[],
+                };
+                let Some(fields) = self.contract_fields(contract_name, args) else {
+                    return Type::Error;
+                };
+                if let Some(field_type) = fields.get(name) {
+                    return field_type.clone();
+                }
+                if let Some(property_type) = self.contract_property(contract_name, args, name) {
+                    return property_type;
                 }
+                let suggestion = closest_match(name, fields.keys().map(String::as_str));
+                self.errors.push(
+                    SemanticError::new(
+                        format!("Contract '{}' has no field '{}'", contract_name, name),
+                        member_access.span,
+                        SemanticErrorType::InvalidMemberAccess,
+                    )
+                    .with_suggestions(
+                        suggestion
+                            .map(|s| vec![format!("did you mean '{}'?", s)])
+                            .unwrap_or_default(),
+                    ),
+                );
+                Type::Error
+            }
+            other => {
+                self.errors.push(SemanticError::new(
+                    format!("Type {} has no field '{}'", other, name),
+                    member_access.span,
+                    SemanticErrorType::InvalidMemberAccess,
+                ));
+                Type::Error
             }
         }
+    }
 
-        // Register the variable
+    /// Checks a call to a function value and returns the type of its result.
+    /// Calling an `async` function produces a `Future` of its declared return type.
+    fn check_function_call(&mut self, call_expr: &CallExpression) -> Type {
+        if let Expression::Identifier(name, span) = &call_expr.function {
+            match self
+                .symbol_table
+                .resolve_visible(name)
+                .map(|symbol| symbol.kind)
+            {
+                Some(SymbolKind::VariadicFunction { return_type }) => {
+                    for arg in &call_expr.arguments {
+                        self.infer_expression_type(arg);
+                    }
+                    return *return_type;
+                }
+                None => {
+                    for arg in &call_expr.arguments {
+                        self.infer_expression_type(arg);
+                    }
+                    self.report_undefined(format!("Undefined function '{}'", name), name, *span);
+                    return Type::Error;
+                }
+                Some(_) => {}
+            }
+        }
+        let callee_type = self.infer_expression_type(&call_expr.function);
+        let (params, return_type) = match callee_type {
+            Type::Function {
+                params,
+                return_type,
+            } => (params, return_type),
+            Type::Error => return Type::Error,
+            other => {
+                self.errors.push(SemanticError::new(
+                    format!("Cannot call a value of type {}", other),
+                    call_expr.span,
+                    SemanticErrorType::InvalidOperation,
+                ));
+                return Type::Error;
+            }
+        };
+
+        if call_expr.arguments.len() != params.len() {
+            self.errors.push(SemanticError::new(
+                format!(
+                    "Expected {} argument(s), found {}",
+                    params.len(),
+                    call_expr.arguments.len()
+                ),
+                call_expr.span,
+                SemanticErrorType::ArityMismatch,
+            ));
+            return Type::Error;
+        }
+        let callee = match &call_expr.function {
+            Expression::Identifier(name, _) => format!("'{}'", name),
+            _ => "the function".to_string(),
+        };
+        // The function's type parameters, like the `T` of `first<T>`, get
+        // fresh type variables that the arguments bind.
+        let mut instances = HashMap::new();
+        let params: Vec<Type> = params
+            .iter()
+            .map(|param| self.unifier.instantiate(param, &mut instances))
+            .collect();
+        let return_type = self.unifier.instantiate(&return_type, &mut instances);
+        for (index, (arg, expected_ty)) in call_expr.arguments.iter().zip(&params).enumerate() {
+            let reason = Reason::new(
+                format!("parameter {} of {}", index + 1, callee),
+                call_expr.function.span(),
+            );
+            self.check_expression_against(arg, expected_ty, &reason);
+        }
+        let bounds = match &call_expr.function {
+            Expression::Identifier(name, _) => match self.symbol_table.resolve(name) {
+                Some(Symbol {
+                    kind: SymbolKind::Function { bounds, .. },
+                    ..
+                }) => bounds,
+                _ => Vec::new(),
+            },
+            _ => Vec::new(),
+        };
+        self.check_bounds(&bounds, &instances, &callee, call_expr.span);
+        self.unifier.resolve(&return_type)
+    }
+
+    /// Reports type arguments that don't implement the bounds of their type
+    /// parameters, e.g., a `Task` passed where `T: Comparable` is required.
+    /// `instances` maps each type parameter to the type variable its type
+    /// argument was inferred into.
+    fn check_bounds(
+        &mut self,
+        bounds: &[(String, String)],
+        instances: &HashMap<String, Type>,
+        callee: &str,
+        span: Span,
+    ) {
+        for (param, interface) in bounds {
+            let Some(arg) = instances.get(param) else {
+                continue;
+            };
+            if self.unifier.is_unbound(arg) || self.unifier.implements(arg, interface) {
+                continue;
+            }
+            self.errors.push(SemanticError::new(
+                format!(
+                    "Type {} doesn't implement '{}', which type parameter '{}' of {} requires",
+                    self.unifier.resolve(arg),
+                    interface,
+                    param,
+                    callee
+                ),
+                span,
+                SemanticErrorType::MissingImplementation,
+            ));
+        }
+    }
+
+    /// Returns every method of `receiver`: those defined on a contract, those
+    /// an interface or a type parameter's bound requires, or the built-in ones.
+    fn methods_of(&self, receiver: &Type) -> Option<Vec<MethodSignature>> {
+        let self_binding = || HashMap::from([("Self".to_string(), receiver.clone())]);
+        let (methods, bindings) = match receiver {
+            Type::Custom(name) | Type::GenericInstance(name, _) => {
+                match self.symbol_table.resolve(name)?.kind {
+                    SymbolKind::Contract { methods, .. } => (methods, HashMap::new()),
+                    SymbolKind::GenericContract {
+                        params, methods, ..
+                    } => {
+                        let args = match receiver {
+                            Type::GenericInstance(_, args) => args.clone(),
+                            _ => Vec::new(),
+                        };
+                        (methods, params.into_iter().zip(args).collect())
+                    }
+                    _ => return None,
+                }
+            }
+            Type::Interface(name) => (self.interface_methods(name)?, self_binding()),
+            Type::Param(name) => {
+                let bound = self.unifier.param_bound(name)?;
+                (self.interface_methods(bound)?, self_binding())
+            }
+            _ => return guardian_stdlib::methods_of(receiver),
+        };
+        let mut methods: Vec<MethodSignature> = methods
+            .into_values()
+            .map(|method| method.substitute(&bindings))
+            .collect();
+        methods.sort_by(|a, b| a.name.cmp(&b.name));
+        Some(methods)
+    }
+
+    /// The methods of the interface `name`.
+    fn interface_methods(&self, name: &str) -> Option<HashMap<String, MethodSignature>> {
+        match self.symbol_table.resolve(name)?.kind {
+            SymbolKind::Interface { methods } => Some(methods),
+            _ => None,
+        }
+    }
+
+    /// Checks a call to a built-in method, e.g., `tasks.add(task)`, and returns
+    /// the type of its result. Calling a mutating method on tracked state is
+    /// recorded as a mutation of that state.
+    fn check_method_call(
+        &mut self,
+        call_expr: &CallExpression,
+        member_access: &MemberAccessExpression,
+    ) -> Type {
+        let receiver_type = self.infer_expression_type(&member_access.object);
+        self.check_method_on(&receiver_type, call_expr, member_access)
+    }
+
+    /// Checks a safe-navigation call, e.g., `name?.trim()`, which calls the
+    /// method on the value inside an optional and is `nothing` when it is.
+    fn check_safe_method_call(
+        &mut self,
+        call_expr: &CallExpression,
+        member_access: &MemberAccessExpression,
+    ) -> Type {
+        let receiver_type = match self.infer_expression_type(&member_access.object) {
+            Type::Optional(inner) => *inner,
+            other => other,
+        };
+        optional_of(self.check_method_on(&receiver_type, call_expr, member_access))
+    }
+
+    /// Checks a method call against the built-in methods of `receiver_type`.
+    fn check_method_on(
+        &mut self,
+        receiver_type: &Type,
+        call_expr: &CallExpression,
+        member_access: &MemberAccessExpression,
+    ) -> Type {
+        let name = &member_access.property;
+        let infer_arguments = |guardian: &mut Self| {
+            for arg in &call_expr.arguments {
+                guardian.infer_expression_type(arg);
+            }
+        };
+
+        let methods = match receiver_type {
+            Type::Error => {
+                infer_arguments(self);
+                return Type::Error;
+            }
+            Type::Dynamic => {
+                infer_arguments(self);
+                return Type::Dynamic;
+            }
+            ty => match self.methods_of(ty) {
+                Some(methods) => methods,
+                None => {
+                    infer_arguments(self);
+                    self.errors.push(SemanticError::new(
+                        format!("Type {} has no method '{}'", receiver_type, name),
+                        call_expr.span,
+                        SemanticErrorType::InvalidMemberAccess,
+                    ));
+                    return Type::Error;
+                }
+            },
+        };
+        let Some(method) = methods.iter().find(|method| method.name == *name) else {
+            infer_arguments(self);
+            if let Type::Optional(inner) = receiver_type {
+                let has_method = self
+                    .methods_of(inner)
+                    .is_some_and(|methods| methods.iter().any(|method| method.name == *name));
+                if has_method {
+                    self.errors.push(
+                        SemanticError::new(
+                            format!(
+                                "Value of type {} may be nothing, so '{}' can't be called directly",
+                                receiver_type, name
+                            ),
+                            call_expr.span,
+                            SemanticErrorType::InvalidMemberAccess,
+                        )
+                        .with_suggestions(vec![
+                            format!("use `?.{}` to call it safely", name),
+                            "check that the value is not `nothing` first".to_string(),
+                        ]),
+                    );
+                    return Type::Error;
+                }
+            }
+            let suggestion = closest_match(name, methods.iter().map(|method| method.name.as_str()));
+            self.errors.push(
+                SemanticError::new(
+                    format!("Type {} has no method '{}'", receiver_type, name),
+                    call_expr.span,
+                    SemanticErrorType::InvalidMemberAccess,
+                )
+                .with_suggestions(
+                    suggestion
+                        .map(|s| vec![format!("did you mean '{}'?", s)])
+                        .unwrap_or_default(),
+                ),
+            );
+            return Type::Error;
+        };
+
+        self.method_receivers
+            .insert(call_expr.span, self.unifier.resolve(receiver_type));
+        if call_expr.arguments.len() != method.params.len() {
+            infer_arguments(self);
+            self.errors.push(SemanticError::new(
+                format!(
+                    "Method '{}' expects {} argument(s), found {}",
+                    name,
+                    method.params.len(),
+                    call_expr.arguments.len()
+                ),
+                call_expr.span,
+                SemanticErrorType::ArityMismatch,
+            ));
+            return Type::Error;
+        }
+
+        // The method's own type parameters, like the `U` of `map`, get fresh
+        // type variables that the arguments bind.
+        let mut instances = HashMap::new();
+        let params: Vec<Type> = method
+            .params
+            .iter()
+            .map(|param| self.unifier.instantiate(param, &mut instances))
+            .collect();
+        let return_type = self
+            .unifier
+            .instantiate(&method.return_type, &mut instances);
+        let error_count = self.errors.len();
+        for (index, (arg, param)) in call_expr.arguments.iter().zip(&params).enumerate() {
+            let reason = Reason::new(
+                format!("parameter {} of '{}'", index + 1, name),
+                member_access.span,
+            );
+            self.check_expression_against(arg, param, &reason);
+        }
+        let callee = format!("'{}'", name);
+        self.check_bounds(&method.bounds, &instances, &callee, call_expr.span);
+        let result_type = if self.errors.len() > error_count {
+            Type::Error
+        } else {
+            self.unifier.resolve(&return_type)
+        };
+
+        if method.mutates {
+            self.check_mutation(&member_access.object, Some(name), call_expr.span);
+        }
+        result_type
+    }
+
+    /// Checks a write through `target`, e.g., `count = 1`,
+    /// `task.is_done = true` or, when `method` is a mutating method,
+    /// `items.add(item)`. Writes to a field or element count as writes to the
+    /// variable they are reached from, which must be tracked state; those
+    /// are recorded so the UI that reads the variable is updated.
+    fn check_mutation(&mut self, target: &Expression, method: Option<&str>, span: Span) {
+        let mut root = target;
+        while let Expression::MemberAccess(member_access) = root {
+            root = &member_access.object;
+        }
+        // Values that aren't bound to a name, like the result of a call, can
+        // be changed freely; nothing else can see them.
+        let Expression::Identifier(variable, _) = root else {
+            return;
+        };
+        let Some(symbol) = self.symbol_table.resolve(variable) else {
+            return;
+        };
+        let action = match (method, target) {
+            (Some(method), _) => format!("call '{}' on '{}'", method, variable),
+            (None, Expression::MemberAccess(member_access)) => {
+                format!(
+                    "change field '{}' of '{}'",
+                    member_access.property, variable
+                )
+            }
+            (None, _) => format!("assign to '{}'", variable),
+        };
+        let error = match symbol.kind {
+            SymbolKind::Variable {
+                is_tracked: true, ..
+            } => {
+                self.dependency_graph.record_mutation(Mutation {
+                    variable: variable.clone(),
+                    method: method.map(str::to_string),
+                    span,
+                });
+                return;
+            }
+            SymbolKind::Variable {
+                is_tracked: false,
+                declared_at,
+            } => {
+                let error = SemanticError::new(
+                    format!(
+                        "Cannot {} because '{}' is not tracked; declare it with `let's track` to make it mutable",
+                        action, variable
+                    ),
+                    span,
+                    SemanticErrorType::ImmutableAssignment,
+                )
+                .with_suggestions(vec![format!("declare it with `let's track {}`", variable)]);
+                match declared_at {
+                    // `declared_at` is the `let's`, so `track` goes after it.
+                    Some(let_span) => error.with_fix(Fix::new(
+                        format!("declare '{}' with `let's track`", variable),
+                        vec![TextEdit::insert(let_span.end, " track".to_string())],
+                    )),
+                    None => error,
+                }
+            }
+            SymbolKind::Parameter => SemanticError::new(
+                format!("Cannot {} because '{}' is a parameter", action, variable),
+                span,
+                SemanticErrorType::ImmutableAssignment,
+            )
+            .with_suggestions(vec![format!(
+                "copy it into a tracked variable, e.g., `let's track {}_copy = {}`",
+                variable, variable
+            )]),
+            _ => SemanticError::new(
+                format!("Cannot {} because '{}' is not a variable", action, variable),
+                span,
+                SemanticErrorType::InvalidOperation,
+            ),
+        };
+        self.errors.push(error);
+    }
+
+    /// Checks a `when` pattern against the type of the value it is matched
+    /// against, collecting the names it binds along with their types.
+    /// Returns false if the pattern can't apply to the subject at all.
+    fn check_pattern(
+        &mut self,
+        pattern: &WhenPattern,
+        expected: &Type,
+        arm_span: Span,
+        bindings: &mut Vec<(String, Type)>,
+    ) -> bool {
+        match pattern {
+            WhenPattern::Wildcard | WhenPattern::Else => true,
+            WhenPattern::Identifier(name) => {
+                bindings.push((name.clone(), expected.clone()));
+                true
+            }
+            WhenPattern::Literal(literal) => {
+                let literal_type = self.infer_literal_type(literal);
+                if !self.types_are_compatible(expected, &literal_type) {
+                    self.errors.push(SemanticError::new(
+                        format!(
+                            "Pattern type mismatch: expected {}, found {}",
+                            expected, literal_type
+                        ),
+                        arm_span,
+                        SemanticErrorType::TypeMismatch,
+                    ));
+                }
+                true
+            }
+            WhenPattern::Range {
+                start,
+                end,
+                inclusive,
+                span,
+            } => {
+                let (Literal::Number(low), Literal::Number(high)) = (start, end) else {
+                    self.errors.push(SemanticError::new(
+                        "Range patterns must be bounded by number literals".to_string(),
+                        *span,
+                        SemanticErrorType::InvalidOperation,
+                    ));
+                    return true;
+                };
+                if !self.types_are_compatible(expected, &Type::Number) {
+                    self.errors.push(SemanticError::new(
+                        format!("Range pattern can't match a value of type {}", expected),
+                        *span,
+                        SemanticErrorType::TypeMismatch,
+                    ));
+                }
+                if let (Ok(low), Ok(high)) = (low.parse::<f64>(), high.parse::<f64>()) {
+                    if low > high || (low == high && !inclusive) {
+                        self.errors.push(SemanticError::new(
+                            format!("Range pattern {}..{} can never match", low, high),
+                            *span,
+                            SemanticErrorType::InvalidOperation,
+                        ));
+                    }
+                }
+                true
+            }
+            // Check enum variant patterns and their positional sub-patterns.
+            WhenPattern::EnumVariant {
+                enum_name,
+                variant_name,
+                arguments,
+                span,
+            } => {
+                // Check that the pattern's enum type matches the subject's enum type.
+                let expected = self.expand_enum(expected);
+                let Type::Enum { name, variants } = &expected else {
+                    if expected != Type::Error {
+                        self.errors.push(SemanticError::new(
+                            format!(
+                                "Pattern '{}::{}' can't match a value of type {}",
+                                enum_name, variant_name, expected
+                            ),
+                            *span,
+                            SemanticErrorType::TypeMismatch,
+                        ));
+                    }
+                    return false;
+                };
+                if name != enum_name {
+                    self.errors.push(SemanticError::new(
+                        format!(
+                            "Pattern is for enum '{}', but the subject is '{}'",
+                            enum_name, name
+                        ),
+                        *span,
+                        SemanticErrorType::TypeMismatch,
+                    ));
+                    return false;
+                }
+                // Check that the variant name is valid for this enum.
+                let Some(payload) = variants.get(variant_name) else {
+                    self.errors.push(SemanticError::new(
+                        format!("Enum '{}' has no variant '{}'", enum_name, variant_name),
+                        *span,
+                        SemanticErrorType::UndefinedSymbol,
+                    ));
+                    return false;
+                };
+                // Without parentheses the payload is simply not destructured.
+                if arguments.is_empty() {
+                    return true;
+                }
+                if arguments.len() != payload.len() {
+                    self.errors.push(SemanticError::new(
+                        format!(
+                            "Pattern '{}::{}' expects {} sub-pattern(s), found {}",
+                            enum_name,
+                            variant_name,
+                            payload.len(),
+                            arguments.len()
+                        ),
+                        *span,
+                        SemanticErrorType::ArityMismatch,
+                    ));
+                    return false;
+                }
+                let payload = payload.clone();
+                arguments
+                    .iter()
+                    .zip(&payload)
+                    .all(|(argument, field_type)| {
+                        self.check_pattern(argument, field_type, arm_span, bindings)
+                    })
+            }
+        }
+    }
+
+    /// The indentation of the line `offset` is on, if the source is known.
+    fn indent_of(&self, offset: usize) -> Option<&str> {
+        let source = self.source.as_deref()?;
+        let line_start = source.get(..offset)?.rfind('\n').map_or(0, |i| i + 1);
+        let line = &source[line_start..];
+        Some(&line[..line.len() - line.trim_start_matches([' ', '\t']).len()])
+    }
+
+    /// Reports `name`, which isn't declared, suggesting the closest name
+    /// that is, or, if another module declares it privately, that it's
+    /// private.
+    fn report_undefined(&mut self, message: String, name: &str, span: Span) {
+        if self.report_private(name, span) {
+            return;
+        }
+        let suggestion = closest_match(name, self.symbol_table.names());
+        let suggestions = suggestion
+            .map(|s| vec![format!("did you mean '{}'?", s)])
+            .unwrap_or_default();
+        self.errors.push(
+            SemanticError::new(message, span, SemanticErrorType::UndefinedSymbol)
+                .with_suggestions(suggestions),
+        );
+    }
+
+    /// Reports naming `name` if it is private to a module this one imports.
+    /// Returns false if it isn't.
+    fn report_private(&mut self, name: &str, span: Span) -> bool {
+        let Some((module, path)) = self.private_names.get(name) else {
+            return false;
+        };
+        let error = SemanticError::new(
+            format!("'{}' is private to module '{}'", name, module),
+            span,
+            SemanticErrorType::PrivateImport,
+        )
+        .with_suggestions(vec![format!("declare it `public` in {}", path)]);
+        self.errors.push(error);
+        true
+    }
+
+    /// Opens a new, innermost scope.
+    fn enter_scope(&mut self) {
+        let outer = std::mem::take(&mut self.symbol_table);
+        self.symbol_table = SymbolTable::new_enclosed(outer);
+    }
+
+    /// Closes the innermost scope, discarding the symbols defined in it.
+    fn exit_scope(&mut self) {
+        let scope = std::mem::take(&mut self.symbol_table);
+        self.symbol_table = scope.into_outer().unwrap_or_default();
+    }
+
+    /// Reports `when` arms that can never match and, if the arms do not cover
+    /// every value of the subject, the patterns that are missing.
+    fn check_when_exhaustiveness(&mut self, when_expr: &WhenExpression, subject_type: &Type) {
+        let report = MatchChecker::new(&self.symbol_table).check(&when_expr.cases, subject_type);
+
+        for arm in &report.unreachable_arms {
+            let message = if arm.after_catch_all {
+                "Unreachable `when` arm: it follows a catch-all arm".to_string()
+            } else {
+                "Unreachable `when` arm: its pattern is already covered by earlier arms".to_string()
+            };
+            self.errors.push(SemanticError::new(
+                message,
+                arm.span,
+                SemanticErrorType::UnreachablePattern,
+            ));
+        }
+
+        if !report.is_exhaustive() {
+            let mut error = SemanticError::new(
+                format!(
+                    "Non-exhaustive `when`: missing {}",
+                    report.missing_patterns.join(", ")
+                ),
+                when_expr.span,
+                SemanticErrorType::NonExhaustiveMatch,
+            );
+            // New arms go after the last one, at its indentation. Their
+            // bodies are left for the programmer to write.
+            let last = when_expr.cases.last().map(|case| case.span);
+            if let Some((last, indent)) =
+                last.and_then(|span| Some((span, self.indent_of(span.start)?)))
+            {
+                let arms: String = report
+                    .missing_patterns
+                    .iter()
+                    .map(|pattern| format!("\n{}is {} => ...", indent, pattern))
+                    .collect();
+                error = error.with_fix(Fix::with_placeholders(
+                    "add arms for the missing patterns".to_string(),
+                    vec![TextEdit::insert(last.end, arms)],
+                ));
+            }
+            self.errors
+                .push(error.with_suggestions(report.missing_patterns));
+        }
+    }
+
+    /// UPDATED: Validates an enum definition, resolving and storing associated types.
+    pub fn check_enum_definition(&mut self, enum_def: &EnumDefinition) {
+        // The variant names keep their declaration order so that diagnostics
+        // such as missing `when` arms are listed in a predictable order.
+        let enum_kind = SymbolKind::Enum {
+            variants: enum_def.variants.iter().map(|v| v.name.clone()).collect(),
+        };
+
+        // 1. Create a temporary scope in which the enum already exists, so that
+        // variants can refer to the enum itself, e.g., `Node(Tree, Tree)`.
+        let mut enum_scope = SymbolTable::new_enclosed(self.symbol_table.clone());
+        enum_scope.define(
+            enum_def.name.clone(),
+            Type::Enum {
+                name: enum_def.name.clone(),
+                variants: HashMap::new(),
+            },
+            enum_kind.clone(),
+        );
+
+        // 2. Resolve the types of each variant's payload, just like contract fields.
+        let mut resolved_variants = HashMap::new();
+        for variant in &enum_def.variants {
+            let resolved_types: Vec<Type> = variant
+                .types
+                .iter()
+                .map(|type_ann| self.resolve_type_identifier(type_ann, &enum_scope))
+                .collect();
+            if resolved_variants
+                .insert(variant.name.clone(), resolved_types)
+                .is_some()
+            {
+                self.errors.push(SemanticError::new(
+                    format!(
+                        "Variant '{}' is declared more than once in enum '{}'",
+                        variant.name, enum_def.name
+                    ),
+                    variant.span,
+                    SemanticErrorType::DuplicateDeclaration,
+                ));
+            }
+        }
+
+        // 3. Define the enum type itself in the current scope.
+        let enum_type = Type::Enum {
+            name: enum_def.name.clone(),
+            variants: resolved_variants,
+        };
+        if !self.define_declaration(&enum_def.name, enum_type, enum_kind) {
+            self.errors.push(SemanticError::new(
+                format!("Enum '{}' is already declared", enum_def.name),
+                enum_def.span,
+                SemanticErrorType::DuplicateDeclaration,
+            ));
+        }
+    }
+
+    /// Returns the full definition of an enum type. Payload types that refer
+    /// to an enum recursively only carry its name, so the variants are looked
+    /// up in the symbol table.
+    fn expand_enum(&self, ty: &Type) -> Type {
+        if let Type::Enum { name, .. } = ty {
+            if let Some(symbol) = self.symbol_table.resolve(name) {
+                if let Type::Enum { .. } = symbol.ty {
+                    return symbol.ty;
+                }
+            }
+        }
+        ty.clone()
+    }
+
+    /// Helper method to infer the type of a literal value.
+    fn infer_literal_type(&mut self, literal: &Literal) -> Type {
+        match literal {
+            Literal::Number(_) => Type::Number,
+            Literal::String(_) => Type::String,
+            Literal::Boolean(_) => Type::Boolean,
+            Literal::Nothing => Type::Nothing,
+            Literal::List(list) => {
+                let element_type =
+                    self.unify_elements("List elements", "the first element", &list.elements);
+                Type::List(Box::new(element_type))
+            }
+            Literal::Map(map) => {
+                let keys: Vec<Expression> = map.pairs.iter().map(|(key, _)| map_key(key)).collect();
+                let values: Vec<Expression> =
+                    map.pairs.iter().map(|(_, value)| value.clone()).collect();
+                let key_type = self.unify_elements("Map keys", "the first key", &keys);
+                let value_type = self.unify_elements("Map values", "the first value", &values);
+                Type::Map(Box::new(key_type), Box::new(value_type))
+            }
+        }
+    }
+
+    /// Infers the common type of the elements of a collection literal. The
+    /// first element decides the type, and any element of a different type is
+    /// reported at its own span. An empty collection's element type is a type
+    /// variable, bound by how the collection is used later.
+    fn unify_elements(&mut self, what: &str, first: &str, elements: &[Expression]) -> Type {
+        let unified = self.unifier.fresh();
+        let Some(first_element) = elements.first() else {
+            return unified;
+        };
+        let reason = Reason::new(first, first_element.span());
+        for element in elements {
+            let element_type = self.infer_expression_type(element);
+            if let Err(error) = self.unifier.unify(&unified, &element_type, &reason) {
+                let context = format!("{} must all have the same type", what);
+                self.report_unify_error(*error, &reason, element.span(), &context);
+            }
+        }
+        let unified = self.unifier.resolve(&unified);
+        // A collection of unknown elements, e.g., `[undefined_name]`, stays unknown.
+        if self.unifier.is_unbound(&unified) {
+            return Type::Error;
+        }
+        unified
+    }
+
+    /// Reports a failure to unify a value's type with the type expected of it
+    /// at `span`. The message says where the expected type came from: the
+    /// binding that fixed it if there was one, and otherwise `reason`.
+    fn report_unify_error(
+        &mut self,
+        error: UnifyError,
+        reason: &Reason,
+        span: Span,
+        context: &str,
+    ) {
+        let error = match error {
+            UnifyError::Mismatch {
+                expected,
+                actual,
+                reason: binding_reason,
+            } => {
+                let reason = binding_reason.as_ref().unwrap_or(reason);
+                SemanticError::new(
+                    format!(
+                        "{}: expected {} because of {}, found {}",
+                        context, expected, reason.description, actual
+                    ),
+                    span,
+                    SemanticErrorType::TypeMismatch,
+                )
+                .with_related(
+                    format!("expected {} because of this", expected),
+                    reason.span,
+                )
+            }
+            UnifyError::Occurs { ty, .. } => SemanticError::new(
+                format!(
+                    "{}: the type would have to contain itself, as in {}",
+                    context, ty
+                ),
+                span,
+                SemanticErrorType::TypeMismatch,
+            ),
+        };
+        self.errors.push(error);
+    }
+
+    /// Checks an expression against the type it is expected to have, e.g.,
+    /// from a `let` annotation or a parameter, and returns the type it was
+    /// checked at. This is the checking half of bidirectional inference: the
+    /// expected type flows into map literals, checked as contract initializers,
+    /// into collection elements and into lambda parameters. `reason` says where
+    /// the expected type came from, for error messages.
+    fn check_expression_against(
+        &mut self,
+        expr: &Expression,
+        expected: &Type,
+        reason: &Reason,
+    ) -> Type {
+        // Match on the resolved type, but unify with the original so that a
+        // mismatch can name the reason its type variables were bound.
+        let (unresolved, expected) = (expected, self.unifier.resolve(expected));
+        match (expr, &expected) {
+            (Expression::Literal(Literal::Map(map_literal), span), Type::Custom(contract_name))
+                if self.contract_fields(contract_name, &[]).is_some() =>
+            {
+                self.check_contract_literal(contract_name, None, map_literal, span)
+            }
+            (
+                Expression::Literal(Literal::Map(map_literal), span),
+                Type::GenericInstance(contract_name, args),
+            ) => self.check_contract_literal(contract_name, Some(args.clone()), map_literal, span),
+            (Expression::Literal(Literal::List(list), _), Type::List(element_type)) => {
+                for element in &list.elements {
+                    self.check_expression_against(element, element_type, reason);
+                }
+                self.unifier.resolve(&expected)
+            }
+            (Expression::Literal(Literal::Map(map), _), Type::Map(key_type, value_type)) => {
+                for (key, value) in &map.pairs {
+                    self.check_expression_against(&map_key(key), key_type, reason);
+                    self.check_expression_against(value, value_type, reason);
+                }
+                self.unifier.resolve(&expected)
+            }
+            (Expression::Literal(Literal::Map(_) | Literal::List(_), _), Type::Optional(inner)) => {
+                let inner = self.check_expression_against(expr, inner, reason);
+                Type::Optional(Box::new(inner))
+            }
+            (Expression::Lambda(lambda), Type::Function { .. }) => {
+                let actual = self.check_lambda(lambda, Some((&expected, reason)));
+                self.expect_type(&expected, &actual, reason, expr.span());
+                self.unifier.resolve(&expected)
+            }
+            // An argument that reads `it`, as in `tasks.findIndex(it.id == id)`,
+            // is a lambda whose parameter is `it`.
+            (_, Type::Function { params, .. })
+                if params.len() == 1 && self.reads_implicit_it(expr) =>
+            {
+                self.implicit_lambdas.insert(expr.span());
+                let lambda = LambdaExpression {
+                    parameters: vec![LambdaParameter {
+                        name: "it".to_string(),
+                        type_annotation: None,
+                        span: expr.span(),
+                    }],
+                    body: expr.clone(),
+                    span: expr.span(),
+                };
+                let actual = self.check_lambda(&lambda, Some((&expected, reason)));
+                self.expect_type(&expected, &actual, reason, expr.span());
+                self.unifier.resolve(&expected)
+            }
+            _ => {
+                let actual = self.infer_expression_type(expr);
+                if actual == Type::Nothing && !self.types_are_compatible(&expected, &actual) {
+                    self.errors.push(
+                        SemanticError::new(
+                            format!(
+                                "`nothing` can't be assigned to non-optional type {}",
+                                expected
+                            ),
+                            expr.span(),
+                            SemanticErrorType::TypeMismatch,
+                        )
+                        .with_suggestions(vec![
+                            "make the type optional by adding `?`, e.g., `User?`".to_string(),
+                        ]),
+                    );
+                } else {
+                    self.expect_type(unresolved, &actual, reason, expr.span());
+                }
+                self.unifier.resolve(&expected)
+            }
+        }
+    }
+
+    /// Unifies the type of a value with the type expected of it, reporting a
+    /// type mismatch at `span` if they conflict.
+    fn expect_type(&mut self, expected: &Type, actual: &Type, reason: &Reason, span: Span) -> bool {
+        match self.unifier.unify(expected, actual, reason) {
+            Ok(()) => true,
+            Err(error) => {
+                self.report_unify_error(*error, reason, span, "Type mismatch");
+                false
+            }
+        }
+    }
+
+    /// Returns true if `expr` reads an `it` that isn't otherwise defined.
+    fn reads_implicit_it(&self, expr: &Expression) -> bool {
+        let mut reads = Vec::new();
+        collect_reads(expr, &mut reads);
+        reads.contains(&"it") && self.symbol_table.resolve("it").is_none()
+    }
+
+    /// Checks a map literal as the initializer of a contract and returns the
+    /// contract's type. For a generic contract the type arguments are inferred
+    /// from the field values unless `args` gives them, e.g., from a
+    /// `Box<number>` annotation.
+    fn check_contract_literal(
+        &mut self,
+        contract_name: &str,
+        args: Option<Vec<Type>>,
+        map_literal: &MapLiteral,
+        span: &Span,
+    ) -> Type {
+        self.contract_literals
+            .insert(*span, contract_name.to_string());
+        let params = match self.symbol_table.resolve(contract_name).map(|s| s.kind) {
+            Some(SymbolKind::GenericContract { params, .. }) => params,
+            _ => {
+                self.check_contract_initialization(contract_name, map_literal, span);
+                return Type::Custom(contract_name.to_string());
+            }
+        };
+        let args = args.unwrap_or_else(|| params.iter().map(|_| self.unifier.fresh()).collect());
+        let fields = self
+            .contract_fields(contract_name, &args)
+            .unwrap_or_default();
+        self.check_contract_fields(contract_name, &fields, map_literal, span);
+        self.unifier
+            .resolve(&Type::GenericInstance(contract_name.to_string(), args))
+    }
+
+    /// UPDATED: This function now handles generic parameters in contract definitions.
+    pub fn check_contract_definition(&mut self, contract_def: &ContractDefinition) {
+        // 1. Create a temporary scope for resolving generic types within the contract.
+        let mut contract_scope = SymbolTable::new_enclosed(self.symbol_table.clone());
+        for param in &contract_def.generic_params {
+            // Register each generic parameter as a `Generic` type within this scope.
+            let generic_type = Type::Generic(param.clone());
+            contract_scope.define(param.clone(), generic_type, SymbolKind::Type);
+        }
+
+        // 2. Resolve the types of the fields using the temporary scope.
+        let mut resolved_fields = HashMap::new();
+        for field in &contract_def.fields {
+            let field_type = self.resolve_type_identifier(&field.type_ann, &contract_scope);
+            resolved_fields.insert(field.name.clone(), field_type);
+        }
+
+        let defaults = contract_def
+            .fields
+            .iter()
+            .filter(|field| field.default_value.is_some())
+            .map(|field| field.name.clone())
+            .collect();
+
+        // 3. Resolve the types of the computed properties and the signatures
+        //    of the methods in the same scope.
+        let mut properties = HashMap::new();
+        for property in &contract_def.properties {
+            let property_type = self.resolve_type_identifier(&property.type_ann, &contract_scope);
+            if resolved_fields.contains_key(&property.name)
+                || properties.contains_key(&property.name)
+            {
+                self.report_duplicate_member(contract_def, &property.name, property.span);
+                continue;
+            }
+            properties.insert(property.name.clone(), property_type);
+        }
+        let mut methods = HashMap::new();
+        for method in &contract_def.methods {
+            let signature = self.method_signature(method, &contract_scope);
+            if resolved_fields.contains_key(&method.name)
+                || properties.contains_key(&method.name)
+                || methods.contains_key(&method.name)
+            {
+                self.report_duplicate_member(contract_def, &method.name, method.span);
+                continue;
+            }
+            methods.insert(method.name.clone(), signature);
+        }
+        // Whether the contract really implements them is checked once every
+        // interface is known, in `check_conformances`.
+        for interface in &contract_def.interfaces {
+            self.unifier
+                .add_implementation(&contract_def.name, interface);
+        }
+
+        // 4. Define the contract in the main scope.
+        // The symbol will note that this is a generic type definition.
+        let contract_kind = if contract_def.generic_params.is_empty() {
+            // Regular contract
+            SymbolKind::Contract {
+                fields: resolved_fields,
+                defaults,
+                properties,
+                methods,
+            }
+        } else {
+            // Generic contract
+            SymbolKind::GenericContract {
+                params: contract_def.generic_params.clone(),
+                fields: resolved_fields,
+                defaults,
+                properties,
+                methods,
+            }
+        };
+
+        // The "type" here is a placeholder, as it can't be a concrete type until instantiated.
+        let contract_type = Type::Custom(contract_def.name.clone());
+
+        if !self.define_declaration(&contract_def.name, contract_type, contract_kind) {
+            self.errors.push(SemanticError::new(
+                format!("Contract '{}' is already declared", contract_def.name),
+                contract_def.span,
+                SemanticErrorType::DuplicateDeclaration,
+            ));
+        }
+    }
+
+    fn report_duplicate_member(
+        &mut self,
+        contract_def: &ContractDefinition,
+        name: &str,
+        span: Span,
+    ) {
+        self.errors.push(SemanticError::new(
+            format!(
+                "Contract '{}' already has a member named '{}'",
+                contract_def.name, name
+            ),
+            span,
+            SemanticErrorType::DuplicateDeclaration,
+        ));
+    }
+
+    /// Resolves the method signatures of an interface. `Self` in a signature
+    /// stands for the type that implements the interface.
+    pub fn check_interface_definition(&mut self, interface_def: &InterfaceDefinition) {
+        let mut interface_scope = SymbolTable::new_enclosed(self.symbol_table.clone());
+        let self_type = Type::Generic("Self".to_string());
+        interface_scope.define("Self".to_string(), self_type, SymbolKind::Type);
+
+        let mut methods = HashMap::new();
+        for method in &interface_def.methods {
+            let params = method
+                .parameters
+                .iter()
+                .map(|p| self.resolve_type_identifier(&p.type_annotation, &interface_scope))
+                .collect();
+            let return_type = match &method.return_type {
+                Some(ret_type) => self.resolve_type_identifier(ret_type, &interface_scope),
+                None => Type::Nothing,
+            };
+            if methods.contains_key(&method.name) {
+                self.errors.push(SemanticError::new(
+                    format!(
+                        "Interface '{}' already has a method named '{}'",
+                        interface_def.name, method.name
+                    ),
+                    method.span,
+                    SemanticErrorType::DuplicateDeclaration,
+                ));
+                continue;
+            }
+            let signature = MethodSignature::new(&method.name, params, return_type);
+            methods.insert(method.name.clone(), signature);
+        }
+
+        let interface_type = Type::Interface(interface_def.name.clone());
+        let interface_kind = SymbolKind::Interface { methods };
+        if !self.define_declaration(&interface_def.name, interface_type, interface_kind) {
+            self.errors.push(SemanticError::new(
+                format!("Interface '{}' is already declared", interface_def.name),
+                interface_def.span,
+                SemanticErrorType::DuplicateDeclaration,
+            ));
+        }
+    }
+
+    /// Reports contracts that don't implement the interfaces they declare.
+    /// Every method of the interface must be defined on the contract, with
+    /// `Self` in its signature replaced by the contract's type.
+    fn check_conformances(&mut self, program: &Program) {
+        for def in &program.definitions {
+            let Definition::Contract(contract_def) = def else {
+                continue;
+            };
+            let name = contract_def.name.clone();
+            let (self_type, methods) = match self.symbol_table.resolve(&name).map(|s| s.kind) {
+                Some(SymbolKind::Contract { methods, .. }) => (Type::Custom(name), methods),
+                Some(SymbolKind::GenericContract {
+                    params, methods, ..
+                }) => {
+                    let args = params.into_iter().map(Type::Generic).collect();
+                    (Type::GenericInstance(name, args), methods)
+                }
+                _ => continue,
+            };
+
+            for interface in &contract_def.interfaces {
+                let required = match self.symbol_table.resolve(interface).map(|s| s.kind) {
+                    Some(SymbolKind::Interface { methods }) => methods,
+                    found => {
+                        let message = match found {
+                            Some(_) => format!("'{}' is not an interface", interface),
+                            None => format!("Undefined interface '{}'", interface),
+                        };
+                        self.errors.push(SemanticError::new(
+                            message,
+                            contract_def.span,
+                            SemanticErrorType::UndefinedType,
+                        ));
+                        continue;
+                    }
+                };
+                let bindings = HashMap::from([("Self".to_string(), self_type.clone())]);
+                let mut required: Vec<MethodSignature> = required
+                    .into_values()
+                    .map(|method| method.substitute(&bindings))
+                    .collect();
+                required.sort_by(|a, b| a.name.cmp(&b.name));
+
+                for expected in required {
+                    let expected_type = method_type(&expected);
+                    let Some(actual) = methods.get(&expected.name) else {
+                        self.errors.push(
+                            SemanticError::new(
+                                format!(
+                                    "Contract '{}' doesn't implement method '{}' of interface '{}'",
+                                    contract_def.name, expected.name, interface
+                                ),
+                                contract_def.span,
+                                SemanticErrorType::MissingImplementation,
+                            )
+                            .with_suggestions(vec![format!(
+                                "add a method '{}' of type {}",
+                                expected.name, expected_type
+                            )]),
+                        );
+                        continue;
+                    };
+                    let actual_type = method_type(actual);
+                    if actual_type != expected_type {
+                        let span = contract_def
+                            .methods
+                            .iter()
+                            .find(|method| method.name == expected.name)
+                            .map_or(contract_def.span, |method| method.span);
+                        self.errors.push(SemanticError::new(
+                            format!(
+                                "Method '{}' of contract '{}' has type {}, but interface '{}' requires {}",
+                                expected.name, contract_def.name, actual_type, interface, expected_type
+                            ),
+                            span,
+                            SemanticErrorType::TypeMismatch,
+                        ));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Checks the default values of a contract's fields, then the values of
+    /// its computed properties and the bodies of its methods. In a property or
+    /// a method, `self` is the value it is read or called on, and the
+    /// contract's type parameters stand for any type.
+    fn check_member_bodies(&mut self, contract_def: &ContractDefinition) {
+        if contract_def.methods.is_empty()
+            && contract_def.properties.is_empty()
+            && contract_def
+                .fields
+                .iter()
+                .all(|field| field.default_value.is_none())
+        {
+            return;
+        }
+        self.enter_scope();
+        let mut args = Vec::new();
+        for param in &contract_def.generic_params {
+            let param_type = Type::Param(param.clone());
+            self.symbol_table
+                .define(param.clone(), param_type.clone(), SymbolKind::Type);
+            args.push(param_type);
+        }
+        let self_type = if args.is_empty() {
+            Type::Custom(contract_def.name.clone())
+        } else {
+            Type::GenericInstance(contract_def.name.clone(), args)
+        };
+        // Errors in the declared types were reported with the contract's shape.
+        let error_count = self.errors.len();
+        let scope = self.symbol_table.clone();
+        let field_types: Vec<Type> = contract_def
+            .fields
+            .iter()
+            .map(|field| self.resolve_type_identifier(&field.type_ann, &scope))
+            .collect();
+        let property_types: Vec<Type> = contract_def
+            .properties
+            .iter()
+            .map(|property| self.resolve_type_identifier(&property.type_ann, &scope))
+            .collect();
+        self.errors.truncate(error_count);
+
+        // A default value is computed before the value exists, so it can't use `self`.
+        for (field, field_type) in contract_def.fields.iter().zip(&field_types) {
+            if let Some(default_value) = &field.default_value {
+                let reason = Reason::new(
+                    format!("field '{}' of contract '{}'", field.name, contract_def.name),
+                    field.type_ann.span(),
+                );
+                self.check_expression_against(default_value, field_type, &reason);
+            }
+        }
+
+        self.symbol_table
+            .define("self".to_string(), self_type, SymbolKind::Parameter);
+        for (property, property_type) in contract_def.properties.iter().zip(&property_types) {
+            let reason = Reason::new(
+                format!("the declared type of property '{}'", property.name),
+                property.type_ann.span(),
+            );
+            self.check_expression_against(&property.value, property_type, &reason);
+        }
+        for method in &contract_def.methods {
+            self.check_function_body(method);
+        }
+        self.exit_scope();
+    }
+
+    /// Resolves a function's signature and declares it. The function's type
+    /// parameters, like the `T` of `let's first<T>(items: List<T>) -> T?`, are
+    /// left as generics for each call to infer.
+    pub fn check_function_definition(&mut self, func: &FunctionDefinition) {
+        let scope = self.symbol_table.clone();
+        let (param_types, mut return_type) = self.function_signature(func, &scope);
+        // Callers of an async function receive a `Future` of its result.
+        if func.is_async {
+            return_type = Type::Future(Box::new(return_type));
+        }
+        let return_type = Box::new(return_type);
+
+        let func_type = Type::Function {
+            params: param_types.clone(),
+            return_type: return_type.clone(),
+        };
+
+        let func_kind = SymbolKind::Function {
+            param_types,
+            return_type,
+            bounds: bounds_of(func),
+        };
+
+        if !self.define_declaration(&func.name, func_type, func_kind) {
+            self.errors.push(SemanticError::new(
+                format!("Function '{}' is already declared", func.name),
+                func.span,
+                SemanticErrorType::DuplicateDeclaration,
+            ));
+        }
+    }
+
+    /// Resolves the signature of a contract method in the contract's scope.
+    fn method_signature(
+        &mut self,
+        method: &FunctionDefinition,
+        scope: &SymbolTable,
+    ) -> MethodSignature {
+        let (params, mut return_type) = self.function_signature(method, scope);
+        if method.is_async {
+            return_type = Type::Future(Box::new(return_type));
+        }
+        let mut signature = MethodSignature::new(&method.name, params, return_type);
+        signature.bounds = bounds_of(method);
+        signature
+    }
+
+    /// Resolves the parameter types and the declared return type of a function
+    /// in a scope, enclosing `scope`, that holds its type parameters.
+    fn function_signature(
+        &mut self,
+        func: &FunctionDefinition,
+        scope: &SymbolTable,
+    ) -> (Vec<Type>, Type) {
+        let mut signature_scope = SymbolTable::new_enclosed(scope.clone());
+        for param in &func.generic_params {
+            let generic_type = Type::Generic(param.name.clone());
+            if !signature_scope.define(param.name.clone(), generic_type, SymbolKind::Type) {
+                self.errors.push(SemanticError::new(
+                    format!(
+                        "Type parameter '{}' of function '{}' is declared more than once",
+                        param.name, func.name
+                    ),
+                    param.span,
+                    SemanticErrorType::DuplicateDeclaration,
+                ));
+            }
+            let Some(bound) = &param.bound else {
+                continue;
+            };
+            let message = match scope.resolve(bound).map(|symbol| symbol.kind) {
+                Some(SymbolKind::Interface { .. }) => continue,
+                Some(_) => format!("'{}' is not an interface", bound),
+                None => format!("Undefined interface '{}'", bound),
+            };
+            self.errors.push(SemanticError::new(
+                message,
+                param.span,
+                SemanticErrorType::UndefinedType,
+            ));
+        }
+
+        let param_types = func
+            .parameters
+            .iter()
+            .map(|p| self.resolve_type_identifier(&p.type_annotation, &signature_scope))
+            .collect();
+        let return_type = match &func.return_type {
+            Some(ret_type) => self.resolve_type_identifier(ret_type, &signature_scope),
+            None => Type::Nothing,
+        };
+        (param_types, return_type)
+    }
+
+    /// Checks the body of a function in a scope holding its parameters. In the
+    /// body, the function's type parameters stand for any type, so a value of
+    /// type `T` can only be passed around, not used as any particular type.
+    fn check_function_body(&mut self, func: &FunctionDefinition) {
+        // Errors in the signature were reported when it was declared.
+        let error_count = self.errors.len();
+        let scope = self.symbol_table.clone();
+        let (param_types, return_type) = self.function_signature(func, &scope);
+        self.errors.truncate(error_count);
+        let rigid = |ty: &Type| {
+            guardian_stdlib::map_generics(ty, &|name| Some(Type::Param(name.to_string())))
+        };
+
+        self.enter_scope();
+        let mut bounds = HashMap::new();
+        for param in &func.generic_params {
+            let param_type = Type::Param(param.name.clone());
+            self.symbol_table
+                .define(param.name.clone(), param_type, SymbolKind::Type);
+            if let Some(bound) = &param.bound {
+                bounds.insert(param.name.clone(), bound.clone());
+            }
+        }
+        let outer_bounds = self.unifier.set_param_bounds(bounds);
+        for (param, ty) in func.parameters.iter().zip(&param_types) {
+            if !self
+                .symbol_table
+                .define(param.name.clone(), rigid(ty), SymbolKind::Parameter)
+            {
+                self.errors.push(SemanticError::new(
+                    format!("Parameter '{}' is already declared", param.name),
+                    param.span,
+                    SemanticErrorType::DuplicateDeclaration,
+                ));
+            }
+        }
+        let declared_return = func.return_type.as_ref().map(|ret_type| {
+            let reason = Reason::new(
+                format!("the return type of '{}'", func.name),
+                ret_type.span(),
+            );
+            (rigid(&return_type), reason)
+        });
+        let outer_return = std::mem::replace(&mut self.current_return_type, declared_return);
+        let was_async = std::mem::replace(&mut self.is_in_async_context, func.is_async);
+        let was_imperative = std::mem::replace(&mut self.is_in_imperative_block, true);
+        for stmt in &func.body.statements {
+            self.check_statement(stmt);
+        }
+        self.is_in_imperative_block = was_imperative;
+        self.is_in_async_context = was_async;
+        self.current_return_type = outer_return;
+        self.unifier.set_param_bounds(outer_bounds);
+        self.exit_scope();
+    }
+
+    /// Checks a `return` against the declared return type of the enclosing
+    /// function, if it has one.
+    fn check_return_statement(&mut self, return_stmt: &ReturnStatement) {
+        match self.current_return_type.clone() {
+            Some((expected, reason)) => {
+                self.check_expression_against(&return_stmt.value, &expected, &reason);
+            }
+            None => {
+                self.infer_expression_type(&return_stmt.value);
+            }
+        }
+    }
+
+    /// Checks a statement
+    pub fn check_statement(&mut self, stmt: &Statement) {
+        match stmt {
+            Statement::Let(let_stmt) => self.check_let_statement(let_stmt),
+            Statement::Return(return_stmt) => self.check_return_statement(return_stmt),
+            Statement::Expression(expr_stmt) => {
+                self.infer_expression_type(&expr_stmt.expression);
+            }
+            Statement::Block(block) => self.check_block(block),
+            Statement::Assign(assign_stmt) => self.check_assign_statement(assign_stmt),
+            _ => {
+                // Other statement types not implemented yet
+            }
+        }
+    }
+
+    /// Checks the statements of a block in a new scope.
+    fn check_block(&mut self, block: &BlockStatement) {
+        self.enter_scope();
+        for stmt in &block.statements {
+            self.check_statement(stmt);
+        }
+        self.exit_scope();
+    }
+
+    /// Checks an assignment. Only `let's track` state, and the fields of
+    /// contract values held in it, may change after it is initialized.
+    fn check_assign_statement(&mut self, assign_stmt: &AssignStatement) {
+        let description = match &assign_stmt.target {
+            Expression::Identifier(name, _) => {
+                if self.symbol_table.resolve_visible(name).is_none() {
+                    self.infer_expression_type(&assign_stmt.value);
+                    self.report_undefined(
+                        format!("Cannot assign to undefined variable '{}'", name),
+                        name,
+                        assign_stmt.span,
+                    );
+                    return;
+                }
+                format!("the declared type of '{}'", name)
+            }
+            Expression::MemberAccess(member_access) => {
+                if self.check_property_assignment(member_access, assign_stmt.span) {
+                    self.infer_expression_type(&assign_stmt.value);
+                    return;
+                }
+                format!("the type of field '{}'", member_access.property)
+            }
+            other => {
+                self.infer_expression_type(other);
+                self.infer_expression_type(&assign_stmt.value);
+                self.errors.push(SemanticError::new(
+                    "Only variables and contract fields can be assigned to".to_string(),
+                    assign_stmt.span,
+                    SemanticErrorType::InvalidOperation,
+                ));
+                return;
+            }
+        };
+
+        let error_count = self.errors.len();
+        self.check_mutation(&assign_stmt.target, None, assign_stmt.span);
+        if self.errors.len() > error_count {
+            self.infer_expression_type(&assign_stmt.value);
+            return;
+        }
+        // The value is checked against the target's type, so a map literal
+        // assigned to a contract builds the contract.
+        let target_type = self.infer_expression_type(&assign_stmt.target);
+        let reason = Reason::new(description, assign_stmt.target.span());
+        self.check_expression_against(&assign_stmt.value, &target_type, &reason);
+    }
+
+    /// Reports an assignment to a computed property, e.g., `employee.bonus = 0`.
+    /// Returns true if the target is one.
+    fn check_property_assignment(
+        &mut self,
+        member_access: &MemberAccessExpression,
+        span: Span,
+    ) -> bool {
+        let object_type = self.infer_expression_type(&member_access.object);
+        let (contract_name, args) = match &object_type {
+            Type::Custom(name) => (name, &[][..]),
+            Type::GenericInstance(name, args) => (name, args.as_slice()),
+            _ => return false,
+        };
+        let is_property = self
+            .contract_property(contract_name, args, &member_access.property)
+            .is_some();
+        if is_property {
+            self.errors.push(SemanticError::new(
+                format!(
+                    "Cannot assign to '{}' because it is a computed property of contract '{}'",
+                    member_access.property, contract_name
+                ),
+                span,
+                SemanticErrorType::ImmutableAssignment,
+            ));
+        }
+        is_property
+    }
+
+    /// Returns the tracked variables an expression reads, looking through
+    /// derived values to the tracked state they are computed from.
+    fn tracked_sources(&self, expr: &Expression) -> Vec<String> {
+        let mut reads = Vec::new();
+        collect_reads(expr, &mut reads);
+
+        let mut sources = Vec::new();
+        for name in reads {
+            match self.symbol_table.resolve(name).map(|symbol| symbol.kind) {
+                Some(SymbolKind::Variable {
+                    is_tracked: true, ..
+                }) => sources.push(name.to_string()),
+                Some(SymbolKind::Variable {
+                    is_tracked: false, ..
+                }) => {
+                    if let Some(derived) = self.dependency_graph.derived_sources(name) {
+                        sources.extend(derived.iter().cloned());
+                    }
+                }
+                _ => {}
+            }
+        }
+        sources
+    }
+
+    /// Recursively checks a UI tree against the component registry and records
+    /// the tracked state each property reads. Event handlers that contain an
+    /// `await` are inferred to be async and recorded in `async_event_handlers`.
+    fn check_ui_node(&mut self, node: &UiNode) {
+        let UiNode::Element(element) = node;
+        let schema = guardian_components::component(&element.name);
+        if schema.is_none() {
+            let names = guardian_components::COMPONENTS.iter().map(|c| c.name);
+            self.push_ui_error(
+                format!("Unknown UI component '{}'", element.name),
+                element.span,
+                closest_match(&element.name, names),
+            );
+        }
+
+        let mut positional_count = 0;
+        let mut named_props = HashSet::new();
+        for (index, property) in element.properties.iter().enumerate() {
+            match property {
+                UiProperty::Positional(expr) | UiProperty::Named(_, expr) => {
+                    if let Some(schema) = schema {
+                        self.check_ui_property(schema, element, property, positional_count);
+                    } else {
+                        self.infer_expression_type(expr);
+                    }
+                    if let UiProperty::Named(name, _) = property {
+                        if !named_props.insert(name.as_str()) {
+                            self.push_ui_error(
+                                format!("Property '{}' is given more than once", name),
+                                element.span,
+                                None,
+                            );
+                        }
+                    } else {
+                        positional_count += 1;
+                    }
+                    let name = match property {
+                        UiProperty::Named(name, _) => Some(name.clone()),
+                        _ => None,
+                    };
+                    let dependent = Dependent::UiProperty {
+                        element: element.name.clone(),
+                        index,
+                        name,
+                        span: element.span,
+                    };
+                    let sources = self.tracked_sources(expr);
+                    self.dependency_graph.add(dependent, sources);
+                }
+                UiProperty::EventBinding(event, handler) => {
+                    if let Some(schema) = schema {
+                        if !schema.events.contains(&event.as_str()) {
+                            self.push_ui_error(
+                                format!("'{}' does not have a '{}' event", element.name, event),
+                                element.span,
+                                closest_match(event, schema.events.iter().copied()),
+                            );
+                        }
+                    }
+                    let is_async = block_contains_await(handler);
+                    if is_async {
+                        self.async_event_handlers.push(handler.span);
+                    }
+                    let was_async = std::mem::replace(&mut self.is_in_async_context, is_async);
+                    let was_imperative = std::mem::replace(&mut self.is_in_imperative_block, true);
+                    self.check_block(handler);
+                    self.is_in_imperative_block = was_imperative;
+                    self.is_in_async_context = was_async;
+                }
+            }
+        }
+
+        if let Some(schema) = schema {
+            for missing in schema.positional.iter().skip(positional_count) {
+                if missing.required {
+                    self.push_ui_error(
+                        format!("'{}' requires a {} argument", element.name, missing.name),
+                        element.span,
+                        None,
+                    );
+                }
+            }
+            if schema.children == ChildRule::None && !element.children.is_empty() {
+                self.push_ui_error(
+                    format!("'{}' cannot contain other components", element.name),
+                    element.span,
+                    None,
+                );
+            }
+        }
+        for child in &element.children {
+            self.check_ui_node(child);
+        }
+    }
+
+    /// Checks a positional argument or named property of a UI element against
+    /// the component's schema.
+    fn check_ui_property(
+        &mut self,
+        schema: &ComponentSchema,
+        element: &UiElement,
+        property: &UiProperty,
+        positional_index: usize,
+    ) {
+        let (spec, expr) = match property {
+            UiProperty::Positional(expr) => match schema.positional.get(positional_index) {
+                Some(spec) => (spec, expr),
+                None => {
+                    self.infer_expression_type(expr);
+                    self.push_ui_error(
+                        format!(
+                            "'{}' takes {} positional argument(s), found more",
+                            element.name,
+                            schema.positional.len()
+                        ),
+                        element.span,
+                        None,
+                    );
+                    return;
+                }
+            },
+            UiProperty::Named(name, expr) => match schema.prop(name) {
+                Some(spec) => (spec, expr),
+                None => {
+                    self.infer_expression_type(expr);
+                    let names = schema.props.iter().map(|prop| prop.name);
+                    self.push_ui_error(
+                        format!("'{}' has no property '{}'", element.name, name),
+                        element.span,
+                        closest_match(name, names),
+                    );
+                    return;
+                }
+            },
+            UiProperty::EventBinding(..) => return,
+        };
+
+        if spec.ty == PropType::Style {
+            self.check_style(expr, element.span);
+            return;
+        }
+        let ty = self.infer_expression_type(expr);
+        if !spec.ty.accepts(&ty) {
+            self.push_ui_error(
+                format!(
+                    "'{}' of '{}' expects {}, found {}",
+                    spec.name,
+                    element.name,
+                    spec.ty.describe(),
+                    ty
+                ),
+                element.span,
+                None,
+            );
+        }
+    }
+
+    /// Checks the style given to a UI element: either a `style { ... }` block
+    /// or the name of a `style` declaration.
+    fn check_style(&mut self, expr: &Expression, span: Span) {
+        match expr {
+            Expression::Literal(Literal::Map(style), _) => {
+                let mut keys = HashSet::new();
+                for (key_expr, value_expr) in &style.pairs {
+                    let key = match key_expr {
+                        Expression::Literal(Literal::String(s), _) => s.trim_matches('"'),
+                        Expression::Identifier(name, _) => name.as_str(),
+                        _ => {
+                            self.infer_expression_type(value_expr);
+                            self.push_ui_error("Style keys must be names".to_string(), span, None);
+                            continue;
+                        }
+                    };
+                    if !keys.insert(key) {
+                        self.push_ui_error(
+                            format!("Style key '{}' is given more than once", key),
+                            span,
+                            None,
+                        );
+                    }
+                    self.check_style_entry(key, value_expr, span);
+                }
+            }
+            Expression::Identifier(name, _) => match self.symbol_table.resolve(name) {
+                Some(symbol) if symbol.kind == SymbolKind::Style => {}
+                Some(_) => self.push_ui_error(format!("'{}' is not a style", name), span, None),
+                None => self.push_ui_error(format!("Undefined style '{}'", name), span, None),
+            },
+            _ => {
+                self.infer_expression_type(expr);
+                self.push_ui_error(
+                    "Expected a `style { ... }` block or the name of a style".to_string(),
+                    span,
+                    None,
+                );
+            }
+        }
+    }
+
+    /// Checks a single `key: value` entry of a style.
+    fn check_style_entry(&mut self, key: &str, value: &Expression, span: Span) {
+        let Some(property) = style_property(key) else {
+            self.infer_expression_type(value);
+            let keys = STYLE_PROPERTIES.iter().map(|property| property.name);
+            self.push_ui_error(
+                format!("Unknown style key '{}'", key),
+                span,
+                closest_match(key, keys),
+            );
+            return;
+        };
+        self.check_style_value(key, property.kind, value, span);
+    }
+
+    /// Checks a style value against the kind its key expects. Literals are
+    /// validated at compile time, and conditional values like
+    /// `if task.is_done: "line-through" else: "none"` are checked branch by branch.
+    fn check_style_value(
+        &mut self,
+        key: &str,
+        kind: StyleValueKind,
+        value: &Expression,
+        span: Span,
+    ) {
+        let (value_type, literal) = match value {
+            Expression::If(if_expr) => {
+                let condition_type = self.infer_expression_type(&if_expr.condition);
+                if !self.types_are_compatible(&Type::Boolean, &condition_type) {
+                    self.errors.push(SemanticError::new(
+                        format!(
+                            "Style condition must be a boolean, found {}",
+                            condition_type
+                        ),
+                        span,
+                        SemanticErrorType::TypeMismatch,
+                    ));
+                }
+                self.check_style_value(key, kind, &if_expr.then_branch, span);
+                match &if_expr.else_branch {
+                    Some(else_branch) => self.check_style_value(key, kind, else_branch, span),
+                    None => self.push_ui_error(
+                        format!(
+                            "Conditional value for style key '{}' needs an `else` branch",
+                            key
+                        ),
+                        span,
+                        None,
+                    ),
+                }
+                return;
+            }
+            Expression::Literal(Literal::Number(n), _) => {
+                (Type::Number, n.parse().ok().map(StyleLiteral::Number))
+            }
+            Expression::Literal(Literal::String(s), _) => (
+                Type::String,
+                Some(StyleLiteral::String(s.trim_matches('"').to_string())),
+            ),
+            _ => (self.infer_expression_type(value), None),
+        };
+
+        if !kind.accepts(&value_type) {
+            self.push_ui_error(
+                format!(
+                    "Style key '{}' expects {}, found {}",
+                    key,
+                    kind.describe(),
+                    value_type
+                ),
+                span,
+                None,
+            );
+        } else if let Some(Err(problem)) = literal.map(|literal| kind.check_literal(&literal)) {
+            self.push_ui_error(
+                format!("Invalid value for style key '{}': {}", key, problem),
+                span,
+                None,
+            );
+        }
+    }
+
+    /// Checks a named `style` declaration and registers it.
+    pub fn check_style_definition(&mut self, style_def: &StyleDefinition) {
+        let mut keys = HashSet::new();
+        for field in &style_def.fields {
+            if !keys.insert(field.name.as_str()) {
+                self.push_ui_error(
+                    format!("Style key '{}' is given more than once", field.name),
+                    field.span,
+                    None,
+                );
+            }
+            self.check_style_entry(&field.name, &field.value, field.span);
+        }
+
+        if !self.define_declaration(&style_def.name, Type::Style, SymbolKind::Style) {
+            self.errors.push(SemanticError::new(
+                format!("Style '{}' is already declared", style_def.name),
+                style_def.span,
+                SemanticErrorType::DuplicateDeclaration,
+            ));
+        }
+    }
+
+    /// Reports an `InvalidUIComponent` error, suggesting `suggestion` if given.
+    fn push_ui_error(&mut self, message: String, span: Span, suggestion: Option<&str>) {
+        let suggestions = suggestion
+            .map(|s| vec![format!("did you mean '{}'?", s)])
+            .unwrap_or_default();
+        self.errors.push(
+            SemanticError::new(message, span, SemanticErrorType::InvalidUIComponent)
+                .with_suggestions(suggestions),
+        );
+    }
+
+    /// Checks an app definition
+    pub fn check_app_definition(&mut self, app: &AppDefinition) {
+        self.enter_scope();
+        for stmt in &app.body.statements {
+            self.check_statement(stmt);
+        }
+        if let Some(show_block) = &app.body.show_block {
+            self.check_ui_node(&show_block.root_node);
+        }
+        self.exit_scope();
+
+        let app_type = Type::Custom(format!("App<{}>", app.name));
+        let app_kind = SymbolKind::Type;
+
+        if !self.define_declaration(&app.name, app_type, app_kind) {
+            self.errors.push(SemanticError::new(
+                format!("App '{}' is already declared", app.name),
+                app.span,
+                SemanticErrorType::DuplicateDeclaration,
+            ));
+        }
+    }
+
+    /// Checks a let statement including contract initializers
+    pub fn check_let_statement(&mut self, let_stmt: &LetStatement) {
+        // With an annotation, the value is checked against the declared type,
+        // which becomes the type of the variable. Otherwise it is inferred.
         let var_type = if let Some(type_annotation) = &let_stmt.type_annotation {
-            self.resolve_type_from_string(type_annotation)
+            let expected_type = self.resolve_annotation(type_annotation);
+            let reason = Reason::new(
+                format!("the annotation on '{}'", let_stmt.name),
+                type_annotation.span(),
+            );
+            self.check_expression_against(&let_stmt.value, &expected_type, &reason)
         } else {
-            value_type
+            self.infer_expression_type(&let_stmt.value)
         };
 
+        // A plain `let's` computed from tracked state is a derived value, kept
+        // up to date whenever that state changes.
+        if !let_stmt.is_tracked && !self.is_in_imperative_block {
+            let sources = self.tracked_sources(&let_stmt.value);
+            let dependent = Dependent::DerivedValue {
+                name: let_stmt.name.clone(),
+                span: let_stmt.span,
+            };
+            self.dependency_graph.add(dependent, sources);
+        }
+
         let var_kind = SymbolKind::Variable {
             is_tracked: let_stmt.is_tracked,
+            declared_at: Some(let_stmt.span),
         };
 
         if !self
@@ -437,134 +2886,425 @@ impl Guardian {
         map_literal: &MapLiteral,
         span: &Span,
     ) {
-        // Look up the contract definition
-        if let Some(contract_symbol) = self.symbol_table.resolve(contract_name) {
-            if let SymbolKind::Contract { fields } = &contract_symbol.kind {
-                let mut found_fields = HashMap::new();
-
-                // Check each field in the map literal
-                for (key_expr, value_expr) in &map_literal.pairs {
-                    let field_name = match key_expr {
-                        Expression::Literal(Literal::String(s), _) => s.trim_matches('"'), // Remove quotes
-                        Expression::Identifier(name, _) => name.as_str(), // Allow identifiers for field names
-                        _ => {
-                            self.errors.push(SemanticError::new(
-                                "Contract field keys must be string literals or identifiers"
-                                    .to_string(),
-                                *span,
-                                SemanticErrorType::InvalidFieldKey,
-                            ));
-                            continue;
-                        }
-                    };
+        match self.contract_fields(contract_name, &[]) {
+            Some(fields) => self.check_contract_fields(contract_name, &fields, map_literal, span),
+            None if self.symbol_table.resolve(contract_name).is_some() => {}
+            None => {
+                self.errors.push(SemanticError::new(
+                    format!("Undefined contract type '{}'", contract_name),
+                    *span,
+                    SemanticErrorType::UndefinedType,
+                ));
+            }
+        }
+    }
 
-                    if let Some(expected_type) = fields.get(field_name) {
-                        let actual_type = self.infer_expression_type(value_expr);
+    /// Checks the entries of a contract initializer against the contract's fields.
+    fn check_contract_fields(
+        &mut self,
+        contract_name: &str,
+        fields: &HashMap<String, Type>,
+        map_literal: &MapLiteral,
+        span: &Span,
+    ) {
+        let mut found_fields = HashSet::new();
+        // The fields that fixes rename unknown fields to, which aren't also
+        // reported as missing.
+        let mut renamed_fields = HashSet::new();
+        let given: HashSet<&str> = map_literal
+            .pairs
+            .iter()
+            .filter_map(|(key, _)| match key {
+                Expression::Literal(Literal::String(s), _) => Some(s.trim_matches('"')),
+                Expression::Identifier(name, _) => Some(name.as_str()),
+                _ => None,
+            })
+            .collect();
 
-                        if !self.types_are_compatible(expected_type, &actual_type) {
-                            self.errors.push(SemanticError::new(
-                                format!(
-                                    "Type mismatch in field '{}': expected {:?}, found {:?}",
-                                    field_name, expected_type, actual_type
-                                ),
-                                *span,
-                                SemanticErrorType::TypeMismatch,
-                            ));
-                        }
+        // Check each field in the map literal
+        for (key_expr, value_expr) in &map_literal.pairs {
+            let field_name = match key_expr {
+                Expression::Literal(Literal::String(s), _) => s.trim_matches('"'), // Remove quotes
+                Expression::Identifier(name, _) => name.as_str(), // Allow identifiers for field names
+                _ => {
+                    self.errors.push(SemanticError::new(
+                        "Contract field keys must be string literals or identifiers".to_string(),
+                        *span,
+                        SemanticErrorType::InvalidFieldKey,
+                    ));
+                    continue;
+                }
+            };
 
-                        found_fields.insert(field_name.to_string(), true);
-                    } else {
-                        self.errors.push(SemanticError::new(
-                            format!(
-                                "Unknown field '{}' in contract '{}'",
-                                field_name, contract_name
-                            ),
-                            *span,
-                            SemanticErrorType::UnknownField,
+            if let Some(expected_type) = fields.get(field_name) {
+                let reason = Reason::new(
+                    format!("field '{}' of contract '{}'", field_name, contract_name),
+                    key_expr.span(),
+                );
+                self.check_expression_against(value_expr, expected_type, &reason);
+                found_fields.insert(field_name.to_string());
+            } else if self
+                .contract_property(contract_name, &[], field_name)
+                .is_some()
+            {
+                self.errors.push(SemanticError::new(
+                    format!(
+                        "'{}' is a computed property of contract '{}', so it can't be set",
+                        field_name, contract_name
+                    ),
+                    key_expr.span(),
+                    SemanticErrorType::ImmutableAssignment,
+                ));
+            } else {
+                let mut error = SemanticError::new(
+                    format!(
+                        "Unknown field '{}' in contract '{}'",
+                        field_name, contract_name
+                    ),
+                    *span,
+                    SemanticErrorType::UnknownField,
+                );
+                // Only a field that isn't given already is worth renaming to.
+                let missing = fields
+                    .keys()
+                    .map(String::as_str)
+                    .filter(|field| !given.contains(field) && !renamed_fields.contains(*field));
+                if let Some(closest) = closest_match(field_name, missing) {
+                    renamed_fields.insert(closest.to_string());
+                    let replacement = match key_expr {
+                        Expression::Literal(..) => format!("\"{}\"", closest),
+                        _ => closest.to_string(),
+                    };
+                    error = error
+                        .with_suggestions(vec![format!("did you mean '{}'?", closest)])
+                        .with_fix(Fix::new(
+                            format!("rename '{}' to '{}'", field_name, closest),
+                            vec![TextEdit::new(key_expr.span(), replacement)],
                         ));
-                    }
                 }
+                self.errors.push(error);
+            }
+        }
 
-                // Check for missing fields
-                for field_name in fields.keys() {
-                    if !found_fields.contains_key(field_name) {
-                        self.errors.push(SemanticError::new(
-                            format!(
-                                "Missing required field '{}' in contract '{}'",
-                                field_name, contract_name
-                            ),
-                            *span,
-                            SemanticErrorType::MissingField,
-                        ));
-                    }
+        // Check for missing fields. Optional fields that are left out are
+        // `nothing`, and fields with a default value take that value.
+        let defaults = match self.symbol_table.resolve(contract_name).map(|s| s.kind) {
+            Some(SymbolKind::Contract { defaults, .. })
+            | Some(SymbolKind::GenericContract { defaults, .. }) => defaults,
+            _ => HashSet::new(),
+        };
+        let mut fields: Vec<(&String, &Type)> = fields.iter().collect();
+        fields.sort_by_key(|(field_name, _)| *field_name);
+        for (field_name, field_type) in fields {
+            if !found_fields.contains(field_name)
+                && !renamed_fields.contains(field_name)
+                && !defaults.contains(field_name)
+                && !matches!(field_type, Type::Optional(_))
+            {
+                let mut error = SemanticError::new(
+                    format!(
+                        "Missing required field '{}' in contract '{}'",
+                        field_name, contract_name
+                    ),
+                    *span,
+                    SemanticErrorType::MissingField,
+                );
+                // The field goes after the last entry, or, in an empty
+                // literal, which spans from `{` to `}`, before the `}`.
+                let value = default_value(field_type).filter(|_| span.end > span.start);
+                if let Some(value) = value {
+                    let (offset, separator) = match map_literal.pairs.last() {
+                        Some((_, last)) => (last.span().end, ", "),
+                        None => (span.end - 1, ""),
+                    };
+                    error = error.with_fix(Fix::new(
+                        format!("add the field '{}'", field_name),
+                        vec![TextEdit::insert(
+                            offset,
+                            format!("{}{}: {}", separator, field_name, value),
+                        )],
+                    ));
                 }
+                self.errors.push(error);
             }
-        } else {
-            self.errors.push(SemanticError::new(
-                format!("Undefined contract type '{}'", contract_name),
-                *span,
-                SemanticErrorType::UndefinedType,
-            ));
         }
     }
 
-    /// Helper method to resolve type from string annotation
-    fn resolve_type_from_string(&self, type_str: &str) -> Type {
-        match type_str {
-            "number" => Type::Number,
-            "string" => Type::String,
-            "boolean" => Type::Boolean,
-            "nothing" => Type::Nothing,
-            _ => {
-                // Check if it's a user-defined type
-                if let Some(symbol) = self.symbol_table.resolve(type_str) {
-                    symbol.ty
-                } else {
-                    Type::Custom(type_str.to_string())
-                }
+    /// Resolves a type annotation against the current scope.
+    /// A generic contract named without type arguments, as in `let's b: Box = ...`,
+    /// has its type arguments inferred.
+    fn resolve_annotation(&mut self, type_ann: &TypeIdentifier) -> Type {
+        let scope = std::mem::take(&mut self.symbol_table);
+        let ty = self.resolve_type_identifier(type_ann, &scope);
+        self.symbol_table = scope;
+        if let Type::Custom(name) = &ty {
+            if let Some(SymbolKind::GenericContract { params, .. }) =
+                self.symbol_table.resolve(name).map(|symbol| symbol.kind)
+            {
+                let args = params.iter().map(|_| self.unifier.fresh()).collect();
+                return Type::GenericInstance(name.clone(), args);
             }
         }
+        ty
     }
 
-    /// NEW: Helper function to resolve a `TypeIdentifier` AST node into a `Type`.
-    #[allow(clippy::only_used_in_recursion)]
+    /// Resolves a `TypeIdentifier` AST node into a `Type`, looking names up in
+    /// `scope`. This is shared by contract fields and enum payloads, and reports
+    /// an `UndefinedType` error for any name that isn't a known type.
     fn resolve_type_identifier(&mut self, type_ann: &TypeIdentifier, scope: &SymbolTable) -> Type {
         match type_ann {
-            TypeIdentifier::Simple { name, .. } => {
-                // Look up the type name in the current scope.
-                // This will find primitive types, custom types, and generic params.
-                scope.resolve(name).map_or_else(
-                    || {
-                        // Fallback to built-in types if not found in scope
-                        match name.as_str() {
-                            "number" => Type::Number,
-                            "string" => Type::String,
-                            "boolean" => Type::Boolean,
-                            "nothing" => Type::Nothing,
-                            _ => Type::Custom(name.clone()),
+            TypeIdentifier::Simple { name, span } => {
+                if let Some(primitive) = primitive_type(name) {
+                    return primitive;
+                }
+                // This will find custom types, enums and generic params.
+                match scope.resolve_visible(name) {
+                    Some(symbol) if symbol.kind.is_type() => symbol.ty,
+                    None if self.report_private(name, *span) => Type::Error,
+                    _ => {
+                        self.errors.push(SemanticError::new(
+                            format!("Undefined type '{}'", name),
+                            *span,
+                            SemanticErrorType::UndefinedType,
+                        ));
+                        Type::Error
+                    }
+                }
+            }
+            TypeIdentifier::Generic { name, args, span } => {
+                let mut resolved: Vec<Type> = args
+                    .iter()
+                    .map(|arg| self.resolve_type_identifier(arg, scope))
+                    .collect();
+                let expected_args = match name.as_str() {
+                    "List" | "Set" | "Optional" | "Future" => 1,
+                    "Map" => 2,
+                    _ => match scope.resolve_visible(name).map(|symbol| symbol.kind) {
+                        Some(SymbolKind::GenericContract { params, .. }) => params.len(),
+                        None if self.report_private(name, *span) => return Type::Error,
+                        _ => {
+                            self.errors.push(SemanticError::new(
+                                format!("Undefined generic type '{}'", name),
+                                *span,
+                                SemanticErrorType::UndefinedType,
+                            ));
+                            return Type::Error;
                         }
                     },
-                    |s| s.ty.clone(),
-                )
-            }
-            TypeIdentifier::Generic { name, args, .. } => {
-                // (Future) This is where we would handle instantiating generic
-                // types like `List<T>`. For now, we'll just handle the simple case.
-                if name == "List" && args.len() == 1 {
-                    let inner_type = self.resolve_type_identifier(&args[0], scope);
-                    return Type::List(Box::new(inner_type));
+                };
+                if resolved.len() != expected_args {
+                    self.errors.push(SemanticError::new(
+                        format!(
+                            "Type '{}' expects {} type argument(s), found {}",
+                            name,
+                            expected_args,
+                            resolved.len()
+                        ),
+                        *span,
+                        SemanticErrorType::ArityMismatch,
+                    ));
+                    return Type::Error;
+                }
+                let mut next = || Box::new(resolved.remove(0));
+                match name.as_str() {
+                    "List" => Type::List(next()),
+                    "Set" => Type::Set(next()),
+                    "Optional" => Type::Optional(next()),
+                    "Future" => Type::Future(next()),
+                    "Map" => Type::Map(next(), next()),
+                    _ => Type::GenericInstance(name.clone(), resolved),
                 }
-                Type::Error
             }
         }
     }
 
     /// Helper method to check type compatibility
     fn types_are_compatible(&self, expected: &Type, actual: &Type) -> bool {
+        let (expected, actual) = (
+            &self.unifier.resolve(expected),
+            &self.unifier.resolve(actual),
+        );
         match (expected, actual) {
             (Type::Error, _) | (_, Type::Error) => true, // Error types are compatible with anything
+            // A type that isn't known yet could still turn out to be either one.
+            (Type::Var(_), _) | (_, Type::Var(_)) => true,
+            (Type::GenericInstance(a, a_args), Type::GenericInstance(b, b_args)) => {
+                a == b
+                    && a_args.len() == b_args.len()
+                    && a_args
+                        .iter()
+                        .zip(b_args)
+                        .all(|(a, b)| self.types_are_compatible(a, b))
+            }
+            // Containers are compared element by element, so an empty `[]` fits any list.
+            (Type::List(a), Type::List(b))
+            | (Type::Set(a), Type::Set(b))
+            | (Type::Optional(a), Type::Optional(b))
+            | (Type::Future(a), Type::Future(b)) => self.types_are_compatible(a, b),
+            (Type::Map(ak, av), Type::Map(bk, bv)) => {
+                self.types_are_compatible(ak, bk) && self.types_are_compatible(av, bv)
+            }
+            // `nothing` is only a value of optional types, and a `T` can be
+            // used wherever a `T?` is expected, but not the other way around.
+            (Type::Optional(_), Type::Nothing) => true,
+            (Type::Optional(inner), actual) => self.types_are_compatible(inner, actual),
+            (Type::Interface(interface), actual) => self.unifier.implements(actual, interface),
             (a, b) if a == b => true,
             _ => false,
         }
     }
 }
+
+/// Returns the built-in type for a primitive type name, if it is one.
+fn primitive_type(name: &str) -> Option<Type> {
+    match name {
+        "number" => Some(Type::Number),
+        "string" => Some(Type::String),
+        "boolean" => Some(Type::Boolean),
+        "nothing" => Some(Type::Nothing),
+        _ => None,
+    }
+}
+
+/// The bounds of a function's type parameters, as pairs of a type parameter
+/// and the interface it must implement.
+fn bounds_of(func: &FunctionDefinition) -> Vec<(String, String)> {
+    func.generic_params
+        .iter()
+        .filter_map(|param| Some((param.name.clone(), param.bound.clone()?)))
+        .collect()
+}
+
+/// The type of a method as a function, for comparing signatures.
+fn method_type(method: &MethodSignature) -> Type {
+    Type::Function {
+        params: method.params.clone(),
+        return_type: Box::new(method.return_type.clone()),
+    }
+}
+
+/// Returns true for definitions that introduce a type or a function signature,
+/// which are checked before any statements.
+fn is_declaration(def: &Definition) -> bool {
+    matches!(
+        def,
+        Definition::Contract(_)
+            | Definition::Enum(_)
+            | Definition::Function(_)
+            | Definition::Interface(_)
+    )
+}
+
+/// Returns true if a block awaits anything, making it an async block.
+fn block_contains_await(block: &BlockStatement) -> bool {
+    block.statements.iter().any(statement_contains_await)
+}
+
+fn statement_contains_await(stmt: &Statement) -> bool {
+    match stmt {
+        Statement::Let(let_stmt) => expression_contains_await(&let_stmt.value),
+        Statement::For(for_stmt) => {
+            expression_contains_await(&for_stmt.collection)
+                || statement_contains_await(&for_stmt.body)
+        }
+        Statement::Return(return_stmt) => expression_contains_await(&return_stmt.value),
+        Statement::Block(block) => block_contains_await(block),
+        Statement::Expression(expr_stmt) => expression_contains_await(&expr_stmt.expression),
+        Statement::Assign(assign_stmt) => {
+            expression_contains_await(&assign_stmt.target)
+                || expression_contains_await(&assign_stmt.value)
+        }
+    }
+}
+
+fn expression_contains_await(expr: &Expression) -> bool {
+    match expr {
+        Expression::Await(_) => true,
+        Expression::Identifier(..) | Expression::AskJs(_) => false,
+        Expression::Literal(Literal::List(list), _) => {
+            list.elements.iter().any(expression_contains_await)
+        }
+        Expression::Literal(Literal::Map(map), _) => map
+            .pairs
+            .iter()
+            .any(|(key, value)| expression_contains_await(key) || expression_contains_await(value)),
+        Expression::Literal(..) => false,
+        Expression::Prefix(prefix) => expression_contains_await(&prefix.right),
+        Expression::Infix(infix) => {
+            expression_contains_await(&infix.left) || expression_contains_await(&infix.right)
+        }
+        Expression::If(if_expr) => {
+            expression_contains_await(&if_expr.condition)
+                || expression_contains_await(&if_expr.then_branch)
+                || if_expr
+                    .else_branch
+                    .as_ref()
+                    .is_some_and(expression_contains_await)
+        }
+        Expression::When(when_expr) => {
+            expression_contains_await(&when_expr.value)
+                || when_expr.cases.iter().any(|case| {
+                    case.guard.as_ref().is_some_and(expression_contains_await)
+                        || expression_contains_await(&case.body)
+                })
+        }
+        Expression::Call(call) => {
+            expression_contains_await(&call.function)
+                || call.arguments.iter().any(expression_contains_await)
+        }
+        Expression::MemberAccess(member_access) | Expression::SafeMemberAccess(member_access) => {
+            expression_contains_await(&member_access.object)
+        }
+        Expression::Lambda(lambda) => expression_contains_await(&lambda.body),
+    }
+}
+
+/// Wraps a type in `Optional`, unless it already is one or is unknown.
+fn optional_of(ty: Type) -> Type {
+    match ty {
+        Type::Optional(_) | Type::Error | Type::Nothing => ty,
+        other => Type::Optional(Box::new(other)),
+    }
+}
+
+/// Recognizes a condition that tests a variable against `nothing`, i.e.,
+/// `x != nothing` or `x == nothing` in either order. Returns the variable
+/// and whether the condition holds when the variable is not `nothing`.
+fn nothing_check(condition: &Expression) -> Option<(&str, bool)> {
+    let Expression::Infix(infix) = condition else {
+        return None;
+    };
+    let holds_when_present = match infix.operator {
+        InfixOperator::NotEqual => true,
+        InfixOperator::Equal => false,
+        _ => return None,
+    };
+    match (&infix.left, &infix.right) {
+        (Expression::Identifier(name, _), Expression::Literal(Literal::Nothing, _))
+        | (Expression::Literal(Literal::Nothing, _), Expression::Identifier(name, _)) => {
+            Some((name, holds_when_present))
+        }
+        _ => None,
+    }
+}
+
+/// Map keys written as bare names, as in `{id: 1}`, are string keys.
+fn map_key(key: &Expression) -> Expression {
+    match key {
+        Expression::Identifier(name, span) => {
+            Expression::Literal(Literal::String(name.clone()), *span)
+        }
+        _ => key.clone(),
+    }
+}
+
+/// The value a fix gives a missing field of type `ty`, if it has an obvious one.
+fn default_value(ty: &Type) -> Option<&'static str> {
+    match ty {
+        Type::Number => Some("0"),
+        Type::String => Some("\"\""),
+        Type::Boolean => Some("false"),
+        Type::List(_) => Some("[]"),
+        _ => None,
+    }
+}
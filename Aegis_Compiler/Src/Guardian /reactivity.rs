@@ -0,0 +1,174 @@
+//! This module holds the reactivity dependency graph the Guardian builds while
+//! checking a program. Aegis has a compile-time reactivity model: every UI
+//! property and every derived value that reads `let's track` state is recorded
+//! here, so a backend knows exactly what to update when that state is mutated,
+//! without any runtime dependency tracking. Every write to tracked state is
+//! recorded too, whether by assignment or by a mutating method like `List.add`.
+
+use crate::ast::{Expression, Literal};
+use crate::token::Span;
+
+/// Something whose value is computed from tracked state.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Dependent {
+    /// A property of a UI element, e.g., the content of `text status_text`.
+    UiProperty {
+        /// The name of the element, e.g., `text`.
+        element: String,
+        /// The position of the property in the element's property list.
+        index: usize,
+        /// The name of a named property, or `None` for a positional argument.
+        name: Option<String>,
+        span: Span,
+    },
+    /// A `let's` binding whose value is computed from tracked state,
+    /// e.g., `let's doubled = count * 2`.
+    DerivedValue { name: String, span: Span },
+}
+
+/// A write to tracked state.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mutation {
+    pub variable: String,
+    /// The mutating method that was called, e.g., `add`, or `None` for an
+    /// assignment.
+    pub method: Option<String>,
+    pub span: Span,
+}
+
+/// Maps each dependent to the tracked variables it reads, directly or through
+/// derived values, and lists the places tracked variables are written.
+#[derive(Debug, Clone, Default)]
+pub struct DependencyGraph {
+    edges: Vec<(Dependent, Vec<String>)>,
+    mutations: Vec<Mutation>,
+}
+
+impl DependencyGraph {
+    /// Records that `dependent` reads the given tracked variables. Dependents
+    /// that read no tracked state are not recorded.
+    pub fn add(&mut self, dependent: Dependent, mut sources: Vec<String>) {
+        sources.sort();
+        sources.dedup();
+        if !sources.is_empty() {
+            self.edges.push((dependent, sources));
+        }
+    }
+
+    /// Returns the tracked variables `dependent` reads.
+    pub fn dependencies_of(&self, dependent: &Dependent) -> &[String] {
+        self.edges
+            .iter()
+            .find(|(d, _)| d == dependent)
+            .map(|(_, sources)| sources.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// Returns everything that must be updated when `variable` changes, in the
+    /// order it was declared.
+    pub fn dependents_of(&self, variable: &str) -> Vec<&Dependent> {
+        self.edges
+            .iter()
+            .filter(|(_, sources)| sources.iter().any(|s| s == variable))
+            .map(|(dependent, _)| dependent)
+            .collect()
+    }
+
+    /// Returns the tracked variables a derived value reads. The most recent
+    /// declaration wins, matching how later bindings shadow earlier ones.
+    pub fn derived_sources(&self, name: &str) -> Option<&[String]> {
+        self.edges
+            .iter()
+            .rev()
+            .find(|(dependent, _)| {
+                matches!(dependent, Dependent::DerivedValue { name: n, .. } if n == name)
+            })
+            .map(|(_, sources)| sources.as_slice())
+    }
+
+    /// Iterates over every dependent along with the tracked variables it reads.
+    pub fn iter(&self) -> impl Iterator<Item = (&Dependent, &[String])> {
+        self.edges
+            .iter()
+            .map(|(dependent, sources)| (dependent, sources.as_slice()))
+    }
+
+    /// Returns true if no dependents were recorded.
+    pub fn is_empty(&self) -> bool {
+        self.edges.is_empty()
+    }
+
+    /// Records a write to a tracked variable.
+    pub fn record_mutation(&mut self, mutation: Mutation) {
+        self.mutations.push(mutation);
+    }
+
+    /// Returns every write to tracked state, in the order they were checked.
+    pub fn mutations(&self) -> &[Mutation] {
+        &self.mutations
+    }
+
+    /// Returns the writes to a single tracked variable.
+    pub fn mutations_of(&self, variable: &str) -> Vec<&Mutation> {
+        self.mutations
+            .iter()
+            .filter(|mutation| mutation.variable == variable)
+            .collect()
+    }
+}
+
+/// Collects the names of every identifier an expression reads.
+pub fn collect_reads<'a>(expr: &'a Expression, reads: &mut Vec<&'a str>) {
+    match expr {
+        Expression::Identifier(name, _) => reads.push(name),
+        Expression::Literal(Literal::List(list), _) => {
+            for element in &list.elements {
+                collect_reads(element, reads);
+            }
+        }
+        Expression::Literal(Literal::Map(map), _) => {
+            for (key, value) in &map.pairs {
+                collect_reads(key, reads);
+                collect_reads(value, reads);
+            }
+        }
+        Expression::Literal(..) | Expression::AskJs(_) => {}
+        Expression::Prefix(prefix) => collect_reads(&prefix.right, reads),
+        Expression::Infix(infix) => {
+            collect_reads(&infix.left, reads);
+            collect_reads(&infix.right, reads);
+        }
+        Expression::If(if_expr) => {
+            collect_reads(&if_expr.condition, reads);
+            collect_reads(&if_expr.then_branch, reads);
+            if let Some(else_branch) = &if_expr.else_branch {
+                collect_reads(else_branch, reads);
+            }
+        }
+        Expression::When(when_expr) => {
+            collect_reads(&when_expr.value, reads);
+            for case in &when_expr.cases {
+                if let Some(guard) = &case.guard {
+                    collect_reads(guard, reads);
+                }
+                collect_reads(&case.body, reads);
+            }
+        }
+        Expression::Call(call) => {
+            collect_reads(&call.function, reads);
+            for argument in &call.arguments {
+                collect_reads(argument, reads);
+            }
+        }
+        Expression::MemberAccess(member_access) | Expression::SafeMemberAccess(member_access) => {
+            collect_reads(&member_access.object, reads)
+        }
+        Expression::Await(await_expr) => collect_reads(&await_expr.expression, reads),
+        Expression::Lambda(lambda) => {
+            let mut body_reads = Vec::new();
+            collect_reads(&lambda.body, &mut body_reads);
+            body_reads.retain(|read| lambda.parameters.iter().all(|p| p.name != *read));
+            reads.extend(body_reads);
+        }
+    }
+}
@@ -0,0 +1,291 @@
+//! This module is the standard-library type environment: the methods the
+//! built-in `List`, `Map`, `Set`, `Optional` and `string` types provide, with
+//! their generic signatures. Signatures are written in terms of the receiver's
+//! type parameters (`T` for element types, `K`/`V` for maps), which are
+//! substituted with the receiver's actual types when a method is looked up.
+//! Methods that modify their receiver are marked as mutating, so the Guardian
+//! can treat a call like `tasks.add(task)` as a write to `tasks`.
+
+use crate::guardian_types::Type;
+use std::collections::HashMap;
+
+/// The signature of a method, built in or defined on a contract or interface.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MethodSignature {
+    pub name: String,
+    pub params: Vec<Type>,
+    pub return_type: Type,
+    /// Whether calling the method modifies the receiver.
+    pub mutates: bool,
+    /// The interfaces the method's own type arguments must implement, as
+    /// pairs of a type parameter and an interface.
+    pub bounds: Vec<(String, String)>,
+}
+
+impl MethodSignature {
+    pub fn new(name: &str, params: Vec<Type>, return_type: Type) -> Self {
+        Self {
+            name: name.to_string(),
+            params,
+            return_type,
+            mutates: false,
+            bounds: Vec::new(),
+        }
+    }
+
+    fn mutating(mut self) -> Self {
+        self.mutates = true;
+        self
+    }
+
+    /// Replaces the generic parameters of the signature with concrete types.
+    pub fn substitute(self, bindings: &HashMap<String, Type>) -> Self {
+        Self {
+            params: self
+                .params
+                .iter()
+                .map(|p| substitute(p, bindings))
+                .collect(),
+            return_type: substitute(&self.return_type, bindings),
+            ..self
+        }
+    }
+
+    /// Checks the argument types of a call against the parameters and returns
+    /// the type of its result, inferring any generic parameters of the method
+    /// itself (like the `U` of `map`) from the arguments. On a mismatch, returns
+    /// the index of the first offending argument.
+    pub fn check_call(&self, args: &[Type]) -> Result<Type, usize> {
+        let mut bindings = HashMap::new();
+        for (index, (param, arg)) in self.params.iter().zip(args).enumerate() {
+            if !bind_generics(param, arg, &mut bindings) {
+                return Err(index);
+            }
+        }
+        Ok(erase_generics(&substitute(&self.return_type, &bindings)))
+    }
+}
+
+fn generic(name: &str) -> Type {
+    Type::Generic(name.to_string())
+}
+
+fn list(ty: Type) -> Type {
+    Type::List(Box::new(ty))
+}
+
+fn optional(ty: Type) -> Type {
+    Type::Optional(Box::new(ty))
+}
+
+fn function(params: Vec<Type>, return_type: Type) -> Type {
+    Type::Function {
+        params,
+        return_type: Box::new(return_type),
+    }
+}
+
+/// The methods of `List<T>`.
+fn list_methods() -> Vec<MethodSignature> {
+    let t = generic("T");
+    let predicate = function(vec![t.clone()], Type::Boolean);
+    vec![
+        MethodSignature::new("add", vec![t.clone()], Type::Nothing).mutating(),
+        MethodSignature::new("insert", vec![Type::Number, t.clone()], Type::Nothing).mutating(),
+        MethodSignature::new("remove", vec![Type::Number], t.clone()).mutating(),
+        MethodSignature::new("clear", vec![], Type::Nothing).mutating(),
+        MethodSignature::new("get", vec![Type::Number], optional(t.clone())),
+        MethodSignature::new("first", vec![], optional(t.clone())),
+        MethodSignature::new("last", vec![], optional(t.clone())),
+        MethodSignature::new("length", vec![], Type::Number),
+        MethodSignature::new("isEmpty", vec![], Type::Boolean),
+        MethodSignature::new("contains", vec![t.clone()], Type::Boolean),
+        MethodSignature::new("indexOf", vec![t.clone()], Type::Number),
+        MethodSignature::new("findIndex", vec![predicate.clone()], Type::Number),
+        MethodSignature::new("find", vec![predicate.clone()], optional(t.clone())),
+        MethodSignature::new("filter", vec![predicate], list(t.clone())),
+        MethodSignature::new(
+            "map",
+            vec![function(vec![t.clone()], generic("U"))],
+            list(generic("U")),
+        ),
+        MethodSignature::new("join", vec![Type::String], Type::String),
+    ]
+}
+
+/// The methods of `Map<K, V>`.
+fn map_methods() -> Vec<MethodSignature> {
+    let (k, v) = (generic("K"), generic("V"));
+    vec![
+        MethodSignature::new("set", vec![k.clone(), v.clone()], Type::Nothing).mutating(),
+        MethodSignature::new("remove", vec![k.clone()], optional(v.clone())).mutating(),
+        MethodSignature::new("clear", vec![], Type::Nothing).mutating(),
+        MethodSignature::new("get", vec![k.clone()], optional(v.clone())),
+        MethodSignature::new("containsKey", vec![k.clone()], Type::Boolean),
+        MethodSignature::new("keys", vec![], list(k)),
+        MethodSignature::new("values", vec![], list(v)),
+        MethodSignature::new("length", vec![], Type::Number),
+        MethodSignature::new("isEmpty", vec![], Type::Boolean),
+    ]
+}
+
+/// The methods of `Set<T>`.
+fn set_methods() -> Vec<MethodSignature> {
+    let t = generic("T");
+    vec![
+        MethodSignature::new("add", vec![t.clone()], Type::Boolean).mutating(),
+        MethodSignature::new("remove", vec![t.clone()], Type::Boolean).mutating(),
+        MethodSignature::new("clear", vec![], Type::Nothing).mutating(),
+        MethodSignature::new("contains", vec![t.clone()], Type::Boolean),
+        MethodSignature::new("length", vec![], Type::Number),
+        MethodSignature::new("isEmpty", vec![], Type::Boolean),
+        MethodSignature::new("toList", vec![], list(t)),
+    ]
+}
+
+/// The methods of `Optional<T>`.
+fn optional_methods() -> Vec<MethodSignature> {
+    let t = generic("T");
+    vec![
+        MethodSignature::new("isNothing", vec![], Type::Boolean),
+        MethodSignature::new("orElse", vec![t.clone()], t.clone()),
+        MethodSignature::new(
+            "map",
+            vec![function(vec![t], generic("U"))],
+            optional(generic("U")),
+        ),
+    ]
+}
+
+/// The methods of `string`. Strings are immutable, so none of them mutate.
+fn string_methods() -> Vec<MethodSignature> {
+    let s = Type::String;
+    vec![
+        MethodSignature::new("length", vec![], Type::Number),
+        MethodSignature::new("isEmpty", vec![], Type::Boolean),
+        MethodSignature::new("trim", vec![], s.clone()),
+        MethodSignature::new("toUpperCase", vec![], s.clone()),
+        MethodSignature::new("toLowerCase", vec![], s.clone()),
+        MethodSignature::new("split", vec![s.clone()], list(s.clone())),
+        MethodSignature::new("contains", vec![s.clone()], Type::Boolean),
+        MethodSignature::new("startsWith", vec![s.clone()], Type::Boolean),
+        MethodSignature::new("endsWith", vec![s.clone()], Type::Boolean),
+        MethodSignature::new("replace", vec![s.clone(), s.clone()], s),
+    ]
+}
+
+/// Returns every method of `receiver`, with the receiver's type parameters
+/// substituted, or `None` if the type has no built-in methods.
+pub fn methods_of(receiver: &Type) -> Option<Vec<MethodSignature>> {
+    let mut bindings = HashMap::new();
+    let methods = match receiver {
+        Type::List(t) => {
+            bindings.insert("T".to_string(), (**t).clone());
+            list_methods()
+        }
+        Type::Set(t) => {
+            bindings.insert("T".to_string(), (**t).clone());
+            set_methods()
+        }
+        Type::Optional(t) => {
+            bindings.insert("T".to_string(), (**t).clone());
+            optional_methods()
+        }
+        Type::Map(k, v) => {
+            bindings.insert("K".to_string(), (**k).clone());
+            bindings.insert("V".to_string(), (**v).clone());
+            map_methods()
+        }
+        Type::String => string_methods(),
+        _ => return None,
+    };
+    Some(
+        methods
+            .into_iter()
+            .map(|method| method.substitute(&bindings))
+            .collect(),
+    )
+}
+
+/// Looks up a method of `receiver` by name.
+pub fn lookup_method(receiver: &Type, name: &str) -> Option<MethodSignature> {
+    methods_of(receiver)?
+        .into_iter()
+        .find(|method| method.name == name)
+}
+
+/// Matches an argument type against a parameter type, binding the generic
+/// parameters the parameter mentions. Returns false if the types don't fit.
+pub fn bind_generics(param: &Type, arg: &Type, bindings: &mut HashMap<String, Type>) -> bool {
+    match (param, arg) {
+        (Type::Error, _) | (_, Type::Error) => true,
+        (Type::Generic(name), _) => match bindings.get(name) {
+            Some(bound) => bind_generics(&bound.clone(), arg, bindings),
+            None => {
+                bindings.insert(name.clone(), arg.clone());
+                true
+            }
+        },
+        (Type::List(p), Type::List(a))
+        | (Type::Set(p), Type::Set(a))
+        | (Type::Optional(p), Type::Optional(a))
+        | (Type::Future(p), Type::Future(a)) => bind_generics(p, a, bindings),
+        (Type::Map(pk, pv), Type::Map(ak, av)) => {
+            bind_generics(pk, ak, bindings) && bind_generics(pv, av, bindings)
+        }
+        (
+            Type::Function {
+                params: pp,
+                return_type: pr,
+            },
+            Type::Function {
+                params: ap,
+                return_type: ar,
+            },
+        ) => {
+            pp.len() == ap.len()
+                && pp
+                    .iter()
+                    .zip(ap)
+                    .all(|(p, a)| bind_generics(p, a, bindings))
+                && bind_generics(pr, ar, bindings)
+        }
+        _ => param == arg,
+    }
+}
+
+/// Replaces generic parameters in `ty` with their bound types. Parameters
+/// with no binding are left as they are.
+pub fn substitute(ty: &Type, bindings: &HashMap<String, Type>) -> Type {
+    map_generics(ty, &|name| bindings.get(name).cloned())
+}
+
+/// Replaces any generic parameters left in `ty` with `Type::Error`, i.e., unknown.
+fn erase_generics(ty: &Type) -> Type {
+    map_generics(ty, &|_| Some(Type::Error))
+}
+
+/// Rebuilds `ty`, replacing each generic parameter for which `f` returns a type.
+pub fn map_generics(ty: &Type, f: &dyn Fn(&str) -> Option<Type>) -> Type {
+    let sub = |t: &Type| Box::new(map_generics(t, f));
+    match ty {
+        Type::Generic(name) => f(name).unwrap_or_else(|| ty.clone()),
+        Type::List(t) => Type::List(sub(t)),
+        Type::Set(t) => Type::Set(sub(t)),
+        Type::Optional(t) => Type::Optional(sub(t)),
+        Type::Future(t) => Type::Future(sub(t)),
+        Type::Map(k, v) => Type::Map(sub(k), sub(v)),
+        Type::GenericInstance(name, args) => Type::GenericInstance(
+            name.clone(),
+            args.iter().map(|arg| map_generics(arg, f)).collect(),
+        ),
+        Type::Function {
+            params,
+            return_type,
+        } => Type::Function {
+            params: params.iter().map(|p| map_generics(p, f)).collect(),
+            return_type: sub(return_type),
+        },
+        other => other.clone(),
+    }
+}
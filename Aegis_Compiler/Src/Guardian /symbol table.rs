@@ -1,5 +1,7 @@
+use crate::guardian_stdlib::MethodSignature;
 use crate::guardian_types::Type;
-use std::collections::HashMap;
+use crate::token::Span;
+use std::collections::{HashMap, HashSet};
 
 /// Represents a declared identifier in the code.
 #[derive(Debug, Clone)]
@@ -13,22 +15,64 @@ pub struct Symbol {
 pub enum SymbolKind {
     Variable {
         is_tracked: bool,
+        /// The `let's` that declares the variable, or `None` for a name a
+        /// `when` pattern binds.
+        declared_at: Option<Span>,
     },
+    /// A parameter of a function, method or lambda, including a method's
+    /// `self`. Like a plain `let's`, it can't be assigned to or changed.
+    Parameter,
     Type,
     Function {
         param_types: Vec<Type>,
         return_type: Box<Type>,
+        /// The interfaces type arguments must implement, as pairs of a type
+        /// parameter and an interface, e.g., `("T", "Comparable")`.
+        bounds: Vec<(String, String)>,
+    },
+    /// A built-in function that takes any number of arguments of any type,
+    /// e.g., `print`.
+    VariadicFunction {
+        return_type: Box<Type>,
     },
     Enum {
         variants: Vec<String>,
     },
     Contract {
         fields: HashMap<String, Type>,
+        /// The fields with a default value, which contract literals may leave out.
+        defaults: HashSet<String>,
+        /// Computed properties, read like fields but never assigned.
+        properties: HashMap<String, Type>,
+        methods: HashMap<String, MethodSignature>,
     },
     GenericContract {
         params: Vec<String>,
         fields: HashMap<String, Type>,
+        defaults: HashSet<String>,
+        properties: HashMap<String, Type>,
+        methods: HashMap<String, MethodSignature>,
     },
+    /// An `interface`. `Self` in its method signatures is `Type::Generic("Self")`.
+    Interface {
+        methods: HashMap<String, MethodSignature>,
+    },
+    /// A named `style` declaration.
+    Style,
+}
+
+impl SymbolKind {
+    /// Returns true if the symbol names a type that can appear in a type annotation.
+    pub fn is_type(&self) -> bool {
+        matches!(
+            self,
+            SymbolKind::Type
+                | SymbolKind::Enum { .. }
+                | SymbolKind::Contract { .. }
+                | SymbolKind::GenericContract { .. }
+                | SymbolKind::Interface { .. }
+        )
+    }
 }
 
 /// A new table is created for each new scope (e.g., function or loop bodies).
@@ -37,6 +81,9 @@ pub struct SymbolTable {
     store: HashMap<String, Symbol>,
     /// Optional link to the parent (outer) scope.
     outer: Option<Box<SymbolTable>>,
+    /// Whether the symbols of this scope can't be named in the source, like
+    /// the declarations of other modules that imported ones mention.
+    hidden: bool,
 }
 
 impl SymbolTable {
@@ -44,6 +91,16 @@ impl SymbolTable {
         Self {
             store: HashMap::new(),
             outer: Some(Box::new(outer)),
+            hidden: false,
+        }
+    }
+
+    /// Creates a scope whose symbols are found by `resolve`, to check the
+    /// values that have their types, but not by `resolve_visible`.
+    pub fn new_hidden() -> Self {
+        Self {
+            hidden: true,
+            ..Self::default()
         }
     }
 
@@ -61,6 +118,49 @@ impl SymbolTable {
         true
     }
 
+    /// Replaces the type and kind of a symbol already defined in the current
+    /// scope, e.g., to complete a forward declaration. Fails if there is none.
+    pub fn replace(&mut self, name: &str, ty: Type, kind: SymbolKind) -> bool {
+        match self.store.get_mut(name) {
+            Some(symbol) => {
+                symbol.ty = ty;
+                symbol.kind = kind;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Places `outer` around the outermost scope of this table, so its
+    /// symbols are found only when no scope here defines the same name.
+    pub fn enclose_in(&mut self, outer: SymbolTable) {
+        match &mut self.outer {
+            Some(parent) => parent.enclose_in(outer),
+            None => self.outer = Some(Box::new(outer)),
+        }
+    }
+
+    /// Consumes this scope and returns its parent scope, if it has one.
+    pub fn into_outer(self) -> Option<SymbolTable> {
+        self.outer.map(|outer| *outer)
+    }
+
+    /// The names visible from the current scope, sorted, e.g., to suggest
+    /// one in place of a misspelled name.
+    pub fn names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = if self.hidden {
+            Vec::new()
+        } else {
+            self.store.keys().map(String::as_str).collect()
+        };
+        if let Some(outer) = &self.outer {
+            names.extend(outer.names());
+        }
+        names.sort_unstable();
+        names.dedup();
+        names
+    }
+
     /// Resolves a symbol by looking in the current scope, then recursively checking outer scopes.
     pub fn resolve(&self, name: &str) -> Option<Symbol> {
         match self.store.get(name) {
@@ -68,4 +168,13 @@ impl SymbolTable {
             None => self.outer.as_ref().and_then(|o| o.resolve(name)),
         }
     }
+
+    /// Resolves a name written in the source, which can't name a symbol of
+    /// a hidden scope.
+    pub fn resolve_visible(&self, name: &str) -> Option<Symbol> {
+        match self.store.get(name) {
+            Some(symbol) if !self.hidden => Some(symbol.clone()),
+            _ => self.outer.as_ref().and_then(|o| o.resolve_visible(name)),
+        }
+    }
 }
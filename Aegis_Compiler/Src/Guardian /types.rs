@@ -1,11 +1,14 @@
 //! This module defines the `Type` enum.
 //! It is used by the Guardian (semantic analyzer) to represent and track the
 //! type of every expression and variable within an Aegis program.
+//! It also defines the typed style system: every key a `style { ... }` block
+//! can contain, and the kind of value each key accepts.
 
 use std::collections::HashMap;
+use std::fmt;
 
 /// The internal representation of types within the Guardian.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone)]
 pub enum Type {
     /// The primitive number type, representing both integers and floating-point values.
     Number,
@@ -23,6 +26,12 @@ pub enum Type {
     /// A user-defined type from a `contract`, identified by its name.
     Custom(String),
 
+    /// A value of any type that implements the named `interface`.
+    Interface(String),
+
+    /// A generic contract applied to type arguments, e.g., `Box<number>`.
+    GenericInstance(String, Vec<Type>),
+
     /// A generic List with an inner type, e.g., `List<Number>`.
     List(Box<Type>),
 
@@ -58,4 +67,286 @@ pub enum Type {
 
     // NEW: A placeholder for a generic type parameter like `T`.
     Generic(String),
+
+    /// A type parameter of the generic function being checked, e.g., the `T`
+    /// in the body of `let's first<T>(items: List<T>)`. Unlike a `Generic`, it
+    /// is never replaced with a type variable: the body must work for any `T`.
+    Param(String),
+
+    /// A set of validated style properties, from a `style { ... }` block or a
+    /// named `style` declaration.
+    Style,
+
+    /// A type that isn't known yet, like the element type of an empty `[]`.
+    /// Type variables are bound by unification as the Guardian learns more.
+    Var(u32),
+}
+
+impl PartialEq for Type {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            // Enums are nominal: two enum types are the same type if they have the
+            // same name. This also lets a recursive enum refer to itself by name
+            // before its variants are fully known.
+            (Type::Enum { name: a, .. }, Type::Enum { name: b, .. }) => a == b,
+            (Type::Custom(a), Type::Custom(b))
+            | (Type::Generic(a), Type::Generic(b))
+            | (Type::Param(a), Type::Param(b))
+            | (Type::Interface(a), Type::Interface(b)) => a == b,
+            (Type::List(a), Type::List(b))
+            | (Type::Set(a), Type::Set(b))
+            | (Type::Optional(a), Type::Optional(b))
+            | (Type::Future(a), Type::Future(b)) => a == b,
+            (Type::Map(ak, av), Type::Map(bk, bv)) => ak == bk && av == bv,
+            (Type::GenericInstance(a, aa), Type::GenericInstance(b, ba)) => a == b && aa == ba,
+            (Type::Var(a), Type::Var(b)) => a == b,
+            (
+                Type::Function {
+                    params: ap,
+                    return_type: ar,
+                },
+                Type::Function {
+                    params: bp,
+                    return_type: br,
+                },
+            ) => ap == bp && ar == br,
+            (Type::Number, Type::Number)
+            | (Type::Boolean, Type::Boolean)
+            | (Type::String, Type::String)
+            | (Type::Nothing, Type::Nothing)
+            | (Type::Error, Type::Error)
+            | (Type::Dynamic, Type::Dynamic)
+            | (Type::Style, Type::Style) => true,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for Type {}
+
+/// Formats a type the way it is written in Aegis source, e.g., `List<number>`
+/// or `User?`. Types that aren't known yet are shown as `_`.
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn join(types: &[Type]) -> String {
+            types
+                .iter()
+                .map(Type::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        }
+        match self {
+            Type::Number => write!(f, "number"),
+            Type::Boolean => write!(f, "boolean"),
+            Type::String => write!(f, "string"),
+            Type::Nothing => write!(f, "nothing"),
+            Type::Error | Type::Var(_) => write!(f, "_"),
+            Type::Dynamic => write!(f, "dynamic"),
+            Type::Style => write!(f, "style"),
+            Type::Custom(name)
+            | Type::Generic(name)
+            | Type::Param(name)
+            | Type::Interface(name)
+            | Type::Enum { name, .. } => {
+                write!(f, "{}", name)
+            }
+            Type::GenericInstance(name, args) => write!(f, "{}<{}>", name, join(args)),
+            Type::List(t) => write!(f, "List<{}>", t),
+            Type::Set(t) => write!(f, "Set<{}>", t),
+            Type::Future(t) => write!(f, "Future<{}>", t),
+            Type::Map(k, v) => write!(f, "Map<{}, {}>", k, v),
+            Type::Optional(t) => write!(f, "{}?", t),
+            Type::Function {
+                params,
+                return_type,
+            } => write!(f, "({}) -> {}", join(params), return_type),
+        }
+    }
+}
+
+/// The kind of value a style property accepts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StyleValueKind {
+    /// A length in density-independent pixels, e.g., `16` or `"16dp"`.
+    Dimension,
+    /// A text size in scale-independent pixels, e.g., `18` or `"18sp"`.
+    FontSize,
+    /// A plain number, e.g., a layout `weight`.
+    Number,
+    /// A number between 0 and 1, e.g., an `opacity`.
+    Fraction,
+    /// A hex color (`#RGB`, `#RRGGBB` or `#AARRGGBB`) or a named color.
+    Color,
+    /// One of a fixed set of keywords, e.g., `"line-through"`.
+    Keyword(&'static [&'static str]),
+}
+
+impl StyleValueKind {
+    /// Returns true if a value of type `ty` may be used for this kind. Strings
+    /// are accepted where units, colors or keywords are expected; their
+    /// contents are checked with `check_literal` when known at compile time.
+    pub fn accepts(&self, ty: &Type) -> bool {
+        matches!(
+            (self, ty),
+            (_, Type::Error)
+                | (
+                    StyleValueKind::Dimension | StyleValueKind::FontSize,
+                    Type::Number | Type::String
+                )
+                | (
+                    StyleValueKind::Number | StyleValueKind::Fraction,
+                    Type::Number
+                )
+                | (
+                    StyleValueKind::Color | StyleValueKind::Keyword(_),
+                    Type::String
+                )
+        )
+    }
+
+    /// Describes the values this kind accepts, for error messages.
+    pub fn describe(&self) -> String {
+        match self {
+            StyleValueKind::Dimension => "a size".to_string(),
+            StyleValueKind::FontSize => "a font size".to_string(),
+            StyleValueKind::Number => "a number".to_string(),
+            StyleValueKind::Fraction => "a number between 0 and 1".to_string(),
+            StyleValueKind::Color => "a color".to_string(),
+            StyleValueKind::Keyword(keywords) => format!("one of {}", keywords.join(", ")),
+        }
+    }
+
+    /// Validates a literal value, returning a description of the problem if
+    /// it isn't valid for this kind.
+    pub fn check_literal(&self, value: &StyleLiteral) -> Result<(), String> {
+        match (self, value) {
+            (StyleValueKind::Dimension, StyleLiteral::String(s)) => check_unit(s, &["dp", "px"]),
+            (StyleValueKind::FontSize, StyleLiteral::String(s)) => check_unit(s, &["sp"]),
+            (StyleValueKind::Fraction, StyleLiteral::Number(n)) if !(0.0..=1.0).contains(n) => {
+                Err(format!("expected a value between 0 and 1, found {}", n))
+            }
+            (StyleValueKind::Color, StyleLiteral::String(s)) => check_color(s),
+            (StyleValueKind::Keyword(keywords), StyleLiteral::String(s)) => {
+                if keywords.contains(&s.as_str()) {
+                    Ok(())
+                } else {
+                    Err(format!(
+                        "expected one of {}, found \"{}\"",
+                        keywords
+                            .iter()
+                            .map(|k| format!("\"{}\"", k))
+                            .collect::<Vec<_>>()
+                            .join(", "),
+                        s
+                    ))
+                }
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// A literal style value, as written in the source.
+#[derive(Debug, Clone, PartialEq)]
+pub enum StyleLiteral {
+    Number(f64),
+    String(String),
+}
+
+/// A property that can appear in a `style { ... }` block.
+#[derive(Debug, Clone, Copy)]
+pub struct StyleProperty {
+    pub name: &'static str,
+    pub kind: StyleValueKind,
+}
+
+const fn style(name: &'static str, kind: StyleValueKind) -> StyleProperty {
+    StyleProperty { name, kind }
+}
+
+/// Every known style property.
+pub const STYLE_PROPERTIES: &[StyleProperty] = &[
+    style("font_size", StyleValueKind::FontSize),
+    style(
+        "font_weight",
+        StyleValueKind::Keyword(&["normal", "medium", "bold"]),
+    ),
+    style("text_color", StyleValueKind::Color),
+    style(
+        "text_decoration",
+        StyleValueKind::Keyword(&["none", "underline", "line-through"]),
+    ),
+    style(
+        "text_align",
+        StyleValueKind::Keyword(&["start", "center", "end"]),
+    ),
+    style("background_color", StyleValueKind::Color),
+    style("border_color", StyleValueKind::Color),
+    style("border_width", StyleValueKind::Dimension),
+    style("padding", StyleValueKind::Dimension),
+    style("padding_h", StyleValueKind::Dimension),
+    style("padding_v", StyleValueKind::Dimension),
+    style("margin", StyleValueKind::Dimension),
+    style("width", StyleValueKind::Dimension),
+    style("height", StyleValueKind::Dimension),
+    style("corner_radius", StyleValueKind::Dimension),
+    style("weight", StyleValueKind::Number),
+    style("opacity", StyleValueKind::Fraction),
+    style(
+        "alignment",
+        StyleValueKind::Keyword(&["start", "center", "end", "space_between"]),
+    ),
+];
+
+/// The colors that can be referred to by name.
+pub const NAMED_COLORS: &[&str] = &[
+    "black",
+    "white",
+    "red",
+    "green",
+    "blue",
+    "yellow",
+    "gray",
+    "transparent",
+];
+
+/// Looks up a style property by name.
+pub fn style_property(name: &str) -> Option<&'static StyleProperty> {
+    STYLE_PROPERTIES
+        .iter()
+        .find(|property| property.name == name)
+}
+
+fn check_unit(value: &str, units: &[&str]) -> Result<(), String> {
+    let valid = units.iter().any(|unit| {
+        value
+            .strip_suffix(unit)
+            .is_some_and(|number| number.parse::<f64>().is_ok())
+    });
+    if valid {
+        Ok(())
+    } else {
+        Err(format!(
+            "expected a number or a size in {}, found \"{}\"",
+            units.join(" or "),
+            value
+        ))
+    }
+}
+
+fn check_color(value: &str) -> Result<(), String> {
+    if let Some(hex) = value.strip_prefix('#') {
+        if matches!(hex.len(), 3 | 6 | 8) && hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Ok(());
+        }
+        return Err(format!(
+            "\"{}\" is not a valid hex color; use #RGB, #RRGGBB or #AARRGGBB",
+            value
+        ));
+    }
+    if NAMED_COLORS.contains(&value) {
+        Ok(())
+    } else {
+        Err(format!("\"{}\" is not a known color", value))
+    }
 }
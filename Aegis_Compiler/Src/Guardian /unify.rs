@@ -0,0 +1,283 @@
+//! This module is the Guardian's unification engine. A type the checker can't
+//! know yet, like the element type of an empty `[]` or the `U` of `List.map`,
+//! is represented by a type variable, which is bound to a concrete type the
+//! first time it is unified with one. Every binding remembers the reason it
+//! was made, so a later conflict can explain where the expected type came
+//! from, e.g., "expected number because of the first element of the list".
+
+use crate::guardian_types::Type;
+use crate::token::Span;
+use std::collections::{HashMap, HashSet};
+
+/// Why a type is expected, e.g., "the annotation on 'total'".
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reason {
+    pub description: String,
+    pub span: Span,
+}
+
+impl Reason {
+    pub fn new(description: impl Into<String>, span: Span) -> Self {
+        Self {
+            description: description.into(),
+            span,
+        }
+    }
+}
+
+/// A failure to unify two types.
+#[derive(Debug, Clone, PartialEq)]
+pub enum UnifyError {
+    /// The types can't be made to fit. If the expected type came from an
+    /// earlier binding of a type variable, `reason` is why that binding was made.
+    Mismatch {
+        expected: Type,
+        actual: Type,
+        reason: Option<Reason>,
+    },
+    /// Binding the variable would make an infinite type, e.g., `T = List<T>`.
+    Occurs { var: u32, ty: Type },
+}
+
+/// The bindings of every type variable created while checking a program,
+/// and the interfaces each named type implements.
+#[derive(Debug, Clone, Default)]
+pub struct Unifier {
+    bindings: Vec<Option<(Type, Reason)>>,
+    /// The interfaces each contract declares it implements.
+    implementations: HashMap<String, HashSet<String>>,
+    /// The bounds of the type parameters in scope, e.g., `T: Comparable`.
+    param_bounds: HashMap<String, String>,
+}
+
+impl Unifier {
+    /// Creates a new, unbound type variable.
+    pub fn fresh(&mut self) -> Type {
+        self.bindings.push(None);
+        Type::Var(self.bindings.len() as u32 - 1)
+    }
+
+    /// Replaces each generic parameter in `ty`, like the `U` of a method
+    /// signature, with a fresh type variable. The same name always gets the
+    /// same variable within `instances`.
+    pub fn instantiate(&mut self, ty: &Type, instances: &mut HashMap<String, Type>) -> Type {
+        collect_generics(ty, &mut |name| {
+            if !instances.contains_key(name) {
+                let var = self.fresh();
+                instances.insert(name.to_string(), var);
+            }
+        });
+        crate::guardian_stdlib::substitute(ty, instances)
+    }
+
+    /// Records that the contract `type_name` implements `interface`.
+    pub fn add_implementation(&mut self, type_name: &str, interface: &str) {
+        self.implementations
+            .entry(type_name.to_string())
+            .or_default()
+            .insert(interface.to_string());
+    }
+
+    /// Records every implementation known to `other`, e.g., those of the
+    /// contracts of an imported module.
+    pub fn add_implementations_from(&mut self, other: &Unifier) {
+        for (type_name, interfaces) in &other.implementations {
+            for interface in interfaces {
+                self.add_implementation(type_name, interface);
+            }
+        }
+    }
+
+    /// Replaces the bounds of the type parameters in scope, returning the
+    /// previous ones so they can be restored when the scope ends.
+    pub fn set_param_bounds(&mut self, bounds: HashMap<String, String>) -> HashMap<String, String> {
+        std::mem::replace(&mut self.param_bounds, bounds)
+    }
+
+    /// The interface the type parameter `name` is bounded by, if any.
+    pub fn param_bound(&self, name: &str) -> Option<&str> {
+        self.param_bounds.get(name).map(String::as_str)
+    }
+
+    /// Returns true if `ty` implements `interface`. Numbers and strings are
+    /// built-in implementations of `Comparable`.
+    pub fn implements(&self, ty: &Type, interface: &str) -> bool {
+        match self.shallow(ty).0 {
+            Type::Error => true,
+            Type::Number | Type::String => interface == "Comparable",
+            Type::Interface(name) => name == interface,
+            Type::Param(name) => self.param_bound(name) == Some(interface),
+            Type::Custom(name) | Type::GenericInstance(name, _) => self
+                .implementations
+                .get(name)
+                .is_some_and(|interfaces| interfaces.contains(interface)),
+            _ => false,
+        }
+    }
+
+    /// Follows bound variables until reaching a type that isn't one, along
+    /// with the reason for the last binding that was followed.
+    fn shallow<'a>(&'a self, mut ty: &'a Type) -> (&'a Type, Option<&'a Reason>) {
+        let mut reason = None;
+        while let Type::Var(var) = ty {
+            match &self.bindings[*var as usize] {
+                Some((bound, bound_reason)) => {
+                    ty = bound;
+                    reason = Some(bound_reason);
+                }
+                None => break,
+            }
+        }
+        (ty, reason)
+    }
+
+    /// Replaces every bound type variable in `ty` with its binding.
+    pub fn resolve(&self, ty: &Type) -> Type {
+        let (ty, _) = self.shallow(ty);
+        let sub = |t: &Type| Box::new(self.resolve(t));
+        match ty {
+            Type::List(t) => Type::List(sub(t)),
+            Type::Set(t) => Type::Set(sub(t)),
+            Type::Optional(t) => Type::Optional(sub(t)),
+            Type::Future(t) => Type::Future(sub(t)),
+            Type::Map(k, v) => Type::Map(sub(k), sub(v)),
+            Type::GenericInstance(name, args) => Type::GenericInstance(
+                name.clone(),
+                args.iter().map(|arg| self.resolve(arg)).collect(),
+            ),
+            Type::Function {
+                params,
+                return_type,
+            } => Type::Function {
+                params: params.iter().map(|p| self.resolve(p)).collect(),
+                return_type: sub(return_type),
+            },
+            other => other.clone(),
+        }
+    }
+
+    /// Returns true if `ty` is a type variable that hasn't been bound.
+    pub fn is_unbound(&self, ty: &Type) -> bool {
+        matches!(self.shallow(ty).0, Type::Var(_))
+    }
+
+    /// Makes `actual` fit where `expected` is required, binding type variables
+    /// on either side. As with assignment, a `T` fits where a `T?` is expected
+    /// but not the other way around. `reason` is recorded on every binding made.
+    pub fn unify(
+        &mut self,
+        expected: &Type,
+        actual: &Type,
+        reason: &Reason,
+    ) -> Result<(), Box<UnifyError>> {
+        let (expected_shallow, expected_reason) = self.shallow(expected);
+        let (expected_shallow, expected_reason) =
+            (expected_shallow.clone(), expected_reason.cloned());
+        let actual_shallow = self.shallow(actual).0.clone();
+
+        match (&expected_shallow, &actual_shallow) {
+            (Type::Error, _) | (_, Type::Error) => Ok(()),
+            (Type::Var(a), Type::Var(b)) if a == b => Ok(()),
+            (Type::Var(var), ty) | (ty, Type::Var(var)) => self.bind(*var, ty, reason),
+            (Type::List(e), Type::List(a))
+            | (Type::Set(e), Type::Set(a))
+            | (Type::Optional(e), Type::Optional(a))
+            | (Type::Future(e), Type::Future(a)) => self.unify(e, a, reason),
+            (Type::Map(ek, ev), Type::Map(ak, av)) => {
+                self.unify(ek, ak, reason)?;
+                self.unify(ev, av, reason)
+            }
+            (Type::GenericInstance(e, e_args), Type::GenericInstance(a, a_args))
+                if e == a && e_args.len() == a_args.len() =>
+            {
+                for (e_arg, a_arg) in e_args.iter().zip(a_args) {
+                    self.unify(e_arg, a_arg, reason)?;
+                }
+                Ok(())
+            }
+            (
+                Type::Function {
+                    params: e_params,
+                    return_type: e_return,
+                },
+                Type::Function {
+                    params: a_params,
+                    return_type: a_return,
+                },
+            ) if e_params.len() == a_params.len() => {
+                for (e_param, a_param) in e_params.iter().zip(a_params) {
+                    self.unify(e_param, a_param, reason)?;
+                }
+                self.unify(e_return, a_return, reason)
+            }
+            // A value of any type that implements an interface has the interface's type.
+            (Type::Interface(interface), actual) if self.implements(actual, interface) => Ok(()),
+            // `nothing` is only a value of optional types.
+            (Type::Optional(_), Type::Nothing) => Ok(()),
+            (Type::Optional(inner), _) => self.unify(inner, &actual_shallow, reason),
+            (e, a) if e == a => Ok(()),
+            _ => Err(Box::new(UnifyError::Mismatch {
+                expected: self.resolve(&expected_shallow),
+                actual: self.resolve(&actual_shallow),
+                reason: expected_reason,
+            })),
+        }
+    }
+
+    fn bind(&mut self, var: u32, ty: &Type, reason: &Reason) -> Result<(), Box<UnifyError>> {
+        if self.occurs(var, ty) {
+            return Err(Box::new(UnifyError::Occurs {
+                var,
+                ty: self.resolve(ty),
+            }));
+        }
+        self.bindings[var as usize] = Some((ty.clone(), reason.clone()));
+        Ok(())
+    }
+
+    /// The occurs check: returns true if `var` appears inside `ty`.
+    fn occurs(&self, var: u32, ty: &Type) -> bool {
+        match self.shallow(ty).0 {
+            Type::Var(other) => *other == var,
+            Type::List(t) | Type::Set(t) | Type::Optional(t) | Type::Future(t) => {
+                self.occurs(var, t)
+            }
+            Type::Map(k, v) => self.occurs(var, k) || self.occurs(var, v),
+            Type::GenericInstance(_, args) => args.iter().any(|arg| self.occurs(var, arg)),
+            Type::Function {
+                params,
+                return_type,
+            } => params.iter().any(|p| self.occurs(var, p)) || self.occurs(var, return_type),
+            _ => false,
+        }
+    }
+}
+
+/// Calls `f` with the name of every generic parameter in `ty`.
+fn collect_generics(ty: &Type, f: &mut dyn FnMut(&str)) {
+    match ty {
+        Type::Generic(name) => f(name),
+        Type::List(t) | Type::Set(t) | Type::Optional(t) | Type::Future(t) => {
+            collect_generics(t, f)
+        }
+        Type::Map(k, v) => {
+            collect_generics(k, f);
+            collect_generics(v, f);
+        }
+        Type::GenericInstance(_, args) => {
+            for arg in args {
+                collect_generics(arg, f);
+            }
+        }
+        Type::Function {
+            params,
+            return_type,
+        } => {
+            for param in params {
+                collect_generics(param, f);
+            }
+            collect_generics(return_type, f);
+        }
+        _ => {}
+    }
+}
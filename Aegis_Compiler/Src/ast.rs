@@ -1,14 +1,44 @@
 //! Abstract Syntax Tree (AST) definitions for the Aegis language
 
 use crate::token::Span;
+use std::fmt;
 
 /// The root node of any parsed Aegis file
 #[derive(Debug, Clone)]
 pub struct Program {
+    /// The `use` declarations that import names from other modules.
+    pub imports: Vec<UseDeclaration>,
     pub definitions: Vec<Definition>,
     pub span: Span,
 }
 
+/// Imports declarations from another module of the project, e.g.,
+/// `use models.task` for every public declaration of `models/task.ag`, or
+/// `use models.task.{Task, Status}` for only the ones named.
+#[derive(Debug, Clone)]
+pub struct UseDeclaration {
+    /// The dotted path of the module, e.g., `["models", "task"]`.
+    pub path: Vec<String>,
+    /// The names imported from the module, or `None` to import all of its
+    /// public declarations.
+    pub names: Option<Vec<ImportedName>>,
+    pub span: Span,
+}
+
+impl UseDeclaration {
+    /// The dotted name of the imported module, e.g., `models.task`.
+    pub fn module_name(&self) -> String {
+        self.path.join(".")
+    }
+}
+
+/// A name listed in a `use` declaration.
+#[derive(Debug, Clone)]
+pub struct ImportedName {
+    pub name: String,
+    pub span: Span,
+}
+
 /// Top-level definitions
 #[derive(Debug, Clone)]
 pub enum Definition {
@@ -17,6 +47,8 @@ pub enum Definition {
     Function(FunctionDefinition),
     Statement(Statement),
     Enum(EnumDefinition),
+    Style(StyleDefinition),
+    Interface(InterfaceDefinition),
 }
 
 /// Statements
@@ -27,6 +59,21 @@ pub enum Statement {
     Return(ReturnStatement),
     Block(BlockStatement),
     Expression(ExpressionStatement),
+    Assign(AssignStatement),
+}
+
+impl Statement {
+    /// The span of source code this statement covers.
+    pub fn span(&self) -> Span {
+        match self {
+            Statement::Let(stmt) => stmt.span,
+            Statement::For(stmt) => stmt.span,
+            Statement::Return(stmt) => stmt.span,
+            Statement::Block(stmt) => stmt.span,
+            Statement::Expression(stmt) => stmt.span,
+            Statement::Assign(stmt) => stmt.span,
+        }
+    }
 }
 
 /// Expressions
@@ -43,8 +90,31 @@ pub enum Expression {
     /// where the `function` is a `MemberAccess` expression, e.g., `LoadState::Success(data)`.
     Call(Box<CallExpression>),
     MemberAccess(Box<MemberAccessExpression>),
+    /// A safe-navigation access, e.g., `user?.name`, which yields `nothing`
+    /// instead of failing when the object is `nothing`.
+    SafeMemberAccess(Box<MemberAccessExpression>),
     Await(Box<AwaitExpression>),
     AskJs(Box<AskJsExpression>),
+    /// An anonymous function, e.g., `task => task.is_done`.
+    Lambda(Box<LambdaExpression>),
+}
+
+impl Expression {
+    /// The span of source code this expression covers.
+    pub fn span(&self) -> Span {
+        match self {
+            Expression::Identifier(_, span) | Expression::Literal(_, span) => *span,
+            Expression::Prefix(expr) => expr.span,
+            Expression::Infix(expr) => expr.span,
+            Expression::If(expr) => expr.span,
+            Expression::When(expr) => expr.span,
+            Expression::Call(expr) => expr.span,
+            Expression::MemberAccess(expr) | Expression::SafeMemberAccess(expr) => expr.span,
+            Expression::Await(expr) => expr.span,
+            Expression::AskJs(expr) => expr.span,
+            Expression::Lambda(expr) => expr.span,
+        }
+    }
 }
 
 /// Literal values
@@ -62,8 +132,10 @@ pub enum Literal {
 #[derive(Debug, Clone)]
 pub struct LetStatement {
     pub name: String,
+    /// The span of the declared name, e.g., for diagnostics about the binding.
+    pub name_span: Span,
     pub is_tracked: bool,
-    pub type_annotation: Option<String>,
+    pub type_annotation: Option<TypeIdentifier>,
     pub value: Expression,
     pub span: Span,
 }
@@ -98,22 +170,43 @@ pub struct ExpressionStatement {
     pub span: Span,
 }
 
+/// Assignment to existing state, e.g., `status_text = "Loading..."`
+#[derive(Debug, Clone)]
+pub struct AssignStatement {
+    pub target: Expression,
+    pub value: Expression,
+    pub span: Span,
+}
+
 /// Function definition
 #[derive(Debug, Clone)]
 pub struct FunctionDefinition {
     pub name: String,
+    /// Whether the function was declared `public`, so other modules can import it.
+    pub is_public: bool,
     pub is_async: bool,
+    /// A list of generic type parameters, e.g., the `T` in `let's first<T>(items: List<T>)`.
+    pub generic_params: Vec<GenericParam>,
     pub parameters: Vec<Parameter>,
-    pub return_type: Option<String>,
+    pub return_type: Option<TypeIdentifier>,
     pub body: BlockStatement,
     pub span: Span,
 }
 
+/// A generic type parameter of a function, with the interface its type
+/// arguments must implement, e.g., the `T: Comparable` in `let's max<T: Comparable>`.
+#[derive(Debug, Clone)]
+pub struct GenericParam {
+    pub name: String,
+    pub bound: Option<String>,
+    pub span: Span,
+}
+
 /// Function parameter
 #[derive(Debug, Clone)]
 pub struct Parameter {
     pub name: String,
-    pub type_annotation: String,
+    pub type_annotation: TypeIdentifier,
     pub span: Span,
 }
 
@@ -121,9 +214,20 @@ pub struct Parameter {
 #[derive(Debug, Clone)]
 pub struct ContractDefinition {
     pub name: String,
+    /// Whether the contract was declared `public`, so other modules can import it.
+    pub is_public: bool,
     /// NEW: A list of generic type parameters, e.g., the `T` in `contract Option<T>`.
     pub generic_params: Vec<String>,
+    /// The interfaces the contract declares it implements, e.g., the
+    /// `Displayable` in `contract Task is Displayable:`.
+    pub interfaces: Vec<String>,
     pub fields: Vec<ContractField>,
+    /// Functions defined in the contract body, called on a value of the
+    /// contract, e.g., `task.display()`.
+    pub methods: Vec<FunctionDefinition>,
+    /// Read-only values computed from the other members, read like fields,
+    /// e.g., `employee.bonus`.
+    pub properties: Vec<ComputedProperty>,
     pub span: Span,
 }
 
@@ -133,6 +237,67 @@ pub struct ContractField {
     pub name: String,
     /// UPDATED: The type is now represented by a `TypeIdentifier` node.
     pub type_ann: TypeIdentifier,
+    /// The value used when a contract literal leaves the field out, e.g.,
+    /// the `false` in `is_done: boolean = false`.
+    pub default_value: Option<Expression>,
+    pub span: Span,
+}
+
+/// A computed property of a contract, e.g.,
+/// `let's bonus: number = self.salary * 0.1`. It is evaluated on each read
+/// and can't be assigned to.
+#[derive(Debug, Clone)]
+pub struct ComputedProperty {
+    pub name: String,
+    pub type_ann: TypeIdentifier,
+    pub value: Expression,
+    pub span: Span,
+}
+
+/// An interface: a set of methods a contract can declare it implements, e.g.
+///
+/// ```text
+/// interface Displayable:
+///     let's display() -> string
+/// ```
+#[derive(Debug, Clone)]
+pub struct InterfaceDefinition {
+    pub name: String,
+    /// Whether the interface was declared `public`, so other modules can import it.
+    pub is_public: bool,
+    pub methods: Vec<InterfaceMethod>,
+    pub span: Span,
+}
+
+/// A method signature in an interface. `Self` in its types stands for the
+/// implementing contract.
+#[derive(Debug, Clone)]
+pub struct InterfaceMethod {
+    pub name: String,
+    pub parameters: Vec<Parameter>,
+    pub return_type: Option<TypeIdentifier>,
+    pub span: Span,
+}
+
+/// A named, reusable style, e.g.
+///
+/// ```text
+/// style HeaderText:
+///     font_size: 28
+///     padding: 16
+/// ```
+#[derive(Debug, Clone)]
+pub struct StyleDefinition {
+    pub name: String,
+    pub fields: Vec<StyleField>,
+    pub span: Span,
+}
+
+/// A single `key: value` entry of a named style
+#[derive(Debug, Clone)]
+pub struct StyleField {
+    pub name: String,
+    pub value: Expression,
     pub span: Span,
 }
 
@@ -149,6 +314,15 @@ pub enum TypeIdentifier {
     },
 }
 
+impl TypeIdentifier {
+    /// The span of source code this type covers.
+    pub fn span(&self) -> Span {
+        match self {
+            TypeIdentifier::Simple { span, .. } | TypeIdentifier::Generic { span, .. } => *span,
+        }
+    }
+}
+
 /// App definition
 #[derive(Debug, Clone)]
 pub struct AppDefinition {
@@ -225,10 +399,40 @@ pub enum InfixOperator {
     Minus,
     Multiply,
     Divide,
+    /// The remainder of a division, `%`.
+    Modulo,
     Equal,
     NotEqual,
     LessThan,
     GreaterThan,
+    /// The default-value operator, `?:`, e.g., `nickname ?: "anonymous"`.
+    Elvis,
+    /// Logical AND, `and`, which only evaluates its right side if the left
+    /// side is `true`.
+    And,
+    /// Logical OR, `or`, which only evaluates its right side if the left
+    /// side is `false`.
+    Or,
+}
+
+impl fmt::Display for InfixOperator {
+    /// Writes the operator as it is written in source, e.g., `+` or `and`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            InfixOperator::Plus => "+",
+            InfixOperator::Minus => "-",
+            InfixOperator::Multiply => "*",
+            InfixOperator::Divide => "/",
+            InfixOperator::Modulo => "%",
+            InfixOperator::Equal => "==",
+            InfixOperator::NotEqual => "!=",
+            InfixOperator::LessThan => "<",
+            InfixOperator::GreaterThan => ">",
+            InfixOperator::Elvis => "?:",
+            InfixOperator::And => "and",
+            InfixOperator::Or => "or",
+        })
+    }
 }
 
 /// If expression
@@ -252,6 +456,9 @@ pub struct WhenExpression {
 #[derive(Debug, Clone)]
 pub struct WhenCase {
     pub pattern: WhenPattern,
+    /// An optional `if` guard, e.g., the `if data.size > 0` in
+    /// `is LoadState::Success(data) if data.size > 0 => ...`.
+    pub guard: Option<Expression>,
     pub body: Expression,
     pub span: Span,
 }
@@ -260,12 +467,25 @@ pub struct WhenCase {
 #[derive(Debug, Clone)]
 pub enum WhenPattern {
     Literal(Literal),
+    /// A name that matches any value and binds it within the arm.
     Identifier(String),
+    /// The wildcard `_`, which matches any value without binding it.
+    Wildcard,
     Else,
+    /// A range of number literals, e.g., `is 1..10` or `is 1..=10`.
+    Range {
+        start: Literal,
+        end: Literal,
+        inclusive: bool,
+        span: Span,
+    },
     /// A pattern that matches an enum variant, e.g., `is MyEnum::Variant`.
     EnumVariant {
         enum_name: String,
         variant_name: String,
+        /// Positional sub-patterns for the variant's payload, e.g., the `data` in
+        /// `is LoadState::Success(data)`. Empty if the payload is not destructured.
+        arguments: Vec<WhenPattern>,
         span: Span,
     },
 }
@@ -293,6 +513,25 @@ pub struct AwaitExpression {
     pub span: Span,
 }
 
+/// Lambda expression. An argument that reads `it`, as in
+/// `tasks.findIndex(it.id == task.id)`, is checked as a lambda whose
+/// parameter is `it`.
+#[derive(Debug, Clone)]
+pub struct LambdaExpression {
+    pub parameters: Vec<LambdaParameter>,
+    pub body: Expression,
+    pub span: Span,
+}
+
+/// A lambda parameter, whose type can be left out when the lambda is passed
+/// where a function type is expected.
+#[derive(Debug, Clone)]
+pub struct LambdaParameter {
+    pub name: String,
+    pub type_annotation: Option<TypeIdentifier>,
+    pub span: Span,
+}
+
 /// Ask JavaScript expression
 #[derive(Debug, Clone)]
 pub struct AskJsExpression {
@@ -318,6 +557,8 @@ pub struct MapLiteral {
 #[derive(Debug, Clone)]
 pub struct EnumDefinition {
     pub name: String,
+    /// Whether the enum was declared `public`, so other modules can import it.
+    pub is_public: bool,
     pub variants: Vec<EnumVariant>,
     pub span: Span,
 }
@@ -326,7 +567,8 @@ pub struct EnumDefinition {
 #[derive(Debug, Clone)]
 pub struct EnumVariant {
     pub name: String,
-    /// NEW: A list of types associated with this variant, e.g., the `Data` in `Success(Data)`.
-    pub types: Vec<String>, // Using String for now, to be resolved by the Guardian.
+    /// A list of types associated with this variant, e.g., the `Data` in `Success(Data)`.
+    /// These are resolved by the Guardian in the same way as contract field types.
+    pub types: Vec<TypeIdentifier>,
     pub span: Span,
 }
@@ -8,11 +8,21 @@ use std::fmt;
 pub struct ParseError {
     pub message: String,
     pub span: Span,
+    pub kind: ParseErrorKind,
 }
 
 impl ParseError {
-    pub fn new(message: String, span: Span) -> Self {
-        Self { message, span }
+    pub fn new(message: String, span: Span, kind: ParseErrorKind) -> Self {
+        Self {
+            message,
+            span,
+            kind,
+        }
+    }
+
+    /// The stable code of the error's kind, e.g., `AEG0003`.
+    pub fn code(&self) -> &'static str {
+        self.kind.code()
     }
 }
 
@@ -20,20 +30,149 @@ impl fmt::Display for ParseError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
-            "Parse error at {}..{}: {}",
-            self.span.start, self.span.end, self.message
+            "Parse error [{}] at {}..{}: {}",
+            self.code(),
+            self.span.start,
+            self.span.end,
+            self.message
         )
     }
 }
 
 impl std::error::Error for ParseError {}
 
+/// Kinds of parse errors
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    /// A token that can't start or continue an expression
+    UnexpectedToken,
+    /// A declaration, parameter, member or import without its name
+    ExpectedName,
+    /// A missing `:`, `=`, `(` or `,` in a declaration or literal
+    ExpectedPunctuation,
+    /// A `)`, `>`, `]` or `}` that is never closed
+    UnclosedDelimiter,
+    /// A missing or malformed type annotation
+    ExpectedType,
+    /// `public`, `async` or `track` on a declaration that can't have it
+    InvalidModifier,
+    /// A declaration in a place it can't be, like a function in a function
+    MisplacedDeclaration,
+}
+
+impl ParseErrorKind {
+    /// The stable code of this kind of error. Codes are never renumbered or
+    /// reused, so they can be searched for and explained with `aegis explain`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            ParseErrorKind::UnexpectedToken => "AEG0001",
+            ParseErrorKind::ExpectedName => "AEG0002",
+            ParseErrorKind::ExpectedPunctuation => "AEG0003",
+            ParseErrorKind::UnclosedDelimiter => "AEG0004",
+            ParseErrorKind::ExpectedType => "AEG0005",
+            ParseErrorKind::InvalidModifier => "AEG0006",
+            ParseErrorKind::MisplacedDeclaration => "AEG0007",
+        }
+    }
+}
+
 /// Semantic errors from the Guardian (semantic analyzer)
 #[derive(Debug, Clone)]
 pub struct SemanticError {
     pub message: String,
     pub span: Span,
     pub error_type: SemanticErrorType,
+    /// Machine-readable suggestions that tooling can offer as quick-fixes,
+    /// e.g. the patterns missing from a non-exhaustive `when`.
+    pub suggestions: Vec<String>,
+    /// Other places in the source that explain the error, e.g. the
+    /// annotation an expected type came from.
+    pub related: Vec<RelatedInfo>,
+    /// Edits that fix the error, e.g. adding `track` to a `let's`.
+    pub fixes: Vec<Fix>,
+}
+
+/// A secondary location attached to a semantic error.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RelatedInfo {
+    pub message: String,
+    pub span: Span,
+}
+
+/// A fix for a diagnostic: edits to its file that are applied together.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fix {
+    /// What the fix does, e.g., "rename 'titel' to 'title'".
+    pub message: String,
+    pub edits: Vec<TextEdit>,
+    pub applicability: Applicability,
+}
+
+impl Fix {
+    /// Creates a fix that can be applied without review.
+    pub fn new(message: String, edits: Vec<TextEdit>) -> Self {
+        Self {
+            message,
+            edits,
+            applicability: Applicability::MachineApplicable,
+        }
+    }
+
+    /// Creates a fix whose edits contain placeholders to be filled in.
+    pub fn with_placeholders(message: String, edits: Vec<TextEdit>) -> Self {
+        Self {
+            applicability: Applicability::HasPlaceholders,
+            ..Self::new(message, edits)
+        }
+    }
+
+    /// Returns true if `aegis fix` can apply the fix without review.
+    pub fn is_machine_applicable(&self) -> bool {
+        self.applicability == Applicability::MachineApplicable
+    }
+}
+
+/// A change to a file: `replacement` in place of the text at `span`. An
+/// empty span inserts and an empty replacement deletes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextEdit {
+    pub span: Span,
+    pub replacement: String,
+}
+
+impl TextEdit {
+    pub fn new(span: Span, replacement: String) -> Self {
+        Self { span, replacement }
+    }
+
+    /// Inserts `text` at `offset`.
+    pub fn insert(offset: usize, text: String) -> Self {
+        Self::new(
+            Span {
+                start: offset,
+                end: offset,
+            },
+            text,
+        )
+    }
+}
+
+/// How sure a fix is to be what the programmer meant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Applicability {
+    /// The fix is certainly right and the code compiles after it.
+    MachineApplicable,
+    /// The fix has placeholders, like the bodies of added `when` arms.
+    HasPlaceholders,
+}
+
+impl fmt::Display for Applicability {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Applicability::MachineApplicable => write!(f, "machine-applicable"),
+            Applicability::HasPlaceholders => write!(f, "has-placeholders"),
+        }
+    }
 }
 
 impl SemanticError {
@@ -42,16 +181,45 @@ impl SemanticError {
             message,
             span,
             error_type,
+            suggestions: Vec::new(),
+            related: Vec::new(),
+            fixes: Vec::new(),
         }
     }
+
+    /// Attaches quick-fix suggestions to the error.
+    pub fn with_suggestions(mut self, suggestions: Vec<String>) -> Self {
+        self.suggestions = suggestions;
+        self
+    }
+
+    /// Attaches a secondary location that explains the error.
+    pub fn with_related(mut self, message: String, span: Span) -> Self {
+        self.related.push(RelatedInfo { message, span });
+        self
+    }
+
+    /// Attaches a fix to the error.
+    pub fn with_fix(mut self, fix: Fix) -> Self {
+        self.fixes.push(fix);
+        self
+    }
+
+    /// The stable code of the error's type, e.g., `AEG0102`.
+    pub fn code(&self) -> &'static str {
+        self.error_type.code()
+    }
 }
 
 impl fmt::Display for SemanticError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
-            "Semantic error at {}..{}: {}",
-            self.span.start, self.span.end, self.message
+            "Semantic error [{}] at {}..{}: {}",
+            self.code(),
+            self.span.start,
+            self.span.end,
+            self.message
         )
     }
 }
@@ -87,10 +255,220 @@ pub enum SemanticErrorType {
     InvalidFieldKey,
     /// Undefined type
     UndefinedType,
+    /// A `when` expression does not cover every possible value of its subject
+    NonExhaustiveMatch,
+    /// A `when` arm can never match because earlier arms cover it
+    UnreachablePattern,
+    /// Assignment to a binding that isn't declared with `let's track`
+    ImmutableAssignment,
+    /// A contract contains itself without a `List` or `Optional` in between
+    RecursiveContract,
+    /// A contract lacks a method of an interface it declares, or a type
+    /// argument doesn't implement the bound of its type parameter
+    MissingImplementation,
+    /// A `use` declaration names a module with no file in the project
+    ModuleNotFound,
+    /// Modules import each other, directly or through other modules
+    ImportCycle,
+    /// A `use` declaration names a declaration that isn't `public`
+    PrivateImport,
     /// Other semantic error
     Other,
 }
 
+impl SemanticErrorType {
+    /// The stable code of this type of error. Codes are never renumbered or
+    /// reused, so they can be searched for and explained with `aegis explain`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            SemanticErrorType::UndefinedSymbol => "AEG0101",
+            SemanticErrorType::TypeMismatch => "AEG0102",
+            SemanticErrorType::ArityMismatch => "AEG0103",
+            SemanticErrorType::ReturnTypeMismatch => "AEG0104",
+            SemanticErrorType::AwaitOutsideAsync => "AEG0105",
+            SemanticErrorType::DuplicateDeclaration => "AEG0106",
+            SemanticErrorType::InvalidMemberAccess => "AEG0107",
+            SemanticErrorType::InvalidOperation => "AEG0108",
+            SemanticErrorType::MissingField => "AEG0109",
+            SemanticErrorType::InvalidUIComponent => "AEG0110",
+            SemanticErrorType::UnknownField => "AEG0111",
+            SemanticErrorType::InvalidFieldKey => "AEG0112",
+            SemanticErrorType::UndefinedType => "AEG0113",
+            SemanticErrorType::NonExhaustiveMatch => "AEG0114",
+            SemanticErrorType::UnreachablePattern => "AEG0115",
+            SemanticErrorType::ImmutableAssignment => "AEG0116",
+            SemanticErrorType::RecursiveContract => "AEG0117",
+            SemanticErrorType::MissingImplementation => "AEG0118",
+            SemanticErrorType::ModuleNotFound => "AEG0119",
+            SemanticErrorType::ImportCycle => "AEG0120",
+            SemanticErrorType::PrivateImport => "AEG0121",
+            SemanticErrorType::Other => "AEG0199",
+        }
+    }
+}
+
+/// How serious a diagnostic is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// The program is wrong and can't be compiled.
+    Error,
+    /// The program compiles, but probably doesn't do what was meant.
+    Warning,
+    /// Something worth knowing about the program.
+    Info,
+    /// A suggestion to improve the program, e.g., a simpler way to write it.
+    Hint,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Info => "info",
+            Severity::Hint => "hint",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// A message about a place in the source from any stage of the compiler,
+/// whether a hard error from the Architect or the Guardian or a lint warning.
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub message: String,
+    pub span: Span,
+    pub severity: Severity,
+    /// The stable code of an error of the Architect or the Guardian, e.g.,
+    /// `AEG0102`, which `aegis explain` describes.
+    pub code: Option<&'static str>,
+    /// The name of the lint that reported it, e.g., `unused_variables`, or
+    /// `None` for the errors of the Architect and the Guardian.
+    pub lint: Option<&'static str>,
+    /// Quick-fix suggestions, as on a `SemanticError`.
+    pub suggestions: Vec<String>,
+    pub related: Vec<RelatedInfo>,
+    /// Extra context that isn't tied to a place in the source.
+    pub notes: Vec<String>,
+    /// Edits that fix the diagnostic.
+    pub fixes: Vec<Fix>,
+}
+
+impl Diagnostic {
+    pub fn new(message: String, span: Span, severity: Severity) -> Self {
+        Self {
+            message,
+            span,
+            severity,
+            code: None,
+            lint: None,
+            suggestions: Vec::new(),
+            related: Vec::new(),
+            notes: Vec::new(),
+            fixes: Vec::new(),
+        }
+    }
+
+    /// Creates a warning.
+    pub fn warning(message: String, span: Span) -> Self {
+        Self::new(message, span, Severity::Warning)
+    }
+
+    /// Attaches quick-fix suggestions to the diagnostic.
+    pub fn with_suggestions(mut self, suggestions: Vec<String>) -> Self {
+        self.suggestions = suggestions;
+        self
+    }
+
+    /// Attaches a secondary location that explains the diagnostic.
+    pub fn with_related(mut self, message: String, span: Span) -> Self {
+        self.related.push(RelatedInfo { message, span });
+        self
+    }
+
+    /// Attaches a note to the diagnostic.
+    pub fn with_note(mut self, note: String) -> Self {
+        self.notes.push(note);
+        self
+    }
+
+    /// Attaches a fix to the diagnostic.
+    pub fn with_fix(mut self, fix: Fix) -> Self {
+        self.fixes.push(fix);
+        self
+    }
+
+    /// Returns true if the diagnostic stops the program from compiling.
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+}
+
+impl From<ParseError> for Diagnostic {
+    fn from(error: ParseError) -> Self {
+        Diagnostic {
+            code: Some(error.code()),
+            ..Diagnostic::new(error.message, error.span, Severity::Error)
+        }
+    }
+}
+
+impl From<SemanticError> for Diagnostic {
+    fn from(error: SemanticError) -> Self {
+        Diagnostic {
+            code: Some(error.code()),
+            suggestions: error.suggestions,
+            related: error.related,
+            fixes: error.fixes,
+            ..Diagnostic::new(error.message, error.span, Severity::Error)
+        }
+    }
+}
+
+impl From<CodeGenError> for Diagnostic {
+    fn from(error: CodeGenError) -> Self {
+        Diagnostic {
+            code: Some(error.code()),
+            ..Diagnostic::new(
+                error.message,
+                error.span.unwrap_or_default(),
+                Severity::Error,
+            )
+        }
+    }
+}
+
+impl From<RuntimeError> for Diagnostic {
+    fn from(error: RuntimeError) -> Self {
+        Diagnostic {
+            code: Some(error.code()),
+            ..Diagnostic::new(
+                error.message,
+                error.span.unwrap_or_default(),
+                Severity::Error,
+            )
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.severity)?;
+        if let Some(code) = self.code {
+            write!(f, "[{}]", code)?;
+        }
+        write!(
+            f,
+            " at {}..{}: {}",
+            self.span.start, self.span.end, self.message
+        )?;
+        if let Some(lint) = self.lint {
+            write!(f, " [{}]", lint)?;
+        }
+        Ok(())
+    }
+}
+
 /// Code generation errors from the Engine
 #[derive(Debug, Clone)]
 pub struct CodeGenError {
@@ -99,9 +477,18 @@ pub struct CodeGenError {
 }
 
 impl CodeGenError {
+    /// The stable code of every code generation error: the program is valid,
+    /// but the target can't run something it does.
+    pub const CODE: &'static str = "AEG0201";
+
     pub fn new(message: String, span: Option<Span>) -> Self {
         Self { message, span }
     }
+
+    /// The stable code of the error, `AEG0201`.
+    pub fn code(&self) -> &'static str {
+        Self::CODE
+    }
 }
 
 impl fmt::Display for CodeGenError {
@@ -109,13 +496,70 @@ impl fmt::Display for CodeGenError {
         if let Some(span) = self.span {
             write!(
                 f,
-                "Code generation error at {}..{}: {}",
-                span.start, span.end, self.message
+                "Code generation error [{}] at {}..{}: {}",
+                self.code(),
+                span.start,
+                span.end,
+                self.message
             )
         } else {
-            write!(f, "Code generation error: {}", self.message)
+            write!(
+                f,
+                "Code generation error [{}]: {}",
+                self.code(),
+                self.message
+            )
         }
     }
 }
 
 impl std::error::Error for CodeGenError {}
+
+/// An error that stops a running program, e.g., a division by zero.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuntimeError {
+    pub message: String,
+    /// The span of the code that failed, if the AIL records it.
+    pub span: Option<Span>,
+    /// The name of the module the code is in, as in its AIL, e.g.,
+    /// `models.task`, or `main` for the entry module.
+    pub module: String,
+}
+
+impl RuntimeError {
+    /// The stable code of every runtime error: the program checked, but
+    /// did something it can't while it ran.
+    pub const CODE: &'static str = "AEG0301";
+
+    pub fn new(message: String, span: Option<Span>, module: String) -> Self {
+        Self {
+            message,
+            span,
+            module,
+        }
+    }
+
+    /// The stable code of the error, `AEG0301`.
+    pub fn code(&self) -> &'static str {
+        Self::CODE
+    }
+}
+
+impl fmt::Display for RuntimeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(span) = self.span {
+            write!(
+                f,
+                "Runtime error [{}] at {}..{}: {}",
+                self.code(),
+                span.start,
+                span.end,
+                self.message
+            )
+        } else {
+            write!(f, "Runtime error [{}]: {}", self.code(), self.message)
+        }
+    }
+}
+
+impl std::error::Error for RuntimeError {}
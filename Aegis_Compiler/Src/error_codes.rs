@@ -0,0 +1,70 @@
+//! Long-form explanations of the compiler's error codes, shown by
+//! `aegis explain AEG0102` and when hovering an error in an editor. Each is a
+//! Markdown file in `error_codes/` with an example of the error and its fix.
+
+/// Every error code with its explanation, in order.
+static EXPLANATIONS: &[(&str, &str)] = &[
+    ("AEG0001", include_str!("error_codes/AEG0001.md")),
+    ("AEG0002", include_str!("error_codes/AEG0002.md")),
+    ("AEG0003", include_str!("error_codes/AEG0003.md")),
+    ("AEG0004", include_str!("error_codes/AEG0004.md")),
+    ("AEG0005", include_str!("error_codes/AEG0005.md")),
+    ("AEG0006", include_str!("error_codes/AEG0006.md")),
+    ("AEG0007", include_str!("error_codes/AEG0007.md")),
+    ("AEG0101", include_str!("error_codes/AEG0101.md")),
+    ("AEG0102", include_str!("error_codes/AEG0102.md")),
+    ("AEG0103", include_str!("error_codes/AEG0103.md")),
+    ("AEG0104", include_str!("error_codes/AEG0104.md")),
+    ("AEG0105", include_str!("error_codes/AEG0105.md")),
+    ("AEG0106", include_str!("error_codes/AEG0106.md")),
+    ("AEG0107", include_str!("error_codes/AEG0107.md")),
+    ("AEG0108", include_str!("error_codes/AEG0108.md")),
+    ("AEG0109", include_str!("error_codes/AEG0109.md")),
+    ("AEG0110", include_str!("error_codes/AEG0110.md")),
+    ("AEG0111", include_str!("error_codes/AEG0111.md")),
+    ("AEG0112", include_str!("error_codes/AEG0112.md")),
+    ("AEG0113", include_str!("error_codes/AEG0113.md")),
+    ("AEG0114", include_str!("error_codes/AEG0114.md")),
+    ("AEG0115", include_str!("error_codes/AEG0115.md")),
+    ("AEG0116", include_str!("error_codes/AEG0116.md")),
+    ("AEG0117", include_str!("error_codes/AEG0117.md")),
+    ("AEG0118", include_str!("error_codes/AEG0118.md")),
+    ("AEG0119", include_str!("error_codes/AEG0119.md")),
+    ("AEG0120", include_str!("error_codes/AEG0120.md")),
+    ("AEG0121", include_str!("error_codes/AEG0121.md")),
+    ("AEG0199", include_str!("error_codes/AEG0199.md")),
+    ("AEG0201", include_str!("error_codes/AEG0201.md")),
+    ("AEG0301", include_str!("error_codes/AEG0301.md")),
+];
+
+/// Returns the explanation of an error code, e.g., `AEG0102`. Codes may be
+/// given in lowercase or as just their number, e.g., `102`.
+pub fn explain(code: &str) -> Option<&'static str> {
+    let code = normalize(code);
+    EXPLANATIONS
+        .iter()
+        .find(|(c, _)| *c == code)
+        .map(|(_, text)| *text)
+}
+
+/// The title of an error code's explanation, e.g., `Type mismatch`.
+pub fn title(code: &str) -> Option<&'static str> {
+    let heading = explain(code)?.lines().next()?;
+    heading.split_once(": ").map(|(_, title)| title)
+}
+
+/// Every error code that has an explanation, in order.
+pub fn codes() -> impl Iterator<Item = &'static str> {
+    EXPLANATIONS.iter().map(|(code, _)| *code)
+}
+
+/// Spells an error code the way the compiler does, e.g., `AEG0102` for
+/// `aeg0102` or `102`.
+pub fn normalize(code: &str) -> String {
+    let code = code.trim();
+    if !code.is_empty() && code.chars().all(|c| c.is_ascii_digit()) {
+        format!("AEG{:0>4}", code)
+    } else {
+        code.to_ascii_uppercase()
+    }
+}
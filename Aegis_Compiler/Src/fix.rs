@@ -0,0 +1,120 @@
+//! Applies the fixes attached to diagnostics to source files, as `aegis fix`
+//! does.
+//!
+//! Only machine-applicable fixes are applied. Fixes whose edits would touch
+//! the same text as an earlier fix are left for the next round: after each
+//! round the project is checked again, so the remaining errors, and their
+//! fixes, are computed against the fixed source.
+
+use crate::error::{Fix, TextEdit};
+use crate::project::Project;
+use crate::render::line_and_column;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// The most rounds of fixing before giving up, in case fixes keep causing
+/// errors with more fixes.
+const MAX_ROUNDS: usize = 8;
+
+/// A fix that was applied to a file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AppliedFix {
+    /// The path of the file, relative to the project root.
+    pub path: PathBuf,
+    /// The line the fix's first edit starts on.
+    pub line: usize,
+    pub message: String,
+}
+
+/// Applies `edits` to `source`. Edits must not overlap; their spans are
+/// byte offsets into `source` as it is before any of them are applied.
+pub fn apply_edits(source: &str, edits: &[TextEdit]) -> String {
+    let mut edits: Vec<&TextEdit> = edits.iter().collect();
+    edits.sort_by_key(|edit| (edit.span.start, edit.span.end));
+    let mut out = String::with_capacity(source.len());
+    let mut copied = 0;
+    for edit in edits {
+        let start = edit.span.start.clamp(copied, source.len());
+        out.push_str(&source[copied..start]);
+        out.push_str(&edit.replacement);
+        copied = edit.span.end.clamp(start, source.len());
+    }
+    out.push_str(&source[copied..]);
+    out
+}
+
+/// Applies the machine-applicable `fixes` to `source`, in order, skipping
+/// any that conflict with one already chosen. Returns the fixed source and
+/// the fixes that were applied.
+pub fn apply_fixes<'a>(source: &str, fixes: &[&'a Fix]) -> (String, Vec<&'a Fix>) {
+    let mut chosen: Vec<&Fix> = Vec::new();
+    let mut edits: Vec<TextEdit> = Vec::new();
+    for &fix in fixes {
+        if !fix.is_machine_applicable() || fix.edits.is_empty() {
+            continue;
+        }
+        let conflicts = fix
+            .edits
+            .iter()
+            .any(|edit| edits.iter().any(|other| overlaps(edit, other)));
+        if !conflicts {
+            edits.extend(fix.edits.iter().cloned());
+            chosen.push(fix);
+        }
+    }
+    (apply_edits(source, &edits), chosen)
+}
+
+/// Loads the project whose entry module is `entry` from `root`, applies
+/// every machine-applicable fix of its diagnostics to its files, and checks
+/// it again, until no fix is left. Returns the fixes applied and the
+/// project as it is after them.
+pub fn fix_project(root: &Path, entry: &str) -> io::Result<(Vec<AppliedFix>, Project)> {
+    let mut applied = Vec::new();
+    let mut project = Project::load(root, entry)?;
+    for _ in 0..MAX_ROUNDS {
+        let mut changed = false;
+        for module in &project.modules {
+            let diagnostics = module.diagnostics();
+            let mut fixes: Vec<&Fix> = diagnostics
+                .iter()
+                .flat_map(|diagnostic| &diagnostic.fixes)
+                .collect();
+            // By where they start, so the fixes, and the order they're
+            // listed in, don't depend on the order they were found in.
+            fixes.sort_by(|a, b| {
+                let span = |fix: &Fix| {
+                    fix.edits
+                        .first()
+                        .map(|edit| (edit.span.start, edit.span.end))
+                };
+                span(a)
+                    .cmp(&span(b))
+                    .then_with(|| a.message.cmp(&b.message))
+            });
+            let (fixed, chosen) = apply_fixes(&module.source, &fixes);
+            if chosen.is_empty() {
+                continue;
+            }
+            fs::write(root.join(&module.path), &fixed)?;
+            changed = true;
+            applied.extend(chosen.into_iter().map(|fix| AppliedFix {
+                path: module.path.clone(),
+                line: line_and_column(&module.source, fix.edits[0].span.start).0,
+                message: fix.message.clone(),
+            }));
+        }
+        if !changed {
+            break;
+        }
+        project = Project::load(root, entry)?;
+    }
+    Ok((applied, project))
+}
+
+/// Returns true if two edits touch the same text. Two insertions at the
+/// same place conflict too, since the order they'd end up in is unclear.
+fn overlaps(a: &TextEdit, b: &TextEdit) -> bool {
+    (a.span.start < b.span.end && b.span.start < a.span.end) || a.span.start == b.span.start
+}
@@ -2,6 +2,13 @@
 
 pub mod ast;
 pub mod error;
+pub mod error_codes;
+pub mod fix;
+pub mod lint;
+pub mod pipeline;
+pub mod project;
+pub mod render;
+pub mod report;
 pub mod token;
 
 // Include the Scribe from mod.rs
@@ -17,6 +24,25 @@ pub mod guardian_types;
 #[path = "Guardian /symbol table.rs"]
 pub mod guardian_symbol_table;
 
+#[path = "Guardian /exhaustiveness.rs"]
+pub mod guardian_exhaustiveness;
+
+#[path = "Guardian /components.rs"]
+pub mod guardian_components;
+
+#[path = "Guardian /reactivity.rs"]
+pub mod guardian_reactivity;
+
+#[path = "Guardian /stdlib.rs"]
+pub mod guardian_stdlib;
+
+#[path = "Guardian /unify.rs"]
+pub mod guardian_unify;
+
+// Include the Engine, which lowers checked programs and generates code
+#[path = "Engine /mod.rs"]
+pub mod engine;
+
 // Include the real Architect module
 #[path = "Architect /mod.rs"]
 pub mod architect_impl;
@@ -30,4 +56,6 @@ pub mod guardian {
 // Re-export main types for convenience
 pub use ast::Expression;
 pub use guardian::Guardian;
+pub use pipeline::{compile, CompileOptions, CompileResult};
+pub use project::Project;
 pub use token::{Span, Token};
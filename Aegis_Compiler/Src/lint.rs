@@ -0,0 +1,1000 @@
+//! Lints find code that compiles but is probably a mistake, like a variable
+//! that is never used or code after a `return`. They run after the Guardian
+//! has checked a program and report warnings, not errors.
+//!
+//! Every lint has a name and a level: `allow` turns it off, `warn` reports
+//! what it finds as warnings and `deny` as errors. A project sets levels in
+//! the `[lints]` table of its `aegis.toml`, a file with `#![allow(name)]`
+//! and a single declaration or statement with `#[allow(name)]` on the line
+//! before it. Since `#` starts a comment, annotations don't change the
+//! program itself.
+//!
+//! New lints implement `Lint` and are added to a `Linter` with `register`.
+
+use crate::ast::*;
+use crate::error::{Diagnostic, Severity};
+use crate::guardian_components::closest_match;
+use crate::project::CONFIG_FILE;
+use crate::token::Span;
+use crate::Guardian;
+use std::collections::HashMap;
+
+/// What a lint does with what it finds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LintLevel {
+    Allow,
+    Warn,
+    Deny,
+}
+
+impl LintLevel {
+    /// Parses `allow`, `warn` or `deny`.
+    pub fn parse(name: &str) -> Option<LintLevel> {
+        match name {
+            "allow" => Some(LintLevel::Allow),
+            "warn" => Some(LintLevel::Warn),
+            "deny" => Some(LintLevel::Deny),
+            _ => None,
+        }
+    }
+
+    /// The name of the level, as written in annotations and `aegis.toml`.
+    pub fn name(self) -> &'static str {
+        match self {
+            LintLevel::Allow => "allow",
+            LintLevel::Warn => "warn",
+            LintLevel::Deny => "deny",
+        }
+    }
+
+    /// The severity of the diagnostics a lint at this level reports, or
+    /// `None` if it is allowed.
+    fn severity(self) -> Option<Severity> {
+        match self {
+            LintLevel::Allow => None,
+            LintLevel::Warn => Some(Severity::Warning),
+            LintLevel::Deny => Some(Severity::Error),
+        }
+    }
+}
+
+/// A check for a kind of likely mistake.
+pub trait Lint {
+    /// The name of the lint in annotations and `aegis.toml`, e.g., `unused_variables`.
+    fn name(&self) -> &'static str;
+
+    /// What the lint looks for, in a sentence.
+    fn description(&self) -> &'static str;
+
+    /// The level of the lint when neither the project nor an annotation sets one.
+    fn default_level(&self) -> LintLevel {
+        LintLevel::Warn
+    }
+
+    /// Reports what the lint finds in a program. The Linter sets the
+    /// severity of each diagnostic from the lint's level where it was found.
+    fn check(&self, cx: &LintContext) -> Vec<Diagnostic>;
+}
+
+/// Everything a lint can look at.
+pub struct LintContext<'a> {
+    pub program: &'a Program,
+    /// The Guardian that checked the program.
+    pub guardian: &'a Guardian,
+    pub source: &'a str,
+    bindings: Vec<Binding>,
+}
+
+impl<'a> LintContext<'a> {
+    pub fn new(program: &'a Program, guardian: &'a Guardian, source: &'a str) -> Self {
+        Self {
+            program,
+            guardian,
+            source,
+            bindings: BindingCollector::collect(program),
+        }
+    }
+
+    /// Every name the program declares, in source order.
+    pub fn bindings(&self) -> &[Binding] {
+        &self.bindings
+    }
+}
+
+/// The lint levels of a project, from the `[lints]` table of its `aegis.toml`:
+///
+/// ```toml
+/// [lints]
+/// shadowing = "allow"
+/// unused_variables = "deny"
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct LintConfig {
+    levels: HashMap<String, LintLevel>,
+}
+
+impl LintConfig {
+    /// Parses the `[lints]` table of an `aegis.toml`, ignoring other tables.
+    pub fn parse(text: &str) -> Result<LintConfig, String> {
+        let mut config = LintConfig::default();
+        let mut in_lints = false;
+        for (index, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+            if line.starts_with('[') {
+                in_lints = line == "[lints]";
+                continue;
+            }
+            if !in_lints {
+                continue;
+            }
+            let Some((name, value)) = line.split_once('=') else {
+                return Err(format!(
+                    "line {}: expected a lint and its level, e.g., `shadowing = \"allow\"`",
+                    index + 1
+                ));
+            };
+            let value = value.trim().trim_matches('"');
+            let level = LintLevel::parse(value).ok_or_else(|| {
+                format!(
+                    "line {}: unknown lint level '{}'; expected allow, warn or deny",
+                    index + 1,
+                    value
+                )
+            })?;
+            config.set(name.trim(), level);
+        }
+        Ok(config)
+    }
+
+    pub fn set(&mut self, lint: &str, level: LintLevel) {
+        self.levels.insert(lint.to_string(), level);
+    }
+
+    /// The level the project sets for a lint, if any.
+    pub fn level(&self, lint: &str) -> Option<LintLevel> {
+        self.levels.get(lint).copied()
+    }
+
+    /// The names of the lints the project sets a level for.
+    pub fn lints(&self) -> impl Iterator<Item = &str> {
+        self.levels.keys().map(String::as_str)
+    }
+}
+
+/// Runs lints over checked programs.
+pub struct Linter {
+    lints: Vec<Box<dyn Lint>>,
+    config: LintConfig,
+}
+
+impl Default for Linter {
+    fn default() -> Self {
+        Self::new(LintConfig::default())
+    }
+}
+
+impl Linter {
+    /// Creates a Linter with the built-in lints and a project's levels.
+    pub fn new(config: LintConfig) -> Self {
+        let mut linter = Self {
+            lints: Vec::new(),
+            config,
+        };
+        linter.register(Box::new(UnusedVariables));
+        linter.register(Box::new(UnusedState));
+        linter.register(Box::new(Shadowing));
+        linter.register(Box::new(UnreachableCode));
+        linter.register(Box::new(ConstantConditions));
+        linter
+    }
+
+    /// Adds a lint to run after the ones already registered.
+    pub fn register(&mut self, lint: Box<dyn Lint>) {
+        self.lints.push(lint);
+    }
+
+    /// Every registered lint, in the order they run.
+    pub fn lints(&self) -> impl Iterator<Item = &dyn Lint> {
+        self.lints.iter().map(|lint| lint.as_ref())
+    }
+
+    /// Finds a registered lint by name.
+    pub fn lint(&self, name: &str) -> Option<&dyn Lint> {
+        self.lints().find(|lint| lint.name() == name)
+    }
+
+    /// Runs every lint that isn't allowed, returning what they report in
+    /// source order. Annotations naming a lint that doesn't exist are
+    /// reported too.
+    pub fn run(&self, cx: &LintContext) -> Vec<Diagnostic> {
+        let annotations = parse_annotations(cx.source);
+        let mut diagnostics = Vec::new();
+        for annotation in &annotations {
+            for name in &annotation.lints {
+                if self.lint(name).is_none() {
+                    diagnostics.push(self.unknown_lint(name, annotation.span));
+                }
+            }
+        }
+
+        for lint in &self.lints {
+            let name = lint.name();
+            let configured = self.config.level(name);
+            let level = configured.unwrap_or(lint.default_level());
+            let annotated = annotations
+                .iter()
+                .any(|annotation| annotation.lints.iter().any(|l| l == name));
+            if level == LintLevel::Allow && !annotated {
+                continue;
+            }
+            for mut diagnostic in lint.check(cx) {
+                let annotated = level_at(&annotations, name, diagnostic.span.start);
+                let level = annotated.unwrap_or(level);
+                let Some(severity) = level.severity() else {
+                    continue;
+                };
+                diagnostic.severity = severity;
+                diagnostic.lint = Some(name);
+                if annotated.is_none() {
+                    let note = match configured {
+                        Some(_) => {
+                            format!("`{}` is set to {} in {}", name, level.name(), CONFIG_FILE)
+                        }
+                        None => format!("`#[{}({})]` is on by default", level.name(), name),
+                    };
+                    diagnostic = diagnostic.with_note(note);
+                }
+                diagnostics.push(diagnostic);
+            }
+        }
+        diagnostics.sort_by_key(|diagnostic| diagnostic.span.start);
+        diagnostics
+    }
+
+    fn unknown_lint(&self, name: &str, span: Span) -> Diagnostic {
+        let suggestion = closest_match(name, self.lints().map(|lint| lint.name()));
+        Diagnostic::warning(format!("Unknown lint '{}'", name), span).with_suggestions(
+            suggestion
+                .map(|s| vec![format!("did you mean '{}'?", s)])
+                .unwrap_or_default(),
+        )
+    }
+}
+
+/// A lint level set by a `#[level(lints)]` or `#![level(lints)]` comment.
+struct Annotation {
+    level: LintLevel,
+    lints: Vec<String>,
+    span: Span,
+    /// The byte range the annotation applies to, or `None` for `#![...]`,
+    /// which applies to the whole file.
+    scope: Option<(usize, usize)>,
+}
+
+/// Finds the lint annotations in a source file. An annotation applies to the
+/// line after it, along with the lines indented under that one.
+fn parse_annotations(source: &str) -> Vec<Annotation> {
+    let mut lines = Vec::new();
+    let mut offset = 0;
+    for line in source.split_inclusive('\n') {
+        lines.push((offset, line.trim_end()));
+        offset += line.len();
+    }
+    let indent = |line: &str| line.len() - line.trim_start().len();
+    let is_code = |line: &str| {
+        let line = line.trim_start();
+        !line.is_empty() && !line.starts_with('#')
+    };
+
+    let mut annotations = Vec::new();
+    for (index, &(start, line)) in lines.iter().enumerate() {
+        let text = line.trim_start();
+        let (is_file_level, rest) = if let Some(rest) = text.strip_prefix("#![") {
+            (true, rest)
+        } else if let Some(rest) = text.strip_prefix("#[") {
+            (false, rest)
+        } else {
+            continue;
+        };
+        // Other comments may start with `#[` too; only `allow`, `warn` and
+        // `deny` with a list of lints are annotations.
+        let Some((level, names)) = rest
+            .strip_suffix(")]")
+            .and_then(|rest| rest.split_once('('))
+        else {
+            continue;
+        };
+        let Some(level) = LintLevel::parse(level.trim()) else {
+            continue;
+        };
+
+        let scope = if is_file_level {
+            None
+        } else {
+            let mut following = lines[index + 1..].iter().filter(|(_, l)| is_code(l));
+            Some(match following.next() {
+                Some(&(item_start, item)) => {
+                    let item_indent = indent(item);
+                    let end = following
+                        .find(|(_, l)| indent(l) <= item_indent)
+                        .map_or(source.len(), |&(end, _)| end);
+                    (item_start, end)
+                }
+                None => (source.len(), source.len()),
+            })
+        };
+        let text_start = start + indent(line);
+        annotations.push(Annotation {
+            level,
+            lints: names
+                .split(',')
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+                .map(str::to_string)
+                .collect(),
+            span: Span {
+                start: text_start,
+                end: start + line.len(),
+            },
+            scope,
+        });
+    }
+    annotations
+}
+
+/// The level the annotations give `lint` at `offset`: the file's, overridden
+/// by those of the items around `offset`, the innermost last. `None` if no
+/// annotation names the lint there, so the project's level applies.
+fn level_at(annotations: &[Annotation], lint: &str, offset: usize) -> Option<LintLevel> {
+    let names = |annotation: &&Annotation| annotation.lints.iter().any(|l| l == lint);
+    let file = annotations
+        .iter()
+        .filter(names)
+        .filter(|annotation| annotation.scope.is_none());
+    let items = annotations.iter().filter(names).filter(|annotation| {
+        matches!(annotation.scope, Some((start, end)) if start <= offset && offset < end)
+    });
+    file.chain(items).last().map(|annotation| annotation.level)
+}
+
+// --- Walking the AST ---
+
+/// Visits every statement and expression of a program, in source order.
+/// Override the methods for the nodes a lint looks at, and call the
+/// matching `walk_` function to visit their children.
+pub trait Visitor {
+    fn visit_function(&mut self, function: &FunctionDefinition) {
+        walk_function(self, function);
+    }
+
+    fn visit_app(&mut self, app: &AppDefinition) {
+        walk_app(self, app);
+    }
+
+    fn visit_block(&mut self, block: &BlockStatement) {
+        walk_block(self, block);
+    }
+
+    fn visit_statement(&mut self, stmt: &Statement) {
+        walk_statement(self, stmt);
+    }
+
+    fn visit_expression(&mut self, expr: &Expression) {
+        walk_expression(self, expr);
+    }
+}
+
+pub fn walk_program<V: Visitor + ?Sized>(visitor: &mut V, program: &Program) {
+    for definition in &program.definitions {
+        match definition {
+            Definition::App(app) => visitor.visit_app(app),
+            Definition::Function(function) => visitor.visit_function(function),
+            Definition::Contract(contract) => {
+                for default in contract
+                    .fields
+                    .iter()
+                    .filter_map(|f| f.default_value.as_ref())
+                {
+                    visitor.visit_expression(default);
+                }
+                for property in &contract.properties {
+                    visitor.visit_expression(&property.value);
+                }
+                for method in &contract.methods {
+                    visitor.visit_function(method);
+                }
+            }
+            Definition::Style(style) => {
+                for field in &style.fields {
+                    visitor.visit_expression(&field.value);
+                }
+            }
+            Definition::Statement(stmt) => visitor.visit_statement(stmt),
+            Definition::Enum(_) | Definition::Interface(_) => {}
+        }
+    }
+}
+
+pub fn walk_function<V: Visitor + ?Sized>(visitor: &mut V, function: &FunctionDefinition) {
+    visitor.visit_block(&function.body);
+}
+
+pub fn walk_app<V: Visitor + ?Sized>(visitor: &mut V, app: &AppDefinition) {
+    for stmt in &app.body.statements {
+        visitor.visit_statement(stmt);
+    }
+    if let Some(show_block) = &app.body.show_block {
+        walk_ui_node(visitor, &show_block.root_node);
+    }
+}
+
+fn walk_ui_node<V: Visitor + ?Sized>(visitor: &mut V, node: &UiNode) {
+    let UiNode::Element(element) = node;
+    for property in &element.properties {
+        match property {
+            UiProperty::Positional(expr) | UiProperty::Named(_, expr) => {
+                visitor.visit_expression(expr)
+            }
+            UiProperty::EventBinding(_, block) => visitor.visit_block(block),
+        }
+    }
+    for child in &element.children {
+        walk_ui_node(visitor, child);
+    }
+}
+
+pub fn walk_block<V: Visitor + ?Sized>(visitor: &mut V, block: &BlockStatement) {
+    for stmt in &block.statements {
+        visitor.visit_statement(stmt);
+    }
+}
+
+pub fn walk_statement<V: Visitor + ?Sized>(visitor: &mut V, stmt: &Statement) {
+    match stmt {
+        Statement::Let(let_stmt) => visitor.visit_expression(&let_stmt.value),
+        Statement::For(for_stmt) => {
+            visitor.visit_expression(&for_stmt.collection);
+            visitor.visit_statement(&for_stmt.body);
+        }
+        Statement::Return(return_stmt) => visitor.visit_expression(&return_stmt.value),
+        Statement::Block(block) => visitor.visit_block(block),
+        Statement::Expression(expr_stmt) => visitor.visit_expression(&expr_stmt.expression),
+        Statement::Assign(assign_stmt) => {
+            visitor.visit_expression(&assign_stmt.target);
+            visitor.visit_expression(&assign_stmt.value);
+        }
+    }
+}
+
+pub fn walk_expression<V: Visitor + ?Sized>(visitor: &mut V, expr: &Expression) {
+    match expr {
+        Expression::Identifier(..) | Expression::AskJs(_) => {}
+        Expression::Literal(Literal::List(list), _) => {
+            for element in &list.elements {
+                visitor.visit_expression(element);
+            }
+        }
+        Expression::Literal(Literal::Map(map), _) => {
+            for (key, value) in &map.pairs {
+                visitor.visit_expression(key);
+                visitor.visit_expression(value);
+            }
+        }
+        Expression::Literal(..) => {}
+        Expression::Prefix(prefix) => visitor.visit_expression(&prefix.right),
+        Expression::Infix(infix) => {
+            visitor.visit_expression(&infix.left);
+            visitor.visit_expression(&infix.right);
+        }
+        Expression::If(if_expr) => {
+            visitor.visit_expression(&if_expr.condition);
+            visitor.visit_expression(&if_expr.then_branch);
+            if let Some(else_branch) = &if_expr.else_branch {
+                visitor.visit_expression(else_branch);
+            }
+        }
+        Expression::When(when_expr) => {
+            visitor.visit_expression(&when_expr.value);
+            for case in &when_expr.cases {
+                if let Some(guard) = &case.guard {
+                    visitor.visit_expression(guard);
+                }
+                visitor.visit_expression(&case.body);
+            }
+        }
+        Expression::Call(call) => {
+            visitor.visit_expression(&call.function);
+            for argument in &call.arguments {
+                visitor.visit_expression(argument);
+            }
+        }
+        Expression::MemberAccess(member_access) | Expression::SafeMemberAccess(member_access) => {
+            visitor.visit_expression(&member_access.object)
+        }
+        Expression::Await(await_expr) => visitor.visit_expression(&await_expr.expression),
+        Expression::Lambda(lambda) => visitor.visit_expression(&lambda.body),
+    }
+}
+
+// --- Bindings ---
+
+/// What declared a binding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BindingKind {
+    /// A plain `let's` at the top level of a module.
+    Global,
+    /// A `let's track` at the top level of a module or an app: state the UI
+    /// can show.
+    State,
+    /// A `let's` in a function, method, event handler or app.
+    Local,
+    /// A parameter of a function, method or lambda.
+    Parameter,
+    /// The variable of a `for` loop.
+    LoopVariable,
+    /// A name bound by a `when` pattern.
+    PatternBinding,
+}
+
+impl BindingKind {
+    fn describe(self) -> &'static str {
+        match self {
+            BindingKind::Global => "a top-level variable",
+            BindingKind::State => "tracked state",
+            BindingKind::Local => "a variable",
+            BindingKind::Parameter => "a parameter",
+            BindingKind::LoopVariable => "a loop variable",
+            BindingKind::PatternBinding => "a pattern binding",
+        }
+    }
+}
+
+/// A name declared in a program, and whether it is read.
+#[derive(Debug, Clone)]
+pub struct Binding {
+    pub name: String,
+    pub kind: BindingKind,
+    pub span: Span,
+    /// Whether anything reads the binding. Assigning to it, or to one of its
+    /// fields, doesn't count.
+    pub is_read: bool,
+    /// The binding of an enclosing scope this one hides, as an index into
+    /// `LintContext::bindings`.
+    pub shadows: Option<usize>,
+}
+
+/// Resolves every read of a name to the binding it reads, scope by scope.
+struct BindingCollector {
+    bindings: Vec<Binding>,
+    /// The bindings of each open scope, innermost last.
+    scopes: Vec<Vec<usize>>,
+}
+
+impl BindingCollector {
+    fn collect(program: &Program) -> Vec<Binding> {
+        let mut collector = Self {
+            bindings: Vec::new(),
+            scopes: vec![Vec::new()],
+        };
+        // Functions can read top-level bindings declared after them.
+        for definition in &program.definitions {
+            if let Definition::Statement(Statement::Let(let_stmt)) = definition {
+                let kind = if let_stmt.is_tracked {
+                    BindingKind::State
+                } else {
+                    BindingKind::Global
+                };
+                collector.declare(&let_stmt.name, kind, let_stmt.name_span);
+            }
+        }
+        walk_program(&mut collector, program);
+        collector.bindings
+    }
+
+    fn declare(&mut self, name: &str, kind: BindingKind, span: Span) {
+        let outer = self.scopes.len() - 1;
+        let shadows = self.scopes[..outer]
+            .iter()
+            .rev()
+            .find_map(|scope| scope.iter().rev().find(|&&i| self.bindings[i].name == name))
+            .copied();
+        self.bindings.push(Binding {
+            name: name.to_string(),
+            kind,
+            span,
+            is_read: false,
+            shadows,
+        });
+        let index = self.bindings.len() - 1;
+        self.scopes.last_mut().unwrap().push(index);
+    }
+
+    fn read(&mut self, name: &str) {
+        let binding = self
+            .scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.iter().rev().find(|&&i| self.bindings[i].name == name))
+            .copied();
+        if let Some(index) = binding {
+            self.bindings[index].is_read = true;
+        }
+    }
+
+    /// Declares the names a `when` pattern binds.
+    fn declare_pattern(&mut self, pattern: &WhenPattern, span: Span) {
+        match pattern {
+            WhenPattern::Identifier(name) => self.declare(name, BindingKind::PatternBinding, span),
+            WhenPattern::EnumVariant {
+                arguments, span, ..
+            } => {
+                for argument in arguments {
+                    self.declare_pattern(argument, *span);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Visits the target of an assignment, which writes the variable it
+    /// names rather than reading it.
+    fn visit_assign_target(&mut self, target: &Expression) {
+        match target {
+            Expression::Identifier(..) => {}
+            Expression::MemberAccess(member_access) => {
+                self.visit_assign_target(&member_access.object)
+            }
+            other => self.visit_expression(other),
+        }
+    }
+}
+
+impl Visitor for BindingCollector {
+    fn visit_function(&mut self, function: &FunctionDefinition) {
+        self.scopes.push(Vec::new());
+        for param in &function.parameters {
+            self.declare(&param.name, BindingKind::Parameter, param.span);
+        }
+        walk_function(self, function);
+        self.scopes.pop();
+    }
+
+    fn visit_app(&mut self, app: &AppDefinition) {
+        self.scopes.push(Vec::new());
+        walk_app(self, app);
+        self.scopes.pop();
+    }
+
+    fn visit_block(&mut self, block: &BlockStatement) {
+        self.scopes.push(Vec::new());
+        walk_block(self, block);
+        self.scopes.pop();
+    }
+
+    fn visit_statement(&mut self, stmt: &Statement) {
+        match stmt {
+            Statement::Let(let_stmt) => {
+                self.visit_expression(&let_stmt.value);
+                // Top-level bindings were declared before the walk.
+                match self.scopes.len() {
+                    1 => {}
+                    // The state of an app.
+                    2 if let_stmt.is_tracked => {
+                        self.declare(&let_stmt.name, BindingKind::State, let_stmt.name_span)
+                    }
+                    _ => self.declare(&let_stmt.name, BindingKind::Local, let_stmt.name_span),
+                }
+            }
+            Statement::For(for_stmt) => {
+                self.visit_expression(&for_stmt.collection);
+                self.scopes.push(Vec::new());
+                self.declare(
+                    &for_stmt.variable_name,
+                    BindingKind::LoopVariable,
+                    for_stmt.span,
+                );
+                self.visit_statement(&for_stmt.body);
+                self.scopes.pop();
+            }
+            Statement::Assign(assign_stmt) => {
+                self.visit_expression(&assign_stmt.value);
+                self.visit_assign_target(&assign_stmt.target);
+            }
+            _ => walk_statement(self, stmt),
+        }
+    }
+
+    fn visit_expression(&mut self, expr: &Expression) {
+        match expr {
+            Expression::Identifier(name, _) => self.read(name),
+            Expression::When(when_expr) => {
+                self.visit_expression(&when_expr.value);
+                for case in &when_expr.cases {
+                    self.scopes.push(Vec::new());
+                    self.declare_pattern(&case.pattern, case.span);
+                    if let Some(guard) = &case.guard {
+                        self.visit_expression(guard);
+                    }
+                    self.visit_expression(&case.body);
+                    self.scopes.pop();
+                }
+            }
+            Expression::Lambda(lambda) => {
+                self.scopes.push(Vec::new());
+                for param in &lambda.parameters {
+                    self.declare(&param.name, BindingKind::Parameter, param.span);
+                }
+                self.visit_expression(&lambda.body);
+                self.scopes.pop();
+            }
+            _ => walk_expression(self, expr),
+        }
+    }
+}
+
+// --- Built-in lints ---
+
+/// Reports variables, parameters and pattern bindings that are never read.
+pub struct UnusedVariables;
+
+impl Lint for UnusedVariables {
+    fn name(&self) -> &'static str {
+        "unused_variables"
+    }
+
+    fn description(&self) -> &'static str {
+        "Variables, parameters and pattern bindings that are never read"
+    }
+
+    fn check(&self, cx: &LintContext) -> Vec<Diagnostic> {
+        cx.bindings()
+            .iter()
+            .filter(|binding| !binding.is_read && !binding.name.starts_with('_'))
+            .filter_map(|binding| {
+                let noun = match binding.kind {
+                    BindingKind::Local => "Variable",
+                    BindingKind::Parameter => "Parameter",
+                    BindingKind::LoopVariable => "Loop variable",
+                    BindingKind::PatternBinding => "Pattern binding",
+                    BindingKind::Global | BindingKind::State => return None,
+                };
+                Some(
+                    Diagnostic::warning(
+                        format!("{} '{}' is never used", noun, binding.name),
+                        binding.span,
+                    )
+                    .with_suggestions(vec![format!(
+                        "if this is intentional, name it '_{}'",
+                        binding.name
+                    )]),
+                )
+            })
+            .collect()
+    }
+}
+
+/// Reports `let's track` state that neither the UI nor any code reads, so
+/// changing it updates nothing.
+pub struct UnusedState;
+
+impl Lint for UnusedState {
+    fn name(&self) -> &'static str {
+        "unused_state"
+    }
+
+    fn description(&self) -> &'static str {
+        "Tracked state that is never read by the UI or any code"
+    }
+
+    fn check(&self, cx: &LintContext) -> Vec<Diagnostic> {
+        cx.bindings()
+            .iter()
+            .filter(|binding| binding.kind == BindingKind::State && !binding.is_read)
+            .map(|binding| {
+                Diagnostic::warning(
+                    format!(
+                        "Tracked state '{}' is never read, so changing it updates nothing",
+                        binding.name
+                    ),
+                    binding.span,
+                )
+                .with_suggestions(vec!["show it in the UI, or remove it".to_string()])
+            })
+            .collect()
+    }
+}
+
+/// Reports bindings that hide a binding of the same name in an enclosing scope.
+pub struct Shadowing;
+
+impl Lint for Shadowing {
+    fn name(&self) -> &'static str {
+        "shadowing"
+    }
+
+    fn description(&self) -> &'static str {
+        "Bindings that hide a binding of the same name in an enclosing scope"
+    }
+
+    fn check(&self, cx: &LintContext) -> Vec<Diagnostic> {
+        let bindings = cx.bindings();
+        bindings
+            .iter()
+            .filter_map(|binding| {
+                let shadowed = &bindings[binding.shadows?];
+                Some(
+                    Diagnostic::warning(
+                        format!(
+                            "'{}' shadows {} with the same name",
+                            binding.name,
+                            shadowed.kind.describe()
+                        ),
+                        binding.span,
+                    )
+                    .with_related(
+                        format!("'{}' is declared here", shadowed.name),
+                        shadowed.span,
+                    )
+                    .with_suggestions(vec![format!("rename '{}'", binding.name)]),
+                )
+            })
+            .collect()
+    }
+}
+
+/// Reports statements after a `return`.
+pub struct UnreachableCode;
+
+impl Lint for UnreachableCode {
+    fn name(&self) -> &'static str {
+        "unreachable_code"
+    }
+
+    fn description(&self) -> &'static str {
+        "Statements after a `return`, which never run"
+    }
+
+    fn check(&self, cx: &LintContext) -> Vec<Diagnostic> {
+        struct Finder(Vec<Diagnostic>);
+
+        impl Visitor for Finder {
+            fn visit_block(&mut self, block: &BlockStatement) {
+                let statements = &block.statements;
+                let returns = statements
+                    .iter()
+                    .position(|stmt| matches!(stmt, Statement::Return(_)));
+                if let (Some(index), Some(last)) = (returns, statements.last()) {
+                    if index + 1 < statements.len() {
+                        let span = Span {
+                            start: statements[index + 1].span().start,
+                            end: last.span().end,
+                        };
+                        self.0.push(
+                            Diagnostic::warning(
+                                "This code never runs because it comes after a `return`"
+                                    .to_string(),
+                                span,
+                            )
+                            .with_related(
+                                "the function returns here".to_string(),
+                                statements[index].span(),
+                            ),
+                        );
+                    }
+                }
+                walk_block(self, block);
+            }
+        }
+
+        let mut finder = Finder(Vec::new());
+        walk_program(&mut finder, cx.program);
+        finder.0
+    }
+}
+
+/// Reports `if` conditions and `when` guards whose value is known without
+/// running the program, like `if 1 > 2: ...`.
+pub struct ConstantConditions;
+
+impl Lint for ConstantConditions {
+    fn name(&self) -> &'static str {
+        "constant_conditions"
+    }
+
+    fn description(&self) -> &'static str {
+        "Conditions and guards that are always true or always false"
+    }
+
+    fn check(&self, cx: &LintContext) -> Vec<Diagnostic> {
+        struct Finder(Vec<Diagnostic>);
+
+        impl Visitor for Finder {
+            fn visit_expression(&mut self, expr: &Expression) {
+                match expr {
+                    Expression::If(if_expr) => {
+                        if let Some(value) = constant_condition(&if_expr.condition) {
+                            let message = match (value, &if_expr.else_branch) {
+                                (true, Some(_)) => {
+                                    "This condition is always true, so the `else` branch never runs"
+                                }
+                                (true, None) => "This condition is always true",
+                                (false, _) => {
+                                    "This condition is always false, so its branch never runs"
+                                }
+                            };
+                            self.0.push(Diagnostic::warning(
+                                message.to_string(),
+                                if_expr.condition.span(),
+                            ));
+                        }
+                    }
+                    Expression::When(when_expr) => {
+                        for guard in when_expr.cases.iter().filter_map(|c| c.guard.as_ref()) {
+                            if let Some(value) = constant_condition(guard) {
+                                let message = if value {
+                                    "This guard is always true, so it can be removed"
+                                } else {
+                                    "This guard is always false, so its arm never matches"
+                                };
+                                self.0
+                                    .push(Diagnostic::warning(message.to_string(), guard.span()));
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+                walk_expression(self, expr);
+            }
+        }
+
+        let mut finder = Finder(Vec::new());
+        walk_program(&mut finder, cx.program);
+        finder.0
+    }
+}
+
+/// A value known without running the program.
+#[derive(PartialEq)]
+enum Constant<'a> {
+    Number(f64),
+    String(&'a str),
+    Boolean(bool),
+}
+
+/// Returns the value of a condition made only of literals, if it has one.
+fn constant_condition(expr: &Expression) -> Option<bool> {
+    match constant(expr)? {
+        Constant::Boolean(value) => Some(value),
+        _ => None,
+    }
+}
+
+fn constant(expr: &Expression) -> Option<Constant<'_>> {
+    match expr {
+        Expression::Literal(Literal::Number(n), _) => n.parse().ok().map(Constant::Number),
+        Expression::Literal(Literal::String(s), _) => Some(Constant::String(s)),
+        Expression::Literal(Literal::Boolean(b), _) => Some(Constant::Boolean(*b)),
+        Expression::Prefix(prefix) => match (&prefix.operator, constant(&prefix.right)?) {
+            (PrefixOperator::Not, Constant::Boolean(b)) => Some(Constant::Boolean(!b)),
+            (PrefixOperator::Minus, Constant::Number(n)) => Some(Constant::Number(-n)),
+            _ => None,
+        },
+        Expression::Infix(infix) => {
+            let (left, right) = (constant(&infix.left)?, constant(&infix.right)?);
+            let value = match (&infix.operator, &left, &right) {
+                (InfixOperator::Equal, _, _) => left == right,
+                (InfixOperator::NotEqual, _, _) => left != right,
+                (InfixOperator::LessThan, Constant::Number(l), Constant::Number(r)) => l < r,
+                (InfixOperator::GreaterThan, Constant::Number(l), Constant::Number(r)) => l > r,
+                (InfixOperator::And, Constant::Boolean(l), Constant::Boolean(r)) => *l && *r,
+                (InfixOperator::Or, Constant::Boolean(l), Constant::Boolean(r)) => *l || *r,
+                _ => return None,
+            };
+            Some(Constant::Boolean(value))
+        }
+        _ => None,
+    }
+}
@@ -28,6 +28,18 @@ impl<'a> Scribe<'a> {
         self.read_position += 1;
     }
 
+    /// Returns the column of a byte offset, counting from 0 at the start of its line.
+    pub fn column_of(&self, offset: usize) -> usize {
+        offset - self.input[..offset.min(self.input.len())].rfind('\n').map_or(0, |i| i + 1)
+    }
+
+    /// Returns the indentation of the line a byte offset is on, the column
+    /// of its first character that isn't a space or tab.
+    pub fn indent_of(&self, offset: usize) -> usize {
+        let start = offset - self.column_of(offset);
+        self.input[start..].bytes().take_while(|b| *b == b' ' || *b == b'\t').count()
+    }
+
     fn peek_char(&self) -> u8 {
         if self.read_position >= self.input.len() { 0 } else { self.input.as_bytes()[self.read_position] }
     }
@@ -55,16 +67,20 @@ impl<'a> Scribe<'a> {
             b'>' => Token::GreaterThan(span(1)),
             b'<' => Token::LessThan(span(1)),
             b'+' => Token::Plus(span(1)),
-            b'-' => Token::Minus(span(1)),
+            b'-' => if self.peek_char() == b'>' { self.read_char(); Token::Arrow(Span { start, end: self.position + 1 }) } else { Token::Minus(span(1)) },
             b'*' => Token::Asterisk(span(1)),
             b'/' => Token::Slash(span(1)),
+            b'%' => Token::Percent(span(1)),
             b'.' => Token::Dot(span(1)),
+            b'?' => match self.peek_char() { b'.' => { self.read_char(); Token::QuestionDot(Span { start, end: self.position + 1 }) } b':' => { self.read_char(); Token::Elvis(Span { start, end: self.position + 1 }) } _ => Token::Question(span(1)) },
             b'(' => Token::LParen(span(1)),
             b')' => Token::RParen(span(1)),
             b'{' => Token::LBrace(span(1)),
             b'}' => Token::RBrace(span(1)),
             b':' => Token::Colon(span(1)),
             b',' => Token::Comma(span(1)),
+            b'[' => Token::LBracket(span(1)),
+            b']' => Token::RBracket(span(1)),
             b'a'..=b'z' | b'A'..=b'Z' | b'_' => return self.read_identifier(),
             b'0'..=b'9' => return self.read_number(),
             b'"' => return self.read_string(),
@@ -78,7 +94,7 @@ impl<'a> Scribe<'a> {
 
     fn read_identifier(&mut self) -> Token {
         let start = self.position;
-        while self.ch.is_ascii_alphabetic() || self.ch == b'_' || self.ch == b'\'' { self.read_char(); }
+        while self.ch.is_ascii_alphanumeric() || self.ch == b'_' || self.ch == b'\'' { self.read_char(); }
         let end = self.position;
         let literal = &self.input[start..end];
         let span = Span { start, end };
@@ -89,7 +105,9 @@ impl<'a> Scribe<'a> {
             "for" => Token::For(span), "in" => Token::In(span), "is" => Token::Is(span),
             "async" => Token::Async(span), "await" => Token::Await(span), "show" => Token::Show(span),
             "nothing" => Token::Nothing(span), "return" => Token::Return(span),
-            "enum" => Token::Enum(span),
+            "enum" => Token::Enum(span), "interface" => Token::Interface(span),
+            "use" => Token::Use(span), "public" => Token::Public(span),
+            "and" => Token::And(span), "or" => Token::Or(span),
             _ => Token::Identifier(literal.to_string(), span),
         }
     }
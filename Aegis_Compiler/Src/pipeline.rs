@@ -0,0 +1,159 @@
+//! Runs the whole compiler over a project's sources: the Scribe, the
+//! Architect and the Guardian check every module, the Lowerer translates them
+//! into AIL, and the code generator of the target translates them for the
+//! platform they will run on.
+//!
+//! Code is only lowered and generated for a project without errors.
+
+use crate::ast::Program;
+use crate::engine::ail::InstructionSequence;
+use crate::engine::codegen_android::AndroidCodeGen;
+use crate::engine::lowerer::Lowerer;
+use crate::engine::Artifact;
+use crate::error::Diagnostic;
+use crate::lint::LintConfig;
+use crate::project::{Module, Project};
+use std::io;
+use std::path::PathBuf;
+
+/// The platform a program is compiled for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Target {
+    /// An Android project, in Kotlin.
+    #[default]
+    Android,
+    /// Only AIL, with no other artifacts.
+    Ail,
+}
+
+/// How to compile a project.
+#[derive(Debug, Clone)]
+pub struct CompileOptions {
+    /// The module the program starts in. Defaults to `main`.
+    pub entry: String,
+    /// The lint levels, as in an `aegis.toml`.
+    pub lint_config: LintConfig,
+    pub target: Target,
+}
+
+impl Default for CompileOptions {
+    fn default() -> Self {
+        Self {
+            entry: "main".to_string(),
+            lint_config: LintConfig::default(),
+            target: Target::default(),
+        }
+    }
+}
+
+/// Everything the compiler made of a project.
+pub struct CompileResult {
+    /// Every module of the project, parsed and checked.
+    pub project: Project,
+    /// Every error and warning, with the path of the file it is in: those of
+    /// the Architect, the Guardian and the lints, then those of the Lowerer
+    /// and the code generator.
+    pub diagnostics: Vec<(PathBuf, Diagnostic)>,
+    /// The AIL of every module, each after the modules it imports. Each
+    /// module's top-level statements come first, in a sequence named after
+    /// it, and the entry module's is named `main`. Empty if the project
+    /// doesn't check.
+    pub ail: Vec<InstructionSequence>,
+    /// The files generated for the target. Empty if the project doesn't check
+    /// or the target can't translate it.
+    pub artifacts: Vec<Artifact>,
+}
+
+impl CompileResult {
+    /// The program of the entry module.
+    pub fn program(&self) -> Option<&Program> {
+        self.project.entry().map(|module| &module.program)
+    }
+
+    /// The module an AIL sequence or a runtime error names, e.g.,
+    /// `models.task`, where `main` is the entry module.
+    pub fn module(&self, name: &str) -> Option<&Module> {
+        if name == "main" {
+            return self.project.entry();
+        }
+        self.project
+            .modules
+            .iter()
+            .find(|module| module.name == name)
+    }
+
+    /// Returns true if any diagnostic is an error.
+    pub fn has_errors(&self) -> bool {
+        self.diagnostics
+            .iter()
+            .any(|(_, diagnostic)| diagnostic.is_error())
+    }
+}
+
+/// Compiles a project whose modules are given as `(name, source)` pairs,
+/// e.g., `("models.task", "public contract Task: ...")`. Fails only if there
+/// is no module `options.entry` or a lint level names an unknown lint.
+pub fn compile<'s>(
+    sources: impl IntoIterator<Item = (&'s str, &'s str)>,
+    options: &CompileOptions,
+) -> io::Result<CompileResult> {
+    let project = Project::from_sources(sources, &options.entry, options.lint_config.clone())?;
+    Ok(compile_project(project, options))
+}
+
+/// Compiles a project that is already loaded and checked, e.g., by
+/// `Project::load`. Its entry module must be `options.entry`.
+pub fn compile_project(project: Project, options: &CompileOptions) -> CompileResult {
+    let mut diagnostics: Vec<(PathBuf, Diagnostic)> = project
+        .modules
+        .iter()
+        .flat_map(|module| {
+            module
+                .diagnostics()
+                .into_iter()
+                .map(|diagnostic| (module.path.clone(), diagnostic))
+        })
+        .collect();
+    let mut result = CompileResult {
+        project,
+        diagnostics: Vec::new(),
+        ail: Vec::new(),
+        artifacts: Vec::new(),
+    };
+    if result.project.has_errors() {
+        result.diagnostics = diagnostics;
+        return result;
+    }
+
+    let mut lowerer = Lowerer::new();
+    for module in &result.project.modules {
+        let name = if module.name == options.entry {
+            "main"
+        } else {
+            &module.name
+        };
+        result
+            .ail
+            .extend(lowerer.lower_module(name, &module.program, &module.guardian));
+        diagnostics.extend(
+            lowerer
+                .errors
+                .drain(..)
+                .map(|error| (module.path.clone(), Diagnostic::from(error))),
+        );
+    }
+
+    match options.target {
+        Target::Android => match AndroidCodeGen::new().generate_project_files(&result.project) {
+            Ok(artifacts) => result.artifacts = artifacts,
+            Err(errors) => diagnostics.extend(
+                errors
+                    .into_iter()
+                    .map(|(path, error)| (path, Diagnostic::from(error))),
+            ),
+        },
+        Target::Ail => {}
+    }
+    result.diagnostics = diagnostics;
+    result
+}
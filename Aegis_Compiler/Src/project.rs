@@ -0,0 +1,527 @@
+//! Loads and checks a project made of several `.ag` files. Each file is a
+//! module named by its path from the project root, e.g., `models/task.ag` is
+//! the module `models.task`, and its `use` declarations import the `public`
+//! declarations of other modules.
+//!
+//! Every module is checked by its own Guardian, after the modules it imports,
+//! and then linted with the lint levels of the project's `aegis.toml`.
+
+use crate::ast::{Definition, Program, UseDeclaration};
+use crate::error::{Diagnostic, ParseError, SemanticError, SemanticErrorType};
+use crate::guardian_components::closest_match;
+use crate::guardian_stdlib::MethodSignature;
+use crate::guardian_symbol_table::{Symbol, SymbolKind};
+use crate::guardian_types::Type;
+use crate::lint::{LintConfig, LintContext, Linter};
+use crate::render::{line_and_column, Renderer};
+use crate::token::Span;
+use crate::{Architect, Guardian, Scribe};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// The extension of Aegis source files.
+pub const SOURCE_EXTENSION: &str = "ag";
+
+/// The configuration file at the root of a project.
+pub const CONFIG_FILE: &str = "aegis.toml";
+
+/// A source file of a project, parsed and checked.
+pub struct Module {
+    /// The dotted name of the module, e.g., `models.task`.
+    pub name: String,
+    /// The path of the file, relative to the project root.
+    pub path: PathBuf,
+    pub source: String,
+    pub program: Program,
+    pub parse_errors: Vec<ParseError>,
+    /// The Guardian that checked the module. Its errors include those of
+    /// the module's `use` declarations.
+    pub guardian: Guardian,
+    /// What the lints found in the module, in source order.
+    pub lints: Vec<Diagnostic>,
+}
+
+impl Module {
+    /// The names of the module's `public` declarations, in source order.
+    pub fn public_names(&self) -> Vec<&str> {
+        self.declared_names(true)
+    }
+
+    /// The names of the declarations the module doesn't declare `public`.
+    pub fn private_names(&self) -> Vec<&str> {
+        self.declared_names(false)
+    }
+
+    fn declared_names(&self, public: bool) -> Vec<&str> {
+        self.program
+            .definitions
+            .iter()
+            .filter_map(|def| match def {
+                Definition::Contract(def) if def.is_public == public => Some(def.name.as_str()),
+                Definition::Enum(def) if def.is_public == public => Some(def.name.as_str()),
+                Definition::Interface(def) if def.is_public == public => Some(def.name.as_str()),
+                Definition::Function(def) if def.is_public == public => Some(def.name.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Returns true if the module declares `name` at the top level, publicly or not.
+    fn declares(&self, name: &str) -> bool {
+        self.program.definitions.iter().any(|def| match def {
+            Definition::Contract(def) => def.name == name,
+            Definition::Enum(def) => def.name == name,
+            Definition::Interface(def) => def.name == name,
+            Definition::Function(def) => def.name == name,
+            _ => false,
+        })
+    }
+
+    /// Formats a diagnostic at `span` of this module, e.g.,
+    /// `models/task.ag:3:5: Undefined type 'Stauts'`.
+    pub fn describe(&self, span: Span, message: &str) -> String {
+        let (line, column) = line_and_column(&self.source, span.start);
+        format!("{}:{}:{}: {}", self.path.display(), line, column, message)
+    }
+
+    /// The module's parse errors, semantic errors and what its lints found.
+    pub fn diagnostics(&self) -> Vec<Diagnostic> {
+        self.parse_errors
+            .iter()
+            .cloned()
+            .map(Diagnostic::from)
+            .chain(self.guardian.errors.iter().cloned().map(Diagnostic::from))
+            .chain(self.lints.iter().cloned())
+            .collect()
+    }
+
+    /// Returns true if the module has a parse or semantic error, or a lint
+    /// it denies found something.
+    pub fn has_errors(&self) -> bool {
+        !self.parse_errors.is_empty()
+            || !self.guardian.errors.is_empty()
+            || self.lints.iter().any(Diagnostic::is_error)
+    }
+}
+
+/// Every module reachable from the entry module of a project.
+pub struct Project {
+    pub root: PathBuf,
+    /// The lint levels of the project's `aegis.toml`.
+    pub lint_config: LintConfig,
+    /// The modules of the project, each after the modules it imports.
+    pub modules: Vec<Module>,
+}
+
+impl Project {
+    /// Loads the module `entry`, e.g., `main`, from the directory `root`,
+    /// along with every module it imports, and checks them. Fails only if
+    /// the entry module or the project's `aegis.toml` can't be read, or the
+    /// `aegis.toml` is invalid; a missing import is reported as an error of
+    /// the module that imports it.
+    pub fn load(root: impl Into<PathBuf>, entry: &str) -> io::Result<Project> {
+        let root = root.into();
+        let lint_config = load_lint_config(&root)?;
+        let dir = root.clone();
+        Self::check(root, entry, lint_config, &|path| {
+            fs::read_to_string(dir.join(path))
+        })
+    }
+
+    /// Checks a project whose modules are given as `(name, source)` pairs,
+    /// e.g., `("models.task", "public contract Task: ...")`, instead of read
+    /// from files, with the lint levels `lint_config`. Fails only if there
+    /// is no module `entry` or a lint level names an unknown lint.
+    pub fn from_sources<'s>(
+        sources: impl IntoIterator<Item = (&'s str, &'s str)>,
+        entry: &str,
+        lint_config: LintConfig,
+    ) -> io::Result<Project> {
+        let sources: HashMap<PathBuf, &str> = sources
+            .into_iter()
+            .map(|(name, source)| (module_path(name), source))
+            .collect();
+        Self::check(PathBuf::new(), entry, lint_config, &|path| {
+            sources
+                .get(path)
+                .map(|source| source.to_string())
+                .ok_or_else(|| {
+                    io::Error::new(
+                        io::ErrorKind::NotFound,
+                        format!("no source for {}", path.display()),
+                    )
+                })
+        })
+    }
+
+    /// Loads the module `entry` and every module it imports with `read`,
+    /// which reads a module's source given its path from the project root,
+    /// and checks them.
+    fn check(
+        root: PathBuf,
+        entry: &str,
+        lint_config: LintConfig,
+        read: &dyn Fn(&Path) -> io::Result<String>,
+    ) -> io::Result<Project> {
+        let linter = Linter::new(lint_config.clone());
+        if let Some(unknown) = lint_config.lints().find(|name| linter.lint(name).is_none()) {
+            let suggestion = closest_match(unknown, linter.lints().map(|lint| lint.name()))
+                .map(|s| format!("; did you mean '{}'?", s))
+                .unwrap_or_default();
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{}: unknown lint '{}'{}", CONFIG_FILE, unknown, suggestion),
+            ));
+        }
+
+        let source = read(&module_path(entry))?;
+        let mut loader = Loader {
+            read,
+            linter: &linter,
+            modules: Vec::new(),
+            in_progress: Vec::new(),
+        };
+        loader.load(entry.to_string(), source);
+        let modules = loader.modules;
+        Ok(Project {
+            root,
+            lint_config,
+            modules,
+        })
+    }
+
+    /// The entry module, which is loaded last, after everything it imports.
+    pub fn entry(&self) -> Option<&Module> {
+        self.modules.last()
+    }
+
+    /// Finds a module by its dotted name.
+    pub fn module(&self, name: &str) -> Option<&Module> {
+        self.modules.iter().find(|module| module.name == name)
+    }
+
+    /// Returns true if any module has an error.
+    pub fn has_errors(&self) -> bool {
+        self.modules.iter().any(Module::has_errors)
+    }
+
+    /// Every error and warning of the project, each naming the file it is
+    /// in. Warnings say so, e.g.,
+    /// `main.ag:2:5: warning: Variable 'x' is never used`.
+    pub fn diagnostics(&self) -> Vec<String> {
+        let mut diagnostics = Vec::new();
+        for module in &self.modules {
+            for diagnostic in module.diagnostics() {
+                let message = if diagnostic.is_error() {
+                    diagnostic.message
+                } else {
+                    format!("{}: {}", diagnostic.severity, diagnostic.message)
+                };
+                diagnostics.push(module.describe(diagnostic.span, &message));
+            }
+        }
+        diagnostics
+    }
+
+    /// Renders every error and warning of the project with the lines of
+    /// source they point at, followed by a count of each, e.g.,
+    /// `error: found 2 errors and 1 warning`.
+    pub fn render_diagnostics(&self, renderer: &Renderer) -> String {
+        let mut out = String::new();
+        let (mut errors, mut warnings) = (0, 0);
+        for module in &self.modules {
+            let path = module.path.display().to_string();
+            for diagnostic in module.diagnostics() {
+                if diagnostic.is_error() {
+                    errors += 1;
+                } else {
+                    warnings += 1;
+                }
+                out.push_str(&renderer.render(&diagnostic, &path, &module.source));
+                out.push('\n');
+            }
+        }
+        if let Some(summary) = renderer.render_summary(errors, warnings) {
+            out.push_str(&summary);
+        }
+        out
+    }
+}
+
+/// Reads the lint levels from the `aegis.toml` at `root`, if there is one.
+fn load_lint_config(root: &Path) -> io::Result<LintConfig> {
+    let text = match fs::read_to_string(root.join(CONFIG_FILE)) {
+        Ok(text) => text,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(LintConfig::default()),
+        Err(error) => return Err(error),
+    };
+    LintConfig::parse(&text).map_err(|message| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{}: {}", CONFIG_FILE, message),
+        )
+    })
+}
+
+/// The path of a module's file relative to the project root, e.g.,
+/// `models/task.ag` for `models.task`.
+pub fn module_path(name: &str) -> PathBuf {
+    name.split('.')
+        .collect::<PathBuf>()
+        .with_extension(SOURCE_EXTENSION)
+}
+
+/// Loads modules depth-first, so that each is checked after its imports.
+struct Loader<'a> {
+    /// Reads a module's source given its path from the project root.
+    read: &'a dyn Fn(&Path) -> io::Result<String>,
+    linter: &'a Linter,
+    modules: Vec<Module>,
+    /// The modules being loaded, each imported by the one before it.
+    in_progress: Vec<String>,
+}
+
+impl Loader<'_> {
+    fn load(&mut self, name: String, source: String) {
+        let mut architect = Architect::new(Scribe::new(&source));
+        let program = architect.parse_program();
+        let parse_errors = architect.errors;
+        let mut guardian = Guardian::new().with_source(&source);
+
+        // Load the imported modules first.
+        self.in_progress.push(name.clone());
+        let mut resolved = Vec::new();
+        for import in &program.imports {
+            let dependency = import.module_name();
+            if let Some(start) = self.in_progress.iter().position(|m| *m == dependency) {
+                let mut cycle = self.in_progress[start..].to_vec();
+                cycle.push(dependency);
+                guardian.errors.push(SemanticError::new(
+                    format!(
+                        "Modules import each other in a cycle: {}",
+                        cycle.join(" -> ")
+                    ),
+                    import.span,
+                    SemanticErrorType::ImportCycle,
+                ));
+                continue;
+            }
+            if !self.modules.iter().any(|module| module.name == dependency) {
+                let path = module_path(&dependency);
+                match (self.read)(&path) {
+                    Ok(dependency_source) => self.load(dependency.clone(), dependency_source),
+                    Err(_) => {
+                        guardian.errors.push(SemanticError::new(
+                            format!(
+                                "Cannot find module '{}'; expected it in {}",
+                                dependency,
+                                path.display()
+                            ),
+                            import.span,
+                            SemanticErrorType::ModuleNotFound,
+                        ));
+                        continue;
+                    }
+                }
+            }
+            resolved.push(import);
+        }
+        self.in_progress.pop();
+
+        // Then check this module with the declarations it imports.
+        let mut imported_from = HashMap::new();
+        for import in resolved {
+            let Some(dependency) = self.modules.iter().find(|m| m.name == import.module_name())
+            else {
+                continue;
+            };
+            import_declarations(&mut guardian, dependency, import, &mut imported_from);
+        }
+        guardian.check_program(&program);
+        let lints = self
+            .linter
+            .run(&LintContext::new(&program, &guardian, &source));
+
+        self.modules.push(Module {
+            path: module_path(&name),
+            name,
+            source,
+            program,
+            parse_errors,
+            guardian,
+            lints,
+        });
+    }
+}
+
+/// Declares the names a `use` declaration imports from `dependency` in the
+/// importing module's Guardian. `imported_from` records the module each
+/// name was imported from, to report a name imported from two modules.
+fn import_declarations(
+    guardian: &mut Guardian,
+    dependency: &Module,
+    import: &UseDeclaration,
+    imported_from: &mut HashMap<String, String>,
+) {
+    let public = dependency.public_names();
+    let names: Vec<(&str, Span)> = match &import.names {
+        None => public.iter().map(|name| (*name, import.span)).collect(),
+        Some(names) => names
+            .iter()
+            .filter(|imported| {
+                let name = imported.name.as_str();
+                if public.contains(&name) {
+                    return true;
+                }
+                let error = if dependency.declares(name) {
+                    SemanticError::new(
+                        format!("'{}' is private to module '{}'", name, dependency.name),
+                        imported.span,
+                        SemanticErrorType::PrivateImport,
+                    )
+                    .with_suggestions(vec![format!(
+                        "declare it `public` in {}",
+                        dependency.path.display()
+                    )])
+                } else {
+                    let suggestion = closest_match(name, public.iter().copied());
+                    SemanticError::new(
+                        format!(
+                            "Module '{}' has no declaration named '{}'",
+                            dependency.name, name
+                        ),
+                        imported.span,
+                        SemanticErrorType::UndefinedSymbol,
+                    )
+                    .with_suggestions(
+                        suggestion
+                            .map(|s| vec![format!("did you mean '{}'?", s)])
+                            .unwrap_or_default(),
+                    )
+                };
+                guardian.errors.push(error);
+                false
+            })
+            .map(|imported| (imported.name.as_str(), imported.span))
+            .collect(),
+    };
+
+    let mut mentioned = Vec::new();
+    for (name, span) in names {
+        let Some(symbol) = dependency.guardian.lookup(name) else {
+            continue;
+        };
+        if let Some(other) = imported_from.get(name) {
+            if *other != dependency.name {
+                guardian.errors.push(SemanticError::new(
+                    format!(
+                        "'{}' is imported from both '{}' and '{}'",
+                        name, other, dependency.name
+                    ),
+                    span,
+                    SemanticErrorType::DuplicateDeclaration,
+                ));
+            }
+            continue;
+        }
+        collect_symbol_types(&symbol, &mut mentioned);
+        imported_from.insert(name.to_string(), dependency.name.clone());
+        guardian.import_symbol(symbol);
+    }
+
+    // The types the imported declarations mention, and the ones those mention.
+    let mut known = HashSet::new();
+    let mut dependencies = Vec::new();
+    while let Some(name) = mentioned.pop() {
+        if !known.insert(name.clone()) {
+            continue;
+        }
+        if let Some(symbol) = dependency.guardian.lookup(&name) {
+            collect_symbol_types(&symbol, &mut mentioned);
+            dependencies.push(symbol);
+        }
+    }
+    guardian.import_dependencies(dependencies, &dependency.guardian);
+    guardian.import_private_names(
+        &dependency.name,
+        &dependency.path.display().to_string(),
+        dependency.private_names(),
+    );
+}
+
+/// Collects the names of the contracts, enums and interfaces that a
+/// declaration's type and signature mention.
+fn collect_symbol_types(symbol: &Symbol, names: &mut Vec<String>) {
+    collect_type_names(&symbol.ty, names);
+    let mut collect_methods = |methods: &HashMap<String, MethodSignature>| {
+        for method in methods.values() {
+            for param in &method.params {
+                collect_type_names(param, names);
+            }
+            collect_type_names(&method.return_type, names);
+            names.extend(method.bounds.iter().map(|(_, bound)| bound.clone()));
+        }
+    };
+    match &symbol.kind {
+        SymbolKind::Contract {
+            fields,
+            properties,
+            methods,
+            ..
+        }
+        | SymbolKind::GenericContract {
+            fields,
+            properties,
+            methods,
+            ..
+        } => {
+            collect_methods(methods);
+            for ty in fields.values().chain(properties.values()) {
+                collect_type_names(ty, names);
+            }
+        }
+        SymbolKind::Interface { methods } => collect_methods(methods),
+        SymbolKind::Function { bounds, .. } => {
+            names.extend(bounds.iter().map(|(_, bound)| bound.clone()));
+        }
+        _ => {}
+    }
+}
+
+/// Collects the names of the contracts, enums and interfaces in a type.
+fn collect_type_names(ty: &Type, names: &mut Vec<String>) {
+    match ty {
+        Type::Custom(name) | Type::Interface(name) => names.push(name.clone()),
+        Type::GenericInstance(name, args) => {
+            names.push(name.clone());
+            for arg in args {
+                collect_type_names(arg, names);
+            }
+        }
+        Type::Enum { name, variants } => {
+            names.push(name.clone());
+            for ty in variants.values().flatten() {
+                collect_type_names(ty, names);
+            }
+        }
+        Type::List(t) | Type::Set(t) | Type::Optional(t) | Type::Future(t) => {
+            collect_type_names(t, names)
+        }
+        Type::Map(k, v) => {
+            collect_type_names(k, names);
+            collect_type_names(v, names);
+        }
+        Type::Function {
+            params,
+            return_type,
+        } => {
+            for param in params {
+                collect_type_names(param, names);
+            }
+            collect_type_names(return_type, names);
+        }
+        _ => {}
+    }
+}
@@ -0,0 +1,328 @@
+//! Renders diagnostics for people reading them in a terminal: the file,
+//! line and column, the lines of source they point at with the primary span
+//! underlined with `^` and related spans with `-`, and their notes and help.
+//!
+//! ```text
+//! error[AEG0102]: Type mismatch: expected number because of the annotation on 'count', found string
+//!  --> main.ag:1:23
+//!   |
+//! 1 | let's count: number = "three"
+//!   |                       ^^^^^^^
+//!   |              ------ expected number because of this
+//!   |
+//!   = help: for more about this error, run `aegis explain AEG0102`
+//! ```
+//!
+//! Color is added when the output is a terminal, unless `NO_COLOR` is set,
+//! and left out in plain mode, e.g., for CI logs.
+
+use crate::error::{Diagnostic, Severity};
+use crate::token::Span;
+use std::env;
+use std::io::IsTerminal;
+
+/// Columns a tab is expanded to, so markers line up under it.
+const TAB_WIDTH: usize = 4;
+
+/// Whether a Renderer colors its output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColorChoice {
+    /// Color if the output is a terminal and `NO_COLOR` isn't set.
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    /// Parses `auto`, `always` or `never`.
+    pub fn parse(name: &str) -> Option<ColorChoice> {
+        match name {
+            "auto" => Some(ColorChoice::Auto),
+            "always" => Some(ColorChoice::Always),
+            "never" => Some(ColorChoice::Never),
+            _ => None,
+        }
+    }
+
+    /// Returns true if output to `stream` should be colored.
+    pub fn should_color(self, stream: &impl IsTerminal) -> bool {
+        match self {
+            ColorChoice::Auto => {
+                stream.is_terminal() && env::var_os("NO_COLOR").is_none_or(|v| v.is_empty())
+            }
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+        }
+    }
+}
+
+/// The ANSI styles of the parts of a diagnostic.
+#[derive(Clone, Copy)]
+enum Style {
+    Error,
+    Warning,
+    Info,
+    Hint,
+    /// Line numbers, the gutter and related spans.
+    Gutter,
+    /// The message in the header.
+    Message,
+}
+
+impl Style {
+    fn code(self) -> &'static str {
+        match self {
+            Style::Error => "\x1b[1;31m",
+            Style::Warning => "\x1b[1;33m",
+            Style::Info => "\x1b[1;36m",
+            Style::Hint => "\x1b[1;32m",
+            Style::Gutter => "\x1b[1;34m",
+            Style::Message => "\x1b[1m",
+        }
+    }
+
+    fn of(severity: Severity) -> Style {
+        match severity {
+            Severity::Error => Style::Error,
+            Severity::Warning => Style::Warning,
+            Severity::Info => Style::Info,
+            Severity::Hint => Style::Hint,
+        }
+    }
+}
+
+/// A span to underline on one line of a snippet, in columns of that line.
+struct Marker<'a> {
+    line: usize,
+    start: usize,
+    width: usize,
+    label: Option<&'a str>,
+    primary: bool,
+}
+
+/// Renders diagnostics as text, with or without color.
+#[derive(Debug, Clone, Copy)]
+pub struct Renderer {
+    color: bool,
+}
+
+impl Renderer {
+    /// Creates a Renderer that colors its output if `color` says to when
+    /// writing to standard error, where diagnostics go.
+    pub fn new(color: ColorChoice) -> Self {
+        Self {
+            color: color.should_color(&std::io::stderr()),
+        }
+    }
+
+    /// Creates a Renderer that never colors its output.
+    pub fn plain() -> Self {
+        Self { color: false }
+    }
+
+    /// Renders `diagnostic`, found in the file at `path` whose text is `source`.
+    pub fn render(&self, diagnostic: &Diagnostic, path: &str, source: &str) -> String {
+        let mut out = self.header(diagnostic);
+        let (line, column) = line_and_column(source, diagnostic.span.start);
+
+        let mut markers = vec![marker(source, diagnostic.span, None, true)];
+        markers.extend(
+            diagnostic
+                .related
+                .iter()
+                .map(|related| marker(source, related.span, Some(&related.message), false)),
+        );
+        let mut lines: Vec<usize> = markers.iter().map(|marker| marker.line).collect();
+        lines.sort_unstable();
+        lines.dedup();
+
+        let width = lines.last().copied().unwrap_or(line).to_string().len();
+        let pad = " ".repeat(width);
+        out.push_str(&format!(
+            "{}{} {}:{}:{}\n",
+            pad,
+            self.paint(Style::Gutter, "-->"),
+            path,
+            line,
+            column
+        ));
+        let gutter = self.paint(Style::Gutter, "|");
+        out.push_str(&format!("{} {}\n", pad, gutter));
+
+        let source_lines: Vec<&str> = source.split('\n').collect();
+        let mut previous = None;
+        for &number in &lines {
+            if previous.is_some_and(|previous| number > previous + 1) {
+                out.push_str(&format!("{}\n", self.paint(Style::Gutter, "...")));
+            }
+            previous = Some(number);
+            let text = source_lines.get(number - 1).copied().unwrap_or("");
+            let text = expand_tabs(text.trim_end_matches('\r'));
+            out.push_str(
+                format!(
+                    "{} {} {}",
+                    self.paint(Style::Gutter, &format!("{:>width$}", number)),
+                    gutter,
+                    text
+                )
+                .trim_end(),
+            );
+            out.push('\n');
+            for marker in markers.iter().filter(|marker| marker.line == number) {
+                let (symbol, style) = if marker.primary {
+                    ('^', Style::of(diagnostic.severity))
+                } else {
+                    ('-', Style::Gutter)
+                };
+                let underline = symbol.to_string().repeat(marker.width);
+                let mut row = format!(
+                    "{} {} {}{}",
+                    pad,
+                    gutter,
+                    " ".repeat(marker.start),
+                    self.paint(style, &underline)
+                );
+                if let Some(label) = marker.label {
+                    row.push(' ');
+                    row.push_str(&self.paint(style, label));
+                }
+                out.push_str(&row);
+                out.push('\n');
+            }
+        }
+
+        let mut footers: Vec<(&str, String)> = Vec::new();
+        footers.extend(diagnostic.notes.iter().map(|note| ("note", note.clone())));
+        footers.extend(
+            diagnostic
+                .suggestions
+                .iter()
+                .map(|suggestion| ("help", suggestion.clone())),
+        );
+        if let Some(code) = diagnostic.code {
+            footers.push((
+                "help",
+                format!("for more about this error, run `aegis explain {}`", code),
+            ));
+        }
+        if !footers.is_empty() {
+            out.push_str(&format!("{} {}\n", pad, gutter));
+            for (kind, text) in footers {
+                out.push_str(&format!(
+                    "{} {} {}: {}\n",
+                    pad,
+                    self.paint(Style::Gutter, "="),
+                    self.paint(Style::Message, kind),
+                    text
+                ));
+            }
+        }
+        out
+    }
+
+    /// Renders a message that isn't about a place in the source, e.g.,
+    /// `error: could not read main.ag`.
+    pub fn render_message(&self, severity: Severity, message: &str) -> String {
+        format!(
+            "{}{}\n",
+            self.paint(Style::of(severity), &severity.to_string()),
+            self.paint(Style::Message, &format!(": {}", message))
+        )
+    }
+
+    /// Summarizes how many errors and warnings were found, e.g.,
+    /// `error: found 2 errors and 1 warning`, or `None` if there were none.
+    pub fn render_summary(&self, errors: usize, warnings: usize) -> Option<String> {
+        let count =
+            |n: usize, what: &str| format!("{} {}{}", n, what, if n == 1 { "" } else { "s" });
+        let (severity, message) = match (errors, warnings) {
+            (0, 0) => return None,
+            (0, _) => (
+                Severity::Warning,
+                format!("found {}", count(warnings, "warning")),
+            ),
+            (_, 0) => (Severity::Error, format!("found {}", count(errors, "error"))),
+            _ => (
+                Severity::Error,
+                format!(
+                    "found {} and {}",
+                    count(errors, "error"),
+                    count(warnings, "warning")
+                ),
+            ),
+        };
+        Some(self.render_message(severity, &message))
+    }
+
+    /// The first line of a diagnostic, e.g., `error[AEG0102]: Type mismatch`.
+    fn header(&self, diagnostic: &Diagnostic) -> String {
+        let name = match diagnostic.code.or(diagnostic.lint) {
+            Some(code) => format!("{}[{}]", diagnostic.severity, code),
+            None => diagnostic.severity.to_string(),
+        };
+        format!(
+            "{}{}\n",
+            self.paint(Style::of(diagnostic.severity), &name),
+            self.paint(Style::Message, &format!(": {}", diagnostic.message))
+        )
+    }
+
+    fn paint(&self, style: Style, text: &str) -> String {
+        if self.color {
+            format!("{}{}\x1b[0m", style.code(), text)
+        } else {
+            text.to_string()
+        }
+    }
+}
+
+/// The 1-based line and column of a byte offset in `source`, counting
+/// columns in characters.
+pub fn line_and_column(source: &str, offset: usize) -> (usize, usize) {
+    let offset = floor_char_boundary(source, offset);
+    let before = &source[..offset];
+    let line = before.matches('\n').count() + 1;
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    (line, before[line_start..].chars().count() + 1)
+}
+
+/// Where `span` is underlined: from its start to its end, or to the end of
+/// its first line if it spans several, and at least one column wide.
+fn marker<'a>(source: &str, span: Span, label: Option<&'a str>, primary: bool) -> Marker<'a> {
+    let start = floor_char_boundary(source, span.start);
+    let end = floor_char_boundary(source, span.end.max(start));
+    let line_start = source[..start].rfind('\n').map_or(0, |i| i + 1);
+    let line_end = source[start..]
+        .find('\n')
+        .map_or(source.len(), |i| start + i);
+    let end = end.min(line_end);
+    let column = display_width(&source[line_start..start]);
+    Marker {
+        line: source[..start].matches('\n').count() + 1,
+        start: column,
+        width: display_width(&source[line_start..end])
+            .saturating_sub(column)
+            .max(1),
+        label,
+        primary,
+    }
+}
+
+/// The columns `text` takes up once its tabs are expanded.
+fn display_width(text: &str) -> usize {
+    expand_tabs(text).chars().count()
+}
+
+fn expand_tabs(text: &str) -> String {
+    text.replace('\t', &" ".repeat(TAB_WIDTH))
+}
+
+/// The largest character boundary of `source` at or before `offset`.
+fn floor_char_boundary(source: &str, offset: usize) -> usize {
+    let mut offset = offset.min(source.len());
+    while !source.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    offset
+}
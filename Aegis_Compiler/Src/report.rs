@@ -0,0 +1,548 @@
+//! Machine-readable reports of a project's diagnostics, for CI and other
+//! tools: a JSON report in Aegis's own format, and a SARIF 2.1.0 log that
+//! code-scanning services can show on pull requests.
+//!
+//! The JSON format is versioned by `JSON_VERSION`. Fields are only ever
+//! added to it; renaming or removing one bumps the version.
+//!
+//! Lines and columns start at 1 and columns count characters. The end of a
+//! range is exclusive, and `offset`s are byte offsets into the file.
+
+use crate::error::{Diagnostic, Fix, Severity};
+use crate::error_codes;
+use crate::lint::Linter;
+use crate::project::{Module, Project};
+use crate::render::line_and_column;
+use crate::token::Span;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// The version of the JSON report's format.
+pub const JSON_VERSION: u32 = 1;
+
+/// The version of SARIF the SARIF report conforms to.
+pub const SARIF_VERSION: &str = "2.1.0";
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// The base of the URIs of a SARIF log's files, which are relative to the
+/// project root.
+const PROJECT_ROOT: &str = "PROJECTROOT";
+
+/// How diagnostics are reported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Format {
+    /// Rendered with source snippets, for people.
+    #[default]
+    Human,
+    Json,
+    Sarif,
+}
+
+impl Format {
+    /// Parses `human`, `json` or `sarif`.
+    pub fn parse(name: &str) -> Option<Format> {
+        match name {
+            "human" => Some(Format::Human),
+            "json" => Some(Format::Json),
+            "sarif" => Some(Format::Sarif),
+            _ => None,
+        }
+    }
+}
+
+/// Reports every diagnostic of `project` as JSON.
+pub fn json(project: &Project) -> String {
+    let mut summary = Summary::default();
+    let diagnostics = each_diagnostic(project)
+        .map(|(module, diagnostic)| {
+            summary.count(&diagnostic);
+            JsonDiagnostic::new(module, diagnostic)
+        })
+        .collect();
+    let report = JsonReport {
+        version: JSON_VERSION,
+        diagnostics,
+        summary,
+    };
+    serde_json::to_string_pretty(&report).expect("reports always serialize")
+}
+
+/// Reports every diagnostic of `project` as a SARIF log with a single run.
+/// The files of the log are relative to the project root, whose absolute
+/// URI is given as `PROJECTROOT` so tools can find them.
+pub fn sarif(project: &Project) -> String {
+    let linter = Linter::new(project.lint_config.clone());
+    let mut rules: Vec<SarifRule> = Vec::new();
+    let mut results = Vec::new();
+    for (module, diagnostic) in each_diagnostic(project) {
+        let rule_id = diagnostic.code.or(diagnostic.lint);
+        let rule_index = rule_id.map(|id| {
+            rules
+                .iter()
+                .position(|rule| rule.id == id)
+                .unwrap_or_else(|| {
+                    let description = error_codes::title(id)
+                        .or_else(|| linter.lint(id).map(|lint| lint.description()))
+                        .unwrap_or_default();
+                    rules.push(SarifRule {
+                        id,
+                        short_description: SarifMessage::new(description),
+                    });
+                    rules.len() - 1
+                })
+        });
+        results.push(SarifResult::new(module, diagnostic, rule_id, rule_index));
+    }
+
+    let log = SarifLog {
+        schema: SARIF_SCHEMA,
+        version: SARIF_VERSION,
+        runs: vec![SarifRun {
+            tool: SarifTool {
+                driver: SarifDriver {
+                    name: "aegis",
+                    version: env!("CARGO_PKG_VERSION"),
+                    information_uri: env!("CARGO_PKG_REPOSITORY"),
+                    rules,
+                },
+            },
+            original_uri_base_ids: [(
+                PROJECT_ROOT,
+                SarifArtifactLocation {
+                    uri: directory_uri(&project.root),
+                    uri_base_id: None,
+                },
+            )]
+            .into_iter()
+            .collect(),
+            column_kind: "unicodeCodePoints",
+            results,
+        }],
+    };
+    serde_json::to_string_pretty(&log).expect("reports always serialize")
+}
+
+/// Every diagnostic of the project with the module it is in.
+fn each_diagnostic(project: &Project) -> impl Iterator<Item = (&Module, Diagnostic)> {
+    project.modules.iter().flat_map(|module| {
+        module
+            .diagnostics()
+            .into_iter()
+            .map(move |diagnostic| (module, diagnostic))
+    })
+}
+
+fn path_of(module: &Module) -> String {
+    // Forward slashes on every platform, as URIs and CI tools expect.
+    module.path.to_string_lossy().replace('\\', "/")
+}
+
+/// The `file://` URI of a directory, ending with a `/` so paths can be
+/// resolved against it.
+fn directory_uri(directory: &Path) -> String {
+    let absolute = directory
+        .canonicalize()
+        .unwrap_or_else(|_| directory.to_path_buf());
+    let path = absolute.to_string_lossy().replace('\\', "/");
+    let mut uri = String::from("file://");
+    if !path.starts_with('/') {
+        uri.push('/');
+    }
+    for byte in path.trim_end_matches('/').bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' | b':' => {
+                uri.push(byte as char)
+            }
+            _ => uri.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    uri.push('/');
+    uri
+}
+
+// --- JSON ---
+
+#[derive(Serialize)]
+struct JsonReport {
+    version: u32,
+    diagnostics: Vec<JsonDiagnostic>,
+    summary: Summary,
+}
+
+#[derive(Serialize, Default)]
+struct Summary {
+    errors: usize,
+    warnings: usize,
+}
+
+impl Summary {
+    fn count(&mut self, diagnostic: &Diagnostic) {
+        if diagnostic.is_error() {
+            self.errors += 1;
+        } else {
+            self.warnings += 1;
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct JsonDiagnostic {
+    /// The error code, e.g., `AEG0102`, if the diagnostic is an error of the compiler.
+    code: Option<&'static str>,
+    /// The name of the lint that found it, if a lint did.
+    lint: Option<&'static str>,
+    severity: String,
+    message: String,
+    file: String,
+    range: JsonRange,
+    related: Vec<JsonRelated>,
+    notes: Vec<String>,
+    suggestions: Vec<JsonSuggestion>,
+    fixes: Vec<JsonFix>,
+}
+
+impl JsonDiagnostic {
+    fn new(module: &Module, diagnostic: Diagnostic) -> Self {
+        let file = path_of(module);
+        Self {
+            code: diagnostic.code,
+            lint: diagnostic.lint,
+            severity: diagnostic.severity.to_string(),
+            message: diagnostic.message,
+            range: JsonRange::new(&module.source, diagnostic.span),
+            related: diagnostic
+                .related
+                .into_iter()
+                .map(|related| JsonRelated {
+                    message: related.message,
+                    file: file.clone(),
+                    range: JsonRange::new(&module.source, related.span),
+                })
+                .collect(),
+            file,
+            notes: diagnostic.notes,
+            suggestions: diagnostic
+                .suggestions
+                .into_iter()
+                .map(|message| JsonSuggestion { message })
+                .collect(),
+            fixes: diagnostic
+                .fixes
+                .iter()
+                .map(|fix| JsonFix::new(&module.source, fix))
+                .collect(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct JsonRelated {
+    message: String,
+    file: String,
+    range: JsonRange,
+}
+
+#[derive(Serialize)]
+struct JsonSuggestion {
+    message: String,
+}
+
+#[derive(Serialize)]
+struct JsonFix {
+    message: String,
+    /// `machine-applicable`, or `has-placeholders` if the edits contain
+    /// placeholders to fill in.
+    applicability: String,
+    edits: Vec<JsonEdit>,
+}
+
+impl JsonFix {
+    fn new(source: &str, fix: &Fix) -> Self {
+        Self {
+            message: fix.message.clone(),
+            applicability: fix.applicability.to_string(),
+            edits: fix
+                .edits
+                .iter()
+                .map(|edit| JsonEdit {
+                    range: JsonRange::new(source, edit.span),
+                    replacement: edit.replacement.clone(),
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct JsonEdit {
+    range: JsonRange,
+    replacement: String,
+}
+
+#[derive(Serialize)]
+struct JsonRange {
+    start: JsonPosition,
+    end: JsonPosition,
+}
+
+impl JsonRange {
+    fn new(source: &str, span: Span) -> Self {
+        Self {
+            start: JsonPosition::new(source, span.start),
+            end: JsonPosition::new(source, span.end.max(span.start)),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct JsonPosition {
+    line: usize,
+    column: usize,
+    offset: usize,
+}
+
+impl JsonPosition {
+    fn new(source: &str, offset: usize) -> Self {
+        let (line, column) = line_and_column(source, offset);
+        Self {
+            line,
+            column,
+            offset: offset.min(source.len()),
+        }
+    }
+}
+
+// --- SARIF ---
+
+#[derive(Serialize)]
+struct SarifLog {
+    #[serde(rename = "$schema")]
+    schema: &'static str,
+    version: &'static str,
+    runs: Vec<SarifRun>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifRun {
+    tool: SarifTool,
+    #[serde(rename = "originalUriBaseIds")]
+    original_uri_base_ids: BTreeMap<&'static str, SarifArtifactLocation>,
+    column_kind: &'static str,
+    results: Vec<SarifResult>,
+}
+
+#[derive(Serialize)]
+struct SarifTool {
+    driver: SarifDriver,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifDriver {
+    name: &'static str,
+    version: &'static str,
+    information_uri: &'static str,
+    rules: Vec<SarifRule>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifRule {
+    id: &'static str,
+    short_description: SarifMessage,
+}
+
+#[derive(Serialize)]
+struct SarifMessage {
+    text: String,
+}
+
+impl SarifMessage {
+    fn new(text: impl Into<String>) -> Self {
+        Self { text: text.into() }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifResult {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rule_id: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rule_index: Option<usize>,
+    level: &'static str,
+    message: SarifMessage,
+    locations: Vec<SarifLocation>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    related_locations: Vec<SarifLocation>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    fixes: Vec<SarifFix>,
+    /// Notes and suggestions, which SARIF has no place for.
+    #[serde(skip_serializing_if = "SarifProperties::is_empty")]
+    properties: SarifProperties,
+}
+
+impl SarifResult {
+    fn new(
+        module: &Module,
+        diagnostic: Diagnostic,
+        rule_id: Option<&'static str>,
+        rule_index: Option<usize>,
+    ) -> Self {
+        let level = match diagnostic.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Info | Severity::Hint => "note",
+        };
+        Self {
+            rule_id,
+            rule_index,
+            level,
+            message: SarifMessage::new(diagnostic.message),
+            locations: vec![SarifLocation::new(module, diagnostic.span, None, None)],
+            related_locations: diagnostic
+                .related
+                .into_iter()
+                .enumerate()
+                .map(|(id, related)| {
+                    SarifLocation::new(module, related.span, Some(id), Some(related.message))
+                })
+                .collect(),
+            fixes: diagnostic
+                .fixes
+                .iter()
+                .map(|fix| SarifFix {
+                    description: SarifMessage::new(fix.message.clone()),
+                    artifact_changes: vec![SarifArtifactChange {
+                        artifact_location: SarifArtifactLocation {
+                            uri: path_of(module),
+                            uri_base_id: Some(PROJECT_ROOT),
+                        },
+                        replacements: fix
+                            .edits
+                            .iter()
+                            .map(|edit| SarifReplacement {
+                                deleted_region: SarifRegion::new(&module.source, edit.span),
+                                inserted_content: Some(edit.replacement.clone())
+                                    .filter(|text| !text.is_empty())
+                                    .map(|text| SarifArtifactContent { text }),
+                            })
+                            .collect(),
+                    }],
+                })
+                .collect(),
+            properties: SarifProperties {
+                notes: diagnostic.notes,
+                suggestions: diagnostic.suggestions,
+            },
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct SarifProperties {
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    notes: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    suggestions: Vec<String>,
+}
+
+impl SarifProperties {
+    fn is_empty(&self) -> bool {
+        self.notes.is_empty() && self.suggestions.is_empty()
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifLocation {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<usize>,
+    physical_location: SarifPhysicalLocation,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message: Option<SarifMessage>,
+}
+
+impl SarifLocation {
+    fn new(module: &Module, span: Span, id: Option<usize>, message: Option<String>) -> Self {
+        Self {
+            id,
+            physical_location: SarifPhysicalLocation {
+                artifact_location: SarifArtifactLocation {
+                    uri: path_of(module),
+                    uri_base_id: Some(PROJECT_ROOT),
+                },
+                region: SarifRegion::new(&module.source, span),
+            },
+            message: message.map(SarifMessage::new),
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifPhysicalLocation {
+    artifact_location: SarifArtifactLocation,
+    region: SarifRegion,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifArtifactLocation {
+    uri: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    uri_base_id: Option<&'static str>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifRegion {
+    start_line: usize,
+    start_column: usize,
+    end_line: usize,
+    end_column: usize,
+}
+
+impl SarifRegion {
+    fn new(source: &str, span: Span) -> Self {
+        let (start_line, start_column) = line_and_column(source, span.start);
+        let (end_line, end_column) = line_and_column(source, span.end.max(span.start));
+        Self {
+            start_line,
+            start_column,
+            end_line,
+            end_column,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifFix {
+    description: SarifMessage,
+    artifact_changes: Vec<SarifArtifactChange>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifArtifactChange {
+    artifact_location: SarifArtifactLocation,
+    replacements: Vec<SarifReplacement>,
+}
+
+/// Replaces a region with new text; an empty region inserts.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifReplacement {
+    deleted_region: SarifRegion,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    inserted_content: Option<SarifArtifactContent>,
+}
+
+#[derive(Serialize)]
+struct SarifArtifactContent {
+    text: String,
+}
@@ -4,7 +4,7 @@
 
 /// Represents a byte-range in the source code string.
 /// It's crucial for providing accurate, user-friendly error messages.
-#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Default)]
 pub struct Span {
     /// The starting byte index of the token in the source string.
     pub start: usize,
@@ -46,6 +46,8 @@ pub enum Token {
     Asterisk(Span),
     /// The division operator, `/`.
     Slash(Span),
+    /// The remainder operator, `%`.
+    Percent(Span),
     /// The less than operator, `<`.
     LessThan(Span),
     /// The greater than operator, `>`.
@@ -54,6 +56,14 @@ pub enum Token {
     Dot(Span),
     /// The fat arrow for `when` cases, `=>`.
     FatArrow(Span),
+    /// The return type arrow of a function signature, `->`.
+    Arrow(Span),
+    /// The optional type marker, `?`, as in `User?`.
+    Question(Span),
+    /// The safe-navigation operator, `?.`.
+    QuestionDot(Span),
+    /// The default-value (elvis) operator, `?:`.
+    Elvis(Span),
 
     // --- Delimiters ---
     Comma(Span),    // ,
@@ -104,6 +114,16 @@ pub enum Token {
     Nothing(Span),
     /// The `enum` keyword for defining sum types.
     Enum(Span),
+    /// The `interface` keyword for declaring methods that contracts implement.
+    Interface(Span),
+    /// The `use` keyword for importing declarations from another module.
+    Use(Span),
+    /// The `public` keyword for making a declaration importable by other modules.
+    Public(Span),
+    /// The logical AND operator, `and`.
+    And(Span),
+    /// The logical OR operator, `or`.
+    Or(Span),
 }
 
 /// Helper methods for the `Token` enum.
@@ -124,10 +144,15 @@ impl Token {
             | Token::Bang(s)
             | Token::Asterisk(s)
             | Token::Slash(s)
+            | Token::Percent(s)
             | Token::LessThan(s)
             | Token::GreaterThan(s)
             | Token::Dot(s)
             | Token::FatArrow(s)
+            | Token::Arrow(s)
+            | Token::Question(s)
+            | Token::QuestionDot(s)
+            | Token::Elvis(s)
             | Token::Comma(s)
             | Token::Colon(s)
             | Token::LParen(s)
@@ -154,7 +179,12 @@ impl Token {
             | Token::Async(s)
             | Token::Await(s)
             | Token::Nothing(s)
-            | Token::Enum(s) => *s,
+            | Token::Enum(s)
+            | Token::Interface(s)
+            | Token::Use(s)
+            | Token::Public(s)
+            | Token::And(s)
+            | Token::Or(s) => *s,
         }
     }
 
@@ -0,0 +1,202 @@
+//! Tests for `async` functions, `await` and `Future<T>` typing
+
+use aegis_compiler::ast::*;
+use aegis_compiler::error::SemanticErrorType;
+use aegis_compiler::guardian_types::Type;
+
+mod common;
+use common::{check_definitions, error_types, ident, let_stmt, number, span, string};
+
+fn call(name: &str, arguments: Vec<Expression>) -> Expression {
+    Expression::Call(Box::new(CallExpression {
+        function: ident(name),
+        arguments,
+        span: span(),
+    }))
+}
+
+fn await_expr(expression: Expression) -> Expression {
+    Expression::Await(Box::new(AwaitExpression {
+        expression,
+        span: span(),
+    }))
+}
+
+fn expr_stmt(expression: Expression) -> Statement {
+    Statement::Expression(ExpressionStatement {
+        expression,
+        span: span(),
+    })
+}
+
+fn block(statements: Vec<Statement>) -> BlockStatement {
+    BlockStatement {
+        statements,
+        span: span(),
+    }
+}
+
+/// `async let's fetch_username(id: number) -> string:` from the async demo.
+fn fetch_username(is_async: bool, body: Vec<Statement>) -> Definition {
+    Definition::Function(FunctionDefinition {
+        name: "fetch_username".to_string(),
+        is_public: false,
+        is_async,
+        generic_params: vec![],
+        parameters: vec![Parameter {
+            name: "id".to_string(),
+            type_annotation: TypeIdentifier::Simple {
+                name: "number".to_string(),
+                span: span(),
+            },
+            span: span(),
+        }],
+        return_type: Some(TypeIdentifier::Simple {
+            name: "string".to_string(),
+            span: span(),
+        }),
+        body: block(body),
+        span: span(),
+    })
+}
+
+fn app_with_handler(handler: Vec<Statement>) -> Definition {
+    Definition::App(AppDefinition {
+        name: "AsyncApp".to_string(),
+        body: AppBody {
+            statements: vec![],
+            show_block: Some(ShowBlock {
+                root_node: UiNode::Element(UiElement {
+                    name: "button".to_string(),
+                    properties: vec![
+                        UiProperty::Positional(string("Fetch User")),
+                        UiProperty::EventBinding("when_clicked".to_string(), block(handler)),
+                    ],
+                    children: vec![],
+                    span: span(),
+                }),
+                span: span(),
+            }),
+        },
+        span: span(),
+    })
+}
+
+#[test]
+fn test_calling_async_function_produces_future() {
+    let mut guardian = check_definitions(vec![fetch_username(true, vec![])]);
+    assert!(guardian.errors.is_empty(), "Got: {:?}", guardian.errors);
+
+    let ty = guardian.infer_expression_type(&call("fetch_username", vec![number("1")]));
+    assert_eq!(ty, Type::Future(Box::new(Type::String)));
+}
+
+#[test]
+fn test_calling_sync_function_produces_its_return_type() {
+    let mut guardian = check_definitions(vec![fetch_username(false, vec![])]);
+    let ty = guardian.infer_expression_type(&call("fetch_username", vec![number("1")]));
+    assert_eq!(ty, Type::String);
+}
+
+#[test]
+fn test_await_inside_async_function_is_allowed() {
+    let guardian = check_definitions(vec![fetch_username(
+        true,
+        vec![expr_stmt(await_expr(call("sleep", vec![number("1000")])))],
+    )]);
+    assert!(guardian.errors.is_empty(), "Got: {:?}", guardian.errors);
+}
+
+#[test]
+fn test_await_inside_sync_function_is_reported() {
+    let guardian = check_definitions(vec![fetch_username(
+        false,
+        vec![expr_stmt(await_expr(call("sleep", vec![number("1000")])))],
+    )]);
+    assert_eq!(
+        error_types(&guardian),
+        vec![SemanticErrorType::AwaitOutsideAsync]
+    );
+    assert!(!guardian.errors[0].suggestions.is_empty());
+}
+
+#[test]
+fn test_await_at_top_level_is_reported() {
+    let guardian = check_definitions(vec![
+        fetch_username(true, vec![]),
+        Definition::Statement(let_stmt(
+            "user",
+            false,
+            await_expr(call("fetch_username", vec![number("1")])),
+        )),
+    ]);
+    assert_eq!(
+        error_types(&guardian),
+        vec![SemanticErrorType::AwaitOutsideAsync]
+    );
+}
+
+#[test]
+fn test_await_unwraps_future() {
+    let mut guardian = check_definitions(vec![fetch_username(true, vec![])]);
+    // Awaiting is only allowed in async code, but the result type is still inferred.
+    let ty = guardian.infer_expression_type(&await_expr(call("fetch_username", vec![number("1")])));
+    assert_eq!(ty, Type::String);
+}
+
+#[test]
+fn test_awaiting_non_future_is_reported() {
+    let guardian = check_definitions(vec![fetch_username(
+        true,
+        vec![expr_stmt(await_expr(number("1")))],
+    )]);
+    assert_eq!(
+        error_types(&guardian),
+        vec![SemanticErrorType::TypeMismatch]
+    );
+}
+
+#[test]
+fn test_async_function_body_sees_parameters() {
+    let guardian = check_definitions(vec![fetch_username(
+        true,
+        vec![expr_stmt(await_expr(call("sleep", vec![ident("id")])))],
+    )]);
+    assert!(guardian.errors.is_empty(), "Got: {:?}", guardian.errors);
+}
+
+#[test]
+fn test_event_handler_with_await_is_inferred_async() {
+    let guardian = check_definitions(vec![
+        fetch_username(true, vec![]),
+        app_with_handler(vec![let_stmt(
+            "user",
+            false,
+            await_expr(call("fetch_username", vec![number("1")])),
+        )]),
+    ]);
+    assert!(guardian.errors.is_empty(), "Got: {:?}", guardian.errors);
+    assert_eq!(guardian.async_event_handlers.len(), 1);
+}
+
+#[test]
+fn test_event_handler_without_await_stays_sync() {
+    let guardian = check_definitions(vec![app_with_handler(vec![let_stmt(
+        "status",
+        false,
+        string("Loading..."),
+    )])]);
+    assert!(guardian.errors.is_empty(), "Got: {:?}", guardian.errors);
+    assert!(guardian.async_event_handlers.is_empty());
+}
+
+#[test]
+fn test_wrong_argument_count_is_reported() {
+    let mut guardian = check_definitions(vec![fetch_username(true, vec![])]);
+    let ty = guardian.infer_expression_type(&call("fetch_username", vec![]));
+    assert_eq!(ty, Type::Error);
+    assert_eq!(
+        error_types(&guardian),
+        vec![SemanticErrorType::ArityMismatch]
+    );
+}
@@ -0,0 +1,147 @@
+//! Tests for type inference and checking of list and map literals
+
+use aegis_compiler::error::SemanticErrorType;
+use aegis_compiler::guardian_types::Type;
+use aegis_compiler::{ast::*, Architect, Scribe};
+
+mod common;
+use common::{check, type_of};
+
+const TASK: &str = r#"contract Task:
+    id: number
+    title: string
+    is_done: boolean
+"#;
+
+#[test]
+fn test_parse_list_literal_and_generic_annotation() {
+    let mut architect = Architect::new(Scribe::new("let's track ids: List<number> = [1, 2, 3]"));
+    let program = architect.parse_program();
+    assert!(architect.errors.is_empty(), "Got: {:?}", architect.errors);
+
+    let Definition::Statement(Statement::Let(let_stmt)) = &program.definitions[0] else {
+        panic!("Expected a let statement");
+    };
+    assert!(matches!(
+        &let_stmt.type_annotation,
+        Some(TypeIdentifier::Generic { name, args, .. }) if name == "List" && args.len() == 1
+    ));
+    let Expression::Literal(Literal::List(list), _) = &let_stmt.value else {
+        panic!("Expected a list literal");
+    };
+    assert_eq!(list.elements.len(), 3);
+}
+
+#[test]
+fn test_list_literal_infers_element_type() {
+    let mut guardian = check("let's ids = [1, 2, 3]");
+    assert!(guardian.errors.is_empty(), "Got: {:?}", guardian.errors);
+    assert_eq!(
+        type_of(&mut guardian, "ids"),
+        Type::List(Box::new(Type::Number))
+    );
+}
+
+#[test]
+fn test_map_literal_infers_key_and_value_types() {
+    let mut guardian = check(r#"let's scores = {"ada": 10, "alan": 12}"#);
+    assert!(guardian.errors.is_empty(), "Got: {:?}", guardian.errors);
+    assert_eq!(
+        type_of(&mut guardian, "scores"),
+        Type::Map(Box::new(Type::String), Box::new(Type::Number))
+    );
+}
+
+#[test]
+fn test_heterogeneous_list_reports_offending_element() {
+    let input = r#"let's mixed = [1, 2, "three"]"#;
+    let guardian = check(input);
+    assert_eq!(guardian.errors.len(), 1, "Got: {:?}", guardian.errors);
+    let error = &guardian.errors[0];
+    assert_eq!(error.error_type, SemanticErrorType::TypeMismatch);
+    assert!(
+        error.message.contains("List elements"),
+        "Got: {}",
+        error.message
+    );
+    assert_eq!(error.span.start, input.find("\"three\"").unwrap());
+}
+
+#[test]
+fn test_heterogeneous_map_values_are_reported() {
+    let guardian = check(r#"let's config = {"retries": 3, "name": "sync"}"#);
+    assert_eq!(guardian.errors.len(), 1, "Got: {:?}", guardian.errors);
+    assert!(guardian.errors[0].message.contains("Map values"));
+}
+
+#[test]
+fn test_annotated_list_checks_elements_as_contract_initializers() {
+    let input = format!(
+        "{}{}",
+        TASK,
+        r#"let's track tasks: List<Task> = [
+    {id: 1, title: "Design the Aegis language", is_done: true},
+    {id: 2, title: "Build the Aegis compiler", is_done: false}
+]"#
+    );
+    let mut guardian = check(&input);
+    assert!(guardian.errors.is_empty(), "Got: {:?}", guardian.errors);
+    assert_eq!(
+        type_of(&mut guardian, "tasks"),
+        Type::List(Box::new(Type::Custom("Task".to_string())))
+    );
+}
+
+#[test]
+fn test_invalid_element_of_annotated_list_is_reported_at_element() {
+    let input = format!(
+        "{}{}",
+        TASK,
+        r#"let's tasks: List<Task> = [
+    {id: 1, title: "Design", is_done: true},
+    {id: 2, title: "Build"}
+]"#
+    );
+    let guardian = check(&input);
+    assert_eq!(guardian.errors.len(), 1, "Got: {:?}", guardian.errors);
+    assert_eq!(
+        guardian.errors[0].error_type,
+        SemanticErrorType::MissingField
+    );
+    assert_eq!(guardian.errors[0].span.start, input.find("{id: 2").unwrap());
+}
+
+#[test]
+fn test_empty_list_fits_annotation() {
+    let input = format!("{}let's track tasks: List<Task> = []", TASK);
+    let guardian = check(&input);
+    assert!(guardian.errors.is_empty(), "Got: {:?}", guardian.errors);
+}
+
+#[test]
+fn test_list_literal_against_wrong_annotation_is_reported() {
+    let guardian = check("let's names: List<string> = [1]");
+    assert_eq!(guardian.errors.len(), 1, "Got: {:?}", guardian.errors);
+    assert_eq!(
+        guardian.errors[0].error_type,
+        SemanticErrorType::TypeMismatch
+    );
+}
+
+#[test]
+fn test_contract_field_holding_list_of_contracts() {
+    let input = format!(
+        "{}{}",
+        TASK,
+        r#"contract Project:
+    name: string
+    tasks: List<Task>
+let's project: Project = {
+    name: "Aegis",
+    tasks: [{id: 1, title: "Ship", is_done: "no"}]
+}"#
+    );
+    let guardian = check(&input);
+    assert_eq!(guardian.errors.len(), 1, "Got: {:?}", guardian.errors);
+    assert!(guardian.errors[0].message.contains("is_done"));
+}
@@ -0,0 +1,108 @@
+//! Helpers shared by the integration tests: parsing and checking sources,
+//! and building the ASTs of constructs the Architect doesn't parse yet
+
+// Each test file uses only some of the helpers.
+#![allow(dead_code)]
+
+use aegis_compiler::ast::*;
+use aegis_compiler::error::SemanticErrorType;
+use aegis_compiler::guardian_types::Type;
+use aegis_compiler::pipeline::Target;
+use aegis_compiler::{compile, CompileOptions, CompileResult};
+use aegis_compiler::{Architect, Guardian, Scribe, Span};
+
+/// Parses `input`, which must have no parse errors.
+pub fn parse(input: &str) -> Program {
+    let mut architect = Architect::new(Scribe::new(input));
+    let program = architect.parse_program();
+    assert!(
+        architect.errors.is_empty(),
+        "Expected no parse errors, but got: {:?}",
+        architect.errors
+    );
+    program
+}
+
+/// Parses and checks `input`, returning the Guardian for inspection.
+pub fn check(input: &str) -> Guardian {
+    let mut guardian = Guardian::new();
+    guardian.check_program(&parse(input));
+    guardian
+}
+
+/// Parses and checks `input`, which must have no errors.
+pub fn check_ok(input: &str) -> Guardian {
+    let guardian = check(input);
+    assert!(guardian.errors.is_empty(), "Got: {:?}", guardian.errors);
+    guardian
+}
+
+/// Checks a program made of `definitions`, e.g., ones built by hand.
+pub fn check_definitions(definitions: Vec<Definition>) -> Guardian {
+    let mut guardian = Guardian::new();
+    guardian.check_program(&Program {
+        imports: vec![],
+        definitions,
+        span: span(),
+    });
+    guardian
+}
+
+/// Compiles a project of `sources`, which must have no errors, for `target`.
+pub fn compile_ok(sources: &[(&str, &str)], target: Target) -> CompileResult {
+    let options = CompileOptions {
+        target,
+        ..CompileOptions::default()
+    };
+    let result = compile(sources.iter().copied(), &options).unwrap();
+    assert!(!result.has_errors(), "Got: {:?}", result.diagnostics);
+    result
+}
+
+/// The type the Guardian inferred for the binding `name`.
+pub fn type_of(guardian: &mut Guardian, name: &str) -> Type {
+    guardian.infer_expression_type(&ident(name))
+}
+
+pub fn error_types(guardian: &Guardian) -> Vec<SemanticErrorType> {
+    guardian
+        .errors
+        .iter()
+        .map(|e| e.error_type.clone())
+        .collect()
+}
+
+pub fn span() -> Span {
+    Default::default()
+}
+
+pub fn ident(name: &str) -> Expression {
+    Expression::Identifier(name.to_string(), span())
+}
+
+pub fn number(n: &str) -> Expression {
+    Expression::Literal(Literal::Number(n.to_string()), span())
+}
+
+pub fn string(s: &str) -> Expression {
+    Expression::Literal(Literal::String(s.to_string()), span())
+}
+
+pub fn let_stmt(name: &str, is_tracked: bool, value: Expression) -> Statement {
+    Statement::Let(LetStatement {
+        name: name.to_string(),
+        name_span: span(),
+        is_tracked,
+        type_annotation: None,
+        value,
+        span: span(),
+    })
+}
+
+pub fn when(value: Expression, cases: Vec<WhenCase>) -> Expression {
+    Expression::When(Box::new(WhenExpression {
+        value,
+        cases,
+        span: span(),
+    }))
+}
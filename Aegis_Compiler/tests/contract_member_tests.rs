@@ -0,0 +1,143 @@
+//! Tests for computed properties and default field values on contracts
+
+use aegis_compiler::error::SemanticErrorType;
+use aegis_compiler::guardian_types::Type;
+use aegis_compiler::{ast::*, Architect, Scribe};
+
+mod common;
+use common::{check, check_ok, parse, type_of};
+
+const EMPLOYEE: &str = r#"contract Employee:
+    name: string
+    salary: number
+    is_active: boolean = true
+    let's bonus: number = self.salary / 10
+    let's raise(amount: number) -> number:
+        return self.salary + amount + self.bonus
+"#;
+
+#[test]
+fn test_parse_computed_properties_and_defaults() {
+    let program = parse(EMPLOYEE);
+    let Definition::Contract(contract) = &program.definitions[0] else {
+        panic!("Expected a contract definition");
+    };
+    assert_eq!(contract.fields.len(), 3);
+    assert!(contract.fields[1].default_value.is_none());
+    assert!(matches!(
+        contract.fields[2].default_value,
+        Some(Expression::Literal(Literal::Boolean(true), _))
+    ));
+    assert_eq!(contract.properties.len(), 1);
+    assert_eq!(contract.properties[0].name, "bonus");
+    assert_eq!(contract.methods.len(), 1);
+}
+
+#[test]
+fn test_computed_properties_need_a_type() {
+    let scribe =
+        Scribe::new("contract Employee:\n    salary: number\n    let's bonus = self.salary / 10");
+    let mut architect = Architect::new(scribe);
+    architect.parse_program();
+    assert_eq!(architect.errors.len(), 1);
+    assert!(
+        architect.errors[0].message.contains("needs a type"),
+        "Got: {}",
+        architect.errors[0].message
+    );
+}
+
+#[test]
+fn test_properties_and_methods_are_read_on_values() {
+    let mut guardian = check_ok(&format!(
+        "{}let's ada: Employee = {{name: \"Ada\", salary: 100}}\nlet's extra = ada.bonus\nlet's raised = ada.raise(5)\nlet's active = ada.is_active",
+        EMPLOYEE
+    ));
+    assert_eq!(type_of(&mut guardian, "extra"), Type::Number);
+    assert_eq!(type_of(&mut guardian, "raised"), Type::Number);
+    assert_eq!(type_of(&mut guardian, "active"), Type::Boolean);
+}
+
+#[test]
+fn test_fields_without_a_default_are_still_required() {
+    let guardian = check(&format!(
+        "{}let's ada: Employee = {{name: \"Ada\"}}",
+        EMPLOYEE
+    ));
+    assert_eq!(guardian.errors.len(), 1);
+    assert_eq!(
+        guardian.errors[0].error_type,
+        SemanticErrorType::MissingField
+    );
+}
+
+#[test]
+fn test_default_values_are_checked_against_the_field_type() {
+    let guardian = check("contract Task:\n    title: string = 42");
+    assert_eq!(guardian.errors.len(), 1);
+    assert!(
+        guardian.errors[0]
+            .message
+            .contains("expected string because of field 'title' of contract 'Task', found number"),
+        "Got: {}",
+        guardian.errors[0].message
+    );
+}
+
+#[test]
+fn test_property_values_are_checked_against_the_declared_type() {
+    let guardian =
+        check("contract Task:\n    title: string\n    let's is_long: boolean = self.title");
+    assert_eq!(guardian.errors.len(), 1);
+    assert!(
+        guardian.errors[0]
+            .message
+            .contains("because of the declared type of property 'is_long'"),
+        "Got: {}",
+        guardian.errors[0].message
+    );
+}
+
+#[test]
+fn test_computed_properties_are_read_only() {
+    let guardian = check(&format!(
+        "{}let's reset(employee: Employee):\n    employee.bonus = 0",
+        EMPLOYEE
+    ));
+    assert_eq!(guardian.errors.len(), 1);
+    assert_eq!(
+        guardian.errors[0].error_type,
+        SemanticErrorType::ImmutableAssignment
+    );
+
+    let guardian = check(&format!(
+        "{}let's ada: Employee = {{name: \"Ada\", salary: 100, bonus: 0}}",
+        EMPLOYEE
+    ));
+    assert_eq!(guardian.errors.len(), 1);
+    assert_eq!(
+        guardian.errors[0].error_type,
+        SemanticErrorType::ImmutableAssignment
+    );
+}
+
+#[test]
+fn test_members_share_one_namespace() {
+    let guardian = check("contract Task:\n    title: string\n    let's title: string = \"x\"");
+    assert_eq!(guardian.errors.len(), 1);
+    assert_eq!(
+        guardian.errors[0].error_type,
+        SemanticErrorType::DuplicateDeclaration
+    );
+}
+
+#[test]
+fn test_generic_contract_properties_are_instantiated() {
+    let mut guardian = check_ok(
+        "contract Box<T>:\n    items: List<T>\n    let's first: T? = self.items.get(0)\nlet's numbers: Box = {items: [1, 2]}\nlet's head = numbers.first",
+    );
+    assert_eq!(
+        type_of(&mut guardian, "head"),
+        Type::Optional(Box::new(Type::Number))
+    );
+}
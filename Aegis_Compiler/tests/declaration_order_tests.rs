@@ -0,0 +1,121 @@
+//! Tests for order-independent top-level declarations and recursive contracts
+
+use aegis_compiler::error::SemanticErrorType;
+use aegis_compiler::Guardian;
+
+mod common;
+use common::check;
+
+fn assert_no_errors(guardian: &Guardian) {
+    assert!(
+        guardian.errors.is_empty(),
+        "Expected no semantic errors, but got: {:?}",
+        guardian.errors
+    );
+}
+
+fn errors_of(guardian: &Guardian, error_type: SemanticErrorType) -> Vec<&str> {
+    guardian
+        .errors
+        .iter()
+        .filter(|e| e.error_type == error_type)
+        .map(|e| e.message.as_str())
+        .collect()
+}
+
+#[test]
+fn test_statement_can_use_enum_declared_later() {
+    let guardian = check(
+        r#"let's initial = Status
+enum Status:
+    Active
+    Inactive"#,
+    );
+    assert_no_errors(&guardian);
+}
+
+#[test]
+fn test_contract_field_can_use_contract_declared_later() {
+    let guardian = check(
+        r#"contract Order:
+    customer: Customer
+contract Customer:
+    name: string"#,
+    );
+    assert_no_errors(&guardian);
+}
+
+#[test]
+fn test_enum_payload_can_use_contract_declared_later() {
+    let guardian = check(
+        r#"enum LoadState:
+    Loading
+    Loaded(Profile)
+contract Profile:
+    name: string"#,
+    );
+    assert_no_errors(&guardian);
+}
+
+#[test]
+fn test_mutual_references_through_list_are_allowed() {
+    let guardian = check(
+        r#"contract Author:
+    books: List<Book>
+contract Book:
+    author: Author"#,
+    );
+    assert_no_errors(&guardian);
+}
+
+#[test]
+fn test_self_reference_through_optional_is_allowed() {
+    let guardian = check(
+        r#"contract Node:
+    value: number
+    next: Optional<Node>"#,
+    );
+    assert_no_errors(&guardian);
+}
+
+#[test]
+fn test_direct_self_reference_is_reported() {
+    let guardian = check(
+        r#"contract Node:
+    value: number
+    next: Node"#,
+    );
+    let errors = errors_of(&guardian, SemanticErrorType::RecursiveContract);
+    assert_eq!(errors.len(), 1, "Got: {:?}", guardian.errors);
+    assert!(
+        errors[0].contains("Node.next -> Node"),
+        "Got: {}",
+        errors[0]
+    );
+    assert!(errors[0].contains("Optional<...>"), "Got: {}", errors[0]);
+}
+
+#[test]
+fn test_mutual_cycle_is_reported_once_with_path() {
+    let guardian = check(
+        r#"contract A:
+    b: B
+contract B:
+    a: A"#,
+    );
+    let errors = errors_of(&guardian, SemanticErrorType::RecursiveContract);
+    assert_eq!(errors.len(), 1, "Got: {:?}", guardian.errors);
+    assert!(errors[0].contains("A.b -> B.a -> A"), "Got: {}", errors[0]);
+}
+
+#[test]
+fn test_duplicate_contract_is_reported_once() {
+    let guardian = check(
+        r#"contract User:
+    name: string
+contract User:
+    email: string"#,
+    );
+    let errors = errors_of(&guardian, SemanticErrorType::DuplicateDeclaration);
+    assert_eq!(errors.len(), 1, "Got: {:?}", guardian.errors);
+}
@@ -3,6 +3,30 @@
 use aegis_compiler::guardian_types::Type;
 use aegis_compiler::{ast::*, Guardian};
 
+/// Builds a non-generic type identifier, e.g., `Data`.
+fn simple_type(name: &str) -> TypeIdentifier {
+    TypeIdentifier::Simple {
+        name: name.to_string(),
+        span: Default::default(),
+    }
+}
+
+/// Declares the `Data` and `Error` contracts used as enum payloads below.
+fn define_payload_contracts(guardian: &mut Guardian) {
+    for name in ["Data", "Error"] {
+        guardian.check_contract_definition(&ContractDefinition {
+            name: name.to_string(),
+            is_public: false,
+            generic_params: vec![],
+            interfaces: vec![],
+            fields: vec![],
+            methods: vec![],
+            properties: vec![],
+            span: Default::default(),
+        });
+    }
+}
+
 #[test]
 fn test_enum_pattern_matching_integration() {
     // This tests the basic integration without actually parsing enum syntax
@@ -13,6 +37,7 @@ fn test_enum_pattern_matching_integration() {
     // Create an enum definition manually to test semantic analysis
     let enum_def = EnumDefinition {
         name: "LoadState".to_string(),
+        is_public: false,
         variants: vec![
             EnumVariant {
                 name: "Success".to_string(),
@@ -46,6 +71,7 @@ fn test_enum_pattern_ast_structure() {
     let enum_pattern = WhenPattern::EnumVariant {
         enum_name: "MyEnum".to_string(),
         variant_name: "MyVariant".to_string(),
+        arguments: vec![],
         span: Default::default(),
     };
 
@@ -70,6 +96,7 @@ fn test_enum_instantiation_via_member_access() {
     // Define an enum
     let enum_def = EnumDefinition {
         name: "Status".to_string(),
+        is_public: false,
         variants: vec![
             EnumVariant {
                 name: "Active".to_string(),
@@ -119,10 +146,12 @@ fn test_enum_instantiation_via_member_access() {
 #[test]
 fn test_enum_with_associated_data() {
     let mut guardian = Guardian::new();
+    define_payload_contracts(&mut guardian);
 
     // Define an enum with associated data
     let enum_def = EnumDefinition {
         name: "LoadState".to_string(),
+        is_public: false,
         variants: vec![
             EnumVariant {
                 name: "Loading".to_string(),
@@ -131,12 +160,12 @@ fn test_enum_with_associated_data() {
             },
             EnumVariant {
                 name: "Success".to_string(),
-                types: vec!["Data".to_string()],
+                types: vec![simple_type("Data")],
                 span: Default::default(),
             },
             EnumVariant {
                 name: "Failure".to_string(),
-                types: vec!["Error".to_string()],
+                types: vec![simple_type("Error")],
                 span: Default::default(),
             },
         ],
@@ -190,6 +219,7 @@ fn test_when_expression_with_enum_variants() {
     // Define an enum
     let enum_def = EnumDefinition {
         name: "Color".to_string(),
+        is_public: false,
         variants: vec![
             EnumVariant {
                 name: "Red".to_string(),
@@ -230,8 +260,10 @@ fn test_when_expression_with_enum_variants() {
                 pattern: WhenPattern::EnumVariant {
                     enum_name: "Color".to_string(),
                     variant_name: "Red".to_string(),
+                    arguments: vec![],
                     span: Default::default(),
                 },
+                guard: None,
                 body: Expression::Literal(
                     Literal::String("It's red!".to_string()),
                     Default::default(),
@@ -242,8 +274,10 @@ fn test_when_expression_with_enum_variants() {
                 pattern: WhenPattern::EnumVariant {
                     enum_name: "Color".to_string(),
                     variant_name: "Green".to_string(),
+                    arguments: vec![],
                     span: Default::default(),
                 },
+                guard: None,
                 body: Expression::Literal(
                     Literal::String("It's green!".to_string()),
                     Default::default(),
@@ -264,11 +298,13 @@ fn test_when_expression_with_enum_variants() {
 #[test]
 fn test_loadstate_enum_comprehensive_example() {
     let mut guardian = Guardian::new();
+    define_payload_contracts(&mut guardian);
 
     // Define the LoadState enum as described in the problem statement:
     // enum LoadState { Loading, Success(Data), Failure(Error) }
     let enum_def = EnumDefinition {
         name: "LoadState".to_string(),
+        is_public: false,
         variants: vec![
             EnumVariant {
                 name: "Loading".to_string(),
@@ -277,12 +313,12 @@ fn test_loadstate_enum_comprehensive_example() {
             },
             EnumVariant {
                 name: "Success".to_string(),
-                types: vec!["Data".to_string()],
+                types: vec![simple_type("Data")],
                 span: Default::default(),
             },
             EnumVariant {
                 name: "Failure".to_string(),
-                types: vec!["Error".to_string()],
+                types: vec![simple_type("Error")],
                 span: Default::default(),
             },
         ],
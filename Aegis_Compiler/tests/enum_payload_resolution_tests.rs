@@ -0,0 +1,241 @@
+//! Tests for resolving the payload types of enum variants
+
+use aegis_compiler::error::SemanticErrorType;
+use aegis_compiler::guardian_types::Type;
+use aegis_compiler::{ast::*, Guardian};
+
+mod common;
+use common::{check, number};
+
+fn variant(enum_name: &str, variant: &str, arguments: Vec<Expression>) -> Expression {
+    let access = Expression::MemberAccess(Box::new(MemberAccessExpression {
+        object: Expression::Identifier(enum_name.to_string(), Default::default()),
+        property: variant.to_string(),
+        span: Default::default(),
+    }));
+    if arguments.is_empty() {
+        return access;
+    }
+    Expression::Call(Box::new(CallExpression {
+        function: access,
+        arguments,
+        span: Default::default(),
+    }))
+}
+
+fn enum_variants(guardian: &mut Guardian, name: &str) -> Vec<(String, Vec<Type>)> {
+    let ty = guardian.infer_expression_type(&Expression::Identifier(
+        name.to_string(),
+        Default::default(),
+    ));
+    let Type::Enum { variants, .. } = ty else {
+        panic!("Expected '{}' to be an enum, got {:?}", name, ty);
+    };
+    let mut variants: Vec<_> = variants.into_iter().collect();
+    variants.sort_by(|a, b| a.0.cmp(&b.0));
+    variants
+}
+
+#[test]
+fn test_primitive_payloads_resolve_to_builtin_types() {
+    let mut guardian = check(
+        r#"enum Response:
+    Ok(number)
+    Failed(string, boolean)"#,
+    );
+    assert!(guardian.errors.is_empty(), "{:?}", guardian.errors);
+
+    assert_eq!(
+        enum_variants(&mut guardian, "Response"),
+        vec![
+            ("Failed".to_string(), vec![Type::String, Type::Boolean]),
+            ("Ok".to_string(), vec![Type::Number]),
+        ]
+    );
+
+    // A number argument now matches a `number` payload.
+    let ok = variant("Response", "Ok", vec![number("200")]);
+    assert!(matches!(
+        guardian.infer_expression_type(&ok),
+        Type::Enum { .. }
+    ));
+}
+
+#[test]
+fn test_contract_enum_and_generic_payloads() {
+    let mut guardian = check(
+        r#"contract User:
+    id: number
+
+enum Status:
+    Active
+    Banned
+
+enum Event:
+    Joined(User)
+    Changed(Status)
+    Batch(List<User>)"#,
+    );
+    assert!(guardian.errors.is_empty(), "{:?}", guardian.errors);
+
+    let variants = enum_variants(&mut guardian, "Event");
+    assert_eq!(
+        variants[0],
+        (
+            "Batch".to_string(),
+            vec![Type::List(Box::new(Type::Custom("User".to_string())))]
+        )
+    );
+    assert!(matches!(&variants[1].1[0], Type::Enum { name, .. } if name == "Status"));
+    assert_eq!(variants[2].1, vec![Type::Custom("User".to_string())]);
+}
+
+#[test]
+fn test_misspelled_payload_type_is_reported() {
+    let guardian = check(
+        r#"contract User:
+    id: number
+
+enum LoadState:
+    Loading
+    Success(Usr)"#,
+    );
+
+    assert_eq!(guardian.errors.len(), 1, "{:?}", guardian.errors);
+    assert_eq!(
+        guardian.errors[0].error_type,
+        SemanticErrorType::UndefinedType
+    );
+    assert!(guardian.errors[0].message.contains("Usr"));
+}
+
+#[test]
+fn test_misspelled_contract_field_type_is_reported() {
+    let guardian = check(
+        r#"contract User:
+    id: numbr"#,
+    );
+
+    assert_eq!(guardian.errors.len(), 1, "{:?}", guardian.errors);
+    assert_eq!(
+        guardian.errors[0].error_type,
+        SemanticErrorType::UndefinedType
+    );
+}
+
+#[test]
+fn test_recursive_enum() {
+    let mut guardian = check(
+        r#"enum Tree:
+    Leaf
+    Node(Tree, number, Tree)"#,
+    );
+    assert!(guardian.errors.is_empty(), "{:?}", guardian.errors);
+
+    let tree = variant(
+        "Tree",
+        "Node",
+        vec![
+            variant("Tree", "Leaf", vec![]),
+            number("1"),
+            variant("Tree", "Leaf", vec![]),
+        ],
+    );
+    assert!(matches!(
+        guardian.infer_expression_type(&tree),
+        Type::Enum { name, .. } if name == "Tree"
+    ));
+}
+
+#[test]
+fn test_nested_patterns_on_recursive_enum() {
+    let mut guardian = check(
+        r#"enum Tree:
+    Leaf
+    Node(Tree, number, Tree)"#,
+    );
+
+    let leaf_pattern = |arguments| WhenPattern::EnumVariant {
+        enum_name: "Tree".to_string(),
+        variant_name: "Leaf".to_string(),
+        arguments,
+        span: Default::default(),
+    };
+    let node_pattern = |arguments| WhenPattern::EnumVariant {
+        enum_name: "Tree".to_string(),
+        variant_name: "Node".to_string(),
+        arguments,
+        span: Default::default(),
+    };
+    let arm = |pattern| WhenCase {
+        pattern,
+        guard: None,
+        body: number("1"),
+        span: Default::default(),
+    };
+    let expr = Expression::When(Box::new(WhenExpression {
+        value: variant("Tree", "Leaf", vec![]),
+        cases: vec![
+            arm(leaf_pattern(vec![])),
+            arm(node_pattern(vec![
+                leaf_pattern(vec![]),
+                WhenPattern::Literal(Literal::Number("0".to_string())),
+                WhenPattern::Wildcard,
+            ])),
+        ],
+        span: Default::default(),
+    }));
+    guardian.infer_expression_type(&expr);
+
+    assert_eq!(guardian.errors.len(), 1, "{:?}", guardian.errors);
+    assert_eq!(
+        guardian.errors[0].suggestions,
+        vec!["Tree::Node(Tree::Node, _, _)"]
+    );
+}
+
+#[test]
+fn test_nested_literal_pattern_is_type_checked() {
+    let mut guardian = check(
+        r#"enum Response:
+    Ok(number)
+    Failed"#,
+    );
+
+    let expr = Expression::When(Box::new(WhenExpression {
+        value: variant("Response", "Failed", vec![]),
+        cases: vec![WhenCase {
+            pattern: WhenPattern::EnumVariant {
+                enum_name: "Response".to_string(),
+                variant_name: "Ok".to_string(),
+                arguments: vec![WhenPattern::Literal(Literal::String("200".to_string()))],
+                span: Default::default(),
+            },
+            guard: None,
+            body: number("1"),
+            span: Default::default(),
+        }],
+        span: Default::default(),
+    }));
+    guardian.infer_expression_type(&expr);
+
+    assert!(guardian
+        .errors
+        .iter()
+        .any(|e| e.error_type == SemanticErrorType::TypeMismatch));
+}
+
+#[test]
+fn test_duplicate_variant_is_reported() {
+    let guardian = check(
+        r#"enum Status:
+    Active
+    Active"#,
+    );
+
+    assert_eq!(guardian.errors.len(), 1, "{:?}", guardian.errors);
+    assert_eq!(
+        guardian.errors[0].error_type,
+        SemanticErrorType::DuplicateDeclaration
+    );
+}
@@ -0,0 +1,125 @@
+//! Tests for error codes and their explanations
+
+use aegis_compiler::error::{
+    CodeGenError, Diagnostic, ParseErrorKind, RuntimeError, SemanticErrorType,
+};
+use aegis_compiler::{error_codes, Architect, Guardian, Scribe};
+use std::collections::HashSet;
+
+const PARSE_ERROR_KINDS: &[ParseErrorKind] = &[
+    ParseErrorKind::UnexpectedToken,
+    ParseErrorKind::ExpectedName,
+    ParseErrorKind::ExpectedPunctuation,
+    ParseErrorKind::UnclosedDelimiter,
+    ParseErrorKind::ExpectedType,
+    ParseErrorKind::InvalidModifier,
+    ParseErrorKind::MisplacedDeclaration,
+];
+
+const SEMANTIC_ERROR_TYPES: &[SemanticErrorType] = &[
+    SemanticErrorType::UndefinedSymbol,
+    SemanticErrorType::TypeMismatch,
+    SemanticErrorType::ArityMismatch,
+    SemanticErrorType::ReturnTypeMismatch,
+    SemanticErrorType::AwaitOutsideAsync,
+    SemanticErrorType::DuplicateDeclaration,
+    SemanticErrorType::InvalidMemberAccess,
+    SemanticErrorType::InvalidOperation,
+    SemanticErrorType::MissingField,
+    SemanticErrorType::InvalidUIComponent,
+    SemanticErrorType::UnknownField,
+    SemanticErrorType::InvalidFieldKey,
+    SemanticErrorType::UndefinedType,
+    SemanticErrorType::NonExhaustiveMatch,
+    SemanticErrorType::UnreachablePattern,
+    SemanticErrorType::ImmutableAssignment,
+    SemanticErrorType::RecursiveContract,
+    SemanticErrorType::MissingImplementation,
+    SemanticErrorType::ModuleNotFound,
+    SemanticErrorType::ImportCycle,
+    SemanticErrorType::PrivateImport,
+    SemanticErrorType::Other,
+];
+
+/// The codes whose examples need syntax the Architect doesn't parse yet,
+/// more than one module, or report a more specific code.
+const UNCHECKED_EXAMPLES: &[&str] = &[
+    "AEG0104", "AEG0110", "AEG0114", "AEG0115", "AEG0119", "AEG0120", "AEG0121",
+];
+
+/// Parses and checks `source`, returning the codes of its errors.
+fn error_codes_of(source: &str) -> Vec<&'static str> {
+    let mut architect = Architect::new(Scribe::new(source));
+    let program = architect.parse_program();
+    if !architect.errors.is_empty() {
+        return architect.errors.iter().map(|e| e.code()).collect();
+    }
+    let mut guardian = Guardian::new();
+    guardian.check_program(&program);
+    guardian.errors.iter().map(|e| e.code()).collect()
+}
+
+#[test]
+fn test_every_error_kind_has_a_unique_explained_code() {
+    let codes: Vec<&str> = PARSE_ERROR_KINDS
+        .iter()
+        .map(ParseErrorKind::code)
+        .chain(SEMANTIC_ERROR_TYPES.iter().map(SemanticErrorType::code))
+        .chain([CodeGenError::CODE, RuntimeError::CODE])
+        .collect();
+    let unique: HashSet<&str> = codes.iter().copied().collect();
+    assert_eq!(unique.len(), codes.len());
+    for code in &codes {
+        assert!(
+            error_codes::explain(code).is_some(),
+            "No explanation for {}",
+            code
+        );
+    }
+    assert_eq!(error_codes::codes().count(), codes.len());
+}
+
+#[test]
+fn test_codes_can_be_looked_up_loosely() {
+    assert_eq!(error_codes::normalize("102"), "AEG0102");
+    assert_eq!(error_codes::normalize(" aeg0001 "), "AEG0001");
+    assert_eq!(error_codes::title("aeg0102"), Some("Type mismatch"));
+    assert_eq!(error_codes::explain("116"), error_codes::explain("AEG0116"));
+    assert!(error_codes::explain("AEG9999").is_none());
+}
+
+#[test]
+fn test_explanation_examples_show_their_error() {
+    for code in error_codes::codes().filter(|code| !UNCHECKED_EXAMPLES.contains(code)) {
+        let text = error_codes::explain(code).unwrap();
+        let examples: Vec<&str> = text
+            .split("```aegis\n")
+            .skip(1)
+            .map(|block| block.split("```").next().unwrap())
+            .collect();
+        if examples.is_empty() {
+            continue;
+        }
+        assert_eq!(
+            examples.len(),
+            2,
+            "{} needs an erroneous and a fixed example",
+            code
+        );
+        let erroneous = error_codes_of(examples[0]);
+        assert!(erroneous.contains(&code), "{}: got {:?}", code, erroneous);
+        let fixed = error_codes_of(examples[1]);
+        assert!(fixed.is_empty(), "{}: the fix still has {:?}", code, fixed);
+    }
+}
+
+#[test]
+fn test_diagnostics_carry_the_code_of_their_error() {
+    let mut architect = Architect::new(Scribe::new("let's numbers = [1, 2"));
+    architect.parse_program();
+    let error = architect.errors.remove(0);
+    assert!(error.to_string().contains("[AEG0004]"), "Got: {}", error);
+    let diagnostic = Diagnostic::from(error);
+    assert_eq!(diagnostic.code, Some("AEG0004"));
+    assert!(diagnostic.to_string().starts_with("error[AEG0004]"));
+}
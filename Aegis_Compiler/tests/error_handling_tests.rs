@@ -1,5 +1,6 @@
 //! Error handling tests for the Aegis compiler
 
+use aegis_compiler::error::SemanticErrorType;
 use aegis_compiler::{Architect, Guardian, Scribe, Token};
 
 #[test]
@@ -57,9 +58,41 @@ fn test_semantic_error_handling() {
     let mut guardian = Guardian::new();
     guardian.check_program(&program);
 
-    // The stub implementation doesn't actually analyze, so it won't generate errors
-    // In a real implementation, this would test semantic error detection
-    assert!(guardian.errors.is_empty()); // Stub behavior
+    assert_eq!(guardian.errors.len(), 1);
+    assert_eq!(
+        guardian.errors[0].error_type,
+        SemanticErrorType::UndefinedSymbol
+    );
+    assert_eq!(
+        guardian.errors[0].message,
+        "Undefined variable 'undefined_variable'"
+    );
+}
+
+#[test]
+fn test_undefined_names_suggest_the_closest_declared_name() {
+    let input = "let's count = 1\nlet's get() -> number:\n    return cuont\n\nlet's name = \"a\"\nlog(name)\nprint(nope2(1))";
+    let scribe = Scribe::new(input);
+    let mut architect = Architect::new(scribe);
+    let program = architect.parse_program();
+
+    let mut guardian = Guardian::new();
+    guardian.check_program(&program);
+
+    let messages: Vec<&str> = guardian.errors.iter().map(|e| e.message.as_str()).collect();
+    assert_eq!(
+        messages,
+        [
+            "Undefined variable 'cuont'",
+            "Undefined function 'log'",
+            "Undefined function 'nope2'"
+        ]
+    );
+    assert!(guardian
+        .errors
+        .iter()
+        .all(|e| e.error_type == SemanticErrorType::UndefinedSymbol));
+    assert_eq!(guardian.errors[0].suggestions, ["did you mean 'count'?"]);
 }
 
 #[test]
@@ -0,0 +1,223 @@
+//! Tests for the fixes attached to diagnostics and for applying them
+//!
+//! Each machine-applicable fix is applied and the fixed source is checked
+//! again, which must leave no errors.
+
+use aegis_compiler::error::{Applicability, Diagnostic, Fix, SemanticErrorType, TextEdit};
+use aegis_compiler::fix::{apply_edits, apply_fixes, fix_project};
+use aegis_compiler::token::Span;
+use aegis_compiler::{ast::*, Architect, Guardian, Scribe};
+use std::fs;
+
+mod common;
+use common::parse;
+
+const TASK: &str = "contract Task:\n    title: string\n    done: boolean\n\n";
+
+/// Parses and checks `source`, with the Guardian knowing the source so it
+/// can attach fixes.
+fn check(source: &str) -> Guardian {
+    let mut guardian = Guardian::new().with_source(source);
+    guardian.check_program(&parse(source));
+    guardian
+}
+
+/// Checks `source`, which must have exactly one error of `error_type`,
+/// and returns that error's only fix.
+fn only_fix(source: &str, error_type: SemanticErrorType) -> Fix {
+    let guardian = check(source);
+    assert_eq!(guardian.errors.len(), 1, "Got: {:?}", guardian.errors);
+    assert_eq!(guardian.errors[0].error_type, error_type);
+    assert_eq!(guardian.errors[0].fixes.len(), 1);
+    guardian.errors[0].fixes[0].clone()
+}
+
+/// Applies `fix` to `source` and checks that the result has no errors.
+fn assert_fixes(source: &str, fix: &Fix, expected: &str) {
+    let (fixed, applied) = apply_fixes(source, &[fix]);
+    assert_eq!(applied.len(), 1);
+    assert_eq!(fixed, expected);
+    let guardian = check(&fixed);
+    assert!(guardian.errors.is_empty(), "Got: {:?}", guardian.errors);
+}
+
+#[test]
+fn test_unknown_field_is_renamed_to_the_closest_field() {
+    let source = format!("{}let's task: Task = {{titl: \"Ship\", done: false}}", TASK);
+    // 'title' is also missing, but renaming 'titl' fixes both errors, so
+    // only the rename is reported.
+    let fix = only_fix(&source, SemanticErrorType::UnknownField);
+    assert_eq!(fix.message, "rename 'titl' to 'title'");
+    assert_eq!(fix.applicability, Applicability::MachineApplicable);
+    assert_fixes(
+        &source,
+        &fix,
+        &format!(
+            "{}let's task: Task = {{title: \"Ship\", done: false}}",
+            TASK
+        ),
+    );
+}
+
+#[test]
+fn test_unknown_field_with_swapped_letters_is_renamed() {
+    let source = format!("{}let's task: Task = {{ titel: \"x\", done: true }}", TASK);
+    let fix = only_fix(&source, SemanticErrorType::UnknownField);
+    assert_eq!(fix.message, "rename 'titel' to 'title'");
+    assert_fixes(
+        &source,
+        &fix,
+        &format!("{}let's task: Task = {{ title: \"x\", done: true }}", TASK),
+    );
+}
+
+#[test]
+fn test_missing_field_is_added_after_the_last_entry() {
+    let source = format!("{}let's task: Task = {{ done: true }}", TASK);
+    let fix = only_fix(&source, SemanticErrorType::MissingField);
+    assert_fixes(
+        &source,
+        &fix,
+        &format!("{}let's task: Task = {{ done: true, title: \"\" }}", TASK),
+    );
+}
+
+#[test]
+fn test_missing_field_is_added_with_a_default_value() {
+    let source = format!("{}let's task: Task = {{title: \"Ship\"}}", TASK);
+    let fix = only_fix(&source, SemanticErrorType::MissingField);
+    assert_eq!(fix.message, "add the field 'done'");
+    assert_fixes(
+        &source,
+        &fix,
+        &format!(
+            "{}let's task: Task = {{title: \"Ship\", done: false}}",
+            TASK
+        ),
+    );
+}
+
+#[test]
+fn test_missing_field_of_an_empty_literal_is_added_without_a_comma() {
+    let source = "contract Counter:\n    count: number\n\nlet's counter: Counter = {}";
+    let fix = only_fix(source, SemanticErrorType::MissingField);
+    assert_fixes(
+        source,
+        &fix,
+        "contract Counter:\n    count: number\n\nlet's counter: Counter = {count: 0}",
+    );
+}
+
+#[test]
+fn test_mutated_binding_is_declared_tracked() {
+    let source = "let's reset():\n    let's count = 0\n    count = count + 1";
+    let fix = only_fix(source, SemanticErrorType::ImmutableAssignment);
+    assert_eq!(fix.message, "declare 'count' with `let's track`");
+    assert_fixes(
+        source,
+        &fix,
+        "let's reset():\n    let's track count = 0\n    count = count + 1",
+    );
+}
+
+#[test]
+fn test_missing_when_arms_are_a_fix_with_placeholders() {
+    // `when` isn't parsed yet, so the AST is built by hand with spans into
+    // the source it would have come from.
+    let source = "when flag:\n    is true => 1";
+    let arm_start = source.find("is true").unwrap();
+    let expr = Expression::When(Box::new(WhenExpression {
+        value: Expression::Literal(Literal::Boolean(true), Span { start: 5, end: 9 }),
+        cases: vec![WhenCase {
+            pattern: WhenPattern::Literal(Literal::Boolean(true)),
+            guard: None,
+            body: Expression::Literal(Literal::Number("1".to_string()), Default::default()),
+            span: Span {
+                start: arm_start,
+                end: source.len(),
+            },
+        }],
+        span: Span {
+            start: 0,
+            end: source.len(),
+        },
+    }));
+    let mut guardian = Guardian::new().with_source(source);
+    guardian.infer_expression_type(&expr);
+
+    assert_eq!(guardian.errors.len(), 1, "Got: {:?}", guardian.errors);
+    let fix = &guardian.errors[0].fixes[0];
+    assert_eq!(fix.applicability, Applicability::HasPlaceholders);
+    assert_eq!(
+        apply_edits(source, &fix.edits),
+        "when flag:\n    is true => 1\n    is false => ..."
+    );
+    // Fixes with placeholders are never applied on their own.
+    let (fixed, applied) = apply_fixes(source, &[fix]);
+    assert!(applied.is_empty());
+    assert_eq!(fixed, source);
+}
+
+#[test]
+fn test_fixes_are_only_offered_when_the_source_is_known() {
+    let source = "let's reset():\n    let's count = 0\n    count = 1";
+    let mut architect = Architect::new(Scribe::new(source));
+    let program = architect.parse_program();
+    let mut guardian = Guardian::new();
+    guardian.check_program(&program);
+    assert_eq!(guardian.errors.len(), 1);
+    let diagnostic = Diagnostic::from(guardian.errors[0].clone());
+    assert_eq!(diagnostic.fixes.len(), 1);
+
+    let expr = Expression::When(Box::new(WhenExpression {
+        value: Expression::Literal(Literal::Boolean(true), Default::default()),
+        cases: vec![],
+        span: Default::default(),
+    }));
+    let mut guardian = Guardian::new();
+    guardian.infer_expression_type(&expr);
+    assert!(guardian.errors[0].fixes.is_empty());
+}
+
+#[test]
+fn test_conflicting_fixes_are_skipped() {
+    let first = Fix::new(
+        "add a".to_string(),
+        vec![TextEdit::new(Span { start: 0, end: 3 }, "a".to_string())],
+    );
+    let overlapping = Fix::new(
+        "add b".to_string(),
+        vec![TextEdit::new(Span { start: 2, end: 5 }, "b".to_string())],
+    );
+    let later = Fix::new(
+        "add c".to_string(),
+        vec![TextEdit::insert(6, "c".to_string())],
+    );
+    let (fixed, applied) = apply_fixes("abcdefg", &[&first, &overlapping, &later]);
+    assert_eq!(fixed, "adefcg");
+    let messages: Vec<&str> = applied.iter().map(|fix| fix.message.as_str()).collect();
+    assert_eq!(messages, ["add a", "add c"]);
+}
+
+#[test]
+fn test_fix_project_repeats_until_the_project_is_clean() {
+    let root = std::env::temp_dir().join(format!("aegis-fix-project-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(&root).unwrap();
+    // Both missing fields are inserted at the same place, so the second is
+    // added in the second round.
+    let source = "contract Task:\n    title: string\n    done: boolean\n    count: number\n\nlet's task: Task = {titl: \"Ship\"}\nlet's reset():\n    let's count = 0\n    count = 1";
+    fs::write(root.join("main.ag"), source).unwrap();
+
+    let (applied, project) = fix_project(&root, "main").unwrap();
+    let fixed = fs::read_to_string(root.join("main.ag")).unwrap();
+    fs::remove_dir_all(&root).unwrap();
+
+    assert!(!project.has_errors(), "Got: {:?}", project.diagnostics());
+    let messages: Vec<&str> = applied.iter().map(|fix| fix.message.as_str()).collect();
+    assert_eq!(messages.len(), 4, "Got: {:?}", messages);
+    assert_eq!(messages[0], "rename 'titl' to 'title'");
+    assert_eq!(applied[0].line, 6);
+    assert!(fixed.contains("{title: \"Ship\", "), "Got: {}", fixed);
+    assert!(fixed.contains("let's track count = 0"), "Got: {}", fixed);
+}
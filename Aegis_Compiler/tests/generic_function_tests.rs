@@ -0,0 +1,128 @@
+//! Tests for function definitions, generic type parameters and the inference
+//! of type arguments at call sites
+
+use aegis_compiler::ast::*;
+use aegis_compiler::error::SemanticErrorType;
+use aegis_compiler::guardian_types::Type;
+
+mod common;
+use common::{check, check_ok, parse, type_of};
+
+fn optional(ty: Type) -> Type {
+    Type::Optional(Box::new(ty))
+}
+
+const FIRST: &str = r#"let's first<T>(items: List<T>) -> T?:
+    return items.get(0)
+"#;
+
+#[test]
+fn test_parse_generic_function() {
+    let program = parse(FIRST);
+    assert_eq!(program.definitions.len(), 1);
+    let Definition::Function(func) = &program.definitions[0] else {
+        panic!("Expected a function definition");
+    };
+    assert_eq!(func.name, "first");
+    assert_eq!(func.generic_params.len(), 1);
+    assert_eq!(func.generic_params[0].name, "T");
+    assert!(func.generic_params[0].bound.is_none());
+    assert!(matches!(
+        &func.parameters[0].type_annotation,
+        TypeIdentifier::Generic { name, .. } if name == "List"
+    ));
+    assert!(matches!(
+        &func.return_type,
+        Some(TypeIdentifier::Generic { name, .. }) if name == "Optional"
+    ));
+    assert!(matches!(func.body.statements[..], [Statement::Return(_)]));
+}
+
+#[test]
+fn test_function_body_ends_at_the_next_unindented_line() {
+    let program = parse(
+        "async let's load(id: number) -> string:\n    let's label = \"item\"\n    await sleep(id)\n    return label\nlet's done = true",
+    );
+    assert_eq!(program.definitions.len(), 2);
+    let Definition::Function(func) = &program.definitions[0] else {
+        panic!("Expected a function definition");
+    };
+    assert!(func.is_async);
+    assert_eq!(func.body.statements.len(), 3);
+    assert!(matches!(
+        &program.definitions[1],
+        Definition::Statement(Statement::Let(let_stmt)) if let_stmt.name == "done"
+    ));
+}
+
+#[test]
+fn test_type_arguments_are_inferred_at_each_call() {
+    let mut guardian = check_ok(&format!(
+        "{}let's head = first([1, 2, 3])\nlet's name = first([\"Ada\"])",
+        FIRST
+    ));
+    assert_eq!(type_of(&mut guardian, "head"), optional(Type::Number));
+    assert_eq!(type_of(&mut guardian, "name"), optional(Type::String));
+}
+
+#[test]
+fn test_a_type_parameter_takes_one_type_per_call() {
+    let guardian = check(
+        "let's pair<T>(a: T, b: T) -> List<T>:\n    return [a, b]\nlet's both = pair(1, \"two\")",
+    );
+    assert_eq!(guardian.errors.len(), 1);
+    assert!(
+        guardian.errors[0]
+            .message
+            .contains("expected number because of parameter 1 of 'pair', found string"),
+        "Got: {}",
+        guardian.errors[0].message
+    );
+}
+
+#[test]
+fn test_type_parameters_stand_for_any_type_in_the_body() {
+    let guardian = check("let's pick<T>(value: T) -> number:\n    return value");
+    assert_eq!(guardian.errors.len(), 1);
+    assert!(
+        guardian.errors[0]
+            .message
+            .contains("expected number because of the return type of 'pick', found T"),
+        "Got: {}",
+        guardian.errors[0].message
+    );
+
+    // A type parameter named like a method's own, e.g., the `U` of `map`, stays distinct.
+    let mut guardian = check_ok(
+        "let's copy<U>(items: List<U>) -> List<U>:\n    return items.map(item => item)\nlet's copied = copy([true])",
+    );
+    assert_eq!(
+        type_of(&mut guardian, "copied"),
+        Type::List(Box::new(Type::Boolean))
+    );
+}
+
+#[test]
+fn test_return_values_are_checked_against_the_declared_type() {
+    let guardian = check("let's add(a: number, b: number) -> number:\n    return \"sum\"");
+    assert_eq!(guardian.errors.len(), 1);
+    assert_eq!(
+        guardian.errors[0].error_type,
+        SemanticErrorType::TypeMismatch
+    );
+    assert_eq!(guardian.errors[0].related.len(), 1);
+
+    check_ok(
+        "async let's fetch_data(id: number) -> string:\n    await sleep(1000)\n    return \"data\"",
+    );
+}
+
+#[test]
+fn test_duplicate_type_parameters_are_reported() {
+    let guardian = check("let's same<T, T>(value: T) -> T:\n    return value");
+    assert_eq!(guardian.errors.len(), 1);
+    assert_eq!(
+        guardian.errors[0].error_type,
+        SemanticErrorType::DuplicateDeclaration
+    );
+}
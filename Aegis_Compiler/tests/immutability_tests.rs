@@ -0,0 +1,123 @@
+//! Tests for immutable bindings, parameters and the writes `let's track` allows
+
+use aegis_compiler::error::SemanticErrorType;
+use aegis_compiler::Guardian;
+
+mod common;
+use common::{check, check_ok};
+
+/// Checks `input` and asserts it has a single error of illegal mutation,
+/// whose message contains `message`.
+fn check_immutable(input: &str, message: &str) -> Guardian {
+    let guardian = check(input);
+    assert_eq!(guardian.errors.len(), 1, "Got: {:?}", guardian.errors);
+    assert_eq!(
+        guardian.errors[0].error_type,
+        SemanticErrorType::ImmutableAssignment
+    );
+    assert!(
+        guardian.errors[0].message.contains(message),
+        "Got: {}",
+        guardian.errors[0].message
+    );
+    guardian
+}
+
+const TASK: &str = r#"contract Task:
+    title: string
+    is_done: boolean
+"#;
+
+#[test]
+fn test_reassigning_a_plain_binding_is_reported_with_a_fix() {
+    let guardian = check_immutable(
+        "let's reset():\n    let's count = 0\n    count = 1",
+        "Cannot assign to 'count' because 'count' is not tracked",
+    );
+    assert_eq!(
+        guardian.errors[0].suggestions,
+        ["declare it with `let's track count`"]
+    );
+}
+
+#[test]
+fn test_tracked_bindings_can_be_reassigned() {
+    check_ok("let's reset():\n    let's track count = 0\n    count = count + 1");
+}
+
+#[test]
+fn test_changing_a_field_of_a_plain_binding_is_reported() {
+    check_immutable(
+        &format!(
+            "{}let's finish():\n    let's task: Task = {{title: \"Ship\", is_done: false}}\n    task.is_done = true",
+            TASK
+        ),
+        "Cannot change field 'is_done' of 'task'",
+    );
+}
+
+#[test]
+fn test_fields_of_tracked_bindings_can_be_changed() {
+    check_ok(&format!(
+        "{}let's finish():\n    let's track task: Task = {{title: \"Ship\", is_done: false}}\n    task.is_done = true",
+        TASK
+    ));
+}
+
+#[test]
+fn test_field_writes_are_checked_against_the_field_type() {
+    let guardian = check(&format!(
+        "{}let's finish():\n    let's track task: Task = {{title: \"Ship\", is_done: false}}\n    task.is_done = \"yes\"",
+        TASK
+    ));
+    assert_eq!(guardian.errors.len(), 1, "Got: {:?}", guardian.errors);
+    assert!(
+        guardian.errors[0]
+            .message
+            .contains("because of the type of field 'is_done'"),
+        "Got: {}",
+        guardian.errors[0].message
+    );
+}
+
+#[test]
+fn test_parameters_cannot_be_assigned_or_changed() {
+    check_immutable(
+        "let's double(n: number) -> number:\n    n = n * 2\n    return n",
+        "Cannot assign to 'n' because 'n' is a parameter",
+    );
+    check_immutable(
+        &format!("{}let's finish(task: Task):\n    task.is_done = true", TASK),
+        "Cannot change field 'is_done' of 'task' because 'task' is a parameter",
+    );
+}
+
+#[test]
+fn test_methods_cannot_change_self() {
+    check_immutable(
+        "contract Counter:\n    count: number\n    let's increment():\n        self.count = self.count + 1",
+        "'self' is a parameter",
+    );
+}
+
+#[test]
+fn test_mutating_methods_need_tracked_lists() {
+    let guardian = check_immutable(
+        "let's collect():\n    let's names: List<string> = []\n    names.add(\"Ada\")",
+        "Cannot call 'add' on 'names'",
+    );
+    assert_eq!(
+        guardian.errors[0].suggestions,
+        ["declare it with `let's track names`"]
+    );
+    check_immutable(
+        "let's clear(names: List<string>):\n    names.remove(0)",
+        "Cannot call 'remove' on 'names' because 'names' is a parameter",
+    );
+    check_ok("let's collect():\n    let's track names: List<string> = []\n    names.add(\"Ada\")");
+}
+
+#[test]
+fn test_reading_methods_work_on_plain_bindings() {
+    check_ok("let's count():\n    let's names: List<string> = []\n    let's size = names.length()");
+}
@@ -0,0 +1,161 @@
+//! Tests for interfaces, contract methods, conformance checks and bounded generics
+
+use aegis_compiler::ast::*;
+use aegis_compiler::error::SemanticErrorType;
+use aegis_compiler::guardian_types::Type;
+
+mod common;
+use common::{check, check_ok, parse, type_of};
+
+const DISPLAYABLE: &str = r#"interface Displayable:
+    let's display() -> string
+
+contract Task is Displayable:
+    title: string
+    is_done: boolean
+    let's display() -> string:
+        return self.title
+
+let's describe(item: Displayable) -> string:
+    return item.display()
+
+let's task: Task = {title: "Ship", is_done: false}
+"#;
+
+#[test]
+fn test_parse_interface_and_conforming_contract() {
+    let program = parse(DISPLAYABLE);
+    let Definition::Interface(interface) = &program.definitions[0] else {
+        panic!("Expected an interface definition");
+    };
+    assert_eq!(interface.name, "Displayable");
+    assert_eq!(interface.methods.len(), 1);
+    assert_eq!(interface.methods[0].name, "display");
+
+    let Definition::Contract(contract) = &program.definitions[1] else {
+        panic!("Expected a contract definition");
+    };
+    assert_eq!(contract.interfaces, ["Displayable"]);
+    assert_eq!(contract.fields.len(), 2);
+    assert_eq!(contract.methods.len(), 1);
+    assert_eq!(contract.methods[0].body.statements.len(), 1);
+}
+
+#[test]
+fn test_contract_methods_and_interface_values() {
+    let mut guardian = check_ok(&format!(
+        "{}let's label = task.display()\nlet's shown = describe(task)",
+        DISPLAYABLE
+    ));
+    assert_eq!(type_of(&mut guardian, "label"), Type::String);
+    assert_eq!(type_of(&mut guardian, "shown"), Type::String);
+
+    let guardian = check(&format!("{}let's shown = describe(42)", DISPLAYABLE));
+    assert_eq!(guardian.errors.len(), 1);
+    assert_eq!(
+        guardian.errors[0].error_type,
+        SemanticErrorType::TypeMismatch
+    );
+}
+
+#[test]
+fn test_method_bodies_see_self() {
+    let guardian = check(
+        "contract Task:\n    title: string\n    let's display() -> string:\n        return self.titl",
+    );
+    assert_eq!(guardian.errors.len(), 1);
+    assert_eq!(guardian.errors[0].suggestions, ["did you mean 'title'?"]);
+}
+
+#[test]
+fn test_missing_method_is_reported() {
+    let guardian = check("interface Displayable:\n    let's display() -> string\ncontract Task is Displayable:\n    title: string");
+    assert_eq!(guardian.errors.len(), 1);
+    assert_eq!(
+        guardian.errors[0].error_type,
+        SemanticErrorType::MissingImplementation
+    );
+    assert_eq!(
+        guardian.errors[0].suggestions,
+        ["add a method 'display' of type () -> string"]
+    );
+}
+
+#[test]
+fn test_mistyped_method_is_reported() {
+    let guardian = check(
+        "interface Displayable:\n    let's display() -> string\ncontract Task is Displayable:\n    id: number\n    let's display() -> number:\n        return self.id",
+    );
+    assert_eq!(guardian.errors.len(), 1);
+    assert!(
+        guardian.errors[0]
+            .message
+            .contains("has type () -> number, but interface 'Displayable' requires () -> string"),
+        "Got: {}",
+        guardian.errors[0].message
+    );
+}
+
+#[test]
+fn test_undefined_interface_is_reported() {
+    let guardian = check("contract Task is Printable:\n    title: string");
+    assert_eq!(guardian.errors.len(), 1);
+    assert_eq!(
+        guardian.errors[0].error_type,
+        SemanticErrorType::UndefinedType
+    );
+}
+
+const IS_LESS: &str = r#"let's is_less<T: Comparable>(a: T, b: T) -> boolean:
+    return a < b
+"#;
+
+#[test]
+fn test_bounded_type_parameters() {
+    let program = parse(IS_LESS);
+    let Definition::Function(func) = &program.definitions[0] else {
+        panic!("Expected a function definition");
+    };
+    assert_eq!(func.generic_params[0].bound.as_deref(), Some("Comparable"));
+
+    let mut guardian = check_ok(&format!(
+        "{}let's smaller = is_less(1, 2)\nlet's earlier = is_less(\"a\", \"b\")",
+        IS_LESS
+    ));
+    assert_eq!(type_of(&mut guardian, "smaller"), Type::Boolean);
+
+    let guardian = check(&format!(
+        "{}contract Task:\n    title: string\nlet's task: Task = {{title: \"Ship\"}}\nlet's wrong = is_less(task, task)",
+        IS_LESS
+    ));
+    assert_eq!(guardian.errors.len(), 1);
+    assert_eq!(
+        guardian.errors[0].error_type,
+        SemanticErrorType::MissingImplementation
+    );
+    assert!(
+        guardian.errors[0]
+            .message
+            .contains("Type Task doesn't implement 'Comparable'"),
+        "Got: {}",
+        guardian.errors[0].message
+    );
+}
+
+#[test]
+fn test_unbounded_type_parameters_cannot_be_ordered() {
+    let guardian = check("let's is_less<T>(a: T, b: T) -> boolean:\n    return a < b");
+    assert_eq!(guardian.errors.len(), 1);
+    assert_eq!(
+        guardian.errors[0].error_type,
+        SemanticErrorType::InvalidOperation
+    );
+}
+
+#[test]
+fn test_contracts_implement_comparable_with_compare_to() {
+    check_ok(&format!(
+        "{}contract Version is Comparable:\n    major: number\n    let's compare_to(other: Version) -> number:\n        return self.major - other.major\nlet's old: Version = {{major: 1}}\nlet's new: Version = {{major: 2}}\nlet's older = is_less(old, new)\nlet's before = old < new",
+        IS_LESS
+    ));
+}
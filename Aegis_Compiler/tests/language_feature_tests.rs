@@ -295,6 +295,10 @@ fn test_complete_language_constructs() {
         "async let's fetch():",
         "contract User:",
         "enum Status:",
+    ];
+    // Statements and expressions that can't be at the top level of a file,
+    // and `app`, which isn't supported yet.
+    let misplaced = vec![
         "app MyApp:",
         "if condition:",
         "for item in items:",
@@ -307,16 +311,17 @@ fn test_complete_language_constructs() {
         "nothing",
     ];
 
-    for construct in constructs {
+    for construct in constructs.into_iter().chain(misplaced.iter().copied()) {
         let scribe = Scribe::new(construct);
         let mut architect = Architect::new(scribe);
         let program = architect.parse_program();
         let mut guardian = Guardian::new();
         guardian.check_program(&program);
 
-        // Should not crash on any language construct
-        // Stub implementations should handle all gracefully
-        assert!(architect.errors.is_empty());
+        // Should not crash on any language construct, and should report
+        // the misplaced ones once each
+        let expected_errors = usize::from(misplaced.contains(&construct));
+        assert_eq!(architect.errors.len(), expected_errors, "{}", construct);
         assert!(guardian.errors.is_empty());
     }
 }
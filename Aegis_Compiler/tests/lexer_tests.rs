@@ -67,6 +67,26 @@ fn test_operators() {
     assert!(matches!(tokens[6], Token::Slash(_)));
 }
 
+#[test]
+fn test_remainder_and_logical_operators() {
+    let mut scribe = Scribe::new("a % b and c or d");
+
+    let tokens: Vec<Token> = std::iter::from_fn(|| match scribe.next_token() {
+        Token::Eof(_) => None,
+        token => Some(token),
+    })
+    .collect();
+
+    assert_eq!(tokens.len(), 7);
+    assert!(matches!(tokens[1], Token::Percent(_)));
+    assert!(matches!(tokens[3], Token::And(_)));
+    assert!(matches!(tokens[5], Token::Or(_)));
+    // Only the exact words are keywords.
+    let mut scribe = Scribe::new("android order");
+    assert!(matches!(scribe.next_token(), Token::Identifier(ref s, _) if s == "android"));
+    assert!(matches!(scribe.next_token(), Token::Identifier(ref s, _) if s == "order"));
+}
+
 #[test]
 fn test_string_literals() {
     let input = r#""hello world""#;
@@ -92,16 +112,18 @@ fn test_numbers() {
 
 #[test]
 fn test_identifiers() {
-    let input = "my_var MyClass _private";
+    let input = "my_var MyClass _private item2";
     let mut scribe = Scribe::new(input);
 
     let token1 = scribe.next_token();
     let token2 = scribe.next_token();
     let token3 = scribe.next_token();
+    let token4 = scribe.next_token();
 
     assert!(matches!(token1, Token::Identifier(ref s, _) if s == "my_var"));
     assert!(matches!(token2, Token::Identifier(ref s, _) if s == "MyClass"));
     assert!(matches!(token3, Token::Identifier(ref s, _) if s == "_private"));
+    assert!(matches!(token4, Token::Identifier(ref s, _) if s == "item2"));
 }
 
 #[test]
@@ -0,0 +1,249 @@
+//! Tests for lints, their levels and the annotations that set them
+
+use aegis_compiler::error::{Diagnostic, Severity};
+use aegis_compiler::lint::{Lint, LintConfig, LintContext, LintLevel, Linter};
+use aegis_compiler::{ast::*, Architect, Guardian, Project, Scribe, Span};
+use std::fs;
+
+mod common;
+use common::parse;
+
+/// Parses, checks and lints `input` with `linter`.
+fn lint_with(linter: &Linter, input: &str) -> Vec<Diagnostic> {
+    let program = parse(input);
+    let mut guardian = Guardian::new();
+    guardian.check_program(&program);
+    assert!(guardian.errors.is_empty(), "Got: {:?}", guardian.errors);
+    linter.run(&LintContext::new(&program, &guardian, input))
+}
+
+fn lint(input: &str) -> Vec<Diagnostic> {
+    lint_with(&Linter::default(), input)
+}
+
+fn lint_names(diagnostics: &[Diagnostic]) -> Vec<&str> {
+    diagnostics
+        .iter()
+        .map(|diagnostic| diagnostic.lint.unwrap_or("-"))
+        .collect()
+}
+
+#[test]
+fn test_unused_variables_and_parameters_are_reported() {
+    let diagnostics = lint(
+        "let's area(width: number, height: number, _depth: number) -> number:\n    let's unused = 1\n    return width * width",
+    );
+    assert_eq!(lint_names(&diagnostics), ["unused_variables"; 2]);
+    assert_eq!(diagnostics[0].message, "Parameter 'height' is never used");
+    assert_eq!(diagnostics[0].severity, Severity::Warning);
+    assert_eq!(diagnostics[1].message, "Variable 'unused' is never used");
+    assert_eq!(
+        diagnostics[1].suggestions,
+        ["if this is intentional, name it '_unused'"]
+    );
+}
+
+#[test]
+fn test_assigning_a_variable_does_not_use_it() {
+    let diagnostics = lint("let's reset():\n    let's track count = 0\n    count = 1");
+    assert_eq!(lint_names(&diagnostics), ["unused_variables"]);
+}
+
+#[test]
+fn test_tracked_state_that_is_never_read_is_reported() {
+    let source = "let's track count = 0\nlet's track draft = \"\"\nlet's doubled = count * 2\nlet's clear():\n    draft = \"\"";
+    let diagnostics = lint(source);
+    assert_eq!(lint_names(&diagnostics), ["unused_state"]);
+    assert!(
+        diagnostics[0].message.contains("'draft' is never read"),
+        "Got: {}",
+        diagnostics[0].message
+    );
+    let span = diagnostics[0].span;
+    assert_eq!(&source[span.start..span.end], "draft");
+}
+
+#[test]
+fn test_shadowing_points_at_the_hidden_binding() {
+    let source = "let's track count = 0\nlet's doubled = count * 2\nlet's show_count(count: number) -> number:\n    return count";
+    let diagnostics = lint(source);
+    assert_eq!(lint_names(&diagnostics), ["shadowing"]);
+    assert_eq!(
+        diagnostics[0].message,
+        "'count' shadows tracked state with the same name"
+    );
+    let hidden = diagnostics[0].related[0].span;
+    assert_eq!(&source[hidden.start..hidden.end], "count");
+}
+
+#[test]
+fn test_code_after_return_is_unreachable() {
+    let source = "let's answer() -> number:\n    return 42\n    let's never = 1\n    never";
+    let diagnostics = lint(source);
+    let unreachable: Vec<&Diagnostic> = diagnostics
+        .iter()
+        .filter(|d| d.lint == Some("unreachable_code"))
+        .collect();
+    assert_eq!(unreachable.len(), 1);
+    assert_eq!(
+        unreachable[0].span.start,
+        source.find("let's never").unwrap()
+    );
+    assert_eq!(unreachable[0].span.end, source.len());
+}
+
+#[test]
+fn test_constant_conditions_are_reported() {
+    // `if` has no syntax yet, so the condition is put in place of the return value.
+    let lint_condition = |left: &str, right: &str| {
+        let source = "let's label(_n: number) -> string:\n    return \"many\"";
+        let mut program = Architect::new(Scribe::new(source)).parse_program();
+        let Definition::Function(function) = &mut program.definitions[0] else {
+            panic!("Expected a function definition");
+        };
+        let Statement::Return(return_stmt) = &mut function.body.statements[0] else {
+            panic!("Expected a return statement");
+        };
+        let operand = |value: &str| match value.parse::<f64>() {
+            Ok(_) => Expression::Literal(Literal::Number(value.to_string()), Span::default()),
+            Err(_) => Expression::Identifier(value.to_string(), Span::default()),
+        };
+        return_stmt.value = Expression::If(Box::new(IfExpression {
+            condition: Expression::Infix(Box::new(InfixExpression {
+                left: operand(left),
+                operator: InfixOperator::GreaterThan,
+                right: operand(right),
+                span: Span::default(),
+            })),
+            then_branch: return_stmt.value.clone(),
+            else_branch: Some(Expression::Literal(
+                Literal::String("few".to_string()),
+                Span::default(),
+            )),
+            span: Span::default(),
+        }));
+        let mut guardian = Guardian::new();
+        guardian.check_program(&program);
+        Linter::default().run(&LintContext::new(&program, &guardian, source))
+    };
+
+    let diagnostics = lint_condition("1", "2");
+    assert_eq!(lint_names(&diagnostics), ["constant_conditions"]);
+    assert_eq!(
+        diagnostics[0].message,
+        "This condition is always false, so its branch never runs"
+    );
+    assert!(lint_condition("_n", "2").is_empty());
+}
+
+#[test]
+fn test_allow_annotations_apply_to_the_next_item() {
+    let diagnostics = lint(
+        "#[allow(unused_variables)]\nlet's first(a: number) -> number:\n    return 1\n\nlet's second(b: number) -> number:\n    return 2",
+    );
+    assert_eq!(diagnostics.len(), 1, "Got: {:?}", diagnostics);
+    assert_eq!(diagnostics[0].message, "Parameter 'b' is never used");
+}
+
+#[test]
+fn test_file_annotations_can_deny_a_lint() {
+    let diagnostics = lint(
+        "#![deny(unused_variables)]\nlet's first(a: number) -> number:\n    #[warn(unused_variables)]\n    let's b = 1\n    return 1",
+    );
+    assert_eq!(diagnostics.len(), 2, "Got: {:?}", diagnostics);
+    assert_eq!(diagnostics[0].severity, Severity::Error);
+    assert_eq!(diagnostics[1].severity, Severity::Warning);
+}
+
+#[test]
+fn test_unknown_lints_in_annotations_are_reported() {
+    let diagnostics = lint("#[allow(unused_variable)]\nlet's x = 1");
+    assert_eq!(diagnostics.len(), 1, "Got: {:?}", diagnostics);
+    assert_eq!(diagnostics[0].message, "Unknown lint 'unused_variable'");
+    assert_eq!(
+        diagnostics[0].suggestions,
+        ["did you mean 'unused_variables'?"]
+    );
+}
+
+#[test]
+fn test_lints_can_be_registered() {
+    struct NoAnswers;
+
+    impl Lint for NoAnswers {
+        fn name(&self) -> &'static str {
+            "no_answers"
+        }
+
+        fn description(&self) -> &'static str {
+            "Variables named 'answer'"
+        }
+
+        fn default_level(&self) -> LintLevel {
+            LintLevel::Deny
+        }
+
+        fn check(&self, cx: &LintContext) -> Vec<Diagnostic> {
+            cx.bindings()
+                .iter()
+                .filter(|binding| binding.name == "answer")
+                .map(|binding| Diagnostic::warning("No answers".to_string(), binding.span))
+                .collect()
+        }
+    }
+
+    let mut linter = Linter::default();
+    linter.register(Box::new(NoAnswers));
+    let diagnostics = lint_with(&linter, "let's answer = 42");
+    assert_eq!(lint_names(&diagnostics), ["no_answers"]);
+    assert!(diagnostics[0].is_error());
+}
+
+#[test]
+fn test_projects_set_lint_levels_in_their_config() {
+    let config = LintConfig::parse(
+        "[package]\nname = \"demo\"\n\n[lints]\nunused_variables = \"deny\"\nshadowing = \"allow\"",
+    )
+    .unwrap();
+    assert_eq!(config.level("unused_variables"), Some(LintLevel::Deny));
+    assert_eq!(config.level("shadowing"), Some(LintLevel::Allow));
+    assert!(LintConfig::parse("[lints]\nshadowing = \"loud\"").is_err());
+
+    let root = std::env::temp_dir().join(format!("aegis-lint-config-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(&root).unwrap();
+    fs::write(
+        root.join("aegis.toml"),
+        "[lints]\nunused_variables = \"deny\"",
+    )
+    .unwrap();
+    fs::write(
+        root.join("main.ag"),
+        "let's track count = 0\nlet's reset(value: number):\n    count = 0",
+    )
+    .unwrap();
+    let project = Project::load(&root, "main").unwrap();
+    fs::write(
+        root.join("aegis.toml"),
+        "[lints]\nunused_variable = \"deny\"",
+    )
+    .unwrap();
+    let unknown = Project::load(&root, "main").err().unwrap();
+    fs::remove_dir_all(&root).unwrap();
+
+    assert!(project.has_errors());
+    assert_eq!(
+        project.diagnostics(),
+        [
+            "main.ag:1:13: warning: Tracked state 'count' is never read, so changing it updates nothing",
+            "main.ag:2:13: Parameter 'value' is never used",
+        ]
+    );
+    assert!(
+        unknown
+            .to_string()
+            .contains("did you mean 'unused_variables'?"),
+        "Got: {}",
+        unknown
+    );
+}
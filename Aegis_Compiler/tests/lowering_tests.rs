@@ -0,0 +1,524 @@
+//! Tests for the Lowerer, which translates checked programs into AIL
+//!
+//! `if`, `for` and `when` aren't parsed yet, so their ASTs are built by hand.
+
+use aegis_compiler::ast::*;
+use aegis_compiler::engine::ail::{Instruction, InstructionSequence};
+use aegis_compiler::engine::lowerer::Lowerer;
+use aegis_compiler::{Architect, Guardian, Scribe};
+use Instruction::*;
+
+mod common;
+use common::{ident, number, string};
+
+/// Parses, checks and lowers `input`, which must have no errors.
+fn lower(input: &str) -> Vec<InstructionSequence> {
+    let mut architect = Architect::new(Scribe::new(input));
+    let program = architect.parse_program();
+    assert!(architect.errors.is_empty(), "Got: {:?}", architect.errors);
+    let mut guardian = Guardian::new();
+    guardian.check_program(&program);
+    assert!(guardian.errors.is_empty(), "Got: {:?}", guardian.errors);
+    Lowerer::new().lower_program(&program)
+}
+
+fn sequence<'a>(sequences: &'a [InstructionSequence], name: &str) -> &'a InstructionSequence {
+    sequences
+        .iter()
+        .find(|seq| seq.name == name)
+        .unwrap_or_else(|| panic!("No sequence {} in {:?}", name, sequences))
+}
+
+fn case(pattern: WhenPattern, guard: Option<Expression>, body: Expression) -> WhenCase {
+    WhenCase {
+        pattern,
+        guard,
+        body,
+        span: Default::default(),
+    }
+}
+
+fn variant(name: &str, arguments: Vec<WhenPattern>) -> WhenPattern {
+    WhenPattern::EnumVariant {
+        enum_name: "LoadState".to_string(),
+        variant_name: name.to_string(),
+        arguments,
+        span: Default::default(),
+    }
+}
+
+/// Lowers `expr` as the value of `let's result` at the top of a module.
+fn lower_let(expr: Expression) -> Vec<Instruction> {
+    let program = Program {
+        imports: vec![],
+        definitions: vec![Definition::Statement(Statement::Let(LetStatement {
+            name: "result".to_string(),
+            name_span: Default::default(),
+            is_tracked: false,
+            type_annotation: None,
+            value: expr,
+            span: Default::default(),
+        }))],
+        span: Default::default(),
+    };
+    let mut sequences = Lowerer::new().lower_program(&program);
+    sequences.remove(0).instructions
+}
+
+fn s(text: &str) -> String {
+    text.to_string()
+}
+
+#[test]
+fn test_every_operator_has_an_instruction() {
+    let sequences = lower("let's odd = 7 % 2 != 0\nlet's small = -1 < 2\nlet's big = 3 > 2");
+    assert_eq!(
+        sequences[0].instructions,
+        [
+            PushI64(7),
+            PushI64(2),
+            Modulo,
+            PushI64(0),
+            NotEqual,
+            Store(s("odd")),
+            PushI64(1),
+            Negate,
+            PushI64(2),
+            LessThan,
+            Store(s("small")),
+            PushI64(3),
+            PushI64(2),
+            GreaterThan,
+            Store(s("big")),
+        ]
+    );
+}
+
+#[test]
+fn test_and_and_or_short_circuit() {
+    let sequences = lower("let's a = true\nlet's b = false\nlet's either = a and b or !a");
+    assert_eq!(
+        sequences[0].instructions[4..],
+        [
+            Load(s("a")),
+            Dup,
+            JumpIfFalse(s("L1")),
+            Pop,
+            Load(s("b")),
+            Label(s("L1")),
+            Dup,
+            JumpIfTrue(s("L0")),
+            Pop,
+            Load(s("a")),
+            Not,
+            Label(s("L0")),
+            Store(s("either")),
+        ]
+    );
+}
+
+#[test]
+fn test_functions_bind_their_parameters_and_calls_count_arguments() {
+    let sequences = lower(
+        "let's add(a: number, b: number) -> number:\n    return a + b\nlet's log(text: string):\n    print(text)\nlet's total = add(1, 2)",
+    );
+    let add = sequence(&sequences, "add");
+    assert_eq!(add.params, ["a", "b"]);
+    assert_eq!(
+        add.instructions,
+        [
+            StoreLocal(s("b")),
+            StoreLocal(s("a")),
+            Load(s("a")),
+            Load(s("b")),
+            Add,
+            Return
+        ]
+    );
+    // A function without a `return` returns `nothing`, and the value of an
+    // expression statement is discarded.
+    assert_eq!(
+        sequence(&sequences, "log").instructions,
+        [
+            StoreLocal(s("text")),
+            Load(s("text")),
+            Call(s("print"), 1),
+            Pop,
+            PushNothing,
+            Return,
+        ]
+    );
+    assert_eq!(
+        sequences[0].instructions,
+        [PushI64(1), PushI64(2), Call(s("add"), 2), Store(s("total"))]
+    );
+}
+
+#[test]
+fn test_contracts_are_built_with_their_default_values() {
+    let sequences = lower(
+        "contract Task:\n    title: string\n    done: boolean = false\n    let's label: string = self.title\n    let's renamed(title: string) -> Task:\n        return {title: title, done: self.done}\n\nlet's reset():\n    let's track task: Task = {title: \"Ship\"}\n    task.done = true\nlet's finish(task: Task) -> Task:\n    return {title: task.title}\nlet's tasks: List<Task> = [{title: \"Ship\", done: true}]\nlet's first = finish({title: \"Test\"})\nlet's options = {title: \"Map\"}",
+    );
+    let build = |title: Instruction, done| {
+        vec![
+            title,
+            PushBool(done),
+            MakeContract {
+                name: s("Task"),
+                fields: vec![s("title"), s("done")],
+            },
+        ]
+    };
+    let finish = &sequence(&sequences, "finish").instructions;
+    assert_eq!(
+        finish[..3],
+        [StoreLocal(s("task")), Load(s("task")), GetField(s("title"))]
+    );
+    assert_eq!(
+        finish[3..],
+        [
+            build(GetField(s("title")), false)[1..].to_vec(),
+            vec![Return]
+        ]
+        .concat()
+    );
+
+    let main = &sequences[0].instructions;
+    let mut expected = build(PushString(s("Ship")), true);
+    expected.extend([MakeList(1), Store(s("tasks"))]);
+    expected.extend(build(PushString(s("Test")), false));
+    expected.extend([Call(s("finish"), 1), Store(s("first"))]);
+    // Without an expected contract type, a map literal is a map.
+    expected.extend([
+        PushString(s("title")),
+        PushString(s("Map")),
+        MakeMap(1),
+        Store(s("options")),
+    ]);
+    assert_eq!(main, &expected);
+
+    let method = sequence(&sequences, "Task.renamed");
+    assert_eq!(method.params, ["self", "title"]);
+    assert_eq!(
+        method.instructions,
+        [
+            StoreLocal(s("title")),
+            StoreLocal(s("self")),
+            Load(s("title")),
+            Load(s("self")),
+            GetField(s("done")),
+            MakeContract {
+                name: s("Task"),
+                fields: vec![s("title"), s("done")],
+            },
+            Return,
+        ]
+    );
+    assert_eq!(
+        sequence(&sequences, "reset").instructions[3..],
+        [
+            StoreLocal(s("task")),
+            Load(s("task")),
+            PushBool(true),
+            SetField(s("done")),
+            PushNothing,
+            Return,
+        ]
+    );
+    assert_eq!(
+        sequence(&sequences, "Task.label").instructions,
+        [
+            StoreLocal(s("self")),
+            Load(s("self")),
+            GetField(s("title")),
+            Return
+        ]
+    );
+}
+
+#[test]
+fn test_enum_values_are_built_by_variant() {
+    let sequences = lower(
+        "enum LoadState:\n    Loading\n    Failure(string)\n\nlet's state = LoadState.Loading\nlet's failed = LoadState.Failure(\"offline\")",
+    );
+    assert_eq!(
+        sequences[0].instructions,
+        [
+            MakeEnum {
+                enum_name: s("LoadState"),
+                variant: s("Loading"),
+                arity: 0
+            },
+            Store(s("state")),
+            PushString(s("offline")),
+            MakeEnum {
+                enum_name: s("LoadState"),
+                variant: s("Failure"),
+                arity: 1
+            },
+            Store(s("failed")),
+        ]
+    );
+}
+
+#[test]
+fn test_lambdas_capture_the_locals_they_read() {
+    let sequences = lower(
+        "contract Task:\n    title: string\n\nlet's titles(tasks: List<Task>, prefix: string) -> List<string>:\n    let's unused = 0\n    return tasks.map(prefix + it.title)",
+    );
+    let titles = &sequence(&sequences, "titles").instructions;
+    assert_eq!(
+        titles[4..],
+        [
+            Load(s("tasks")),
+            MakeClosure {
+                function: s("lambda0"),
+                captures: vec![s("prefix")],
+            },
+            CallMethod(s("map"), 1),
+            Return,
+        ]
+    );
+    let lambda = sequence(&sequences, "lambda0");
+    assert_eq!(lambda.params, ["it"]);
+    assert_eq!(
+        lambda.instructions,
+        [
+            StoreLocal(s("it")),
+            Load(s("prefix")),
+            Load(s("it")),
+            GetField(s("title")),
+            Add,
+            Return,
+        ]
+    );
+}
+
+#[test]
+fn test_for_loops_walk_the_list_by_index() {
+    let program = Program {
+        imports: vec![],
+        definitions: vec![Definition::Statement(Statement::For(ForStatement {
+            variable_name: "n".to_string(),
+            collection: ident("numbers"),
+            body: Box::new(Statement::Expression(ExpressionStatement {
+                expression: Expression::Call(Box::new(CallExpression {
+                    function: ident("print"),
+                    arguments: vec![ident("n")],
+                    span: Default::default(),
+                })),
+                span: Default::default(),
+            })),
+            span: Default::default(),
+        }))],
+        span: Default::default(),
+    };
+    let sequences = Lowerer::new().lower_program(&program);
+    assert_eq!(
+        sequences[0].instructions,
+        [
+            Load(s("numbers")),
+            Store(s("$list0")),
+            PushI64(0),
+            Store(s("$i1")),
+            Label(s("L0")),
+            Load(s("$i1")),
+            Load(s("$list0")),
+            Length,
+            LessThan,
+            JumpIfFalse(s("L1")),
+            Load(s("$list0")),
+            Load(s("$i1")),
+            Index,
+            Store(s("n")),
+            Load(s("n")),
+            Call(s("print"), 1),
+            Pop,
+            Load(s("$i1")),
+            PushI64(1),
+            Add,
+            Store(s("$i1")),
+            Jump(s("L0")),
+            Label(s("L1")),
+        ]
+    );
+}
+
+#[test]
+fn test_when_over_enum_variants_is_a_jump_table() {
+    // when state:
+    //     is LoadState::Success(data) => data
+    //     is LoadState::Loading => "loading"
+    //     _ => "failed"
+    let instructions = lower_let(Expression::When(Box::new(WhenExpression {
+        value: ident("state"),
+        cases: vec![
+            case(
+                variant("Success", vec![WhenPattern::Identifier(s("data"))]),
+                None,
+                ident("data"),
+            ),
+            case(variant("Loading", vec![]), None, string("loading")),
+            case(WhenPattern::Wildcard, None, string("failed")),
+        ],
+        span: Default::default(),
+    })));
+    assert_eq!(
+        instructions,
+        [
+            Load(s("state")),
+            Store(s("$when0")),
+            Load(s("$when0")),
+            JumpTable {
+                cases: vec![(s("Success"), s("L1")), (s("Loading"), s("L2"))],
+                default: s("L3"),
+            },
+            Label(s("L1")),
+            Load(s("$when0")),
+            GetPayload(0),
+            Store(s("data")),
+            Load(s("data")),
+            Jump(s("L0")),
+            Label(s("L2")),
+            PushString(s("loading")),
+            Jump(s("L0")),
+            Label(s("L3")),
+            PushString(s("failed")),
+            Jump(s("L0")),
+            Label(s("L0")),
+            Store(s("result")),
+        ]
+    );
+}
+
+#[test]
+fn test_when_with_literals_ranges_and_guards_tests_each_arm() {
+    // when score:
+    //     is 0 => "none"
+    //     is 1..=9 if score > 5 => "most"
+    //     other => "many"
+    let instructions = lower_let(Expression::When(Box::new(WhenExpression {
+        value: ident("score"),
+        cases: vec![
+            case(
+                WhenPattern::Literal(Literal::Number(s("0"))),
+                None,
+                string("none"),
+            ),
+            case(
+                WhenPattern::Range {
+                    start: Literal::Number(s("1")),
+                    end: Literal::Number(s("9")),
+                    inclusive: true,
+                    span: Default::default(),
+                },
+                Some(Expression::Infix(Box::new(InfixExpression {
+                    left: ident("score"),
+                    operator: InfixOperator::GreaterThan,
+                    right: number("5"),
+                    span: Default::default(),
+                }))),
+                string("most"),
+            ),
+            case(WhenPattern::Identifier(s("other")), None, string("many")),
+        ],
+        span: Default::default(),
+    })));
+    assert_eq!(
+        instructions,
+        [
+            Load(s("score")),
+            Store(s("$when0")),
+            Load(s("$when0")),
+            PushI64(0),
+            Equals,
+            JumpIfFalse(s("L1")),
+            PushString(s("none")),
+            Jump(s("L0")),
+            Label(s("L1")),
+            Load(s("$when0")),
+            PushI64(1),
+            LessThan,
+            JumpIfTrue(s("L2")),
+            PushI64(9),
+            Load(s("$when0")),
+            LessThan,
+            JumpIfTrue(s("L2")),
+            Load(s("score")),
+            PushI64(5),
+            GreaterThan,
+            JumpIfFalse(s("L2")),
+            PushString(s("most")),
+            Jump(s("L0")),
+            Label(s("L2")),
+            Load(s("$when0")),
+            Store(s("other")),
+            PushString(s("many")),
+            Jump(s("L0")),
+            Label(s("L3")),
+            PushNothing,
+            Label(s("L0")),
+            Store(s("result")),
+        ]
+    );
+}
+
+#[test]
+fn test_elvis_and_safe_access_keep_a_value_that_decides_the_result() {
+    let member = Box::new(MemberAccessExpression {
+        object: ident("user"),
+        property: s("name"),
+        span: Default::default(),
+    });
+    let instructions = lower_let(Expression::Infix(Box::new(InfixExpression {
+        left: Expression::SafeMemberAccess(member),
+        operator: InfixOperator::Elvis,
+        right: string("anonymous"),
+        span: Default::default(),
+    })));
+    assert_eq!(
+        instructions,
+        [
+            Load(s("user")),
+            Dup,
+            PushNothing,
+            Equals,
+            JumpIfTrue(s("L1")),
+            GetField(s("name")),
+            Label(s("L1")),
+            Dup,
+            PushNothing,
+            Equals,
+            JumpIfFalse(s("L0")),
+            Pop,
+            PushString(s("anonymous")),
+            Label(s("L0")),
+            Store(s("result")),
+        ]
+    );
+}
+
+#[test]
+fn test_ask_js_is_reported() {
+    let mut lowerer = Lowerer::new();
+    let program = Program {
+        imports: vec![],
+        definitions: vec![Definition::Statement(Statement::Expression(
+            ExpressionStatement {
+                expression: Expression::AskJs(Box::new(AskJsExpression {
+                    code: s("alert(1)"),
+                    span: aegis_compiler::Span { start: 0, end: 16 },
+                })),
+                span: Default::default(),
+            },
+        ))],
+        span: Default::default(),
+    };
+    let sequences = lowerer.lower_program(&program);
+    assert_eq!(sequences[0].instructions, [PushNothing, Pop]);
+    assert_eq!(lowerer.errors.len(), 1);
+    assert_eq!(
+        lowerer.errors[0].span,
+        Some(aegis_compiler::Span { start: 0, end: 16 })
+    );
+}
@@ -0,0 +1,275 @@
+//! Tests for `use` declarations and projects made of several modules
+
+use aegis_compiler::error::SemanticErrorType;
+use aegis_compiler::guardian_types::Type;
+use aegis_compiler::project::module_path;
+use aegis_compiler::{ast::*, Architect, Expression, Project, Scribe};
+use std::fs;
+use std::path::PathBuf;
+
+/// Writes `files` to a fresh directory named after the test and loads the
+/// project whose entry module is `main`.
+fn load(test: &str, files: &[(&str, &str)]) -> Project {
+    let root = std::env::temp_dir().join(format!("aegis-{}-{}", test, std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    for (name, source) in files {
+        let path = root.join(module_path(name));
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, source).unwrap();
+    }
+    let project = Project::load(&root, "main").unwrap();
+    fs::remove_dir_all(&root).unwrap();
+    project
+}
+
+fn load_ok(test: &str, files: &[(&str, &str)]) -> Project {
+    let project = load(test, files);
+    assert!(!project.has_errors(), "Got: {:?}", project.diagnostics());
+    project
+}
+
+const TASK: &str = r#"public enum Status:
+    Todo
+    Done
+
+public contract Task:
+    title: string
+    status: Status?
+
+public let's rename(task: Task, title: string) -> Task:
+    return {title: title, status: task.status}
+
+let's helper() -> number:
+    return 1
+"#;
+
+#[test]
+fn test_parse_use_declarations() {
+    let mut architect = Architect::new(Scribe::new(
+        "use models.task\nuse models.task.{Task, Status}\npublic contract Note:\n    text: string",
+    ));
+    let program = architect.parse_program();
+    assert!(architect.errors.is_empty(), "Got: {:?}", architect.errors);
+
+    assert_eq!(program.imports.len(), 2);
+    assert_eq!(program.imports[0].module_name(), "models.task");
+    assert!(program.imports[0].names.is_none());
+    let names: Vec<&str> = program.imports[1]
+        .names
+        .iter()
+        .flatten()
+        .map(|imported| imported.name.as_str())
+        .collect();
+    assert_eq!(names, ["Task", "Status"]);
+    assert!(matches!(
+        &program.definitions[0],
+        Definition::Contract(contract) if contract.is_public
+    ));
+}
+
+#[test]
+fn test_public_declarations_keep_their_indented_bodies() {
+    let mut architect = Architect::new(Scribe::new(
+        "public contract Counter:\n    count: number\n    let's next() -> number:\n        return self.count + 1\n\npublic let's double(n: number) -> number:\n    return n * 2",
+    ));
+    let program = architect.parse_program();
+    assert!(architect.errors.is_empty(), "Got: {:?}", architect.errors);
+    let Definition::Contract(contract) = &program.definitions[0] else {
+        panic!("Expected a contract");
+    };
+    assert_eq!(contract.methods.len(), 1);
+    let Definition::Function(function) = &program.definitions[1] else {
+        panic!("Expected a function");
+    };
+    assert_eq!(function.body.statements.len(), 1);
+}
+
+#[test]
+fn test_only_declarations_can_be_public() {
+    let mut architect = Architect::new(Scribe::new("public let's count = 1"));
+    architect.parse_program();
+    assert_eq!(architect.errors.len(), 1);
+}
+
+#[test]
+fn test_use_imports_every_public_declaration() {
+    let mut project = load_ok(
+        "use-all",
+        &[
+            ("models.task", TASK),
+            (
+                "main",
+                "use models.task\nlet's task: Task = {title: \"Ship\"}\nlet's renamed = rename(task, \"Launch\")",
+            ),
+        ],
+    );
+    assert_eq!(project.modules.len(), 2);
+    assert_eq!(project.modules[0].name, "models.task");
+    assert_eq!(project.modules[0].path, PathBuf::from("models/task.ag"));
+
+    let main = project
+        .modules
+        .iter_mut()
+        .find(|m| m.name == "main")
+        .unwrap();
+    let renamed = main.guardian.infer_expression_type(&Expression::Identifier(
+        "renamed".to_string(),
+        Default::default(),
+    ));
+    assert_eq!(renamed, Type::Custom("Task".to_string()));
+}
+
+#[test]
+fn test_use_with_names_imports_only_those() {
+    let project = load(
+        "use-names",
+        &[
+            ("models.task", TASK),
+            (
+                "main",
+                "use models.task.{Task, Status}\nlet's task: Task = {title: \"Ship\"}",
+            ),
+        ],
+    );
+    let main = project.module("main").unwrap();
+    assert!(
+        main.guardian.errors.is_empty(),
+        "Got: {:?}",
+        main.guardian.errors
+    );
+    assert!(main.guardian.lookup("Status").is_some());
+    assert!(main.guardian.lookup("rename").is_none());
+}
+
+#[test]
+fn test_values_of_types_that_were_not_imported_can_be_used() {
+    load_ok(
+        "use-mentioned",
+        &[
+            ("models.task", TASK),
+            (
+                "main",
+                "use models.task.{rename}\nlet's renamed = rename({title: \"Ship\"}, \"Launch\")\nlet's title = renamed.title",
+            ),
+        ],
+    );
+}
+
+#[test]
+fn test_private_declarations_cannot_be_imported() {
+    let project = load(
+        "use-private",
+        &[
+            ("models.task", TASK),
+            ("main", "use models.task.{helper, Tsk}"),
+        ],
+    );
+    let errors = &project.module("main").unwrap().guardian.errors;
+    assert_eq!(errors.len(), 2, "Got: {:?}", errors);
+    assert_eq!(errors[0].error_type, SemanticErrorType::PrivateImport);
+    assert_eq!(
+        errors[0].suggestions,
+        ["declare it `public` in models/task.ag"]
+    );
+    assert_eq!(errors[1].error_type, SemanticErrorType::UndefinedSymbol);
+    assert_eq!(errors[1].suggestions, ["did you mean 'Task'?"]);
+}
+
+#[test]
+fn test_private_types_cannot_be_named_by_importers() {
+    let task = "contract Hidden:\n    code: number\n\npublic contract Task:\n    title: string\n    hidden: Hidden\n\npublic let's make(title: string) -> Task:\n    return {title: title, hidden: {code: 1}}";
+    let project = load(
+        "use-private-type",
+        &[
+            ("models.task", task),
+            (
+                "main",
+                "use models.task\nlet's task = make(\"Ship\")\nlet's code = task.hidden.code\nlet's hidden: Hidden = {code: 2}",
+            ),
+        ],
+    );
+    let errors = &project.module("main").unwrap().guardian.errors;
+    assert_eq!(errors.len(), 1, "Got: {:?}", errors);
+    assert_eq!(errors[0].error_type, SemanticErrorType::PrivateImport);
+    assert_eq!(
+        errors[0].message,
+        "'Hidden' is private to module 'models.task'"
+    );
+}
+
+#[test]
+fn test_private_functions_cannot_be_called_by_importers() {
+    let project = load(
+        "use-private-call",
+        &[
+            ("models.task", TASK),
+            ("main", "use models.task\nprint(helper())"),
+        ],
+    );
+    let errors = &project.module("main").unwrap().guardian.errors;
+    assert_eq!(errors.len(), 1, "Got: {:?}", errors);
+    assert_eq!(errors[0].error_type, SemanticErrorType::PrivateImport);
+    assert_eq!(
+        errors[0].suggestions,
+        ["declare it `public` in models/task.ag"]
+    );
+}
+
+#[test]
+fn test_import_cycles_are_reported() {
+    let project = load(
+        "use-cycle",
+        &[
+            ("main", "use a"),
+            ("a", "use b\npublic let's one() -> number:\n    return 1"),
+            ("b", "use a"),
+        ],
+    );
+    let errors = &project.module("b").unwrap().guardian.errors;
+    assert_eq!(errors.len(), 1, "Got: {:?}", errors);
+    assert_eq!(errors[0].error_type, SemanticErrorType::ImportCycle);
+    assert!(
+        errors[0].message.contains("a -> b -> a"),
+        "Got: {}",
+        errors[0].message
+    );
+}
+
+#[test]
+fn test_diagnostics_name_the_file_they_are_in() {
+    let project = load(
+        "use-missing",
+        &[
+            ("main", "use models.task"),
+            (
+                "models.task",
+                "use models.status\npublic contract Task:\n    status: Stauts",
+            ),
+        ],
+    );
+    assert_eq!(
+        project.diagnostics(),
+        [
+            "models/task.ag:1:1: Cannot find module 'models.status'; expected it in models/status.ag",
+            "models/task.ag:3:13: Undefined type 'Stauts'",
+        ]
+    );
+}
+
+#[test]
+fn test_a_name_imported_from_two_modules_is_reported() {
+    let project = load(
+        "use-twice",
+        &[
+            ("a", "public let's make() -> number:\n    return 1"),
+            ("b", "public let's make() -> string:\n    return \"one\""),
+            ("main", "use a\nuse b"),
+        ],
+    );
+    let errors = &project.module("main").unwrap().guardian.errors;
+    assert_eq!(errors.len(), 1, "Got: {:?}", errors);
+    assert_eq!(
+        errors[0].error_type,
+        SemanticErrorType::DuplicateDeclaration
+    );
+}
@@ -0,0 +1,323 @@
+//! Tests for optional types, `nothing`, safe navigation and narrowing
+
+use aegis_compiler::ast::*;
+use aegis_compiler::error::SemanticErrorType;
+use aegis_compiler::guardian_types::Type;
+
+mod common;
+use common::{check, check_ok, ident, parse, type_of};
+
+fn optional(ty: Type) -> Type {
+    Type::Optional(Box::new(ty))
+}
+
+fn nothing() -> Expression {
+    Expression::Literal(Literal::Nothing, Default::default())
+}
+
+fn infix(left: Expression, operator: InfixOperator, right: Expression) -> Expression {
+    Expression::Infix(Box::new(InfixExpression {
+        left,
+        operator,
+        right,
+        span: Default::default(),
+    }))
+}
+
+fn length_of(receiver: &str) -> Expression {
+    Expression::Call(Box::new(CallExpression {
+        function: Expression::MemberAccess(Box::new(MemberAccessExpression {
+            object: ident(receiver),
+            property: "length".to_string(),
+            span: Default::default(),
+        })),
+        arguments: vec![],
+        span: Default::default(),
+    }))
+}
+
+const STATE: &str = r#"contract User:
+    name: string
+    nickname: string?
+let's user: User = {name: "Ada"}
+let's maybe_user: User? = nothing
+let's maybe_name: string? = "Ada"
+let's track selected: string? = nothing
+"#;
+
+#[test]
+fn test_question_mark_is_optional_shorthand() {
+    let program = parse("let's nickname: string? = nothing");
+    let Definition::Statement(Statement::Let(let_stmt)) = &program.definitions[0] else {
+        panic!("Expected a let statement");
+    };
+    let Some(TypeIdentifier::Generic { name, args, .. }) = &let_stmt.type_annotation else {
+        panic!("Expected an optional annotation");
+    };
+    assert_eq!(name, "Optional");
+    assert!(matches!(&args[0], TypeIdentifier::Simple { name, .. } if name == "string"));
+
+    let mut guardian = check_ok("let's nickname: string? = nothing");
+    assert_eq!(type_of(&mut guardian, "nickname"), optional(Type::String));
+}
+
+#[test]
+fn test_nothing_is_not_assignable_to_non_optional_types() {
+    let guardian = check("let's name: string = nothing");
+    assert_eq!(guardian.errors.len(), 1);
+    assert_eq!(
+        guardian.errors[0].error_type,
+        SemanticErrorType::TypeMismatch
+    );
+    assert!(guardian.errors[0].suggestions[0].contains('?'));
+}
+
+#[test]
+fn test_values_are_assignable_to_optional_types_but_not_back() {
+    check_ok("let's name = \"Ada\"\nlet's maybe: string? = name");
+
+    let guardian = check("let's maybe: string? = \"Ada\"\nlet's name: string = maybe");
+    assert_eq!(guardian.errors.len(), 1);
+    assert_eq!(
+        guardian.errors[0].error_type,
+        SemanticErrorType::TypeMismatch
+    );
+}
+
+#[test]
+fn test_optional_contract_fields_can_be_left_out() {
+    let mut guardian = check_ok(STATE);
+    assert_eq!(
+        type_of(&mut guardian, "user"),
+        Type::Custom("User".to_string())
+    );
+
+    let guardian = check("contract User:\n    name: string\nlet's user: User = {name: nothing}");
+    assert_eq!(guardian.errors.len(), 1);
+    assert_eq!(
+        guardian.errors[0].error_type,
+        SemanticErrorType::TypeMismatch
+    );
+}
+
+#[test]
+fn test_field_access_on_contracts() {
+    let mut guardian = check_ok(&format!(
+        "{}let's name = user.name\nlet's nickname = user.nickname",
+        STATE
+    ));
+    assert_eq!(type_of(&mut guardian, "name"), Type::String);
+    assert_eq!(type_of(&mut guardian, "nickname"), optional(Type::String));
+
+    let guardian = check(&format!("{}let's name = user.nme", STATE));
+    assert_eq!(guardian.errors.len(), 1);
+    assert_eq!(guardian.errors[0].suggestions, ["did you mean 'name'?"]);
+}
+
+#[test]
+fn test_direct_access_through_an_optional_is_rejected() {
+    let guardian = check(&format!("{}let's name = maybe_user.name", STATE));
+    assert_eq!(guardian.errors.len(), 1);
+    assert_eq!(
+        guardian.errors[0].error_type,
+        SemanticErrorType::InvalidMemberAccess
+    );
+    assert_eq!(
+        guardian.errors[0].message,
+        "Value of type User? may be nothing, so 'name' can't be accessed directly"
+    );
+    assert_eq!(
+        guardian.errors[0].suggestions[0],
+        "use `?.name` to access it safely"
+    );
+
+    let guardian = check(&format!("{}let's size = maybe_name.length()", STATE));
+    assert_eq!(guardian.errors.len(), 1);
+    assert_eq!(
+        guardian.errors[0].suggestions[0],
+        "use `?.length` to call it safely"
+    );
+}
+
+#[test]
+fn test_safe_navigation_yields_optional() {
+    let mut guardian = check_ok(&format!(
+        "{}let's name = maybe_user?.name\nlet's nickname = maybe_user?.nickname\nlet's size = maybe_name?.length()",
+        STATE
+    ));
+    assert_eq!(type_of(&mut guardian, "name"), optional(Type::String));
+    // Optionals don't nest: a missing user and a missing nickname are both `nothing`.
+    assert_eq!(type_of(&mut guardian, "nickname"), optional(Type::String));
+    assert_eq!(type_of(&mut guardian, "size"), optional(Type::Number));
+}
+
+#[test]
+fn test_elvis_supplies_a_default() {
+    let mut guardian = check_ok(&format!(
+        "{}let's name = maybe_user?.name ?: \"anonymous\"\nlet's either = maybe_name ?: selected",
+        STATE
+    ));
+    assert_eq!(type_of(&mut guardian, "name"), Type::String);
+    assert_eq!(type_of(&mut guardian, "either"), optional(Type::String));
+
+    let guardian = check(&format!("{}let's name = maybe_name ?: 1", STATE));
+    assert_eq!(guardian.errors.len(), 1);
+    assert_eq!(
+        guardian.errors[0].error_type,
+        SemanticErrorType::TypeMismatch
+    );
+}
+
+#[test]
+fn test_elvis_on_a_non_optional_is_reported() {
+    let guardian = check(&format!("{}let's name = user.name ?: \"anonymous\"", STATE));
+    assert_eq!(guardian.errors.len(), 1);
+    assert_eq!(
+        guardian.errors[0].error_type,
+        SemanticErrorType::InvalidOperation
+    );
+}
+
+#[test]
+fn test_if_not_nothing_narrows_the_then_branch() {
+    let mut guardian = check_ok(STATE);
+    // `if maybe_name != nothing: maybe_name.length() else: 0`
+    let if_expr = Expression::If(Box::new(IfExpression {
+        condition: infix(ident("maybe_name"), InfixOperator::NotEqual, nothing()),
+        then_branch: length_of("maybe_name"),
+        else_branch: Some(Expression::Literal(
+            Literal::Number("0".to_string()),
+            Default::default(),
+        )),
+        span: Default::default(),
+    }));
+    assert_eq!(guardian.infer_expression_type(&if_expr), Type::Number);
+    assert!(guardian.errors.is_empty(), "Got: {:?}", guardian.errors);
+
+    // Outside the branch, the variable is optional again.
+    guardian.infer_expression_type(&length_of("maybe_name"));
+    assert_eq!(guardian.errors.len(), 1);
+}
+
+#[test]
+fn test_if_nothing_narrows_the_else_branch_and_tracked_state_is_not_narrowed() {
+    let mut guardian = check_ok(STATE);
+    // `if nothing == maybe_name: 0 else: maybe_name.length()`
+    let if_expr = Expression::If(Box::new(IfExpression {
+        condition: infix(nothing(), InfixOperator::Equal, ident("maybe_name")),
+        then_branch: Expression::Literal(Literal::Number("0".to_string()), Default::default()),
+        else_branch: Some(length_of("maybe_name")),
+        span: Default::default(),
+    }));
+    assert_eq!(guardian.infer_expression_type(&if_expr), Type::Number);
+    assert!(guardian.errors.is_empty(), "Got: {:?}", guardian.errors);
+
+    // Tracked state can change between the check and the use.
+    let if_expr = Expression::If(Box::new(IfExpression {
+        condition: infix(ident("selected"), InfixOperator::NotEqual, nothing()),
+        then_branch: length_of("selected"),
+        else_branch: None,
+        span: Default::default(),
+    }));
+    guardian.infer_expression_type(&if_expr);
+    assert_eq!(guardian.errors.len(), 1);
+    assert_eq!(
+        guardian.errors[0].error_type,
+        SemanticErrorType::InvalidMemberAccess
+    );
+}
+
+#[test]
+fn test_when_is_nothing_narrows_later_arms() {
+    let mut guardian = check_ok(STATE);
+    // when maybe_name:
+    //     is nothing => 0
+    //     name => name.length() + maybe_name.length()
+    let when_expr = Expression::When(Box::new(WhenExpression {
+        value: ident("maybe_name"),
+        cases: vec![
+            WhenCase {
+                pattern: WhenPattern::Literal(Literal::Nothing),
+                guard: None,
+                body: Expression::Literal(Literal::Number("0".to_string()), Default::default()),
+                span: Default::default(),
+            },
+            WhenCase {
+                pattern: WhenPattern::Identifier("name".to_string()),
+                guard: None,
+                body: infix(
+                    length_of("name"),
+                    InfixOperator::Plus,
+                    length_of("maybe_name"),
+                ),
+                span: Default::default(),
+            },
+        ],
+        span: Default::default(),
+    }));
+    assert_eq!(guardian.infer_expression_type(&when_expr), Type::Number);
+    assert!(guardian.errors.is_empty(), "Got: {:?}", guardian.errors);
+}
+
+#[test]
+fn test_comparing_a_non_optional_with_nothing_is_reported() {
+    let guardian = check(&format!("{}let's missing = user == nothing", STATE));
+    assert_eq!(guardian.errors.len(), 1);
+    assert_eq!(
+        guardian.errors[0].error_type,
+        SemanticErrorType::InvalidOperation
+    );
+}
+
+#[test]
+fn test_operators_parse_with_precedence() {
+    let program = parse("let's total = 1 + 2 * 3");
+    let Definition::Statement(Statement::Let(let_stmt)) = &program.definitions[0] else {
+        panic!("Expected a let statement");
+    };
+    let Expression::Infix(sum) = &let_stmt.value else {
+        panic!("Expected an infix expression");
+    };
+    assert_eq!(sum.operator, InfixOperator::Plus);
+    assert!(
+        matches!(&sum.right, Expression::Infix(product) if product.operator == InfixOperator::Multiply)
+    );
+
+    let mut guardian = check_ok("let's total = 1 + 2 * 3\nlet's big = total > 5");
+    assert_eq!(type_of(&mut guardian, "total"), Type::Number);
+    assert_eq!(type_of(&mut guardian, "big"), Type::Boolean);
+}
+
+#[test]
+fn test_logical_operators_bind_looser_than_comparisons() {
+    let program = parse("let's ok = 1 < 2 or 3 > 4 and 5 % 2 == 1");
+    let Definition::Statement(Statement::Let(let_stmt)) = &program.definitions[0] else {
+        panic!("Expected a let statement");
+    };
+    let Expression::Infix(either) = &let_stmt.value else {
+        panic!("Expected an infix expression");
+    };
+    assert_eq!(either.operator, InfixOperator::Or);
+    let Expression::Infix(both) = &either.right else {
+        panic!("Expected an infix expression");
+    };
+    assert_eq!(both.operator, InfixOperator::And);
+    assert!(matches!(
+        &both.right,
+        Expression::Infix(equal) if matches!(
+            &equal.left,
+            Expression::Infix(remainder) if remainder.operator == InfixOperator::Modulo
+        )
+    ));
+
+    let mut guardian = check_ok("let's ok = 1 < 2 or 3 > 4 and 5 % 2 == 1\nlet's rest = 7 % 3");
+    assert_eq!(type_of(&mut guardian, "ok"), Type::Boolean);
+    assert_eq!(type_of(&mut guardian, "rest"), Type::Number);
+
+    let guardian = check("let's wrong = 1 and true");
+    assert_eq!(guardian.errors.len(), 1, "Got: {:?}", guardian.errors);
+    assert_eq!(
+        guardian.errors[0].error_type,
+        SemanticErrorType::InvalidOperation
+    );
+}
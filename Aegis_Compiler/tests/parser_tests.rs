@@ -1,5 +1,7 @@
 //! Parser tests for the Aegis compiler
 
+use aegis_compiler::ast::{Definition, Statement};
+use aegis_compiler::error::ParseErrorKind;
 use aegis_compiler::{Architect, Scribe};
 
 #[test]
@@ -38,10 +40,14 @@ fn test_parse_function_stub() {
 
     let program = architect.parse_program();
 
-    // Function parsing is not fully implemented yet, so it will generate errors
-    // when trying to parse as let statement but finding function syntax
-    println!("Errors: {:?}", architect.errors);
-    assert_eq!(program.definitions.len(), 0);
+    assert!(architect.errors.is_empty(), "Got: {:?}", architect.errors);
+    assert_eq!(program.definitions.len(), 1);
+    let Definition::Function(func) = &program.definitions[0] else {
+        panic!("Expected a function definition");
+    };
+    assert_eq!(func.name, "add");
+    assert_eq!(func.parameters.len(), 2);
+    assert_eq!(func.body.statements.len(), 1);
 }
 
 #[test]
@@ -69,7 +75,61 @@ fn test_parse_app_stub() {
 
     let program = architect.parse_program();
 
-    // The stub implementation should handle this gracefully
-    assert!(architect.errors.is_empty());
+    // `app` isn't parsed yet, so it's reported and skipped whole
+    assert_eq!(architect.errors.len(), 1);
+    assert_eq!(architect.errors[0].kind, ParseErrorKind::UnexpectedToken);
     assert_eq!(program.definitions.len(), 0);
 }
+
+#[test]
+fn test_parse_recovers_from_a_bad_statement_in_a_function() {
+    let input = "let's bonus(score: number) -> number:\n    let's base = 100\n    if score > 1000:\n        return 500\n    return base\n\nlet's total = bonus(3)";
+    let scribe = Scribe::new(input);
+    let mut architect = Architect::new(scribe);
+
+    let program = architect.parse_program();
+
+    assert_eq!(architect.errors.len(), 1, "Got: {:?}", architect.errors);
+    assert_eq!(program.definitions.len(), 2);
+    let Definition::Function(bonus) = &program.definitions[0] else {
+        panic!("Expected a function, got {:?}", program.definitions[0]);
+    };
+    assert_eq!(bonus.body.statements.len(), 2);
+}
+
+#[test]
+fn test_parse_skips_the_declarations_in_an_app_block() {
+    let input = "app Counter:
+    let's track count = 0
+    let's increment():
+        count = count + 1
+
+let's total = 3";
+    let scribe = Scribe::new(input);
+    let mut architect = Architect::new(scribe);
+
+    let program = architect.parse_program();
+
+    assert_eq!(architect.errors.len(), 1, "Got: {:?}", architect.errors);
+    assert_eq!(program.definitions.len(), 1);
+    assert!(matches!(
+        &program.definitions[0],
+        Definition::Statement(Statement::Let(let_stmt)) if let_stmt.name == "total"
+    ));
+}
+
+#[test]
+fn test_parse_reports_unhandled_top_level_constructs() {
+    let input = "let's numbers = [1, 2]\nfor n in numbers:\n    print(n)\nlet's total = 3";
+    let scribe = Scribe::new(input);
+    let mut architect = Architect::new(scribe);
+
+    let program = architect.parse_program();
+
+    // The loop is reported once, and parsing resumes at the next declaration.
+    assert_eq!(architect.errors.len(), 1, "Got: {:?}", architect.errors);
+    let error = &architect.errors[0];
+    assert_eq!(error.kind, ParseErrorKind::UnexpectedToken);
+    assert_eq!(&input[error.span.start..error.span.end], "for");
+    assert_eq!(program.definitions.len(), 2);
+}
@@ -0,0 +1,309 @@
+//! Tests for `compile()`, which runs the whole compiler, and for the
+//! Android code generator it runs
+
+use aegis_compiler::ast::*;
+use aegis_compiler::engine::ail::Instruction;
+use aegis_compiler::engine::codegen_android::AndroidCodeGen;
+use aegis_compiler::error::CodeGenError;
+use aegis_compiler::pipeline::Target;
+use aegis_compiler::{compile, CompileOptions, CompileResult, Project};
+use std::path::{Path, PathBuf};
+
+mod common;
+use common::{compile_ok, span};
+
+const TASK: &str = "public contract Task:\n    title: string\n    done: boolean = false\n\npublic let's finish(task: Task) -> Task:\n    return {title: task.title, done: true}";
+
+const MAIN: &str = "use models.task\nlet's task: Task = {title: \"Ship\"}\nlet's total = 1 + 2 * 3";
+
+fn artifact<'a>(result: &'a CompileResult, path: &str) -> &'a str {
+    result
+        .artifacts
+        .iter()
+        .find(|artifact| artifact.path == Path::new(path))
+        .map(|artifact| artifact.contents.as_str())
+        .unwrap_or_else(|| panic!("No {} in {:?}", path, result.artifacts))
+}
+
+#[test]
+fn test_compile_returns_the_program_ail_and_android_project() {
+    let result = compile_ok(&[("main", MAIN), ("models.task", TASK)], Target::Android);
+    assert_eq!(result.program().unwrap().definitions.len(), 2);
+
+    let names: Vec<&str> = result.ail.iter().map(|seq| seq.name.as_str()).collect();
+    assert_eq!(names, ["models.task", "models.task.finish", "main"]);
+    let main = &result.ail[2].instructions;
+    assert!(main.contains(&Instruction::Multiply), "Got: {:?}", main);
+
+    let task = artifact(&result, "app/src/main/java/com/aegisapp/models/task.kt");
+    assert!(task.starts_with("package com.aegisapp\n"), "Got: {}", task);
+    assert!(
+        task.contains("data class Task(val title: String, val done: Boolean = false)"),
+        "Got: {}",
+        task
+    );
+    assert!(
+        task.contains("fun finish(task: Task): Task {\n"),
+        "Got: {}",
+        task
+    );
+
+    let activity = artifact(&result, "app/src/main/java/com/aegisapp/MainActivity.kt");
+    assert!(
+        activity.contains("val task: Task = Task(title = \"Ship\")\n"),
+        "Got: {}",
+        activity
+    );
+    assert!(
+        activity.contains("class MainActivity : AppCompatActivity() {"),
+        "Got: {}",
+        activity
+    );
+    let layout = artifact(&result, "app/src/main/res/layout/activity_main.xml");
+    assert!(layout.starts_with("<?xml"), "Got: {}", layout);
+}
+
+#[test]
+fn test_numbers_contracts_and_methods_become_kotlin_that_compiles() {
+    let source = "contract Task:\n    title: string\n    note: string?\n\nlet's track tasks: List<Task> = [{title: \"a\"}]\nlet's ages = {ada: 36}\nlet's second = tasks.get(1)\ntasks.insert(0, {title: \"b\", note: \"c\"})\nlet's count = tasks.length() + ages.length()\nlet's titles = tasks.map(it.title).join(\", \").toUpperCase()\nlet's first_title = tasks.first().map(it.title).orElse(\"none\")\nlet's note = second?.note?.trim()";
+    let result = compile_ok(&[("main", source)], Target::Android);
+    let activity = artifact(&result, "app/src/main/java/com/aegisapp/MainActivity.kt");
+    for expected in [
+        "data class Task(val title: String, val note: String? = null)",
+        "var tasks: MutableList<Task> = mutableListOf(Task(title = \"a\"))",
+        "val ages = mutableMapOf(\"ada\" to 36.0)",
+        "val second = tasks.getOrNull(1)",
+        "tasks.add(0, Task(title = \"b\", note = \"c\"))",
+        "val count = (tasks.size.toDouble() + ages.size.toDouble())",
+        "val titles = tasks.map({ it.title }).toMutableList().joinToString(\", \").uppercase()",
+        "val first_title = (tasks.firstOrNull()?.let({ it.title }) ?: \"none\")",
+        "val note = second?.note?.trim()",
+    ] {
+        assert!(
+            activity.contains(expected),
+            "No {} in: {}",
+            expected,
+            activity
+        );
+    }
+}
+
+#[test]
+fn test_only_ail_is_generated_for_the_ail_target() {
+    let result = compile_ok(&[("main", "let's total = 1 + 2")], Target::Ail);
+    assert_eq!(result.ail.len(), 1);
+    assert_eq!(
+        result.ail[0].instructions,
+        [
+            Instruction::PushI64(1),
+            Instruction::PushI64(2),
+            Instruction::Add,
+            Instruction::Store("total".to_string()),
+        ]
+    );
+    assert!(result.artifacts.is_empty());
+}
+
+#[test]
+fn test_errors_stop_compilation_and_name_their_file() {
+    let options = CompileOptions::default();
+    let sources = [
+        ("main", "use models.task\nlet's count: number = \"three\""),
+        ("models.task", TASK),
+    ];
+    let result = compile(sources, &options).unwrap();
+    assert!(result.has_errors());
+    assert_eq!(result.diagnostics.len(), 1, "Got: {:?}", result.diagnostics);
+    let (path, diagnostic) = &result.diagnostics[0];
+    assert_eq!(path, Path::new("main.ag"));
+    assert_eq!(diagnostic.code, Some("AEG0102"));
+    assert!(result.ail.is_empty());
+    assert!(result.artifacts.is_empty());
+}
+
+#[test]
+fn test_a_missing_entry_module_fails() {
+    let error = compile([("models.task", TASK)], &CompileOptions::default())
+        .err()
+        .unwrap();
+    assert_eq!(error.kind(), std::io::ErrorKind::NotFound);
+}
+
+#[test]
+fn test_constructs_the_target_cannot_translate_are_reported() {
+    let mut project = Project::from_sources(
+        [("main", "let's numbers = [1, 2]")],
+        "main",
+        Default::default(),
+    )
+    .unwrap();
+    let body = Statement::For(ForStatement {
+        variable_name: "n".to_string(),
+        collection: Expression::Identifier("numbers".to_string(), span()),
+        body: Box::new(Statement::Block(BlockStatement {
+            statements: vec![],
+            span: span(),
+        })),
+        span: aegis_compiler::Span { start: 4, end: 9 },
+    });
+    project.modules[0]
+        .program
+        .definitions
+        .push(Definition::Statement(body));
+
+    let errors = AndroidCodeGen::new()
+        .generate_project_files(&project)
+        .unwrap_err();
+    assert_eq!(errors.len(), 1);
+    let (path, error) = &errors[0];
+    assert_eq!(path, &PathBuf::from("main.ag"));
+    assert_eq!(error.code(), CodeGenError::CODE);
+    assert_eq!(error.span, Some(aegis_compiler::Span { start: 4, end: 9 }));
+    assert!(
+        error
+            .to_string()
+            .starts_with("Code generation error [AEG0201]"),
+        "Got: {}",
+        error
+    );
+}
+
+#[test]
+fn test_the_app_becomes_a_layout_and_an_activity() {
+    let mut project = Project::from_sources(
+        [("main", "let's greeting = \"Hello\"")],
+        "main",
+        Default::default(),
+    )
+    .unwrap();
+    let count = || Expression::Identifier("count".to_string(), span());
+    let text = |property| {
+        UiNode::Element(UiElement {
+            name: "text".to_string(),
+            properties: vec![UiProperty::Positional(property)],
+            children: vec![],
+            span: span(),
+        })
+    };
+    let button = UiNode::Element(UiElement {
+        name: "button".to_string(),
+        properties: vec![
+            UiProperty::Positional(Expression::Literal(
+                Literal::String("Add".to_string()),
+                span(),
+            )),
+            UiProperty::EventBinding(
+                "when_clicked".to_string(),
+                BlockStatement {
+                    statements: vec![Statement::Assign(AssignStatement {
+                        target: count(),
+                        value: Expression::Literal(Literal::Number("1".to_string()), span()),
+                        span: span(),
+                    })],
+                    span: span(),
+                },
+            ),
+        ],
+        children: vec![],
+        span: span(),
+    });
+    let app = AppDefinition {
+        name: "Counter".to_string(),
+        body: AppBody {
+            statements: vec![Statement::Let(LetStatement {
+                name: "count".to_string(),
+                name_span: span(),
+                is_tracked: true,
+                type_annotation: None,
+                value: Expression::Literal(Literal::Number("0".to_string()), span()),
+                span: span(),
+            })],
+            show_block: Some(ShowBlock {
+                root_node: UiNode::Element(UiElement {
+                    name: "column".to_string(),
+                    properties: vec![],
+                    children: vec![
+                        text(Expression::Identifier("greeting".to_string(), span())),
+                        text(count()),
+                        button,
+                    ],
+                    span: span(),
+                }),
+                span: span(),
+            }),
+        },
+        span: span(),
+    };
+    let greet = Statement::Expression(ExpressionStatement {
+        expression: Expression::Call(Box::new(CallExpression {
+            function: Expression::Identifier("print".to_string(), span()),
+            arguments: vec![Expression::Identifier("greeting".to_string(), span())],
+            span: span(),
+        })),
+        span: span(),
+    });
+    let definitions = &mut project.modules[0].program.definitions;
+    definitions.push(Definition::Statement(greet));
+    definitions.push(Definition::App(app));
+
+    let files = AndroidCodeGen::new()
+        .generate_project_files(&project)
+        .unwrap();
+    let layout = &files[1].contents;
+    assert_eq!(
+        layout,
+        r#"<?xml version="1.0" encoding="utf-8"?>
+<LinearLayout
+    xmlns:android="http://schemas.android.com/apk/res/android"
+    android:id="@+id/view_0"
+    android:layout_width="match_parent"
+    android:layout_height="match_parent"
+    android:orientation="vertical">
+    <TextView
+        android:id="@+id/view_1"
+        android:layout_width="wrap_content"
+        android:layout_height="wrap_content" />
+    <TextView
+        android:id="@+id/view_2"
+        android:layout_width="wrap_content"
+        android:layout_height="wrap_content" />
+    <Button
+        android:id="@+id/view_3"
+        android:layout_width="wrap_content"
+        android:layout_height="wrap_content"
+        android:text="Add" />
+</LinearLayout>
+"#
+    );
+    let activity = &files[0].contents;
+    assert!(
+        activity.contains("\nval greeting = \"Hello\"\n"),
+        "Got: {}",
+        activity
+    );
+    assert!(
+        activity.contains("    private var count = 0.0\n"),
+        "Got: {}",
+        activity
+    );
+    // Top-level statements run when the app starts.
+    assert!(
+        activity.contains("setContentView(R.layout.activity_main)\n        println(greeting)\n"),
+        "Got: {}",
+        activity
+    );
+    assert!(
+        activity.contains(
+            "        findViewById<Button>(R.id.view_3).setOnClickListener {\n            lifecycleScope.launch {\n                count = 1.0\n                render()\n            }\n        }\n"
+        ),
+        "Got: {}",
+        activity
+    );
+    assert!(
+        activity.contains(
+            "    private fun render() {\n        findViewById<TextView>(R.id.view_1).text = (greeting).toString()\n        findViewById<TextView>(R.id.view_2).text = (count).toString()\n    }\n"
+        ),
+        "Got: {}",
+        activity
+    );
+}
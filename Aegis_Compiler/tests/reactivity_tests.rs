@@ -0,0 +1,217 @@
+//! Tests for the reactivity dependency graph and tracked-state mutation rules
+
+use aegis_compiler::ast::*;
+use aegis_compiler::error::SemanticErrorType;
+use aegis_compiler::guardian_reactivity::Dependent;
+
+mod common;
+use common::{check_definitions, error_types, ident, let_stmt, number, span, string};
+
+fn plus(left: Expression, right: Expression) -> Expression {
+    Expression::Infix(Box::new(InfixExpression {
+        left,
+        operator: InfixOperator::Plus,
+        right,
+        span: span(),
+    }))
+}
+
+fn assign(name: &str, value: Expression) -> Statement {
+    Statement::Assign(AssignStatement {
+        target: ident(name),
+        value,
+        span: span(),
+    })
+}
+
+fn element(name: &str, properties: Vec<UiProperty>, children: Vec<UiNode>) -> UiNode {
+    UiNode::Element(UiElement {
+        name: name.to_string(),
+        properties,
+        children,
+        span: span(),
+    })
+}
+
+fn on_click(statements: Vec<Statement>) -> UiProperty {
+    UiProperty::EventBinding(
+        "when_clicked".to_string(),
+        BlockStatement {
+            statements,
+            span: span(),
+        },
+    )
+}
+
+fn app(statements: Vec<Statement>, root_node: UiNode) -> Definition {
+    Definition::App(AppDefinition {
+        name: "Counter".to_string(),
+        body: AppBody {
+            statements,
+            show_block: Some(ShowBlock {
+                root_node,
+                span: span(),
+            }),
+        },
+        span: span(),
+    })
+}
+
+fn text_property(index: usize) -> Dependent {
+    Dependent::UiProperty {
+        element: "text".to_string(),
+        index,
+        name: None,
+        span: span(),
+    }
+}
+
+#[test]
+fn test_ui_property_depends_on_tracked_variable() {
+    let guardian = check_definitions(vec![app(
+        vec![let_stmt("count", true, number("0"))],
+        element(
+            "column",
+            vec![],
+            vec![
+                element("text", vec![UiProperty::Positional(ident("count"))], vec![]),
+                element(
+                    "button",
+                    vec![
+                        UiProperty::Positional(string("Add")),
+                        on_click(vec![assign("count", plus(ident("count"), number("1")))]),
+                    ],
+                    vec![],
+                ),
+            ],
+        ),
+    )]);
+
+    assert!(guardian.errors.is_empty(), "Got: {:?}", guardian.errors);
+    let graph = &guardian.dependency_graph;
+    assert_eq!(graph.dependents_of("count"), vec![&text_property(0)]);
+    assert_eq!(graph.dependencies_of(&text_property(0)), ["count"]);
+}
+
+#[test]
+fn test_constant_properties_are_not_recorded() {
+    let guardian = check_definitions(vec![app(
+        vec![let_stmt("title", false, string("Hello"))],
+        element("text", vec![UiProperty::Positional(ident("title"))], vec![]),
+    )]);
+    assert!(guardian.errors.is_empty(), "Got: {:?}", guardian.errors);
+    assert!(guardian.dependency_graph.is_empty());
+}
+
+#[test]
+fn test_derived_value_depends_on_tracked_variables() {
+    let guardian = check_definitions(vec![app(
+        vec![
+            let_stmt("count", true, number("0")),
+            let_stmt("step", true, number("1")),
+            let_stmt("next", false, plus(ident("count"), ident("step"))),
+        ],
+        element("text", vec![UiProperty::Positional(ident("next"))], vec![]),
+    )]);
+
+    let graph = &guardian.dependency_graph;
+    let derived = Dependent::DerivedValue {
+        name: "next".to_string(),
+        span: span(),
+    };
+    assert_eq!(graph.dependencies_of(&derived), ["count", "step"]);
+    // The UI reads `next`, so it must update when either source changes.
+    assert_eq!(graph.dependencies_of(&text_property(0)), ["count", "step"]);
+    assert_eq!(
+        graph.dependents_of("step"),
+        vec![&derived, &text_property(0)]
+    );
+}
+
+#[test]
+fn test_named_property_records_its_name() {
+    let guardian = check_definitions(vec![app(
+        vec![let_stmt("name", true, string(""))],
+        element(
+            "input",
+            vec![
+                UiProperty::Positional(string("Your name")),
+                UiProperty::Named("value".to_string(), ident("name")),
+            ],
+            vec![],
+        ),
+    )]);
+
+    assert!(guardian.errors.is_empty(), "Got: {:?}", guardian.errors);
+    let dependents = guardian.dependency_graph.dependents_of("name");
+    assert_eq!(
+        dependents,
+        vec![&Dependent::UiProperty {
+            element: "input".to_string(),
+            index: 1,
+            name: Some("value".to_string()),
+            span: span(),
+        }]
+    );
+}
+
+#[test]
+fn test_handler_locals_are_not_derived_values() {
+    let guardian = check_definitions(vec![app(
+        vec![let_stmt("count", true, number("0"))],
+        element(
+            "button",
+            vec![
+                UiProperty::Positional(string("Go")),
+                on_click(vec![
+                    let_stmt("snapshot", false, ident("count")),
+                    assign("count", ident("snapshot")),
+                ]),
+            ],
+            vec![],
+        ),
+    )]);
+    assert!(guardian.errors.is_empty(), "Got: {:?}", guardian.errors);
+    assert!(guardian.dependency_graph.is_empty());
+}
+
+#[test]
+fn test_assigning_non_tracked_binding_is_reported() {
+    let guardian = check_definitions(vec![app(
+        vec![let_stmt("status", false, string("Idle"))],
+        element(
+            "button",
+            vec![
+                UiProperty::Positional(string("Go")),
+                on_click(vec![assign("status", string("Loading..."))]),
+            ],
+            vec![],
+        ),
+    )]);
+    assert_eq!(
+        error_types(&guardian),
+        vec![SemanticErrorType::ImmutableAssignment]
+    );
+    assert!(guardian.errors[0].message.contains("let's track"));
+}
+
+#[test]
+fn test_assigning_wrong_type_to_tracked_binding_is_reported() {
+    let guardian = check_definitions(vec![
+        Definition::Statement(let_stmt("count", true, number("0"))),
+        Definition::Statement(assign("count", string("many"))),
+    ]);
+    assert_eq!(
+        error_types(&guardian),
+        vec![SemanticErrorType::TypeMismatch]
+    );
+}
+
+#[test]
+fn test_assigning_undefined_variable_is_reported() {
+    let guardian = check_definitions(vec![Definition::Statement(assign("missing", number("1")))]);
+    assert_eq!(
+        error_types(&guardian),
+        vec![SemanticErrorType::UndefinedSymbol]
+    );
+}
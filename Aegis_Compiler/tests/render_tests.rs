@@ -0,0 +1,174 @@
+//! Tests for rendering diagnostics with the source they point at
+
+use aegis_compiler::error::{Diagnostic, Severity};
+use aegis_compiler::render::{line_and_column, ColorChoice, Renderer};
+use aegis_compiler::{Architect, Guardian, Project, Scribe, Span};
+use std::fs;
+
+/// Parses and checks `source`, returning its first semantic error as a diagnostic.
+fn first_error(source: &str) -> Diagnostic {
+    let mut architect = Architect::new(Scribe::new(source));
+    let program = architect.parse_program();
+    assert!(architect.errors.is_empty(), "Got: {:?}", architect.errors);
+    let mut guardian = Guardian::new();
+    guardian.check_program(&program);
+    Diagnostic::from(guardian.errors.remove(0))
+}
+
+#[test]
+fn test_errors_show_their_source_with_primary_and_related_spans() {
+    let source = "let's count: number = \"three\"";
+    let rendered = Renderer::plain().render(&first_error(source), "main.ag", source);
+    assert_eq!(
+        rendered,
+        "error[AEG0102]: Type mismatch: expected number because of the annotation on 'count', found string
+ --> main.ag:1:23
+  |
+1 | let's count: number = \"three\"
+  |                       ^^^^^^^
+  |              ------ expected number because of this
+  |
+  = help: for more about this error, run `aegis explain AEG0102`
+"
+    );
+}
+
+#[test]
+fn test_spans_on_distant_lines_are_separated() {
+    let source = format!(
+        "let's track count = 0\n{}let's doubled = count * 2",
+        "\n".repeat(9)
+    );
+    let end = source.len();
+    let diagnostic = Diagnostic::warning(
+        "Tracked state is read here".to_string(),
+        Span {
+            start: end - 9,
+            end: end - 4,
+        },
+    )
+    .with_related(
+        "'count' is declared here".to_string(),
+        Span { start: 12, end: 17 },
+    )
+    .with_note("a note".to_string());
+    let rendered = Renderer::plain().render(&diagnostic, "main.ag", &source);
+    assert_eq!(
+        rendered,
+        "warning: Tracked state is read here
+  --> main.ag:11:17
+   |
+ 1 | let's track count = 0
+   |             ----- 'count' is declared here
+...
+11 | let's doubled = count * 2
+   |                 ^^^^^
+   |
+   = note: a note
+"
+    );
+}
+
+#[test]
+fn test_markers_line_up_under_tabs_and_wide_text() {
+    let source = "let's name = \"Zoë\"\n\tlet's x = ünknown";
+    let start = source.find("ünknown").unwrap();
+    let span = Span {
+        start,
+        end: start + "ünknown".len(),
+    };
+    let diagnostic = Diagnostic::warning("Undefined variable 'ünknown'".to_string(), span);
+    let rendered = Renderer::plain().render(&diagnostic, "main.ag", source);
+    assert!(
+        rendered.contains("2 |     let's x = ünknown\n  |               ^^^^^^^\n"),
+        "Got:\n{}",
+        rendered
+    );
+    assert_eq!(line_and_column(source, start), (2, 12));
+}
+
+#[test]
+fn test_spans_over_several_lines_are_underlined_to_the_end_of_the_first() {
+    let source = "let's answer() -> number:\n    return 42";
+    let diagnostic = Diagnostic::warning(
+        "Whole function".to_string(),
+        Span {
+            start: 6,
+            end: source.len(),
+        },
+    );
+    let rendered = Renderer::plain().render(&diagnostic, "main.ag", source);
+    assert!(
+        rendered.contains("1 | let's answer() -> number:\n  |       ^^^^^^^^^^^^^^^^^^^\n"),
+        "Got:\n{}",
+        rendered
+    );
+}
+
+#[test]
+fn test_color_is_only_added_when_asked_for() {
+    let source = "let's count: number = \"three\"";
+    let diagnostic = first_error(source);
+    let colored = Renderer::new(ColorChoice::Always).render(&diagnostic, "main.ag", source);
+    assert!(
+        colored.contains("\x1b[1;31merror[AEG0102]\x1b[0m"),
+        "Got: {:?}",
+        colored
+    );
+    let plain = Renderer::new(ColorChoice::Never).render(&diagnostic, "main.ag", source);
+    assert!(!plain.contains('\x1b'), "Got: {:?}", plain);
+    assert_eq!(ColorChoice::parse("always"), Some(ColorChoice::Always));
+    assert_eq!(ColorChoice::parse("sometimes"), None);
+}
+
+#[test]
+fn test_summaries_count_errors_and_warnings() {
+    let renderer = Renderer::plain();
+    assert_eq!(renderer.render_summary(0, 0), None);
+    assert_eq!(
+        renderer.render_summary(0, 2).unwrap(),
+        "warning: found 2 warnings\n"
+    );
+    assert_eq!(
+        renderer.render_summary(1, 1).unwrap(),
+        "error: found 1 error and 1 warning\n"
+    );
+    assert_eq!(
+        renderer.render_message(Severity::Error, "could not read main.ag"),
+        "error: could not read main.ag\n"
+    );
+}
+
+#[test]
+fn test_projects_render_every_diagnostic() {
+    let root = std::env::temp_dir().join(format!("aegis-render-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(&root).unwrap();
+    fs::write(
+        root.join("aegis.toml"),
+        "[lints]\nunused_variables = \"deny\"",
+    )
+    .unwrap();
+    fs::write(
+        root.join("main.ag"),
+        "let's area(width: number, height: number) -> number:\n    return width * width",
+    )
+    .unwrap();
+    let project = Project::load(&root, "main").unwrap();
+    fs::remove_dir_all(&root).unwrap();
+
+    assert_eq!(
+        project.render_diagnostics(&Renderer::plain()),
+        "error[unused_variables]: Parameter 'height' is never used
+ --> main.ag:1:27
+  |
+1 | let's area(width: number, height: number) -> number:
+  |                           ^^^^^^
+  |
+  = note: `unused_variables` is set to deny in aegis.toml
+  = help: if this is intentional, name it '_height'
+
+error: found 1 error
+"
+    );
+}
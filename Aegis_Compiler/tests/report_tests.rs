@@ -0,0 +1,93 @@
+//! Snapshot tests for the JSON and SARIF reports of diagnostics
+//!
+//! The expected reports are in `tests/snapshots`. After changing a report on
+//! purpose, run the tests with `AEGIS_UPDATE_SNAPSHOTS=1` to rewrite them, and
+//! review the difference.
+
+use aegis_compiler::project::module_path;
+use aegis_compiler::report::{self, Format};
+use aegis_compiler::Project;
+use std::fs;
+use std::path::Path;
+
+/// A project with errors, a related span, a fix, a lint with a suggestion and a
+/// module in a directory.
+const FILES: &[(&str, &str)] = &[
+    (
+        "main",
+        "use models.task.{Task}\nlet's count: number = \"three\"\nlet's task: Task = {titl: \"Ship\"}",
+    ),
+    (
+        "models.task",
+        "public contract Task:\n    title: string\n\npublic let's make(title: string, unused: number) -> Task:\n    return {title: title}",
+    ),
+];
+
+/// Writes the files to a fresh directory named after the test and loads
+/// the project.
+fn load(test: &str) -> Project {
+    let root = std::env::temp_dir().join(format!("aegis-{}-{}", test, std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    for (name, source) in FILES {
+        let path = root.join(module_path(name));
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, source).unwrap();
+    }
+    let project = Project::load(&root, "main").unwrap();
+    fs::remove_dir_all(&root).unwrap();
+    project
+}
+
+/// Compares `actual` with the snapshot `name`, or rewrites the snapshot if
+/// `AEGIS_UPDATE_SNAPSHOTS` is set.
+fn assert_snapshot(name: &str, actual: &str) {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/snapshots")
+        .join(name);
+    if std::env::var_os("AEGIS_UPDATE_SNAPSHOTS").is_some() {
+        fs::write(&path, actual).unwrap();
+        return;
+    }
+    let expected = fs::read_to_string(&path).unwrap();
+    assert!(
+        expected == actual,
+        "{} doesn't match; run with AEGIS_UPDATE_SNAPSHOTS=1 to update it. Got:\n{}",
+        path.display(),
+        actual
+    );
+}
+
+#[test]
+fn test_json_report_snapshot() {
+    let project = load("json-report");
+    assert_snapshot("report.json", &report::json(&project));
+}
+
+#[test]
+fn test_sarif_report_snapshot() {
+    let project = load("sarif-report");
+    let root = project.root.canonicalize().unwrap_or(project.root.clone());
+    let sarif =
+        report::sarif(&project).replace(&format!("file://{}/", root.display()), "file:///project/");
+    assert_snapshot("report.sarif", &sarif);
+}
+
+#[test]
+fn test_reports_are_valid_json_with_every_diagnostic() {
+    let project = load("valid-report");
+    let count = project.diagnostics().len();
+    let json: serde_json::Value = serde_json::from_str(&report::json(&project)).unwrap();
+    assert_eq!(json["version"], report::JSON_VERSION);
+    assert_eq!(json["diagnostics"].as_array().unwrap().len(), count);
+    let sarif: serde_json::Value = serde_json::from_str(&report::sarif(&project)).unwrap();
+    assert_eq!(sarif["version"], report::SARIF_VERSION);
+    assert_eq!(sarif["runs"][0]["results"].as_array().unwrap().len(), count);
+}
+
+#[test]
+fn test_formats_are_parsed_by_name() {
+    assert_eq!(Format::parse("json"), Some(Format::Json));
+    assert_eq!(Format::parse("sarif"), Some(Format::Sarif));
+    assert_eq!(Format::parse("human"), Some(Format::Human));
+    assert_eq!(Format::parse("xml"), None);
+}
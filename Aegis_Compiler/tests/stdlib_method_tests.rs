@@ -0,0 +1,178 @@
+//! Tests for the built-in methods of List, Map, Set, Optional and string
+
+use aegis_compiler::ast::*;
+use aegis_compiler::error::SemanticErrorType;
+use aegis_compiler::guardian_stdlib::{lookup_method, methods_of};
+use aegis_compiler::guardian_types::Type;
+
+mod common;
+use common::{check_ok, ident, number, string};
+
+fn method_call(receiver: &str, method: &str, arguments: Vec<Expression>) -> Expression {
+    Expression::Call(Box::new(CallExpression {
+        function: Expression::MemberAccess(Box::new(MemberAccessExpression {
+            object: ident(receiver),
+            property: method.to_string(),
+            span: Default::default(),
+        })),
+        arguments,
+        span: Default::default(),
+    }))
+}
+
+fn list(ty: Type) -> Type {
+    Type::List(Box::new(ty))
+}
+
+const STATE: &str = r#"contract Task:
+    id: number
+    title: string
+let's track tasks: List<Task> = []
+let's track input_text = ""
+let's names = ["ada", "alan"]
+let's ages: Map<string, number> = {"ada": 36}
+let's task: Task = {id: 1, title: "Ship"}
+"#;
+
+#[test]
+fn test_method_tables_substitute_receiver_types() {
+    let add = lookup_method(&list(Type::Number), "add").unwrap();
+    assert_eq!(add.params, vec![Type::Number]);
+    assert!(add.mutates);
+
+    let keys = lookup_method(
+        &Type::Map(Box::new(Type::String), Box::new(Type::Boolean)),
+        "keys",
+    )
+    .unwrap();
+    assert_eq!(keys.return_type, list(Type::String));
+    assert!(!keys.mutates);
+
+    assert!(methods_of(&Type::Number).is_none());
+    assert!(methods_of(&Type::String)
+        .unwrap()
+        .iter()
+        .all(|method| !method.mutates));
+}
+
+#[test]
+fn test_list_methods_resolve_against_element_type() {
+    let mut guardian = check_ok(STATE);
+    let task = Type::Custom("Task".to_string());
+
+    let ty = guardian.infer_expression_type(&method_call("tasks", "remove", vec![number("0")]));
+    assert_eq!(ty, task);
+    let ty = guardian.infer_expression_type(&method_call("tasks", "get", vec![number("0")]));
+    assert_eq!(ty, Type::Optional(Box::new(task)));
+    let ty = guardian.infer_expression_type(&method_call("names", "join", vec![string(", ")]));
+    assert_eq!(ty, Type::String);
+    assert!(guardian.errors.is_empty(), "Got: {:?}", guardian.errors);
+}
+
+#[test]
+fn test_map_and_string_methods() {
+    let mut guardian = check_ok(STATE);
+    let ty = guardian.infer_expression_type(&method_call("ages", "get", vec![string("ada")]));
+    assert_eq!(ty, Type::Optional(Box::new(Type::Number)));
+    let ty = guardian.infer_expression_type(&method_call("ages", "values", vec![]));
+    assert_eq!(ty, list(Type::Number));
+    let ty = guardian.infer_expression_type(&method_call("input_text", "length", vec![]));
+    assert_eq!(ty, Type::Number);
+    let ty = guardian.infer_expression_type(&method_call("input_text", "split", vec![string(",")]));
+    assert_eq!(ty, list(Type::String));
+    assert!(guardian.errors.is_empty(), "Got: {:?}", guardian.errors);
+}
+
+#[test]
+fn test_generic_method_infers_result_from_function_argument() {
+    let mut guardian = check_ok(STATE);
+    // `let's title_of(task: Task) -> string:` passed to `tasks.map`.
+    guardian.check_function_definition(&FunctionDefinition {
+        name: "title_of".to_string(),
+        is_public: false,
+        is_async: false,
+        generic_params: vec![],
+        parameters: vec![Parameter {
+            name: "task".to_string(),
+            type_annotation: TypeIdentifier::Simple {
+                name: "Task".to_string(),
+                span: Default::default(),
+            },
+            span: Default::default(),
+        }],
+        return_type: Some(TypeIdentifier::Simple {
+            name: "string".to_string(),
+            span: Default::default(),
+        }),
+        body: BlockStatement {
+            statements: vec![],
+            span: Default::default(),
+        },
+        span: Default::default(),
+    });
+
+    let ty = guardian.infer_expression_type(&method_call("tasks", "map", vec![ident("title_of")]));
+    assert_eq!(ty, list(Type::String));
+    assert!(guardian.errors.is_empty(), "Got: {:?}", guardian.errors);
+}
+
+#[test]
+fn test_wrong_argument_type_is_reported() {
+    let mut guardian = check_ok(STATE);
+    let ty = guardian.infer_expression_type(&method_call("tasks", "add", vec![number("1")]));
+    assert_eq!(ty, Type::Error);
+    assert_eq!(guardian.errors.len(), 1);
+    assert_eq!(
+        guardian.errors[0].error_type,
+        SemanticErrorType::TypeMismatch
+    );
+}
+
+#[test]
+fn test_wrong_argument_count_is_reported() {
+    let mut guardian = check_ok(STATE);
+    guardian.infer_expression_type(&method_call("input_text", "trim", vec![string("x")]));
+    assert_eq!(guardian.errors.len(), 1);
+    assert_eq!(
+        guardian.errors[0].error_type,
+        SemanticErrorType::ArityMismatch
+    );
+}
+
+#[test]
+fn test_unknown_method_is_reported_with_suggestion() {
+    let mut guardian = check_ok(STATE);
+    guardian.infer_expression_type(&method_call("tasks", "ad", vec![ident("task")]));
+    assert_eq!(guardian.errors.len(), 1);
+    assert_eq!(
+        guardian.errors[0].error_type,
+        SemanticErrorType::InvalidMemberAccess
+    );
+    assert_eq!(guardian.errors[0].suggestions, ["did you mean 'add'?"]);
+    assert_eq!(
+        guardian.errors[0].message,
+        "Type List<Task> has no method 'ad'"
+    );
+}
+
+#[test]
+fn test_method_on_type_without_methods_is_reported() {
+    let mut guardian = check_ok("let's count = 1");
+    guardian.infer_expression_type(&method_call("count", "length", vec![]));
+    assert_eq!(guardian.errors.len(), 1);
+    assert_eq!(
+        guardian.errors[0].error_type,
+        SemanticErrorType::InvalidMemberAccess
+    );
+}
+
+#[test]
+fn test_mutating_method_on_tracked_state_is_recorded() {
+    let mut guardian = check_ok(STATE);
+    guardian.infer_expression_type(&method_call("tasks", "add", vec![ident("task")]));
+    guardian.infer_expression_type(&method_call("tasks", "length", vec![]));
+
+    let mutations = guardian.dependency_graph.mutations_of("tasks");
+    assert_eq!(mutations.len(), 1);
+    assert_eq!(mutations[0].method.as_deref(), Some("add"));
+}
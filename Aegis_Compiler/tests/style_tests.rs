@@ -0,0 +1,234 @@
+//! Tests for the typed style system and named `style` declarations
+
+use aegis_compiler::ast::*;
+use aegis_compiler::error::{SemanticError, SemanticErrorType};
+
+mod common;
+use common::{check_definitions, ident, number, parse, span, string};
+
+fn if_else(
+    condition: Expression,
+    then_branch: Expression,
+    else_branch: Option<Expression>,
+) -> Expression {
+    Expression::If(Box::new(IfExpression {
+        condition,
+        then_branch,
+        else_branch,
+        span: span(),
+    }))
+}
+
+fn style_block(pairs: Vec<(&str, Expression)>) -> Expression {
+    let pairs = pairs
+        .into_iter()
+        .map(|(key, value)| (ident(key), value))
+        .collect();
+    Expression::Literal(
+        Literal::Map(MapLiteral {
+            pairs,
+            span: span(),
+        }),
+        span(),
+    )
+}
+
+/// A `text` element with the given style, inside an app with a tracked
+/// `is_done` flag and a plain `title` string.
+fn styled_text(style: Expression) -> Definition {
+    Definition::App(AppDefinition {
+        name: "Demo".to_string(),
+        body: AppBody {
+            statements: vec![
+                Statement::Let(LetStatement {
+                    name: "is_done".to_string(),
+                    name_span: span(),
+                    is_tracked: true,
+                    type_annotation: None,
+                    value: Expression::Literal(Literal::Boolean(false), span()),
+                    span: span(),
+                }),
+                Statement::Let(LetStatement {
+                    name: "title".to_string(),
+                    name_span: span(),
+                    is_tracked: false,
+                    type_annotation: None,
+                    value: string("Tasks"),
+                    span: span(),
+                }),
+            ],
+            show_block: Some(ShowBlock {
+                root_node: UiNode::Element(UiElement {
+                    name: "text".to_string(),
+                    properties: vec![
+                        UiProperty::Positional(ident("title")),
+                        UiProperty::Named("style".to_string(), style),
+                    ],
+                    children: vec![],
+                    span: span(),
+                }),
+                span: span(),
+            }),
+        },
+        span: span(),
+    })
+}
+
+fn check(definitions: Vec<Definition>) -> Vec<SemanticError> {
+    check_definitions(definitions).errors
+}
+
+fn assert_single_error(errors: &[SemanticError], message: &str) {
+    assert_eq!(errors.len(), 1, "Got: {:?}", errors);
+    assert!(
+        errors[0].message.contains(message),
+        "Expected '{}' in: {}",
+        message,
+        errors[0].message
+    );
+}
+
+#[test]
+fn test_parse_named_style() {
+    let program = parse(
+        r##"style HeaderText:
+    font_size: 28
+    text_color: "#333333"
+    padding: 16"##,
+    );
+    assert_eq!(program.definitions.len(), 1);
+    let Definition::Style(style) = &program.definitions[0] else {
+        panic!("Expected a style definition");
+    };
+    assert_eq!(style.name, "HeaderText");
+    let keys: Vec<_> = style.fields.iter().map(|f| f.name.as_str()).collect();
+    assert_eq!(keys, ["font_size", "text_color", "padding"]);
+    assert!(check(program.definitions).is_empty());
+}
+
+#[test]
+fn test_named_style_can_be_used_before_it_is_declared() {
+    let mut definitions = vec![styled_text(ident("HeaderText"))];
+    definitions.extend(parse("style HeaderText:\n    font_size: 28").definitions);
+    let errors = check(definitions);
+    assert!(errors.is_empty(), "Got: {:?}", errors);
+}
+
+#[test]
+fn test_undefined_style_is_reported() {
+    let errors = check(vec![styled_text(ident("HeaderText"))]);
+    assert_single_error(&errors, "Undefined style 'HeaderText'");
+}
+
+#[test]
+fn test_non_style_used_as_style_is_reported() {
+    let errors = check(vec![styled_text(ident("title"))]);
+    assert_single_error(&errors, "'title' is not a style");
+}
+
+#[test]
+fn test_invalid_hex_color_is_reported() {
+    let errors = check(parse("style Broken:\n    text_color: \"#12\"").definitions);
+    assert_single_error(&errors, "not a valid hex color");
+    assert_eq!(errors[0].error_type, SemanticErrorType::InvalidUIComponent);
+}
+
+#[test]
+fn test_named_colors_and_units_are_accepted() {
+    let errors = check(vec![styled_text(style_block(vec![
+        ("text_color", string("red")),
+        ("background_color", string("#80FFFFFF")),
+        ("font_size", string("18sp")),
+        ("padding", string("16dp")),
+        ("opacity", number("0.5")),
+    ]))]);
+    assert!(errors.is_empty(), "Got: {:?}", errors);
+}
+
+#[test]
+fn test_wrong_unit_is_reported() {
+    let errors = check(vec![styled_text(style_block(vec![(
+        "padding",
+        string("16sp"),
+    )]))]);
+    assert_single_error(&errors, "Invalid value for style key 'padding'");
+}
+
+#[test]
+fn test_fraction_out_of_range_is_reported() {
+    let errors = check(vec![styled_text(style_block(vec![(
+        "opacity",
+        number("1.5"),
+    )]))]);
+    assert_single_error(&errors, "between 0 and 1");
+}
+
+#[test]
+fn test_unknown_keyword_lists_allowed_values() {
+    let errors = check(vec![styled_text(style_block(vec![(
+        "text_decoration",
+        string("strike"),
+    )]))]);
+    assert_single_error(&errors, "\"line-through\"");
+}
+
+#[test]
+fn test_unknown_key_in_named_style_is_reported_with_suggestion() {
+    let errors = check(parse("style Title:\n    text_colour: \"red\"").definitions);
+    assert_single_error(&errors, "Unknown style key 'text_colour'");
+    assert_eq!(errors[0].suggestions, ["did you mean 'text_color'?"]);
+}
+
+#[test]
+fn test_conditional_style_value_checks_each_branch() {
+    let valid = check(vec![styled_text(style_block(vec![
+        (
+            "text_decoration",
+            if_else(
+                ident("is_done"),
+                string("line-through"),
+                Some(string("none")),
+            ),
+        ),
+        (
+            "text_color",
+            if_else(ident("is_done"), string("#888888"), Some(string("#000000"))),
+        ),
+    ]))]);
+    assert!(valid.is_empty(), "Got: {:?}", valid);
+
+    let invalid = check(vec![styled_text(style_block(vec![(
+        "text_color",
+        if_else(ident("is_done"), string("#888888"), Some(string("#GGGGGG"))),
+    )]))]);
+    assert_single_error(&invalid, "\"#GGGGGG\" is not a valid hex color");
+}
+
+#[test]
+fn test_conditional_style_value_needs_else_branch() {
+    let errors = check(vec![styled_text(style_block(vec![(
+        "text_decoration",
+        if_else(ident("is_done"), string("line-through"), None),
+    )]))]);
+    assert_single_error(&errors, "needs an `else` branch");
+}
+
+#[test]
+fn test_conditional_style_value_needs_boolean_condition() {
+    let errors = check(vec![styled_text(style_block(vec![(
+        "text_decoration",
+        if_else(ident("title"), string("line-through"), Some(string("none"))),
+    )]))]);
+    assert_eq!(errors.len(), 1, "Got: {:?}", errors);
+    assert_eq!(errors[0].error_type, SemanticErrorType::TypeMismatch);
+}
+
+#[test]
+fn test_duplicate_style_declaration_is_reported() {
+    let errors = check(parse("style A:\n    padding: 1\nstyle A:\n    padding: 2").definitions);
+    assert_eq!(errors.len(), 1, "Got: {:?}", errors);
+    assert_eq!(
+        errors[0].error_type,
+        SemanticErrorType::DuplicateDeclaration
+    );
+}
@@ -124,3 +124,9 @@ impl TestReport {
         self.total_failed() == 0
     }
 }
+
+impl Default for TestReport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -98,3 +98,9 @@ impl PerformanceTimer {
         self.elapsed().as_millis()
     }
 }
+
+impl Default for PerformanceTimer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,264 @@
+//! Tests for unification-based type inference: empty collections, lambdas,
+//! generic methods and generic contract initializers
+
+use aegis_compiler::ast::*;
+use aegis_compiler::error::SemanticErrorType;
+use aegis_compiler::guardian_types::Type;
+use aegis_compiler::guardian_unify::{Reason, Unifier, UnifyError};
+
+mod common;
+use common::{check, check_ok, ident, type_of};
+
+fn method_call(receiver: &str, method: &str, arguments: Vec<Expression>) -> Expression {
+    Expression::Call(Box::new(CallExpression {
+        function: Expression::MemberAccess(Box::new(MemberAccessExpression {
+            object: ident(receiver),
+            property: method.to_string(),
+            span: Default::default(),
+        })),
+        arguments,
+        span: Default::default(),
+    }))
+}
+
+fn list(ty: Type) -> Type {
+    Type::List(Box::new(ty))
+}
+
+const TASKS: &str = r#"contract Task:
+    id: number
+    title: string
+    is_done: boolean
+let's tasks: List<Task> = [{id: 1, title: "Ship", is_done: false}]
+"#;
+
+#[test]
+fn test_unifier_binds_variables_and_runs_occurs_check() {
+    let mut unifier = Unifier::default();
+    let reason = Reason::new("the test", Default::default());
+    let t = unifier.fresh();
+
+    unifier
+        .unify(&list(t.clone()), &list(Type::Number), &reason)
+        .unwrap();
+    assert_eq!(unifier.resolve(&t), Type::Number);
+
+    let u = unifier.fresh();
+    let error = unifier.unify(&u, &list(u.clone()), &reason).unwrap_err();
+    assert!(matches!(*error, UnifyError::Occurs { .. }));
+}
+
+#[test]
+fn test_mismatch_reports_the_reason_for_the_binding() {
+    let mut unifier = Unifier::default();
+    let first = Reason::new("the first element", Default::default());
+    let t = unifier.fresh();
+    unifier.unify(&t, &Type::Number, &first).unwrap();
+
+    let later = Reason::new("a later element", Default::default());
+    let error = unifier.unify(&t, &Type::String, &later).unwrap_err();
+    assert_eq!(
+        *error,
+        UnifyError::Mismatch {
+            expected: Type::Number,
+            actual: Type::String,
+            reason: Some(first),
+        }
+    );
+}
+
+#[test]
+fn test_empty_list_element_type_is_inferred_from_use() {
+    let mut guardian = check_ok("let's track items = []");
+    guardian.infer_expression_type(&method_call(
+        "items",
+        "add",
+        vec![Expression::Literal(
+            Literal::Number("1".to_string()),
+            Default::default(),
+        )],
+    ));
+    assert!(guardian.errors.is_empty(), "Got: {:?}", guardian.errors);
+    assert_eq!(type_of(&mut guardian, "items"), list(Type::Number));
+
+    guardian.infer_expression_type(&method_call(
+        "items",
+        "add",
+        vec![Expression::Literal(
+            Literal::String("two".to_string()),
+            Default::default(),
+        )],
+    ));
+    assert_eq!(guardian.errors.len(), 1);
+    assert!(
+        guardian.errors[0]
+            .message
+            .contains("expected number because of parameter 1 of 'add', found string"),
+        "Got: {}",
+        guardian.errors[0].message
+    );
+}
+
+#[test]
+fn test_list_that_would_contain_itself_is_reported() {
+    let mut guardian = check_ok("let's track items = []");
+    guardian.infer_expression_type(&method_call("items", "add", vec![ident("items")]));
+    assert_eq!(guardian.errors.len(), 1);
+    assert!(
+        guardian.errors[0].message.contains("contain itself"),
+        "Got: {}",
+        guardian.errors[0].message
+    );
+}
+
+#[test]
+fn test_element_mismatch_points_at_the_first_element() {
+    let input = r#"let's mixed = [1, "two"]"#;
+    let guardian = check(input);
+    assert_eq!(guardian.errors.len(), 1);
+    let error = &guardian.errors[0];
+    assert!(
+        error
+            .message
+            .contains("expected number because of the first element, found string"),
+        "Got: {}",
+        error.message
+    );
+    assert_eq!(error.related.len(), 1);
+    assert_eq!(error.related[0].span.start, input.find('1').unwrap());
+}
+
+#[test]
+fn test_annotation_is_named_as_the_reason() {
+    let guardian = check("let's total: number = \"ten\"");
+    assert_eq!(guardian.errors.len(), 1);
+    assert!(
+        guardian.errors[0]
+            .message
+            .contains("because of the annotation on 'total'"),
+        "Got: {}",
+        guardian.errors[0].message
+    );
+}
+
+#[test]
+fn test_lambda_parameters_come_from_the_expected_function_type() {
+    let mut guardian = check_ok(&format!(
+        "{}let's done = tasks.filter(task => task.is_done)\nlet's titles = tasks.map(task => task.title)",
+        TASKS
+    ));
+    assert_eq!(
+        type_of(&mut guardian, "done"),
+        list(Type::Custom("Task".to_string()))
+    );
+    assert_eq!(type_of(&mut guardian, "titles"), list(Type::String));
+}
+
+#[test]
+fn test_implicit_it_argument_is_a_lambda() {
+    let mut guardian = check_ok(&format!(
+        "{}let's index = tasks.findIndex(it.id == 1)",
+        TASKS
+    ));
+    assert_eq!(type_of(&mut guardian, "index"), Type::Number);
+
+    let guardian = check(&format!("{}let's index = tasks.findIndex(it.title)", TASKS));
+    assert_eq!(guardian.errors.len(), 1);
+    assert_eq!(
+        guardian.errors[0].error_type,
+        SemanticErrorType::TypeMismatch
+    );
+}
+
+#[test]
+fn test_unannotated_lambda_parameter_is_inferred_from_its_body() {
+    let mut guardian = check_ok("let's double = x => x * 2");
+    assert_eq!(
+        type_of(&mut guardian, "double"),
+        Type::Function {
+            params: vec![Type::Number],
+            return_type: Box::new(Type::Number),
+        }
+    );
+}
+
+#[test]
+fn test_generic_contract_arguments_are_inferred_from_fields() {
+    let mut guardian = check_ok(
+        "contract Box<T>:\n    value: T\nlet's boxed: Box = {value: 1}\nlet's inner = boxed.value",
+    );
+    assert_eq!(
+        type_of(&mut guardian, "boxed"),
+        Type::GenericInstance("Box".to_string(), vec![Type::Number])
+    );
+    assert_eq!(type_of(&mut guardian, "inner"), Type::Number);
+}
+
+#[test]
+fn test_generic_contract_arguments_are_checked_against_annotation() {
+    let guardian = check("contract Box<T>:\n    value: T\nlet's boxed: Box<string> = {value: 1}");
+    assert_eq!(guardian.errors.len(), 1);
+    assert!(
+        guardian.errors[0]
+            .message
+            .contains("because of field 'value' of contract 'Box'"),
+        "Got: {}",
+        guardian.errors[0].message
+    );
+}
+
+#[test]
+fn test_function_argument_mismatch_names_the_parameter() {
+    let mut guardian = check_ok("");
+    guardian.check_function_definition(&FunctionDefinition {
+        name: "greet".to_string(),
+        is_public: false,
+        is_async: false,
+        generic_params: vec![],
+        parameters: vec![Parameter {
+            name: "name".to_string(),
+            type_annotation: TypeIdentifier::Simple {
+                name: "string".to_string(),
+                span: Default::default(),
+            },
+            span: Default::default(),
+        }],
+        return_type: Some(TypeIdentifier::Simple {
+            name: "string".to_string(),
+            span: Default::default(),
+        }),
+        body: BlockStatement {
+            statements: vec![],
+            span: Default::default(),
+        },
+        span: Default::default(),
+    });
+    guardian.infer_expression_type(&Expression::Call(Box::new(CallExpression {
+        function: ident("greet"),
+        arguments: vec![Expression::Literal(
+            Literal::Number("1".to_string()),
+            Default::default(),
+        )],
+        span: Default::default(),
+    })));
+    assert_eq!(guardian.errors.len(), 1);
+    assert!(
+        guardian.errors[0]
+            .message
+            .contains("because of parameter 1 of 'greet'"),
+        "Got: {}",
+        guardian.errors[0].message
+    );
+}
+
+#[test]
+fn test_print_takes_any_arguments_and_checks_them() {
+    check_ok("let's names = [\"ada\"]\nprint()\nprint(names, 1, names.length() > 0)");
+
+    let guardian = check("let's names = [\"ada\"]\nprint(\"count:\", names.lenght())");
+    assert_eq!(guardian.errors.len(), 1, "Got: {:?}", guardian.errors);
+    assert_eq!(
+        guardian.errors[0].error_type,
+        SemanticErrorType::InvalidMemberAccess
+    );
+}
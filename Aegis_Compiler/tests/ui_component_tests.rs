@@ -0,0 +1,252 @@
+//! Tests for validating `show:` trees against the UI component registry
+
+use aegis_compiler::error::{SemanticError, SemanticErrorType};
+use aegis_compiler::{ast::*, Guardian};
+
+mod common;
+use common::{number, span, string};
+
+fn style(pairs: Vec<(&str, Expression)>) -> UiProperty {
+    let pairs = pairs
+        .into_iter()
+        .map(|(key, value)| (Expression::Identifier(key.to_string(), span()), value))
+        .collect();
+    UiProperty::Named(
+        "style".to_string(),
+        Expression::Literal(
+            Literal::Map(MapLiteral {
+                pairs,
+                span: span(),
+            }),
+            span(),
+        ),
+    )
+}
+
+fn event(name: &str) -> UiProperty {
+    UiProperty::EventBinding(
+        name.to_string(),
+        BlockStatement {
+            statements: vec![],
+            span: span(),
+        },
+    )
+}
+
+fn element(name: &str, properties: Vec<UiProperty>, children: Vec<UiNode>) -> UiNode {
+    UiNode::Element(UiElement {
+        name: name.to_string(),
+        properties,
+        children,
+        span: span(),
+    })
+}
+
+/// Checks an app whose `show:` block is `root_node` and returns its errors.
+fn check_ui(root_node: UiNode) -> Vec<SemanticError> {
+    let app = Definition::App(AppDefinition {
+        name: "Demo".to_string(),
+        body: AppBody {
+            statements: vec![],
+            show_block: Some(ShowBlock {
+                root_node,
+                span: span(),
+            }),
+        },
+        span: span(),
+    });
+    let mut guardian = Guardian::new();
+    guardian.check_program(&Program {
+        imports: vec![],
+        definitions: vec![app],
+        span: span(),
+    });
+    guardian.errors
+}
+
+fn assert_single_ui_error(errors: &[SemanticError], message: &str, suggestion: Option<&str>) {
+    assert_eq!(errors.len(), 1, "Got: {:?}", errors);
+    assert_eq!(errors[0].error_type, SemanticErrorType::InvalidUIComponent);
+    assert!(
+        errors[0].message.contains(message),
+        "Expected '{}' in: {}",
+        message,
+        errors[0].message
+    );
+    match suggestion {
+        Some(s) => assert!(
+            errors[0].suggestions.iter().any(|x| x.contains(s)),
+            "Expected a suggestion of '{}', got: {:?}",
+            s,
+            errors[0].suggestions
+        ),
+        None => assert!(errors[0].suggestions.is_empty()),
+    }
+}
+
+#[test]
+fn test_valid_tree_has_no_errors() {
+    let errors = check_ui(element(
+        "column",
+        vec![],
+        vec![
+            element(
+                "text",
+                vec![
+                    UiProperty::Positional(string("Aegis Tasks")),
+                    style(vec![("font_size", number("28")), ("padding", number("16"))]),
+                ],
+                vec![],
+            ),
+            element(
+                "row",
+                vec![style(vec![
+                    ("padding", number("16")),
+                    ("background_color", string("#EEEEEE")),
+                ])],
+                vec![
+                    element(
+                        "input",
+                        vec![
+                            UiProperty::Positional(string("Add a new task...")),
+                            style(vec![("weight", number("1"))]),
+                            event("on_change"),
+                        ],
+                        vec![],
+                    ),
+                    element(
+                        "button",
+                        vec![UiProperty::Positional(string("Add")), event("when_clicked")],
+                        vec![],
+                    ),
+                ],
+            ),
+        ],
+    ));
+    assert!(errors.is_empty(), "Got: {:?}", errors);
+}
+
+#[test]
+fn test_unknown_component_is_reported_with_suggestion() {
+    let errors = check_ui(element("colum", vec![], vec![]));
+    assert_single_ui_error(&errors, "Unknown UI component 'colum'", Some("column"));
+}
+
+#[test]
+fn test_children_of_unknown_component_are_still_checked() {
+    let errors = check_ui(element(
+        "panel",
+        vec![],
+        vec![element("txt", vec![], vec![])],
+    ));
+    assert_eq!(errors.len(), 2, "Got: {:?}", errors);
+}
+
+#[test]
+fn test_wrong_style_value_type_is_reported() {
+    let errors = check_ui(element(
+        "text",
+        vec![
+            UiProperty::Positional(string("Hi")),
+            style(vec![("font_size", string("big"))]),
+        ],
+        vec![],
+    ));
+    assert_single_ui_error(&errors, "Invalid value for style key 'font_size'", None);
+}
+
+#[test]
+fn test_unknown_style_key_is_reported_with_suggestion() {
+    let errors = check_ui(element(
+        "text",
+        vec![
+            UiProperty::Positional(string("Hi")),
+            style(vec![("font_sise", number("12"))]),
+        ],
+        vec![],
+    ));
+    assert_single_ui_error(&errors, "Unknown style key 'font_sise'", Some("font_size"));
+}
+
+#[test]
+fn test_unknown_property_is_reported() {
+    let errors = check_ui(element(
+        "button",
+        vec![
+            UiProperty::Positional(string("Go")),
+            UiProperty::Named("colour".to_string(), string("red")),
+        ],
+        vec![],
+    ));
+    assert_single_ui_error(&errors, "'button' has no property 'colour'", None);
+}
+
+#[test]
+fn test_wrong_property_type_is_reported() {
+    let errors = check_ui(element(
+        "button",
+        vec![
+            UiProperty::Positional(string("Go")),
+            UiProperty::Named("enabled".to_string(), string("yes")),
+        ],
+        vec![],
+    ));
+    assert_single_ui_error(
+        &errors,
+        "'enabled' of 'button' expects a boolean, found string",
+        None,
+    );
+}
+
+#[test]
+fn test_unknown_event_is_reported_with_suggestion() {
+    let errors = check_ui(element(
+        "button",
+        vec![UiProperty::Positional(string("Go")), event("when_click")],
+        vec![],
+    ));
+    assert_single_ui_error(
+        &errors,
+        "does not have a 'when_click' event",
+        Some("when_clicked"),
+    );
+}
+
+#[test]
+fn test_missing_required_argument_is_reported() {
+    let errors = check_ui(element("button", vec![event("when_clicked")], vec![]));
+    assert_single_ui_error(&errors, "'button' requires a label argument", None);
+}
+
+#[test]
+fn test_extra_positional_argument_is_reported() {
+    let errors = check_ui(element(
+        "text",
+        vec![
+            UiProperty::Positional(string("a")),
+            UiProperty::Positional(string("b")),
+        ],
+        vec![],
+    ));
+    assert_single_ui_error(&errors, "takes 1 positional argument(s)", None);
+}
+
+#[test]
+fn test_children_of_leaf_component_are_reported() {
+    let errors = check_ui(element(
+        "text",
+        vec![UiProperty::Positional(string("Hi"))],
+        vec![element(
+            "text",
+            vec![UiProperty::Positional(string("nested"))],
+            vec![],
+        )],
+    ));
+    assert_single_ui_error(&errors, "'text' cannot contain other components", None);
+}
+
+#[test]
+fn test_duplicate_property_is_reported() {
+    let errors = check_ui(element("row", vec![style(vec![]), style(vec![])], vec![]));
+    assert_single_ui_error(&errors, "Property 'style' is given more than once", None);
+}
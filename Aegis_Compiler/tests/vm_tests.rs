@@ -0,0 +1,169 @@
+//! Tests for the VM, which runs AIL directly
+
+use aegis_compiler::engine::ail::{Instruction, InstructionSequence};
+use aegis_compiler::engine::vm::{Value, Vm};
+use aegis_compiler::error::RuntimeError;
+use aegis_compiler::pipeline::Target;
+use aegis_compiler::{compile, CompileOptions};
+
+mod common;
+use common::compile_ok;
+
+/// Runs a project and returns what it printed, or its runtime error.
+fn run_project(sources: &[(&str, &str)]) -> Result<String, RuntimeError> {
+    let result = compile_ok(sources, Target::Ail);
+    let mut vm = Vm::with_output(&result.ail, Vec::new());
+    vm.run()?;
+    Ok(String::from_utf8(vm.into_output()).unwrap())
+}
+
+fn run(source: &str) -> Result<String, RuntimeError> {
+    run_project(&[("main", source)])
+}
+
+#[test]
+fn test_run_prints_arithmetic_and_strings() {
+    let output = run("let's total = 1 + 2 * 3\nlet's half = 7 / 2\nlet's greeting = \"Hello, \" + \"Aegis\"\nprint(total, half, 7 % 3)\nprint(greeting)");
+    assert_eq!(output.unwrap(), "7 3.5 1\nHello, Aegis\n");
+}
+
+#[test]
+fn test_run_calls_functions() {
+    let source = "let's square(n: number) -> number:\n    return n * n\n\nlet's sum_of_squares(a: number, b: number) -> number:\n    let's sum = square(a) + square(b)\n    return sum\n\nprint(sum_of_squares(3, 4), square(3) / 2)";
+    assert_eq!(run(source).unwrap(), "25 4.5\n");
+}
+
+#[test]
+fn test_run_parameters_and_locals_shadow_globals() {
+    let source = "let's count = 10\nlet's total = 0\nlet's track calls = 0\nlet's tally(count: number):\n    let's total = count * 2\n    calls = calls + 1\n    print(count, total)\n\ntally(3)\nprint(count, total, calls)";
+    assert_eq!(run(source).unwrap(), "3 6\n10 0 1\n");
+}
+
+#[test]
+fn test_run_builds_contracts_with_methods_and_defaults() {
+    let source = "contract Task:\n    title: string\n    done: boolean = false\n\n    let's renamed(title: string) -> Task:\n        return {title: title, done: self.done}\n\nlet's task: Task = {title: \"Ship\"}\nprint(task)\nprint(task.renamed(\"Launch\").title, task.done)";
+    assert_eq!(
+        run(source).unwrap(),
+        "Task(title: Ship, done: false)\nLaunch false\n"
+    );
+}
+
+#[test]
+fn test_run_builds_contracts_the_guardian_checked_map_literals_as() {
+    let source = "contract User:\n    name: string\n\nlet's track users: List<User> = []\nusers.add({name: \"C\"})\nlet's track best: User = {name: \"A\"}\nbest = {name: \"B\"}\nprint(users.first().orElse(best).name, best.name)";
+    assert_eq!(run(source).unwrap(), "C B\n");
+}
+
+#[test]
+fn test_run_lists_maps_and_closures() {
+    let source = "let's track numbers = [3, 1, 4, 1, 5]\nnumbers.add(9)\nlet's limit = 2\nlet's big = numbers.filter(it > limit)\nprint(big, numbers.length(), big.map(it * 10).join(\"-\"))\nlet's ages = {\"ada\": 36}\nprint(ages.get(\"ada\"), ages.containsKey(\"bob\"))";
+    assert_eq!(
+        run(source).unwrap(),
+        "[3, 4, 5, 9] 6 30-40-50-90\n36 false\n"
+    );
+}
+
+#[test]
+fn test_run_lambdas_of_it_that_pass_it_to_a_function() {
+    let source = "let's big(n: number) -> boolean:\n    return n > 2\n\nlet's xs = [1, 3, 5]\nprint(xs.filter(big(it)), print(xs.map(it * 2).length()))";
+    assert_eq!(run(source).unwrap(), "3\n[3, 5] nothing\n");
+}
+
+#[test]
+fn test_run_optional_methods_and_safe_calls() {
+    let source = "let's xss = [[1, 2], [3]]\nprint(xss.first().map(it.length()), xss.get(5).map(it.length()).orElse(0))\nlet's name: string? = \" ada \"\nlet's missing: string? = nothing\nprint(name?.trim(), missing?.trim(), missing.isNothing())";
+    assert_eq!(run(source).unwrap(), "2 0\nada nothing true\n");
+}
+
+#[test]
+fn test_run_string_methods_and_logical_operators() {
+    let source = "let's name = \"  Aegis  \".trim()\nprint(name.toUpperCase(), name.length(), name.startsWith(\"Ae\") and !name.isEmpty())\nprint(\"a,b,c\".split(\",\"))";
+    assert_eq!(run(source).unwrap(), "AEGIS 5 true\n[a, b, c]\n");
+}
+
+#[test]
+fn test_run_enum_values() {
+    let source = "enum LoadState:\n    Loading\n    Failure(string)\n\nlet's state = LoadState.Failure(\"offline\")\nprint(state, LoadState.Loading)";
+    assert_eq!(run(source).unwrap(), "Failure(offline) Loading\n");
+}
+
+#[test]
+fn test_run_imported_modules_first() {
+    let task = "public let's shout(text: string) -> string:\n    return text.toUpperCase()\n\nprint(\"loaded\")";
+    let main = "use models.task\nprint(shout(\"ship\"))";
+    let output = run_project(&[("main", main), ("models.task", task)]);
+    assert_eq!(output.unwrap(), "loaded\nSHIP\n");
+}
+
+#[test]
+fn test_run_keeps_the_private_declarations_of_modules_apart() {
+    let math = "let's factor = 3\nlet's helper(n: number) -> number:\n    return n * factor\n\npublic let's triple(n: number) -> number:\n    return helper(n)";
+    let main = "use util.math\nlet's factor = 100\nlet's helper(n: number) -> number:\n    return n + factor\n\nprint(helper(1), triple(2))";
+    let output = run_project(&[("main", main), ("util.math", math)]);
+    assert_eq!(output.unwrap(), "101 6\n");
+}
+
+#[test]
+fn test_runtime_errors_point_at_their_source() {
+    let source = "let's average(total: number, count: number) -> number:\n    return total / count\n\nprint(average(10, 0))";
+    let error = run(source).unwrap_err();
+    assert_eq!(error.message, "division by zero");
+    assert_eq!(error.module, "main");
+    let span = error.span.unwrap();
+    assert_eq!(&source[span.start..span.end], "total / count");
+    assert_eq!(
+        error.to_string(),
+        format!(
+            "Runtime error [AEG0301] at {}..{}: division by zero",
+            span.start, span.end
+        )
+    );
+}
+
+#[test]
+fn test_runtime_errors_stop_endless_recursion() {
+    let source =
+        "let's forever(n: number) -> number:\n    return forever(n + 1)\n\nprint(forever(0))";
+    let error = run(source).unwrap_err();
+    assert!(error.message.contains("recurse forever"), "Got: {}", error);
+}
+
+#[test]
+fn test_runtime_errors_report_instructions_that_take_more_values_than_pushed() {
+    let mut main = InstructionSequence::new("main");
+    main.module = "main".to_string();
+    main.instructions = vec![Instruction::PushI64(1), Instruction::Add];
+    let mut vm = Vm::with_output(&[main], Vec::new());
+    let error = vm.run().unwrap_err();
+    assert!(error.message.contains("fewer values"), "Got: {}", error);
+}
+
+#[test]
+fn test_set_methods_are_not_compiled_to_ail() {
+    let options = CompileOptions {
+        target: Target::Ail,
+        ..CompileOptions::default()
+    };
+    let source = "let's has_one(numbers: Set<number>) -> boolean:\n    return numbers.contains(1)";
+    let result = compile([("main", source)], &options).unwrap();
+    assert!(result.has_errors());
+}
+
+#[test]
+fn test_call_runs_a_function_after_the_top_level() {
+    let result = compile_ok(
+        &[(
+            "main",
+            "let's base = 10\nlet's add(n: number) -> number:\n    return base + n",
+        )],
+        Target::Ail,
+    );
+    let mut vm = Vm::with_output(&result.ail, Vec::new());
+    vm.run().unwrap();
+    assert_eq!(vm.global("base"), Some(&Value::Number(10.0)));
+    assert_eq!(
+        vm.call("add", vec![Value::Number(5.0)]).unwrap(),
+        Value::Number(15.0)
+    );
+    assert!(vm.call("missing", Vec::new()).is_err());
+}
@@ -0,0 +1,200 @@
+//! Tests for exhaustiveness and reachability checking of `when` expressions
+
+use aegis_compiler::error::SemanticErrorType;
+use aegis_compiler::{ast::*, Guardian};
+
+mod common;
+use common::when;
+
+/// Defines `enum Color: Red, Green, Blue` in the given Guardian.
+fn define_color(guardian: &mut Guardian) {
+    let enum_def = EnumDefinition {
+        name: "Color".to_string(),
+        is_public: false,
+        variants: ["Red", "Green", "Blue"]
+            .iter()
+            .map(|name| EnumVariant {
+                name: name.to_string(),
+                types: vec![],
+                span: Default::default(),
+            })
+            .collect(),
+        span: Default::default(),
+    };
+    guardian.check_enum_definition(&enum_def);
+}
+
+fn color(variant: &str) -> Expression {
+    Expression::MemberAccess(Box::new(MemberAccessExpression {
+        object: Expression::Identifier("Color".to_string(), Default::default()),
+        property: variant.to_string(),
+        span: Default::default(),
+    }))
+}
+
+fn color_arm(variant: &str) -> WhenCase {
+    arm(WhenPattern::EnumVariant {
+        enum_name: "Color".to_string(),
+        variant_name: variant.to_string(),
+        arguments: vec![],
+        span: Default::default(),
+    })
+}
+
+fn arm(pattern: WhenPattern) -> WhenCase {
+    WhenCase {
+        pattern,
+        guard: None,
+        body: Expression::Literal(Literal::Number("1".to_string()), Default::default()),
+        span: Default::default(),
+    }
+}
+
+fn errors_of_type(guardian: &Guardian, error_type: SemanticErrorType) -> usize {
+    guardian
+        .errors
+        .iter()
+        .filter(|e| e.error_type == error_type)
+        .count()
+}
+
+#[test]
+fn test_non_exhaustive_enum_lists_missing_variants() {
+    let mut guardian = Guardian::new();
+    define_color(&mut guardian);
+
+    let expr = when(color("Red"), vec![color_arm("Green")]);
+    guardian.infer_expression_type(&expr);
+
+    assert_eq!(guardian.errors.len(), 1, "{:?}", guardian.errors);
+    let error = &guardian.errors[0];
+    assert_eq!(error.error_type, SemanticErrorType::NonExhaustiveMatch);
+    // Missing variants are reported in declaration order.
+    assert_eq!(error.suggestions, vec!["Color::Red", "Color::Blue"]);
+    assert!(error.message.contains("Color::Red, Color::Blue"));
+}
+
+#[test]
+fn test_exhaustive_enum_has_no_errors() {
+    let mut guardian = Guardian::new();
+    define_color(&mut guardian);
+
+    let expr = when(
+        color("Red"),
+        vec![color_arm("Red"), color_arm("Green"), color_arm("Blue")],
+    );
+    guardian.infer_expression_type(&expr);
+
+    assert!(guardian.errors.is_empty(), "{:?}", guardian.errors);
+}
+
+#[test]
+fn test_else_makes_enum_exhaustive() {
+    let mut guardian = Guardian::new();
+    define_color(&mut guardian);
+
+    let expr = when(color("Red"), vec![color_arm("Red"), arm(WhenPattern::Else)]);
+    guardian.infer_expression_type(&expr);
+
+    assert!(guardian.errors.is_empty(), "{:?}", guardian.errors);
+}
+
+#[test]
+fn test_non_exhaustive_boolean() {
+    let mut guardian = Guardian::new();
+
+    let expr = when(
+        Expression::Literal(Literal::Boolean(true), Default::default()),
+        vec![arm(WhenPattern::Literal(Literal::Boolean(true)))],
+    );
+    guardian.infer_expression_type(&expr);
+
+    assert_eq!(
+        errors_of_type(&guardian, SemanticErrorType::NonExhaustiveMatch),
+        1
+    );
+    assert_eq!(guardian.errors[0].suggestions, vec!["false"]);
+}
+
+#[test]
+fn test_number_subject_requires_else() {
+    let mut guardian = Guardian::new();
+
+    let expr = when(
+        Expression::Literal(Literal::Number("3".to_string()), Default::default()),
+        vec![
+            arm(WhenPattern::Literal(Literal::Number("1".to_string()))),
+            arm(WhenPattern::Literal(Literal::Number("2".to_string()))),
+        ],
+    );
+    guardian.infer_expression_type(&expr);
+
+    assert_eq!(guardian.errors.len(), 1);
+    assert_eq!(guardian.errors[0].suggestions, vec!["else"]);
+}
+
+#[test]
+fn test_arm_after_else_is_unreachable() {
+    let mut guardian = Guardian::new();
+    define_color(&mut guardian);
+
+    let expr = when(
+        color("Red"),
+        vec![color_arm("Red"), arm(WhenPattern::Else), color_arm("Blue")],
+    );
+    guardian.infer_expression_type(&expr);
+
+    assert_eq!(guardian.errors.len(), 1, "{:?}", guardian.errors);
+    assert_eq!(
+        guardian.errors[0].error_type,
+        SemanticErrorType::UnreachablePattern
+    );
+    assert!(guardian.errors[0].message.contains("catch-all"));
+}
+
+#[test]
+fn test_duplicate_pattern_is_unreachable() {
+    let mut guardian = Guardian::new();
+    define_color(&mut guardian);
+
+    let expr = when(
+        color("Red"),
+        vec![
+            color_arm("Red"),
+            color_arm("Green"),
+            color_arm("Red"),
+            color_arm("Blue"),
+        ],
+    );
+    guardian.infer_expression_type(&expr);
+
+    assert_eq!(
+        errors_of_type(&guardian, SemanticErrorType::UnreachablePattern),
+        1
+    );
+    assert_eq!(
+        errors_of_type(&guardian, SemanticErrorType::NonExhaustiveMatch),
+        0
+    );
+}
+
+#[test]
+fn test_else_after_complete_match_is_unreachable() {
+    let mut guardian = Guardian::new();
+
+    let expr = when(
+        Expression::Literal(Literal::Boolean(false), Default::default()),
+        vec![
+            arm(WhenPattern::Literal(Literal::Boolean(true))),
+            arm(WhenPattern::Literal(Literal::Boolean(false))),
+            arm(WhenPattern::Else),
+        ],
+    );
+    guardian.infer_expression_type(&expr);
+
+    assert_eq!(guardian.errors.len(), 1);
+    assert_eq!(
+        guardian.errors[0].error_type,
+        SemanticErrorType::UnreachablePattern
+    );
+}
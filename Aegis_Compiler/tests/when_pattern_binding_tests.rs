@@ -0,0 +1,320 @@
+//! Tests for bindings, wildcards, ranges and guards in `when` patterns
+
+use aegis_compiler::error::SemanticErrorType;
+use aegis_compiler::guardian_types::Type;
+use aegis_compiler::{ast::*, Guardian};
+
+mod common;
+use common::{ident, number, when};
+
+/// Defines `contract Data` and `enum LoadState: Loading, Success(Data), Pair(Data, Data)`.
+fn define_load_state(guardian: &mut Guardian) {
+    guardian.check_contract_definition(&ContractDefinition {
+        name: "Data".to_string(),
+        is_public: false,
+        generic_params: vec![],
+        interfaces: vec![],
+        fields: vec![],
+        methods: vec![],
+        properties: vec![],
+        span: Default::default(),
+    });
+    let variant = |name: &str, types: &[&str]| EnumVariant {
+        name: name.to_string(),
+        types: types
+            .iter()
+            .map(|t| TypeIdentifier::Simple {
+                name: t.to_string(),
+                span: Default::default(),
+            })
+            .collect(),
+        span: Default::default(),
+    };
+    guardian.check_enum_definition(&EnumDefinition {
+        name: "LoadState".to_string(),
+        is_public: false,
+        variants: vec![
+            variant("Loading", &[]),
+            variant("Success", &["Data"]),
+            variant("Pair", &["Data", "Data"]),
+        ],
+        span: Default::default(),
+    });
+}
+
+fn loading() -> Expression {
+    Expression::MemberAccess(Box::new(MemberAccessExpression {
+        object: Expression::Identifier("LoadState".to_string(), Default::default()),
+        property: "Loading".to_string(),
+        span: Default::default(),
+    }))
+}
+
+fn variant_pattern(variant: &str, arguments: Vec<WhenPattern>) -> WhenPattern {
+    WhenPattern::EnumVariant {
+        enum_name: "LoadState".to_string(),
+        variant_name: variant.to_string(),
+        arguments,
+        span: Default::default(),
+    }
+}
+
+fn arm(pattern: WhenPattern, guard: Option<Expression>, body: Expression) -> WhenCase {
+    WhenCase {
+        pattern,
+        guard,
+        body,
+        span: Default::default(),
+    }
+}
+
+fn range(start: &str, end: &str, inclusive: bool) -> WhenPattern {
+    WhenPattern::Range {
+        start: Literal::Number(start.to_string()),
+        end: Literal::Number(end.to_string()),
+        inclusive,
+        span: Default::default(),
+    }
+}
+
+#[test]
+fn test_binding_has_payload_type_in_arm() {
+    let mut guardian = Guardian::new();
+    define_load_state(&mut guardian);
+
+    let expr = when(
+        loading(),
+        vec![
+            arm(
+                variant_pattern("Success", vec![WhenPattern::Identifier("data".to_string())]),
+                None,
+                ident("data"),
+            ),
+            arm(WhenPattern::Else, None, ident("data")),
+        ],
+    );
+
+    // The first arm's body sees `data`; the `else` arm does not, so the arms disagree.
+    assert_eq!(guardian.infer_expression_type(&expr), Type::Error);
+
+    let expr = when(
+        loading(),
+        vec![arm(
+            variant_pattern("Success", vec![WhenPattern::Identifier("data".to_string())]),
+            None,
+            ident("data"),
+        )],
+    );
+    assert_eq!(
+        guardian.infer_expression_type(&expr),
+        Type::Custom("Data".to_string())
+    );
+}
+
+#[test]
+fn test_binding_does_not_leak_out_of_arm() {
+    let mut guardian = Guardian::new();
+    define_load_state(&mut guardian);
+
+    let expr = when(
+        loading(),
+        vec![
+            arm(
+                variant_pattern("Success", vec![WhenPattern::Identifier("data".to_string())]),
+                None,
+                number("1"),
+            ),
+            arm(WhenPattern::Else, None, number("2")),
+        ],
+    );
+    guardian.infer_expression_type(&expr);
+
+    assert_eq!(guardian.infer_expression_type(&ident("data")), Type::Error);
+}
+
+#[test]
+fn test_wrong_number_of_sub_patterns() {
+    let mut guardian = Guardian::new();
+    define_load_state(&mut guardian);
+
+    let expr = when(
+        loading(),
+        vec![arm(
+            variant_pattern("Pair", vec![WhenPattern::Wildcard]),
+            None,
+            number("1"),
+        )],
+    );
+
+    assert_eq!(guardian.infer_expression_type(&expr), Type::Error);
+    assert_eq!(guardian.errors.len(), 1);
+    assert_eq!(
+        guardian.errors[0].error_type,
+        SemanticErrorType::ArityMismatch
+    );
+}
+
+#[test]
+fn test_duplicate_binding_in_pattern() {
+    let mut guardian = Guardian::new();
+    define_load_state(&mut guardian);
+
+    let expr = when(
+        loading(),
+        vec![
+            arm(
+                variant_pattern(
+                    "Pair",
+                    vec![
+                        WhenPattern::Identifier("x".to_string()),
+                        WhenPattern::Identifier("x".to_string()),
+                    ],
+                ),
+                None,
+                number("1"),
+            ),
+            arm(WhenPattern::Else, None, number("2")),
+        ],
+    );
+    guardian.infer_expression_type(&expr);
+
+    assert_eq!(guardian.errors.len(), 1);
+    assert_eq!(
+        guardian.errors[0].error_type,
+        SemanticErrorType::DuplicateDeclaration
+    );
+}
+
+#[test]
+fn test_wildcards_cover_payloads() {
+    let mut guardian = Guardian::new();
+    define_load_state(&mut guardian);
+
+    let expr = when(
+        loading(),
+        vec![
+            arm(variant_pattern("Loading", vec![]), None, number("1")),
+            arm(
+                variant_pattern("Success", vec![WhenPattern::Wildcard]),
+                None,
+                number("2"),
+            ),
+            arm(
+                variant_pattern(
+                    "Pair",
+                    vec![
+                        WhenPattern::Wildcard,
+                        WhenPattern::Identifier("b".to_string()),
+                    ],
+                ),
+                None,
+                number("3"),
+            ),
+        ],
+    );
+    guardian.infer_expression_type(&expr);
+
+    assert!(guardian.errors.is_empty(), "{:?}", guardian.errors);
+}
+
+#[test]
+fn test_guarded_arms_do_not_count_towards_exhaustiveness() {
+    let mut guardian = Guardian::new();
+    define_load_state(&mut guardian);
+
+    let truth = Expression::Literal(Literal::Boolean(true), Default::default());
+    let expr = when(
+        loading(),
+        vec![
+            arm(variant_pattern("Loading", vec![]), None, number("1")),
+            arm(variant_pattern("Pair", vec![]), None, number("2")),
+            arm(
+                variant_pattern("Success", vec![WhenPattern::Identifier("data".to_string())]),
+                Some(truth),
+                number("3"),
+            ),
+        ],
+    );
+    guardian.infer_expression_type(&expr);
+
+    assert_eq!(guardian.errors.len(), 1, "{:?}", guardian.errors);
+    assert_eq!(
+        guardian.errors[0].error_type,
+        SemanticErrorType::NonExhaustiveMatch
+    );
+    assert_eq!(guardian.errors[0].suggestions, vec!["LoadState::Success"]);
+}
+
+#[test]
+fn test_guard_must_be_boolean() {
+    let mut guardian = Guardian::new();
+
+    let expr = when(
+        number("5"),
+        vec![
+            arm(
+                WhenPattern::Identifier("n".to_string()),
+                Some(ident("n")),
+                number("1"),
+            ),
+            arm(WhenPattern::Else, None, number("2")),
+        ],
+    );
+    guardian.infer_expression_type(&expr);
+
+    assert_eq!(guardian.errors.len(), 1, "{:?}", guardian.errors);
+    assert_eq!(
+        guardian.errors[0].error_type,
+        SemanticErrorType::TypeMismatch
+    );
+}
+
+#[test]
+fn test_range_patterns() {
+    let mut guardian = Guardian::new();
+
+    let expr = when(
+        number("5"),
+        vec![
+            arm(range("0", "10", false), None, number("1")),
+            arm(range("10", "20", true), None, number("2")),
+            arm(WhenPattern::Wildcard, None, number("3")),
+        ],
+    );
+    guardian.infer_expression_type(&expr);
+    assert!(guardian.errors.is_empty(), "{:?}", guardian.errors);
+
+    // Ranges alone can never exhaust a number.
+    let expr = when(
+        number("5"),
+        vec![arm(range("0", "10", true), None, number("1"))],
+    );
+    guardian.infer_expression_type(&expr);
+    assert_eq!(guardian.errors.len(), 1);
+    assert_eq!(guardian.errors[0].suggestions, vec!["else"]);
+}
+
+#[test]
+fn test_empty_and_duplicate_ranges() {
+    let mut guardian = Guardian::new();
+
+    let expr = when(
+        number("5"),
+        vec![
+            arm(range("10", "1", true), None, number("1")),
+            arm(range("0", "5", false), None, number("2")),
+            arm(range("0", "5", false), None, number("3")),
+            arm(WhenPattern::Else, None, number("4")),
+        ],
+    );
+    guardian.infer_expression_type(&expr);
+
+    let kinds: Vec<_> = guardian.errors.iter().map(|e| &e.error_type).collect();
+    assert_eq!(
+        kinds,
+        vec![
+            &SemanticErrorType::InvalidOperation,
+            &SemanticErrorType::UnreachablePattern
+        ]
+    );
+}
@@ -0,0 +1,245 @@
+//! The Forge: the `aegis` command-line toolchain for Aegis projects.
+
+use aegis_compiler::engine::vm::Vm;
+use aegis_compiler::error::{Diagnostic, Severity};
+use aegis_compiler::error_codes;
+use aegis_compiler::fix;
+use aegis_compiler::pipeline::{self, CompileOptions, Target};
+use aegis_compiler::render::{ColorChoice, Renderer};
+use aegis_compiler::report::{self, Format};
+use aegis_compiler::Project;
+use std::env;
+use std::io;
+use std::path::Path;
+use std::process::ExitCode;
+
+const USAGE: &str = "Usage: aegis <command> [arguments]
+
+Commands:
+    check [DIR]       Check the project in DIR, or the current directory,
+                      and report its errors and warnings
+    explain [CODE]    Explain an error code, e.g., `aegis explain AEG0102`,
+                      or list every code
+    fix [DIR]         Apply the fixes the compiler is sure of to the project
+                      in DIR, or the current directory, then check it
+    run [DIR]         Run the project in DIR, or the current directory,
+                      if it has no UI
+
+Options:
+    --color WHEN      Color diagnostics: auto (the default), always or never
+    --format FORMAT   Report diagnostics as human (the default), json or
+                      sarif; json and sarif are printed to standard output";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    match args.first().map(String::as_str) {
+        Some("check") => check(&args[1..]),
+        Some("fix") => fix(&args[1..]),
+        Some("run") => run(&args[1..]),
+        Some("explain") => explain(args.get(1).map(String::as_str)),
+        Some("help" | "--help" | "-h") => {
+            println!("{}", USAGE);
+            ExitCode::SUCCESS
+        }
+        Some(other) => {
+            eprintln!("error: unknown command '{}'\n\n{}", other, USAGE);
+            ExitCode::FAILURE
+        }
+        None => {
+            eprintln!("{}", USAGE);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Prints the explanation of an error code, or every code with its title.
+fn explain(code: Option<&str>) -> ExitCode {
+    let Some(code) = code else {
+        for code in error_codes::codes() {
+            println!("{}  {}", code, error_codes::title(code).unwrap_or_default());
+        }
+        return ExitCode::SUCCESS;
+    };
+    match error_codes::explain(code) {
+        Some(text) => {
+            print!("{}", text);
+            ExitCode::SUCCESS
+        }
+        None => {
+            eprintln!(
+                "error: '{}' is not an Aegis error code; run `aegis explain` to list them",
+                code
+            );
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// The options of the commands that check a project.
+struct CheckOptions<'a> {
+    root: &'a str,
+    color: ColorChoice,
+    format: Format,
+}
+
+impl<'a> CheckOptions<'a> {
+    /// Parses the directory and options given to `check`, `fix` or `run`,
+    /// reporting any that are invalid.
+    fn parse(args: &'a [String]) -> Option<Self> {
+        let mut options = CheckOptions {
+            root: ".",
+            color: ColorChoice::Auto,
+            format: Format::Human,
+        };
+        let mut args = args.iter().map(String::as_str);
+        while let Some(arg) = args.next() {
+            match arg {
+                "--color" => match args.next().and_then(ColorChoice::parse) {
+                    Some(choice) => options.color = choice,
+                    None => {
+                        eprintln!("error: --color takes auto, always or never");
+                        return None;
+                    }
+                },
+                "--format" => match args.next().and_then(Format::parse) {
+                    Some(choice) => options.format = choice,
+                    None => {
+                        eprintln!("error: --format takes human, json or sarif");
+                        return None;
+                    }
+                },
+                _ if arg.starts_with('-') => {
+                    eprintln!("error: unknown option '{}'\n\n{}", arg, USAGE);
+                    return None;
+                }
+                _ => options.root = arg,
+            }
+        }
+        Some(options)
+    }
+}
+
+/// Checks the project whose `main.ag` is in the directory given, printing
+/// its diagnostics to standard error, or as a JSON or SARIF report to
+/// standard output. Fails if the project has an error.
+fn check(args: &[String]) -> ExitCode {
+    let Some(options) = CheckOptions::parse(args) else {
+        return ExitCode::FAILURE;
+    };
+    let renderer = Renderer::new(options.color);
+    match Project::load(options.root, "main") {
+        Ok(project) => report_diagnostics(&project, &options, &renderer),
+        Err(error) => load_failed(options.root, &error, &renderer),
+    }
+}
+
+/// Applies every machine-applicable fix to the project in the directory
+/// given, listing the fixes on standard error, then reports what is left
+/// like `check` does.
+fn fix(args: &[String]) -> ExitCode {
+    let Some(options) = CheckOptions::parse(args) else {
+        return ExitCode::FAILURE;
+    };
+    let renderer = Renderer::new(options.color);
+    let (applied, project) = match fix::fix_project(Path::new(options.root), "main") {
+        Ok(result) => result,
+        Err(error) => return load_failed(options.root, &error, &renderer),
+    };
+    for fix in &applied {
+        eprintln!("fixed {}:{}: {}", fix.path.display(), fix.line, fix.message);
+    }
+    report_diagnostics(&project, &options, &renderer)
+}
+
+/// Checks the project in the directory given and runs it on the AIL VM,
+/// printing its diagnostics and any runtime error to standard error. A
+/// program with an `app` block can't run: the Architect reports `app` as
+/// unsupported, so the project has an error.
+fn run(args: &[String]) -> ExitCode {
+    let Some(options) = CheckOptions::parse(args) else {
+        return ExitCode::FAILURE;
+    };
+    let renderer = Renderer::new(options.color);
+    if !matches!(options.format, Format::Human) {
+        eprint!(
+            "{}",
+            renderer.render_message(Severity::Error, "`run` only reports diagnostics as human")
+        );
+        return ExitCode::FAILURE;
+    }
+    let project = match Project::load(options.root, "main") {
+        Ok(project) => project,
+        Err(error) => return load_failed(options.root, &error, &renderer),
+    };
+    eprint!("{}", project.render_diagnostics(&renderer));
+    if project.has_errors() {
+        return ExitCode::FAILURE;
+    }
+    let compile_options = CompileOptions {
+        target: Target::Ail,
+        ..CompileOptions::default()
+    };
+    let result = pipeline::compile_project(project, &compile_options);
+    if result.has_errors() {
+        for (path, diagnostic) in &result.diagnostics {
+            if let Some(module) = result
+                .project
+                .modules
+                .iter()
+                .find(|module| module.path == *path)
+            {
+                eprint!(
+                    "{}",
+                    renderer.render(diagnostic, &path.display().to_string(), &module.source)
+                );
+            }
+        }
+        return ExitCode::FAILURE;
+    }
+    match Vm::new(&result.ail).run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            match result.module(&error.module) {
+                Some(module) => eprint!(
+                    "{}",
+                    renderer.render(
+                        &Diagnostic::from(error),
+                        &module.path.display().to_string(),
+                        &module.source
+                    )
+                ),
+                None => eprint!(
+                    "{}",
+                    renderer.render_message(Severity::Error, &error.to_string())
+                ),
+            }
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Reports the diagnostics of a project in the format asked for. Fails if
+/// the project has an error.
+fn report_diagnostics(project: &Project, options: &CheckOptions, renderer: &Renderer) -> ExitCode {
+    match options.format {
+        Format::Human => eprint!("{}", project.render_diagnostics(renderer)),
+        Format::Json => println!("{}", report::json(project)),
+        Format::Sarif => println!("{}", report::sarif(project)),
+    }
+    if project.has_errors() {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+fn load_failed(root: &str, error: &io::Error, renderer: &Renderer) -> ExitCode {
+    eprint!(
+        "{}",
+        renderer.render_message(
+            Severity::Error,
+            &format!("could not load the project in '{}': {}", root, error)
+        )
+    );
+    ExitCode::FAILURE
+}
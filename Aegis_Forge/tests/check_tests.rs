@@ -0,0 +1,104 @@
+//! Tests for `aegis check` and `aegis fix`
+
+use std::fs;
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+fn aegis(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_aegis"))
+        .args(args)
+        .env_remove("NO_COLOR")
+        .output()
+        .unwrap()
+}
+
+/// Creates a project named `name` whose `main.ag` is `source`.
+fn project(name: &str, source: &str) -> PathBuf {
+    let root = std::env::temp_dir().join(format!("aegis-check-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(&root).unwrap();
+    fs::write(root.join("main.ag"), source).unwrap();
+    root
+}
+
+#[test]
+fn test_check_renders_errors_and_fails() {
+    let root = project("error", "let's count: number = \"three\"");
+    let output = aegis(&["check", root.to_str().unwrap(), "--color", "never"]);
+    fs::remove_dir_all(&root).unwrap();
+    assert!(!output.status.success());
+    let text = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        text.starts_with("error[AEG0102]: Type mismatch"),
+        "Got: {}",
+        text
+    );
+    assert!(text.contains(" --> main.ag:1:23\n"), "Got: {}", text);
+    assert!(text.ends_with("error: found 1 error\n"), "Got: {}", text);
+}
+
+#[test]
+fn test_check_passes_with_only_warnings() {
+    let root = project(
+        "warning",
+        "let's twice(n: number, unused: number) -> number:\n    return n * 2",
+    );
+    let output = aegis(&["check", root.to_str().unwrap(), "--color", "always"]);
+    fs::remove_dir_all(&root).unwrap();
+    assert!(output.status.success());
+    let text = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        text.contains("\x1b[1;33mwarning[unused_variables]\x1b[0m"),
+        "Got: {:?}",
+        text
+    );
+}
+
+#[test]
+fn test_check_reports_a_missing_project() {
+    let output = aegis(&["check", "/nonexistent/aegis-project", "--color", "never"]);
+    assert!(!output.status.success());
+    let text = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        text.starts_with("error: could not load the project in '/nonexistent/aegis-project'"),
+        "Got: {}",
+        text
+    );
+}
+
+#[test]
+fn test_check_reports_json_and_sarif_on_standard_output() {
+    let root = project("report", "let's count: number = \"three\"");
+    let json = aegis(&["check", root.to_str().unwrap(), "--format", "json"]);
+    let sarif = aegis(&["check", root.to_str().unwrap(), "--format", "sarif"]);
+    fs::remove_dir_all(&root).unwrap();
+    assert!(!json.status.success());
+    assert!(json.stderr.is_empty());
+    let text = String::from_utf8(json.stdout).unwrap();
+    assert!(text.contains("\"code\": \"AEG0102\""), "Got: {}", text);
+    let text = String::from_utf8(sarif.stdout).unwrap();
+    assert!(text.contains("\"version\": \"2.1.0\""), "Got: {}", text);
+
+    let unknown = aegis(&["check", "--format", "xml"]);
+    assert!(!unknown.status.success());
+}
+
+#[test]
+fn test_fix_applies_fixes_and_reports_what_is_left() {
+    let root = project(
+        "fix",
+        "contract Task:\n    title: string\n\nlet's task: Task = {titl: \"Ship\"}\nlet's count: number = \"three\"",
+    );
+    let output = aegis(&["fix", root.to_str().unwrap(), "--color", "never"]);
+    let fixed = fs::read_to_string(root.join("main.ag")).unwrap();
+    fs::remove_dir_all(&root).unwrap();
+    assert!(!output.status.success());
+    assert!(fixed.contains("{title: \"Ship\"}"), "Got: {}", fixed);
+    let text = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        text.starts_with("fixed main.ag:4: rename 'titl' to 'title'\n"),
+        "Got: {}",
+        text
+    );
+    assert!(text.ends_with("error: found 1 error\n"), "Got: {}", text);
+}
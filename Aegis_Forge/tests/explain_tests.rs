@@ -0,0 +1,44 @@
+//! Tests for `aegis explain`
+
+use std::process::{Command, Output};
+
+fn aegis(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_aegis"))
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn test_explain_prints_the_explanation_of_a_code() {
+    let output = aegis(&["explain", "aeg0102"]);
+    assert!(output.status.success());
+    let text = String::from_utf8(output.stdout).unwrap();
+    assert!(
+        text.starts_with("# AEG0102: Type mismatch"),
+        "Got: {}",
+        text
+    );
+    assert!(text.contains("```aegis"), "Got: {}", text);
+}
+
+#[test]
+fn test_explain_lists_every_code() {
+    let output = aegis(&["explain"]);
+    assert!(output.status.success());
+    let text = String::from_utf8(output.stdout).unwrap();
+    assert!(text.contains("AEG0001  Unexpected token"), "Got: {}", text);
+    assert!(
+        text.contains("AEG0116  Assignment to an immutable value"),
+        "Got: {}",
+        text
+    );
+}
+
+#[test]
+fn test_explain_rejects_unknown_codes() {
+    let output = aegis(&["explain", "AEG9999"]);
+    assert!(!output.status.success());
+    let text = String::from_utf8(output.stderr).unwrap();
+    assert!(text.contains("not an Aegis error code"), "Got: {}", text);
+}
@@ -0,0 +1,98 @@
+//! Tests for `aegis run`
+
+use std::fs;
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+fn aegis(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_aegis"))
+        .args(args)
+        .env_remove("NO_COLOR")
+        .output()
+        .unwrap()
+}
+
+/// Creates a project named `name` whose `main.ag` is `source`.
+fn project(name: &str, source: &str) -> PathBuf {
+    let root = std::env::temp_dir().join(format!("aegis-run-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(&root).unwrap();
+    fs::write(root.join("main.ag"), source).unwrap();
+    root
+}
+
+#[test]
+fn test_run_prints_what_the_program_prints() {
+    let root = project(
+        "hello",
+        "let's greet(name: string) -> string:\n    return \"Hello, \" + name\n\nprint(greet(\"Aegis\"), 6 * 7)",
+    );
+    let output = aegis(&["run", root.to_str().unwrap(), "--color", "never"]);
+    fs::remove_dir_all(&root).unwrap();
+    assert!(output.status.success(), "Got: {:?}", output);
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "Hello, Aegis 42\n"
+    );
+}
+
+#[test]
+fn test_run_renders_runtime_errors_at_their_source() {
+    let root = project(
+        "error",
+        "let's average(total: number, count: number) -> number:\n    return total / count\n\nprint(\"before\")\nprint(average(10, 0))",
+    );
+    let output = aegis(&["run", root.to_str().unwrap(), "--color", "never"]);
+    fs::remove_dir_all(&root).unwrap();
+    assert!(!output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "before\n");
+    let text = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        text.starts_with("error[AEG0301]: division by zero"),
+        "Got: {}",
+        text
+    );
+    assert!(text.contains(" --> main.ag:2:12\n"), "Got: {}", text);
+}
+
+#[test]
+fn test_run_does_not_run_a_project_with_errors() {
+    let root = project("invalid", "let's count: number = \"three\"\nprint(count)");
+    let output = aegis(&["run", root.to_str().unwrap(), "--color", "never"]);
+    fs::remove_dir_all(&root).unwrap();
+    assert!(!output.status.success());
+    assert!(output.stdout.is_empty());
+    let text = String::from_utf8(output.stderr).unwrap();
+    assert!(text.starts_with("error[AEG0102]"), "Got: {}", text);
+}
+
+#[test]
+fn test_run_refuses_an_app() {
+    let root = project(
+        "app",
+        "app Counter:\n    let's track count = 0\n\n    show:\n        text count",
+    );
+    let output = aegis(&["run", root.to_str().unwrap(), "--color", "never"]);
+    fs::remove_dir_all(&root).unwrap();
+    assert!(!output.status.success());
+    assert!(output.stdout.is_empty());
+    let text = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        text.contains("`app` blocks aren't supported yet"),
+        "Got: {}",
+        text
+    );
+}
+
+#[test]
+fn test_run_the_conditions_example() {
+    let example = concat!(env!("CARGO_MANIFEST_DIR"), "/../Examples/conditions.ag");
+    let root = project("example", &fs::read_to_string(example).unwrap());
+    let output = aegis(&["run", root.to_str().unwrap(), "--color", "never"]);
+    fs::remove_dir_all(&root).unwrap();
+    assert!(output.status.success(), "Got: {:?}", output);
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "Manny is a veteran: true\nAda is a veteran: false\nManny's bonus is: 150\n"
+    );
+}
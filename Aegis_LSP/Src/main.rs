@@ -5,7 +5,9 @@ use tower_lsp::{Client, LanguageServer, LspService, Server};
 use tracing::info;
 
 // Import all the necessary components from our compiler.
-use aegis_compiler::{token::Span, Architect, Guardian, Scribe};
+use aegis_compiler::error::{Diagnostic as AegisDiagnostic, Severity};
+use aegis_compiler::lint::{LintContext, Linter};
+use aegis_compiler::{error_codes, Architect, Guardian, Scribe};
 
 // Simple type system for LSP autocompletion
 #[derive(Debug, Clone)]
@@ -22,6 +24,8 @@ struct Backend {
     client: Client,
     // A thread-safe map to store the contents of open documents.
     document_map: DashMap<Url, String>,
+    // The diagnostics last published for each document, for hover.
+    diagnostic_map: DashMap<Url, Vec<AegisDiagnostic>>,
 }
 
 #[tower_lsp::async_trait]
@@ -46,6 +50,8 @@ impl LanguageServer for Backend {
                 }),
                 // Announce that we can provide information on hover.
                 hover_provider: Some(HoverProviderCapability::Simple(true)),
+                // Announce that errors with fixes offer them as quick fixes.
+                code_action_provider: Some(CodeActionProviderCapability::Simple(true)),
                 ..ServerCapabilities::default()
             },
         })
@@ -80,7 +86,19 @@ impl LanguageServer for Backend {
         Ok(Some(CompletionResponse::Array(items)))
     }
 
-    async fn hover(&self, _params: HoverParams) -> Result<Option<Hover>> {
+    async fn hover(&self, params: HoverParams) -> Result<Option<Hover>> {
+        // Over an error with a code, show the long-form explanation of the code.
+        let position = params.text_document_position_params;
+        if let Some(explanation) = self.explain_at(&position.text_document.uri, position.position) {
+            return Ok(Some(Hover {
+                contents: HoverContents::Markup(MarkupContent {
+                    kind: MarkupKind::Markdown,
+                    value: explanation.to_string(),
+                }),
+                range: None,
+            }));
+        }
+
         // A full implementation would find the AST node under the cursor.
         // For this prototype, we'll return a static example.
         let markdown = MarkupContent {
@@ -93,6 +111,48 @@ impl LanguageServer for Backend {
         }))
     }
 
+    async fn code_action(&self, params: CodeActionParams) -> Result<Option<CodeActionResponse>> {
+        let uri = params.text_document.uri;
+        let Some(text) = self.document_map.get(&uri).map(|text| text.clone()) else {
+            return Ok(None);
+        };
+        let start = position_to_offset(&text, params.range.start);
+        let end = position_to_offset(&text, params.range.end);
+        let Some(diagnostics) = self.diagnostic_map.get(&uri) else {
+            return Ok(None);
+        };
+        let actions = diagnostics
+            .iter()
+            .filter(|d| d.span.start <= end && start <= d.span.end)
+            .flat_map(|d| d.fixes.iter().map(move |fix| (d, fix)))
+            .map(|(diagnostic, fix)| {
+                let edits = fix
+                    .edits
+                    .iter()
+                    .map(|edit| TextEdit {
+                        range: Range {
+                            start: offset_to_position(&text, edit.span.start),
+                            end: offset_to_position(&text, edit.span.end),
+                        },
+                        new_text: edit.replacement.clone(),
+                    })
+                    .collect();
+                CodeActionOrCommand::CodeAction(CodeAction {
+                    title: fix.message.clone(),
+                    kind: Some(CodeActionKind::QUICKFIX),
+                    diagnostics: Some(vec![self.create_diagnostic(&text, diagnostic, "Guardian")]),
+                    edit: Some(WorkspaceEdit {
+                        changes: Some([(uri.clone(), edits)].into_iter().collect()),
+                        ..Default::default()
+                    }),
+                    is_preferred: Some(fix.is_machine_applicable()),
+                    ..Default::default()
+                })
+            })
+            .collect();
+        Ok(Some(actions))
+    }
+
     async fn shutdown(&self) -> Result<()> {
         info!("Aegis LSP: Shutting down...");
         Ok(())
@@ -105,38 +165,83 @@ impl Backend {
         let scribe = Scribe::new(&text);
         let mut architect = Architect::new(scribe);
         let program = architect.parse_program();
-        let mut diagnostics = Vec::new();
+        let mut found: Vec<(AegisDiagnostic, &str)> = Vec::new();
 
         // Collect parsing errors
         for err in architect.errors {
-            diagnostics.push(self.create_diagnostic(err.span, err.message, "Architect"));
+            found.push((err.into(), "Architect"));
         }
 
-        // If no parsing errors, proceed to semantic analysis
-        if diagnostics.is_empty() {
-            let mut guardian = Guardian::new();
+        // If no parsing errors, proceed to semantic analysis and the lints
+        if found.is_empty() {
+            let mut guardian = Guardian::new().with_source(&text);
             guardian.check_program(&program);
-            for err in guardian.errors {
-                diagnostics.push(self.create_diagnostic(err.span, err.message, "Guardian"));
+            let lints = Linter::default().run(&LintContext::new(&program, &guardian, &text));
+            for err in guardian.errors.iter().cloned() {
+                found.push((err.into(), "Guardian"));
+            }
+            for lint in lints {
+                found.push((lint, "Lint"));
             }
         }
+
+        let diagnostics = found
+            .iter()
+            .map(|(diagnostic, source)| self.create_diagnostic(&text, diagnostic, source))
+            .collect();
+        self.diagnostic_map.insert(
+            uri.clone(),
+            found
+                .into_iter()
+                .map(|(diagnostic, _)| diagnostic)
+                .collect(),
+        );
         self.client
             .publish_diagnostics(uri, diagnostics, None)
             .await;
     }
 
-    // Helper to create a diagnostic message
-    fn create_diagnostic(&self, _span: Span, message: String, source: &str) -> Diagnostic {
-        // A real implementation would convert byte-offset Span to line/character Range
+    // Helper to convert a compiler diagnostic to an LSP one
+    fn create_diagnostic(
+        &self,
+        text: &str,
+        diagnostic: &AegisDiagnostic,
+        source: &str,
+    ) -> Diagnostic {
+        let severity = match diagnostic.severity {
+            Severity::Error => DiagnosticSeverity::ERROR,
+            Severity::Warning => DiagnosticSeverity::WARNING,
+            Severity::Info => DiagnosticSeverity::INFORMATION,
+            Severity::Hint => DiagnosticSeverity::HINT,
+        };
         Diagnostic {
-            range: Range::default(),
-            severity: Some(DiagnosticSeverity::ERROR),
+            range: Range {
+                start: offset_to_position(text, diagnostic.span.start),
+                end: offset_to_position(text, diagnostic.span.end),
+            },
+            severity: Some(severity),
+            // Errors are identified by their code, lint warnings by the lint's name.
+            code: diagnostic
+                .code
+                .or(diagnostic.lint)
+                .map(|code| NumberOrString::String(code.to_string())),
             source: Some(format!("Aegis ({})", source)),
-            message,
+            message: diagnostic.message.clone(),
             ..Default::default()
         }
     }
 
+    // Finds the explanation of the code of the error under the cursor, if any.
+    fn explain_at(&self, uri: &Url, position: Position) -> Option<&'static str> {
+        let text = self.document_map.get(uri)?;
+        let offset = position_to_offset(&text, position);
+        let diagnostics = self.diagnostic_map.get(uri)?;
+        diagnostics
+            .iter()
+            .filter(|d| d.span.start <= offset && offset <= d.span.end)
+            .find_map(|d| error_codes::explain(d.code?))
+    }
+
     // Helper to generate completion items
     fn get_suggestions_for_type(&self, _ty: &Type) -> Vec<CompletionItem> {
         // ... (as implemented before)
@@ -144,12 +249,43 @@ impl Backend {
     }
 }
 
+// Converts a byte offset into the document to an LSP position, which counts
+// characters in UTF-16 code units.
+fn offset_to_position(text: &str, offset: usize) -> Position {
+    let before = &text[..offset.min(text.len())];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    Position {
+        line: before.matches('\n').count() as u32,
+        character: before[line_start..].encode_utf16().count() as u32,
+    }
+}
+
+// Converts an LSP position back to a byte offset into the document.
+fn position_to_offset(text: &str, position: Position) -> usize {
+    let mut line_start = 0;
+    for _ in 0..position.line {
+        match text[line_start..].find('\n') {
+            Some(i) => line_start += i + 1,
+            None => return text.len(),
+        }
+    }
+    let mut units = 0;
+    for (i, c) in text[line_start..].char_indices() {
+        if units >= position.character as usize || c == '\n' {
+            return line_start + i;
+        }
+        units += c.len_utf16();
+    }
+    text.len()
+}
+
 #[tokio::main]
 async fn main() {
     let (stdin, stdout) = (tokio::io::stdin(), tokio::io::stdout());
     let (service, socket) = LspService::new(|client| Backend {
         client,
         document_map: DashMap::new(),
+        diagnostic_map: DashMap::new(),
     });
     Server::new(stdin, stdout, socket).serve(service).await;
 }